@@ -2,6 +2,7 @@
 use proc_macro::TokenStream;
 
 mod common;
+mod mcp_error;
 mod prompt;
 mod prompt_handler;
 mod prompt_router;
@@ -22,6 +23,12 @@ mod tool_router;
 /// | `description`     | `String`                   | A description of the tool. The document of this function will be used. |
 /// | `input_schema`    | `Expr`                     | A JSON Schema object defining the expected parameters for the tool. If not provide, if will use the json schema of its argument with type `Parameters<T>` |
 /// | `annotations`     | `ToolAnnotationsAttribute` | Additional tool information. Defaults to `None`. |
+/// | `streaming`       | `bool`                     | Experimental. The function returns a `Stream` of `Content`, which is sent to opted-in clients as partial results and aggregated as the tool result. |
+/// | `cache_ttl_ms`    | `u64`                      | Cache successful results by arguments for this many milliseconds, a cache hit skips the function. Applied by `#[tool_router]`. |
+/// | `cache_capacity`  | `usize`                    | The max number of cached results, least recently used ones are evicted. Defaults to `ToolResultCache::DEFAULT_CAPACITY`. |
+/// | `coalesce`        | `bool`                     | Concurrent calls with the same arguments, locale and accepted types share the execution of the first one, unlike the cache nothing is kept once it's done, see `ToolRouter::with_coalescing_key`. Applied by `#[tool_router]`. |
+/// | `max_arg_bytes`   | `usize`                    | Reject the calls whose arguments, serialized as JSON, are larger with `invalid_params`, before the tool runs. Applied by `#[tool_router]`. |
+/// | `deprecated`      | `String`                   | Mark the tool deprecated with a migration message, like `"use get_cinema_list instead"`, sent in the `_meta` of the tool. Each call logs a warning. |
 ///
 /// ## Example
 ///
@@ -31,6 +38,16 @@ mod tool_router;
 ///     // handling tool request
 /// }
 /// ```
+///
+/// The function doesn't have to be `async`, a synchronous function is kept as is and its result is
+/// returned as a ready future when the tool is called, which suits CPU-only tools:
+///
+/// ```rust,ignore
+/// #[tool(description = "Get the current time")]
+/// fn get_current_time(&self) -> String {
+///     chrono::Utc::now().to_rfc3339()
+/// }
+/// ```
 #[proc_macro_attribute]
 pub fn tool(attr: TokenStream, input: TokenStream) -> TokenStream {
     tool::tool(attr.into(), input.into())
@@ -151,10 +168,9 @@ pub fn tool_router(attr: TokenStream, input: TokenStream) -> TokenStream {
 ///     async fn list_tools(
 ///         &self,
 ///         _request: Option<PaginatedRequestParam>,
-///         _context: RequestContext<RoleServer>,
+///         context: RequestContext<RoleServer>,
 ///     ) -> Result<ListToolsResult, rmcp::ErrorData> {
-///         let items = self.tool_router.list_all();
-///         Ok(ListToolsResult::with_all_items(items))
+///         Ok(self.tool_router.list_tools(&context))
 ///     }
 /// }
 /// ```
@@ -263,3 +279,40 @@ pub fn prompt_handler(attr: TokenStream, input: TokenStream) -> TokenStream {
         .unwrap_or_else(|err| err.to_compile_error())
         .into()
 }
+
+/// # McpError
+///
+/// This derive macro converts an error enum into `ErrorData`, so the tools can use `?` with domain errors.
+///
+/// ## Usage
+///
+/// Each variant is mapped with an `#[mcp(...)]` attribute:
+///
+/// | field     | type      | usage |
+/// | :-        | :-        | :-    |
+/// | `code`    | `Expr`    | The JSON-RPC code, an `ErrorCode` or an `i32`. Defaults to `ErrorCode::INTERNAL_ERROR`. |
+/// | `message` | `LitStr`  | The message, formatted with the fields of the variant in scope, the unnamed ones as `_0`, `_1`... Defaults to the `Display` of the error. |
+///
+/// ## Example
+///
+/// ```rust,ignore
+/// #[derive(Debug, McpError)]
+/// enum MovieError {
+///     #[mcp(code = ErrorCode::RESOURCE_NOT_FOUND, message = "movie {movie_id} not found")]
+///     NotFound { movie_id: i32 },
+///     #[mcp(code = -32602, message = "unknown city {_0}")]
+///     UnknownCity(String),
+/// }
+///
+/// #[tool(description = "Get movie details based on the movie ID")]
+/// async fn get_movie_detail_info(&self, Parameters(req): Parameters<Request>) -> Result<String, ErrorData> {
+///     let movie = self.movies.get(&req.movie_id).ok_or(MovieError::NotFound { movie_id: req.movie_id })?;
+///     Ok(movie.clone())
+/// }
+/// ```
+#[proc_macro_derive(McpError, attributes(mcp))]
+pub fn mcp_error(input: TokenStream) -> TokenStream {
+    mcp_error::mcp_error(input.into())
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
@@ -0,0 +1,84 @@
+use darling::{FromMeta, ast::NestedMeta};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Data, DeriveInput, Expr, Fields, LitStr};
+
+#[derive(FromMeta, Debug, Default)]
+#[darling(default)]
+pub struct McpErrorAttribute {
+    /// The JSON-RPC code, an `ErrorCode` or an `i32`
+    pub code: Option<Expr>,
+    /// The message, formatted with the fields of the variant
+    pub message: Option<LitStr>,
+}
+
+impl McpErrorAttribute {
+    fn from_attributes(attrs: &[syn::Attribute]) -> syn::Result<Self> {
+        let Some(attr) = attrs.iter().find(|attr| attr.path().is_ident("mcp")) else {
+            return Ok(Self::default());
+        };
+        let attr_args = NestedMeta::parse_meta_list(attr.meta.require_list()?.tokens.clone())?;
+        Ok(Self::from_list(&attr_args)?)
+    }
+}
+
+pub fn mcp_error(input: TokenStream) -> syn::Result<TokenStream> {
+    let input = syn::parse2::<DeriveInput>(input)?;
+    let Data::Enum(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "McpError can only be derived for enums",
+        ));
+    };
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let mut arms = Vec::new();
+    for variant in &data.variants {
+        let attribute = McpErrorAttribute::from_attributes(&variant.attrs)?;
+        let variant_ident = &variant.ident;
+        let code = match attribute.code {
+            Some(code) => quote! { rmcp::model::ErrorCode::from(#code) },
+            None => quote! { rmcp::model::ErrorCode::INTERNAL_ERROR },
+        };
+        // without a message, the error is displayed
+        let Some(message) = attribute.message else {
+            let pattern = match &variant.fields {
+                Fields::Named(_) => quote! { #ident::#variant_ident { .. } },
+                Fields::Unnamed(_) => quote! { #ident::#variant_ident(..) },
+                Fields::Unit => quote! { #ident::#variant_ident },
+            };
+            arms.push(quote! {
+                #pattern => (#code, error.to_string()),
+            });
+            continue;
+        };
+        // the fields are in scope of the message, the unnamed ones as `_0`, `_1`...
+        let pattern = match &variant.fields {
+            Fields::Named(fields) => {
+                let names = fields.named.iter().map(|field| &field.ident);
+                quote! { #ident::#variant_ident { #(#names),* } }
+            }
+            Fields::Unnamed(fields) => {
+                let names = (0..fields.unnamed.len()).map(|index| format_ident!("_{index}"));
+                quote! { #ident::#variant_ident(#(#names),*) }
+            }
+            Fields::Unit => quote! { #ident::#variant_ident },
+        };
+        arms.push(quote! {
+            #pattern => (#code, format!(#message)),
+        });
+    }
+
+    Ok(quote! {
+        impl #impl_generics ::core::convert::From<#ident #ty_generics> for rmcp::ErrorData #where_clause {
+            #[allow(unused_variables)]
+            fn from(error: #ident #ty_generics) -> Self {
+                let (code, message): (rmcp::model::ErrorCode, String) = match &error {
+                    #(#arms)*
+                };
+                rmcp::ErrorData::new(code, message, None)
+            }
+        }
+    })
+}
@@ -48,16 +48,14 @@ pub fn prompt_handler(attr: TokenStream, input: TokenStream) -> syn::Result<Toke
             _context: RequestContext<RoleServer>,
         ) -> Result<ListPromptsResult, rmcp::ErrorData> {
             let prompts = #router_expr.list_all();
-            Ok(ListPromptsResult {
-                prompts,
-                next_cursor: None,
-            })
+            Ok(ListPromptsResult::with_all_items(prompts))
         }
     };
 
     // Check if methods already exist and replace them if they do
     let mut has_get_prompt = false;
     let mut has_list_prompts = false;
+    let mut has_handles_prompts = false;
 
     for item in &mut impl_block.items {
         if let ImplItem::Fn(fn_item) = item {
@@ -70,6 +68,8 @@ pub fn prompt_handler(attr: TokenStream, input: TokenStream) -> syn::Result<Toke
                     *item = list_prompts_impl.clone();
                     has_list_prompts = true;
                 }
+                // keep the user's own answer
+                "handles_prompts" => has_handles_prompts = true,
                 _ => {}
             }
         }
@@ -82,6 +82,13 @@ pub fn prompt_handler(attr: TokenStream, input: TokenStream) -> syn::Result<Toke
     if !has_list_prompts {
         impl_block.items.push(list_prompts_impl);
     }
+    if !has_handles_prompts {
+        impl_block.items.push(parse_quote! {
+            fn handles_prompts(&self) -> bool {
+                true
+            }
+        });
+    }
 
     Ok(quote! {
         #impl_block
@@ -108,6 +115,7 @@ mod test {
         assert!(result_str.contains("PromptContext") && result_str.contains("new"));
         assert!(result_str.contains("async fn list_prompts"));
         assert!(result_str.contains("ListPromptsResult"));
+        assert!(result_str.contains("fn handles_prompts"));
 
         Ok(())
     }
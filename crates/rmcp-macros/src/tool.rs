@@ -77,6 +77,18 @@ pub struct ToolAttribute {
     pub annotations: Option<ToolAnnotationsAttribute>,
     /// Optional icons for the tool
     pub icons: Option<Expr>,
+    /// Experimental: the function returns a stream of `Content` which is sent as partial results
+    pub streaming: bool,
+    /// Cache successful results by arguments for this many milliseconds, applied by `#[tool_router]`
+    pub cache_ttl_ms: Option<u64>,
+    /// The max number of cached results, defaults to `ToolResultCache::DEFAULT_CAPACITY`
+    pub cache_capacity: Option<usize>,
+    /// Concurrent calls with the same arguments share one execution, applied by `#[tool_router]`
+    pub coalesce: bool,
+    /// Reject the calls with larger arguments, serialized as JSON, applied by `#[tool_router]`
+    pub max_arg_bytes: Option<usize>,
+    /// Mark the tool deprecated with this message, sent in its `_meta`
+    pub deprecated: Option<String>,
 }
 
 pub struct ResolvedToolAttribute {
@@ -87,6 +99,7 @@ pub struct ResolvedToolAttribute {
     pub output_schema: Option<Expr>,
     pub annotations: Expr,
     pub icons: Option<Expr>,
+    pub deprecated: Option<String>,
 }
 
 impl ResolvedToolAttribute {
@@ -99,6 +112,7 @@ impl ResolvedToolAttribute {
             output_schema,
             annotations,
             icons,
+            deprecated,
         } = self;
         let description = if let Some(description) = description {
             quote! { Some(#description.into()) }
@@ -120,6 +134,17 @@ impl ResolvedToolAttribute {
         } else {
             quote! { None }
         };
+        let meta = if let Some(deprecated) = deprecated {
+            quote! {
+                Some({
+                    let mut meta = rmcp::model::Meta::new();
+                    meta.set_deprecated(#deprecated);
+                    meta
+                })
+            }
+        } else {
+            quote! { None }
+        };
         let doc_comment = format!("Generated tool metadata function for {name}");
         let doc_attr: syn::Attribute = parse_quote!(#[doc = #doc_comment]);
         let tokens = quote! {
@@ -133,6 +158,7 @@ impl ResolvedToolAttribute {
                     output_schema: #output_schema,
                     annotations: #annotations,
                     icons: #icons,
+                    meta: #meta,
                 }
             }
         };
@@ -177,6 +203,21 @@ pub struct ToolAnnotationsAttribute {
     pub open_world_hint: Option<bool>,
 }
 
+/// The lifetime of the boxed future returned by the modified function
+fn receiver_lifetime(fn_item: &ImplItemFn) -> TokenStream {
+    let mut lt = quote! { 'static };
+    if let Some(receiver) = fn_item.sig.receiver() {
+        if let Some((_, receiver_lt)) = receiver.reference.as_ref() {
+            if let Some(receiver_lt) = receiver_lt {
+                lt = quote! { #receiver_lt };
+            } else {
+                lt = quote! { '_ };
+            }
+        }
+    }
+    lt
+}
+
 pub fn tool(attr: TokenStream, input: TokenStream) -> syn::Result<TokenStream> {
     let attribute = if attr.is_empty() {
         Default::default()
@@ -264,24 +305,41 @@ pub fn tool(attr: TokenStream, input: TokenStream) -> syn::Result<TokenStream> {
         annotations: annotations_expr,
         title: attribute.title,
         icons: attribute.icons,
+        deprecated: attribute.deprecated,
     };
     let tool_attr_fn = resolved_tool_attr.into_fn(tool_attr_fn_ident)?;
     // modify the the input function
-    if fn_item.sig.asyncness.is_some() {
+    if attribute.streaming {
+        // 1. add a `StreamingToolContext` argument, extracted before any other argument
+        // 2. make return type: `std::pin::Pin<Box<dyn std::future::Future<Output = Result<CallToolResult, ErrorData>> + Send + '_>>`
+        // 3. make body: { Box::pin(async move { context.collect(#body).await }) }
+        let context_ident = format_ident!("__rmcp_streaming_tool_context");
+        let context_arg: syn::FnArg = parse_quote! {
+            #context_ident: rmcp::handler::server::tool::StreamingToolContext
+        };
+        let index = usize::from(fn_item.sig.receiver().is_some());
+        fn_item.sig.inputs.insert(index, context_arg);
+        let lt = receiver_lifetime(&fn_item);
+        fn_item.sig.output = syn::parse2::<ReturnType>(quote! {
+            -> ::std::pin::Pin<Box<dyn ::std::future::Future<Output = Result<rmcp::model::CallToolResult, rmcp::ErrorData>> + Send + #lt>>
+        })?;
+        let prev_block = &fn_item.block;
+        let stream = if fn_item.sig.asyncness.is_some() {
+            quote! { async move #prev_block.await }
+        } else {
+            quote! { #prev_block }
+        };
+        let new_block = syn::parse2::<syn::Block>(quote! {
+           { Box::pin(async move { #context_ident.collect(#stream).await }) }
+        })?;
+        fn_item.sig.asyncness = None;
+        fn_item.block = new_block;
+    } else if fn_item.sig.asyncness.is_some() {
         // 1. remove asyncness from sig
         // 2. make return type: `std::pin::Pin<Box<dyn std::future::Future<Output = #ReturnType> + Send + '_>>`
         // 3. make body: { Box::pin(async move { #body }) }
+        let lt = receiver_lifetime(&fn_item);
         let new_output = syn::parse2::<ReturnType>({
-            let mut lt = quote! { 'static };
-            if let Some(receiver) = fn_item.sig.receiver() {
-                if let Some((_, receiver_lt)) = receiver.reference.as_ref() {
-                    if let Some(receiver_lt) = receiver_lt {
-                        lt = quote! { #receiver_lt };
-                    } else {
-                        lt = quote! { '_ };
-                    }
-                }
-            }
             match &fn_item.sig.output {
                 syn::ReturnType::Default => {
                     quote! { -> ::std::pin::Pin<Box<dyn ::std::future::Future<Output = ()> + Send + #lt>> }
@@ -38,14 +38,28 @@ pub fn tool_handler(attr: TokenStream, input: TokenStream) -> syn::Result<TokenS
         async fn list_tools(
             &self,
             _request: Option<rmcp::model::PaginatedRequestParam>,
-            _context: rmcp::service::RequestContext<rmcp::RoleServer>,
+            context: rmcp::service::RequestContext<rmcp::RoleServer>,
         ) -> Result<rmcp::model::ListToolsResult, rmcp::ErrorData> {
-            Ok(rmcp::model::ListToolsResult::with_all_items(#router.list_all()))
+            Ok(#router.list_tools(&context))
+        }
+    };
+    let handles_tools_fn = quote! {
+        fn handles_tools(&self) -> bool {
+            true
         }
     };
     let tool_call_fn = syn::parse2::<ImplItem>(tool_call_fn)?;
     let tool_list_fn = syn::parse2::<ImplItem>(tool_list_fn)?;
+    let handles_tools_fn = syn::parse2::<ImplItem>(handles_tools_fn)?;
     item_impl.items.push(tool_call_fn);
     item_impl.items.push(tool_list_fn);
+    // keep the user's own answer
+    let has_handles_tools = item_impl
+        .items
+        .iter()
+        .any(|item| matches!(item, ImplItem::Fn(fn_item) if fn_item.sig.ident == "handles_tools"));
+    if !has_handles_tools {
+        item_impl.items.push(handles_tools_fn);
+    }
     Ok(item_impl.into_token_stream())
 }
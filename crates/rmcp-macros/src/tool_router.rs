@@ -11,6 +11,8 @@ use proc_macro2::TokenStream;
 use quote::{ToTokens, format_ident, quote};
 use syn::{Ident, ImplItem, ItemImpl, Visibility};
 
+use crate::tool::ToolAttribute;
+
 #[derive(FromMeta)]
 #[darling(default)]
 pub struct ToolRouterAttribute {
@@ -40,24 +42,61 @@ pub fn tool_router(attr: TokenStream, input: TokenStream) -> syn::Result<TokenSt
                 fn_item
                     .attrs
                     .iter()
-                    .any(|attr| {
+                    .find(|attr| {
                         attr.path()
                             .segments
                             .last()
                             .is_some_and(|seg| seg.ident == "tool")
                     })
-                    .then_some(&fn_item.sig.ident)
+                    .map(|attr| (&fn_item.sig.ident, attr))
             } else {
                 None
             }
         })
         .collect();
     let mut routers = vec![];
-    for handler in tool_attr_fns {
+    for (handler, attr) in tool_attr_fns {
         let tool_attr_fn_ident = format_ident!("{handler}_tool_attr");
-        routers.push(quote! {
-            .with_route((Self::#tool_attr_fn_ident(), Self::#handler))
-        })
+        let (cache_ttl_ms, cache_capacity, coalesce, max_arg_bytes) = match &attr.meta {
+            syn::Meta::List(list) => {
+                let tool_attr =
+                    ToolAttribute::from_list(&NestedMeta::parse_meta_list(list.tokens.clone())?)?;
+                (
+                    tool_attr.cache_ttl_ms,
+                    tool_attr.cache_capacity,
+                    tool_attr.coalesce,
+                    tool_attr.max_arg_bytes,
+                )
+            }
+            _ => (None, None, false, None),
+        };
+        let mut route_options = vec![];
+        if let Some(cache_ttl_ms) = cache_ttl_ms {
+            let cache_capacity = cache_capacity.map(|capacity| quote! { #capacity }).unwrap_or(
+                quote! { rmcp::handler::server::router::tool::ToolResultCache::DEFAULT_CAPACITY },
+            );
+            route_options.push(quote! {
+                .with_cache(::std::time::Duration::from_millis(#cache_ttl_ms), #cache_capacity)
+            });
+        }
+        if coalesce {
+            route_options.push(quote! { .with_coalescing() });
+        }
+        if let Some(max_arg_bytes) = max_arg_bytes {
+            route_options.push(quote! { .with_max_arg_bytes(#max_arg_bytes) });
+        }
+        if route_options.is_empty() {
+            routers.push(quote! {
+                .with_route((Self::#tool_attr_fn_ident(), Self::#handler))
+            })
+        } else {
+            routers.push(quote! {
+                .with_route(
+                    rmcp::handler::server::router::tool::ToolRoute::new(Self::#tool_attr_fn_ident(), Self::#handler)
+                        #(#route_options)*
+                )
+            })
+        }
     }
     let router_fn = syn::parse2::<ImplItem>(quote! {
         #vis fn #router() -> rmcp::handler::server::router::tool::ToolRouter<Self> {
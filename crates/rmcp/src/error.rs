@@ -1,6 +1,7 @@
 use std::{borrow::Cow, fmt::Display};
 
 use crate::ServiceError;
+use crate::model::ErrorCode;
 pub use crate::model::ErrorData;
 #[deprecated(
     note = "Use `rmcp::ErrorData` instead, `rmcp::ErrorData` could become `RmcpError` in the future."
@@ -18,6 +19,78 @@ impl Display for ErrorData {
 
 impl std::error::Error for ErrorData {}
 
+impl ErrorData {
+    const SOURCE_CHAIN_FIELD: &str = "sourceChain";
+    const RETRYABLE_FIELD: &str = "retryable";
+
+    /// Whether the same request may succeed if it's sent again later.
+    ///
+    /// The sender decides with `{"retryable": true}` or `false` in `data`, see
+    /// [`ErrorData::with_retryable`]. Without it, only [`ErrorCode::UNAVAILABLE`] and
+    /// [`ErrorCode::REQUEST_TIMEOUT`] are retryable. The other codes aren't, since they
+    /// describe something wrong with the request itself, or an error the sender can't tell is
+    /// transient.
+    ///
+    /// Clients retry these errors with [`Peer::set_retry_policy`](crate::Peer::set_retry_policy).
+    pub fn is_retryable(&self) -> bool {
+        self.data
+            .as_ref()
+            .and_then(|data| data.get(Self::RETRYABLE_FIELD))
+            .and_then(serde_json::Value::as_bool)
+            .unwrap_or(matches!(
+                self.code,
+                ErrorCode::UNAVAILABLE | ErrorCode::REQUEST_TIMEOUT
+            ))
+    }
+
+    /// Mark the error as retryable or not with a `retryable` flag in `data`, whatever its code.
+    ///
+    /// The flag is added to the other fields of `data` if it's an object, and it does nothing if
+    /// `data` is set to something else.
+    pub fn with_retryable(mut self, retryable: bool) -> Self {
+        match self
+            .data
+            .get_or_insert_with(|| serde_json::Value::Object(Default::default()))
+        {
+            serde_json::Value::Object(data) => {
+                data.insert(Self::RETRYABLE_FIELD.to_string(), retryable.into());
+            }
+            _ => tracing::warn!("can't flag an error with non-object data as retryable"),
+        }
+        self
+    }
+
+    /// Create an error with the message of `error`, and its sources in debug builds only, see
+    /// [`ErrorData::from_error_with_chain`].
+    ///
+    /// Release builds keep the sources out of the error sent to the client, as they may leak
+    /// internal details.
+    pub fn from_error(code: ErrorCode, error: &(dyn std::error::Error + 'static)) -> Self {
+        if cfg!(debug_assertions) {
+            Self::from_error_with_chain(code, error)
+        } else {
+            Self::new(code, error.to_string(), None)
+        }
+    }
+
+    /// Create an error with the message of `error`, and the messages of its
+    /// [sources](std::error::Error::source) recorded as `{"sourceChain": [...]}` in `data`.
+    ///
+    /// The sources are recorded whatever the build, prefer [`ErrorData::from_error`] not to
+    /// leak internal details to the client in production.
+    pub fn from_error_with_chain(
+        code: ErrorCode,
+        error: &(dyn std::error::Error + 'static),
+    ) -> Self {
+        let chain = std::iter::successors(error.source(), |source| source.source())
+            .map(|source| serde_json::Value::String(source.to_string()))
+            .collect::<Vec<_>>();
+        let data =
+            (!chain.is_empty()).then(|| serde_json::json!({ Self::SOURCE_CHAIN_FIELD: chain }));
+        Self::new(code, error.to_string(), data)
+    }
+}
+
 /// This is an unified error type for the errors could be returned by the service.
 #[derive(Debug, thiserror::Error)]
 pub enum RmcpError {
@@ -54,8 +54,22 @@ impl<H: ClientHandler> Service<RoleClient> for H {
                 self.on_tool_list_changed(context).await
             }
             ServerNotification::PromptListChangedNotification(_notification_no_param) => {
+                context.peer.forget_cached_prompts();
                 self.on_prompt_list_changed(context).await
             }
+            ServerNotification::ToolPartialResultNotification(notification) => {
+                self.on_tool_partial_result(notification.params, context)
+                    .await
+            }
+            ServerNotification::ResourceChunkNotification(notification) => {
+                self.on_resource_chunk(notification.params, context).await
+            }
+            ServerNotification::ConfigChangedNotification(notification) => {
+                self.on_config_changed(notification.params, context).await
+            }
+            ServerNotification::SessionClosedNotification(notification) => {
+                self.on_session_closed(notification.params, context).await
+            }
         };
         Ok(())
     }
@@ -166,7 +180,42 @@ pub trait ClientHandler: Sized + Send + Sync + 'static {
     ) -> impl Future<Output = ()> + Send + '_ {
         std::future::ready(())
     }
+    /// Experimental: receive partial content of a streaming tool call
+    fn on_tool_partial_result(
+        &self,
+        params: ToolPartialResultNotificationParam,
+        context: NotificationContext<RoleClient>,
+    ) -> impl Future<Output = ()> + Send + '_ {
+        std::future::ready(())
+    }
+    /// Experimental: receive a chunk of a blob resource, also written by
+    /// [`Peer::read_resource_blob_to`](crate::Peer::read_resource_blob_to) for the read it sent
+    fn on_resource_chunk(
+        &self,
+        params: ResourceChunkNotificationParam,
+        context: NotificationContext<RoleClient>,
+    ) -> impl Future<Output = ()> + Send + '_ {
+        std::future::ready(())
+    }
+    /// Experimental: a setting of the server changed, see [`ConfigChangedNotificationParam`]
+    fn on_config_changed(
+        &self,
+        params: ConfigChangedNotificationParam,
+        context: NotificationContext<RoleClient>,
+    ) -> impl Future<Output = ()> + Send + '_ {
+        std::future::ready(())
+    }
+    /// The server is closing the session, see [`Peer::close`](crate::Peer::close)
+    fn on_session_closed(
+        &self,
+        params: SessionClosedNotificationParam,
+        context: NotificationContext<RoleClient>,
+    ) -> impl Future<Output = ()> + Send + '_ {
+        std::future::ready(())
+    }
 
+    /// The info sent in the initialize request, advertise the requests the client answers with
+    /// [`ClientInfo::with_capabilities`]
     fn get_info(&self) -> ClientInfo {
         ClientInfo::default()
     }
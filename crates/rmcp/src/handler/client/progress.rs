@@ -9,6 +9,9 @@ type Dispatcher =
     Arc<RwLock<HashMap<ProgressToken, tokio::sync::mpsc::Sender<ProgressNotificationParam>>>>;
 
 /// A dispatcher for progress notifications.
+///
+/// To follow the progress of a single request, [`Peer::track_progress`](crate::Peer::track_progress)
+/// needs no dispatcher in the handler.
 #[derive(Debug, Clone, Default)]
 pub struct ProgressDispatcher {
     pub(crate) dispatcher: Dispatcher,
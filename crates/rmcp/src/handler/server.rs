@@ -5,8 +5,17 @@ use crate::{
 };
 
 pub mod common;
+pub mod consistency;
+pub mod fn_handler;
+pub mod pagination;
 pub mod prompt;
+#[cfg(feature = "client")]
+#[cfg_attr(docsrs, doc(cfg(feature = "client")))]
+pub mod proxy;
 mod resource;
+#[cfg(feature = "session-resumption")]
+#[cfg_attr(docsrs, doc(cfg(feature = "session-resumption")))]
+pub mod resumption;
 pub mod router;
 pub mod tool;
 pub mod wrapper;
@@ -17,10 +26,13 @@ impl<H: ServerHandler> Service<RoleServer> for H {
         context: RequestContext<RoleServer>,
     ) -> Result<<RoleServer as ServiceRole>::Resp, McpError> {
         match request {
-            ClientRequest::InitializeRequest(request) => self
-                .initialize(request.params, context)
-                .await
-                .map(ServerResult::InitializeResult),
+            ClientRequest::InitializeRequest(request) => {
+                let result = self.initialize(request.params, context).await?;
+                for mismatch in consistency::check_capabilities(self, &result.capabilities) {
+                    tracing::warn!(%mismatch, "advertised capabilities don't match the handler");
+                }
+                Ok(ServerResult::InitializeResult(result))
+            }
             ClientRequest::PingRequest(_request) => {
                 self.ping(context).await.map(ServerResult::empty)
             }
@@ -28,10 +40,12 @@ impl<H: ServerHandler> Service<RoleServer> for H {
                 .complete(request.params, context)
                 .await
                 .map(ServerResult::CompleteResult),
-            ClientRequest::SetLevelRequest(request) => self
-                .set_level(request.params, context)
-                .await
-                .map(ServerResult::empty),
+            ClientRequest::SetLevelRequest(request) => {
+                context.peer.set_min_log_level(request.params.level);
+                self.set_level(request.params, context)
+                    .await
+                    .map(ServerResult::empty)
+            }
             ClientRequest::GetPromptRequest(request) => self
                 .get_prompt(request.params, context)
                 .await
@@ -68,6 +82,10 @@ impl<H: ServerHandler> Service<RoleServer> for H {
                 .list_tools(request.params, context)
                 .await
                 .map(ServerResult::ListToolsResult),
+            ClientRequest::CustomRequest(request) => self
+                .on_custom_request(request, context)
+                .await
+                .map(ServerResult::CustomResult),
         }
     }
 
@@ -89,6 +107,9 @@ impl<H: ServerHandler> Service<RoleServer> for H {
             ClientNotification::RootsListChangedNotification(_notification) => {
                 self.on_roots_list_changed(context).await
             }
+            ClientNotification::SessionClosedNotification(notification) => {
+                self.on_session_closed(notification.params, context).await
+            }
         };
         Ok(())
     }
@@ -98,6 +119,24 @@ impl<H: ServerHandler> Service<RoleServer> for H {
     }
 }
 
+/// Include a markdown file as the [`instructions`](crate::model::InitializeResult::instructions)
+/// of a server, at compile time. The path is relative to the current file, like [`include_str!`].
+///
+/// ```rust,ignore
+/// fn get_info(&self) -> ServerInfo {
+///     ServerInfo {
+///         instructions: Some(rmcp::include_instructions!("instructions.md")),
+///         ..Default::default()
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! include_instructions {
+    ($path:literal) => {
+        ::std::string::String::from(::std::include_str!($path).trim())
+    };
+}
+
 #[allow(unused_variables)]
 pub trait ServerHandler: Sized + Send + Sync + 'static {
     fn ping(
@@ -107,6 +146,15 @@ pub trait ServerHandler: Sized + Send + Sync + 'static {
         std::future::ready(Ok(()))
     }
     // handle requests
+    /// Answer the `initialize` request of the client.
+    ///
+    /// The `_meta` of the request is in `context.meta`, and stays available to the later requests
+    /// with [`Peer::initialize_meta`](crate::Peer::initialize_meta), e.g. to preload the data of
+    /// a tenant before its first tool call.
+    ///
+    /// The `_meta` only carries hints outside of the protocol: when they overlap the capabilities
+    /// of the client, the capabilities win. A server never uses a feature the client didn't
+    /// declare, like sampling, because of a flag in `_meta`.
     fn initialize(
         &self,
         request: InitializeRequestParam,
@@ -196,6 +244,24 @@ pub trait ServerHandler: Sized + Send + Sync + 'static {
     ) -> impl Future<Output = Result<ListToolsResult, McpError>> + Send + '_ {
         std::future::ready(Ok(ListToolsResult::default()))
     }
+    /// Experimental: handle a request of a method outside the MCP specification, like
+    /// `x-movie/refresh-cache`, it's method-not-found by default.
+    ///
+    /// Custom requests are isolated from the core methods: a request of a
+    /// [core method](crate::model::CustomRequestMethod::CORE_METHODS) never gets here, even a
+    /// malformed one, and a custom method can't shadow a core one. Prefix the vendor methods,
+    /// e.g. with `x-<vendor>/`, so they won't clash with the methods of future MCP versions.
+    fn on_custom_request(
+        &self,
+        request: CustomRequest,
+        context: RequestContext<RoleServer>,
+    ) -> impl Future<Output = Result<CustomResult, McpError>> + Send + '_ {
+        std::future::ready(Err(McpError::new(
+            ErrorCode::METHOD_NOT_FOUND,
+            request.method.as_str().to_string(),
+            None,
+        )))
+    }
 
     fn on_cancelled(
         &self,
@@ -224,8 +290,29 @@ pub trait ServerHandler: Sized + Send + Sync + 'static {
     ) -> impl Future<Output = ()> + Send + '_ {
         std::future::ready(())
     }
+    /// The client is closing the session, see [`Peer::close`](crate::Peer::close)
+    fn on_session_closed(
+        &self,
+        params: SessionClosedNotificationParam,
+        context: NotificationContext<RoleServer>,
+    ) -> impl Future<Output = ()> + Send + '_ {
+        std::future::ready(())
+    }
 
     fn get_info(&self) -> ServerInfo {
         ServerInfo::default()
     }
+
+    /// Whether the tool methods are implemented, `#[tool_handler]` returns `true`.
+    ///
+    /// It's checked against the advertised capabilities, see [`consistency::check_capabilities`].
+    /// By default, a handler handles what it advertises, so only a router or an override can
+    /// disagree with [`ServerHandler::get_info`].
+    fn handles_tools(&self) -> bool {
+        self.get_info().capabilities.tools.is_some()
+    }
+    /// Whether the prompt methods are implemented, `#[prompt_handler]` returns `true`.
+    fn handles_prompts(&self) -> bool {
+        self.get_info().capabilities.prompts.is_some()
+    }
 }
@@ -5,7 +5,10 @@ use std::{any::TypeId, collections::HashMap, sync::Arc};
 use schemars::JsonSchema;
 
 use crate::{
-    RoleServer, model::JsonObject, schemars::generate::SchemaSettings, service::RequestContext,
+    RoleServer,
+    model::{CallToolResult, JsonObject, Representations},
+    schemars::generate::SchemaSettings,
+    service::RequestContext,
 };
 
 /// A shortcut for generating a JSON schema for a type.
@@ -27,6 +30,117 @@ pub fn schema_for_type<T: JsonSchema>() -> JsonObject {
     }
 }
 
+/// Inline the local `$ref`s of `schema`, like `#/definitions/Location`, so it's self-contained,
+/// for the clients which can't resolve them.
+///
+/// The keywords next to a `$ref`, like its `description`, are kept over the ones of the
+/// definition. The definitions are dropped once inlined, unless a type refers to itself: its
+/// `$ref` can't be inlined, so it's kept along with the definitions.
+pub fn inline_schema_refs(schema: &JsonObject) -> JsonObject {
+    let mut stack = Vec::new();
+    let mut recursive = false;
+    let mut inlined: JsonObject = schema
+        .iter()
+        .filter(|(key, _)| !matches!(key.as_str(), "definitions" | "$defs"))
+        .map(|(key, value)| {
+            let value = inline_value(schema, value, &mut stack, &mut recursive);
+            (key.clone(), value)
+        })
+        .collect();
+    if recursive {
+        for key in ["definitions", "$defs"] {
+            if let Some(definitions) = schema.get(key) {
+                inlined.insert(key.to_owned(), definitions.clone());
+            }
+        }
+    }
+    inlined
+}
+
+fn inline_value(
+    root: &JsonObject,
+    value: &serde_json::Value,
+    stack: &mut Vec<String>,
+    recursive: &mut bool,
+) -> serde_json::Value {
+    match value {
+        serde_json::Value::Object(object) => {
+            serde_json::Value::Object(inline_object(root, object, stack, recursive))
+        }
+        serde_json::Value::Array(items) => serde_json::Value::Array(
+            items
+                .iter()
+                .map(|item| inline_value(root, item, stack, recursive))
+                .collect(),
+        ),
+        value => value.clone(),
+    }
+}
+
+fn inline_object(
+    root: &JsonObject,
+    object: &JsonObject,
+    stack: &mut Vec<String>,
+    recursive: &mut bool,
+) -> JsonObject {
+    let reference = object.get("$ref").and_then(serde_json::Value::as_str);
+    let Some((reference, definition)) = reference
+        .and_then(|reference| Some((reference, resolve_ref(root, reference)?.as_object()?)))
+    else {
+        return object
+            .iter()
+            .map(|(key, value)| (key.clone(), inline_value(root, value, stack, recursive)))
+            .collect();
+    };
+    if stack.iter().any(|expanding| expanding == reference) {
+        *recursive = true;
+        return object.clone();
+    }
+    stack.push(reference.to_owned());
+    let mut inlined = inline_object(root, definition, stack, recursive);
+    stack.pop();
+    for (key, value) in object.iter().filter(|(key, _)| *key != "$ref") {
+        inlined.insert(key.clone(), inline_value(root, value, stack, recursive));
+    }
+    inlined
+}
+
+/// The definition a local `$ref` like `#/definitions/Location` points to in the `root` schema
+pub(crate) fn resolve_ref<'a>(
+    root: &'a JsonObject,
+    reference: &str,
+) -> Option<&'a serde_json::Value> {
+    let pointer = reference.strip_prefix("#/")?;
+    let (first, rest) = pointer.split_once('/').unwrap_or((pointer, ""));
+    let rest = if rest.is_empty() {
+        String::new()
+    } else {
+        format!("/{rest}")
+    };
+    root.get(first)?.pointer(&rest)
+}
+
+/// Sort the object keys recursively, so equal values serialize the same way whatever their key
+/// order, and whether `serde_json` keeps the insertion order or not
+pub(crate) fn canonical_json(value: serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::Object(object) => {
+            let mut entries: Vec<_> = object.into_iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            serde_json::Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key, canonical_json(value)))
+                    .collect(),
+            )
+        }
+        serde_json::Value::Array(array) => {
+            serde_json::Value::Array(array.into_iter().map(canonical_json).collect())
+        }
+        value => value,
+    }
+}
+
 /// Call [`schema_for_type`] with a cache
 pub fn cached_schema_for_type<T: JsonSchema + std::any::Any>() -> Arc<JsonObject> {
     thread_local! {
@@ -139,6 +253,81 @@ where
     }
 }
 
+/// Trait for extractors only needing the [`RequestContext`], like the identity of the caller.
+///
+/// Unlike [`FromContextPart`], it doesn't depend on the kind of handler: any type implementing it
+/// can be taken as an argument by tool and prompt handlers alike, next to the other extractors.
+///
+/// ```rust
+/// # use rmcp::{RoleServer, ErrorData, handler::server::common::FromRequestContext, service::RequestContext};
+/// struct AuthUser(String);
+///
+/// impl FromRequestContext for AuthUser {
+///     fn from_request_context(context: &RequestContext<RoleServer>) -> Result<Self, ErrorData> {
+///         context
+///             .meta
+///             .get("user")
+///             .and_then(|user| user.as_str())
+///             .map(|user| AuthUser(user.to_owned()))
+///             .ok_or_else(|| ErrorData::invalid_request("unauthenticated", None))
+///     }
+/// }
+/// ```
+pub trait FromRequestContext: Sized {
+    fn from_request_context(context: &RequestContext<RoleServer>)
+    -> Result<Self, crate::ErrorData>;
+}
+
+impl<C, T> FromContextPart<C> for T
+where
+    C: AsRequestContext,
+    T: FromRequestContext,
+{
+    fn from_context_part(context: &mut C) -> Result<Self, crate::ErrorData> {
+        T::from_request_context(context.as_request_context())
+    }
+}
+
+/// The locale to reply in, see [`RequestContext::locale`]
+pub struct Locale(pub String);
+
+impl FromRequestContext for Locale {
+    fn from_request_context(
+        context: &RequestContext<RoleServer>,
+    ) -> Result<Self, crate::ErrorData> {
+        Ok(Locale(context.locale().to_owned()))
+    }
+}
+
+/// Whether the requester asked for a dry run, see [`RequestContext::is_dry_run`]
+pub struct DryRun(pub bool);
+
+impl FromRequestContext for DryRun {
+    fn from_request_context(
+        context: &RequestContext<RoleServer>,
+    ) -> Result<Self, crate::ErrorData> {
+        Ok(DryRun(context.is_dry_run()))
+    }
+}
+
+/// The MIME types the client prefers for the result, see [`RequestContext::accept`]
+pub struct Accept(pub Vec<String>);
+
+impl Accept {
+    /// Pick the representation the client prefers, see [`Representations::negotiate`]
+    pub fn negotiate(&self, representations: Representations) -> CallToolResult {
+        representations.negotiate(&self.0)
+    }
+}
+
+impl FromRequestContext for Accept {
+    fn from_request_context(
+        context: &RequestContext<RoleServer>,
+    ) -> Result<Self, crate::ErrorData> {
+        Ok(Accept(context.accept()))
+    }
+}
+
 /// Trait for types that can provide access to RequestContext
 pub trait AsRequestContext {
     fn as_request_context(&self) -> &RequestContext<RoleServer>;
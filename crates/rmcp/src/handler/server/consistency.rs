@@ -0,0 +1,61 @@
+//! Check the capabilities a server advertises against the handlers it wires.
+//!
+//! A client trusts the capabilities from `initialize`: advertising `prompts` without handling
+//! them gets it errors, and handling tools without advertising `tools` hides them. The check runs
+//! on every `initialize` and logs a warning per mismatch.
+use std::fmt;
+
+use super::ServerHandler;
+use crate::model::ServerCapabilities;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CapabilityMismatch {
+    /// `tools` is advertised, but [`ServerHandler::handles_tools`] says they aren't handled
+    ToolsNotHandled,
+    /// The handler has a tool router, but `tools` isn't advertised
+    ToolsNotAdvertised,
+    /// `prompts` is advertised, but [`ServerHandler::handles_prompts`] says they aren't handled
+    PromptsNotHandled,
+    /// The handler has a prompt router, but `prompts` isn't advertised
+    PromptsNotAdvertised,
+}
+
+impl fmt::Display for CapabilityMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            CapabilityMismatch::ToolsNotHandled => {
+                "the tools capability is advertised but the tools aren't handled"
+            }
+            CapabilityMismatch::ToolsNotAdvertised => {
+                "a tool router is registered but the tools capability isn't advertised"
+            }
+            CapabilityMismatch::PromptsNotHandled => {
+                "the prompts capability is advertised but the prompts aren't handled"
+            }
+            CapabilityMismatch::PromptsNotAdvertised => {
+                "a prompt router is registered but the prompts capability isn't advertised"
+            }
+        };
+        f.write_str(message)
+    }
+}
+
+/// Compare the advertised `capabilities` with what the `handler` reports it handles, see
+/// [`ServerHandler::handles_tools`] and [`ServerHandler::handles_prompts`].
+pub fn check_capabilities<H: ServerHandler>(
+    handler: &H,
+    capabilities: &ServerCapabilities,
+) -> Vec<CapabilityMismatch> {
+    let mut mismatches = Vec::new();
+    match (capabilities.tools.is_some(), handler.handles_tools()) {
+        (true, false) => mismatches.push(CapabilityMismatch::ToolsNotHandled),
+        (false, true) => mismatches.push(CapabilityMismatch::ToolsNotAdvertised),
+        _ => {}
+    }
+    match (capabilities.prompts.is_some(), handler.handles_prompts()) {
+        (true, false) => mismatches.push(CapabilityMismatch::PromptsNotHandled),
+        (false, true) => mismatches.push(CapabilityMismatch::PromptsNotAdvertised),
+        _ => {}
+    }
+    mismatches
+}
@@ -0,0 +1,298 @@
+//! Build a [`ServerHandler`] out of closures, for prototypes and one-tool servers.
+//!
+//! ```rust
+//! # use rmcp::{handler::server::fn_handler::ServerHandlerBuilder, model::*};
+//! let handler = ServerHandlerBuilder::new()
+//!     .call_tool(|request: CallToolRequestParam, _context| async move {
+//!         Ok(CallToolResult::success(vec![Content::text(format!(
+//!             "called {}",
+//!             request.name
+//!         ))]))
+//!     })
+//!     .build();
+//! ```
+use std::sync::Arc;
+
+use futures::{FutureExt, future::BoxFuture};
+
+use super::ServerHandler;
+use crate::{
+    error::ErrorData as McpError,
+    model::*,
+    service::{RequestContext, RoleServer},
+};
+
+type RequestFn<P, R> = Arc<
+    dyn Fn(P, RequestContext<RoleServer>) -> BoxFuture<'static, Result<R, McpError>> + Send + Sync,
+>;
+
+fn request_fn<P, R, F, Fut>(f: F) -> RequestFn<P, R>
+where
+    F: Fn(P, RequestContext<RoleServer>) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<R, McpError>> + Send + 'static,
+{
+    Arc::new(move |params, context| f(params, context).boxed())
+}
+
+/// A [`ServerHandler`] calling the closures given to its [`ServerHandlerBuilder`].
+///
+/// The methods without a closure behave like the default ones of [`ServerHandler`].
+#[derive(Clone, Default)]
+pub struct FnServerHandler {
+    info: ServerInfo,
+    initialize: Option<RequestFn<InitializeRequestParam, InitializeResult>>,
+    complete: Option<RequestFn<CompleteRequestParam, CompleteResult>>,
+    get_prompt: Option<RequestFn<GetPromptRequestParam, GetPromptResult>>,
+    list_prompts: Option<RequestFn<Option<PaginatedRequestParam>, ListPromptsResult>>,
+    list_resources: Option<RequestFn<Option<PaginatedRequestParam>, ListResourcesResult>>,
+    read_resource: Option<RequestFn<ReadResourceRequestParam, ReadResourceResult>>,
+    call_tool: Option<RequestFn<CallToolRequestParam, CallToolResult>>,
+    list_tools: Option<RequestFn<Option<PaginatedRequestParam>, ListToolsResult>>,
+}
+
+impl std::fmt::Debug for FnServerHandler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FnServerHandler")
+            .field("info", &self.info)
+            .field("initialize", &self.initialize.is_some())
+            .field("complete", &self.complete.is_some())
+            .field("get_prompt", &self.get_prompt.is_some())
+            .field("list_prompts", &self.list_prompts.is_some())
+            .field("list_resources", &self.list_resources.is_some())
+            .field("read_resource", &self.read_resource.is_some())
+            .field("call_tool", &self.call_tool.is_some())
+            .field("list_tools", &self.list_tools.is_some())
+            .finish()
+    }
+}
+
+/// Builder for [`FnServerHandler`], taking one closure per request method.
+///
+/// Each closure receives the request params and the [`RequestContext`], and returns a future of
+/// the result.
+#[derive(Debug, Default)]
+pub struct ServerHandlerBuilder {
+    handler: FnServerHandler,
+}
+
+impl ServerHandlerBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The server info returned by `initialize`, the capabilities of the given closures are
+    /// added to it by [`build`](Self::build).
+    pub fn info(mut self, info: ServerInfo) -> Self {
+        self.handler.info = info;
+        self
+    }
+
+    /// Replace the default `initialize`, the client info is recorded on the peer beforehand.
+    pub fn initialize<F, Fut>(mut self, f: F) -> Self
+    where
+        F: Fn(InitializeRequestParam, RequestContext<RoleServer>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<InitializeResult, McpError>> + Send + 'static,
+    {
+        self.handler.initialize = Some(request_fn(f));
+        self
+    }
+
+    pub fn complete<F, Fut>(mut self, f: F) -> Self
+    where
+        F: Fn(CompleteRequestParam, RequestContext<RoleServer>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<CompleteResult, McpError>> + Send + 'static,
+    {
+        self.handler.complete = Some(request_fn(f));
+        self
+    }
+
+    pub fn get_prompt<F, Fut>(mut self, f: F) -> Self
+    where
+        F: Fn(GetPromptRequestParam, RequestContext<RoleServer>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<GetPromptResult, McpError>> + Send + 'static,
+    {
+        self.handler.get_prompt = Some(request_fn(f));
+        self
+    }
+
+    pub fn list_prompts<F, Fut>(mut self, f: F) -> Self
+    where
+        F: Fn(Option<PaginatedRequestParam>, RequestContext<RoleServer>) -> Fut
+            + Send
+            + Sync
+            + 'static,
+        Fut: Future<Output = Result<ListPromptsResult, McpError>> + Send + 'static,
+    {
+        self.handler.list_prompts = Some(request_fn(f));
+        self
+    }
+
+    pub fn list_resources<F, Fut>(mut self, f: F) -> Self
+    where
+        F: Fn(Option<PaginatedRequestParam>, RequestContext<RoleServer>) -> Fut
+            + Send
+            + Sync
+            + 'static,
+        Fut: Future<Output = Result<ListResourcesResult, McpError>> + Send + 'static,
+    {
+        self.handler.list_resources = Some(request_fn(f));
+        self
+    }
+
+    pub fn read_resource<F, Fut>(mut self, f: F) -> Self
+    where
+        F: Fn(ReadResourceRequestParam, RequestContext<RoleServer>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<ReadResourceResult, McpError>> + Send + 'static,
+    {
+        self.handler.read_resource = Some(request_fn(f));
+        self
+    }
+
+    pub fn call_tool<F, Fut>(mut self, f: F) -> Self
+    where
+        F: Fn(CallToolRequestParam, RequestContext<RoleServer>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<CallToolResult, McpError>> + Send + 'static,
+    {
+        self.handler.call_tool = Some(request_fn(f));
+        self
+    }
+
+    pub fn list_tools<F, Fut>(mut self, f: F) -> Self
+    where
+        F: Fn(Option<PaginatedRequestParam>, RequestContext<RoleServer>) -> Fut
+            + Send
+            + Sync
+            + 'static,
+        Fut: Future<Output = Result<ListToolsResult, McpError>> + Send + 'static,
+    {
+        self.handler.list_tools = Some(request_fn(f));
+        self
+    }
+
+    /// Finish the handler, advertising the `tools`, `prompts` and `resources` capabilities when
+    /// a closure of theirs was given and the server info doesn't declare them already.
+    pub fn build(mut self) -> FnServerHandler {
+        let handler = &mut self.handler;
+        let capabilities = &mut handler.info.capabilities;
+        if handler.call_tool.is_some() || handler.list_tools.is_some() {
+            capabilities.tools.get_or_insert_with(Default::default);
+        }
+        if handler.get_prompt.is_some() || handler.list_prompts.is_some() {
+            capabilities.prompts.get_or_insert_with(Default::default);
+        }
+        if handler.read_resource.is_some() || handler.list_resources.is_some() {
+            capabilities.resources.get_or_insert_with(Default::default);
+        }
+        if handler.complete.is_some() {
+            capabilities
+                .completions
+                .get_or_insert_with(Default::default);
+        }
+        self.handler
+    }
+}
+
+impl ServerHandler for FnServerHandler {
+    async fn initialize(
+        &self,
+        request: InitializeRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<InitializeResult, McpError> {
+        if context.peer.peer_info().is_none() {
+            context.peer.set_peer_info(request.clone());
+        }
+        match &self.initialize {
+            Some(f) => f(request, context).await,
+            None => Ok(self.get_info()),
+        }
+    }
+
+    async fn complete(
+        &self,
+        request: CompleteRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CompleteResult, McpError> {
+        match &self.complete {
+            Some(f) => f(request, context).await,
+            None => Ok(CompleteResult::default()),
+        }
+    }
+
+    async fn get_prompt(
+        &self,
+        request: GetPromptRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<GetPromptResult, McpError> {
+        match &self.get_prompt {
+            Some(f) => f(request, context).await,
+            None => Err(McpError::method_not_found::<GetPromptRequestMethod>()),
+        }
+    }
+
+    async fn list_prompts(
+        &self,
+        request: Option<PaginatedRequestParam>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<ListPromptsResult, McpError> {
+        match &self.list_prompts {
+            Some(f) => f(request, context).await,
+            None => Ok(ListPromptsResult::default()),
+        }
+    }
+
+    async fn list_resources(
+        &self,
+        request: Option<PaginatedRequestParam>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<ListResourcesResult, McpError> {
+        match &self.list_resources {
+            Some(f) => f(request, context).await,
+            None => Ok(ListResourcesResult::default()),
+        }
+    }
+
+    async fn read_resource(
+        &self,
+        request: ReadResourceRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<ReadResourceResult, McpError> {
+        match &self.read_resource {
+            Some(f) => f(request, context).await,
+            None => Err(McpError::method_not_found::<ReadResourceRequestMethod>()),
+        }
+    }
+
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        match &self.call_tool {
+            Some(f) => f(request, context).await,
+            None => Err(McpError::method_not_found::<CallToolRequestMethod>()),
+        }
+    }
+
+    async fn list_tools(
+        &self,
+        request: Option<PaginatedRequestParam>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<ListToolsResult, McpError> {
+        match &self.list_tools {
+            Some(f) => f(request, context).await,
+            None => Ok(ListToolsResult::default()),
+        }
+    }
+
+    fn get_info(&self) -> ServerInfo {
+        self.info.clone()
+    }
+
+    fn handles_tools(&self) -> bool {
+        self.call_tool.is_some() || self.list_tools.is_some()
+    }
+
+    fn handles_prompts(&self) -> bool {
+        self.get_prompt.is_some() || self.list_prompts.is_some()
+    }
+}
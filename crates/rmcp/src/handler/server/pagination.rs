@@ -0,0 +1,60 @@
+//! Build the pages of the `*/list` responses from all the items, see [`paginate`].
+use crate::{ErrorData, model::Cursor};
+
+/// A page of items, with the cursor of the next page if there are more items
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next_cursor: Option<Cursor>,
+}
+
+/// Take the page of at most `page_size` items starting at `cursor`, the first page if `None`.
+///
+/// The cursor is the `next_cursor` of the previous page, so the same `items` in the same order
+/// give the following page:
+///
+/// ```rust,ignore
+/// async fn list_resources(
+///     &self,
+///     request: Option<PaginatedRequestParam>,
+///     _context: RequestContext<RoleServer>,
+/// ) -> Result<ListResourcesResult, ErrorData> {
+///     let cursor = request.and_then(|request| request.cursor);
+///     let page = paginate(self.resources(), cursor.as_deref(), 50)?;
+///     Ok(ListResourcesResult {
+///         resources: page.items,
+///         next_cursor: page.next_cursor,
+///         meta: None,
+///     })
+/// }
+/// ```
+///
+/// A cursor which isn't one of ours fails with `invalid_params`. A `page_size` of 0 is taken as 1.
+pub fn paginate<I>(
+    items: I,
+    cursor: Option<&str>,
+    page_size: usize,
+) -> Result<Page<I::Item>, ErrorData>
+where
+    I: IntoIterator,
+{
+    let offset = match cursor {
+        Some(cursor) => cursor.parse::<usize>().map_err(|_| {
+            ErrorData::invalid_params(
+                format!("invalid cursor {cursor:?}"),
+                Some(serde_json::json!({ "cursor": cursor })),
+            )
+        })?,
+        None => 0,
+    };
+    let mut items = items.into_iter().skip(offset);
+    let page: Vec<_> = items.by_ref().take(page_size.max(1)).collect();
+    let next_cursor = items
+        .next()
+        .is_some()
+        .then(|| (offset + page.len()).to_string());
+    Ok(Page {
+        items: page,
+        next_cursor,
+    })
+}
@@ -10,7 +10,7 @@ use futures::future::{BoxFuture, FutureExt};
 use serde::de::DeserializeOwned;
 
 use super::common::{AsRequestContext, FromContextPart};
-pub use super::common::{Extension, RequestId};
+pub use super::common::{DryRun, Extension, FromRequestContext, Locale, RequestId};
 use crate::{
     RoleServer,
     handler::server::wrapper::Parameters,
@@ -0,0 +1,369 @@
+//! Serve the tools of upstream MCP servers along with your own, see [`ToolProxy`].
+use std::sync::{
+    Arc,
+    atomic::{AtomicU64, Ordering},
+};
+
+use futures::{FutureExt, StreamExt};
+
+use super::ServerHandler;
+use crate::{
+    ClientHandler,
+    error::ErrorData as McpError,
+    handler::client::progress::ProgressDispatcher,
+    model::*,
+    service::{
+        ClientManager, NotificationContext, PeerRequestOptions, RequestContext, RoleClient,
+        RoleServer, ServiceError,
+    },
+};
+
+/// The client handler of an upstream server of a [`ToolProxy`].
+///
+/// Connect the upstream servers with it, so their progress notifications are relayed:
+///
+/// ```rust,ignore
+/// manager
+///     .connect("movie", || UpstreamClient::default().serve(movie_transport()))
+///     .await?;
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct UpstreamClient {
+    progress: ProgressDispatcher,
+    info: ClientInfo,
+}
+
+impl UpstreamClient {
+    pub fn new(info: ClientInfo) -> Self {
+        Self {
+            progress: ProgressDispatcher::new(),
+            info,
+        }
+    }
+}
+
+impl ClientHandler for UpstreamClient {
+    async fn on_progress(
+        &self,
+        params: ProgressNotificationParam,
+        _context: NotificationContext<RoleClient>,
+    ) {
+        self.progress.handle_notification(params).await;
+    }
+
+    fn get_info(&self) -> ClientInfo {
+        self.info.clone()
+    }
+}
+
+/// A [`ServerHandler`] serving the tools of upstream servers along with the ones of `S`.
+///
+/// The tools of the upstream servers in the [`ClientManager`] are listed after the tools of `S`,
+/// prefixed with the name of their server, see [`ClientManager::list_all_tools`]. A call to a
+/// prefixed tool is forwarded to its server, other calls and all the other requests are handled
+/// by `S`.
+///
+/// When the upstream server is connected with an [`UpstreamClient`], the progress of a forwarded
+/// call is relayed to the client. Cancelling the call cancels it upstream. The errors of the
+/// upstream server are returned as is, a failure to reach it is an internal error.
+///
+/// An upstream server failing to list its tools is skipped with a warning, so the other tools are
+/// still served.
+#[derive(Debug, Clone)]
+pub struct ToolProxy<S> {
+    inner: S,
+    upstreams: Arc<ClientManager>,
+}
+
+impl<S: ServerHandler> ToolProxy<S> {
+    pub fn new(inner: S, upstreams: Arc<ClientManager>) -> Self {
+        Self { inner, upstreams }
+    }
+
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    pub fn upstreams(&self) -> &Arc<ClientManager> {
+        &self.upstreams
+    }
+
+    fn with_tools_capability(mut info: ServerInfo) -> ServerInfo {
+        info.capabilities.tools.get_or_insert_with(Default::default);
+        info
+    }
+
+    async fn forward_tool_call(
+        &self,
+        server: &str,
+        tool: &str,
+        arguments: Option<JsonObject>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        static NEXT_PROGRESS_TOKEN: AtomicU64 = AtomicU64::new(0);
+
+        let upstream_error = |error: ServiceError| match error {
+            ServiceError::McpError(error) => error,
+            // the upstream server may be back later
+            error => McpError::unavailable(format!("upstream server {server}: {error}"), None),
+        };
+        let peer = self.upstreams.peer(server).ok_or_else(|| {
+            McpError::unavailable(format!("upstream server {server} is gone"), None)
+        })?;
+        // subscribe with our own token before sending, so no progress is missed
+        let mut progress = match (
+            context.meta.get_progress_token(),
+            self.upstreams.service::<UpstreamClient>(server),
+        ) {
+            (Some(token), Some(upstream)) => {
+                let upstream_token = ProgressToken(NumberOrString::String(
+                    format!(
+                        "proxy-{}",
+                        NEXT_PROGRESS_TOKEN.fetch_add(1, Ordering::Relaxed)
+                    )
+                    .into(),
+                ));
+                Some((token, upstream.progress.subscribe(upstream_token).await))
+            }
+            _ => None,
+        };
+        let mut meta = Meta::new();
+        if let Some((_, subscriber)) = &progress {
+            meta.set_progress_token(subscriber.progress_token().clone());
+        }
+        let handle = peer
+            .send_request_with_option(
+                ClientRequest::CallToolRequest(Request::new(CallToolRequestParam {
+                    name: tool.to_string().into(),
+                    arguments,
+                })),
+                PeerRequestOptions {
+                    timeout: context.time_remaining(),
+                    meta: Some(meta),
+                },
+            )
+            .await
+            .map_err(upstream_error)?;
+        let request_id = handle.id.clone();
+        let response = handle.await_response();
+        tokio::pin!(response);
+        let result = loop {
+            tokio::select! {
+                result = &mut response => break result,
+                Some((token, notification)) = async {
+                    let (token, subscriber) = progress.as_mut()?;
+                    Some((token.clone(), subscriber.next().await?))
+                } => {
+                    let _ = context
+                        .peer
+                        .notify_progress(ProgressNotificationParam {
+                            progress_token: token,
+                            ..notification
+                        })
+                        .await;
+                }
+                _ = context.ct.cancelled() => {
+                    let _ = peer
+                        .notify_cancelled(CancelledNotificationParam {
+                            request_id,
+                            reason: Some("cancelled by the downstream client".to_string()),
+                        })
+                        .await;
+                    return Err(McpError::internal_error("the tool call was cancelled", None));
+                }
+            }
+        };
+        // the progress received along with the response
+        if let Some((token, subscriber)) = &mut progress {
+            while let Some(Some(notification)) = subscriber.next().now_or_never() {
+                let _ = context
+                    .peer
+                    .notify_progress(ProgressNotificationParam {
+                        progress_token: token.clone(),
+                        ..notification
+                    })
+                    .await;
+            }
+        }
+        match result.map_err(upstream_error)? {
+            ServerResult::CallToolResult(result) => Ok(result),
+            _ => Err(McpError::internal_error(
+                format!("upstream server {server}: unexpected response"),
+                None,
+            )),
+        }
+    }
+}
+
+impl<S: ServerHandler> ServerHandler for ToolProxy<S> {
+    async fn ping(&self, context: RequestContext<RoleServer>) -> Result<(), McpError> {
+        self.inner.ping(context).await
+    }
+
+    async fn initialize(
+        &self,
+        request: InitializeRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<InitializeResult, McpError> {
+        let result = self.inner.initialize(request, context).await?;
+        Ok(Self::with_tools_capability(result))
+    }
+
+    async fn complete(
+        &self,
+        request: CompleteRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CompleteResult, McpError> {
+        self.inner.complete(request, context).await
+    }
+
+    async fn set_level(
+        &self,
+        request: SetLevelRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<(), McpError> {
+        self.inner.set_level(request, context).await
+    }
+
+    async fn get_prompt(
+        &self,
+        request: GetPromptRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<GetPromptResult, McpError> {
+        self.inner.get_prompt(request, context).await
+    }
+
+    async fn list_prompts(
+        &self,
+        request: Option<PaginatedRequestParam>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<ListPromptsResult, McpError> {
+        self.inner.list_prompts(request, context).await
+    }
+
+    async fn list_resources(
+        &self,
+        request: Option<PaginatedRequestParam>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<ListResourcesResult, McpError> {
+        self.inner.list_resources(request, context).await
+    }
+
+    async fn list_resource_templates(
+        &self,
+        request: Option<PaginatedRequestParam>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<ListResourceTemplatesResult, McpError> {
+        self.inner.list_resource_templates(request, context).await
+    }
+
+    async fn read_resource(
+        &self,
+        request: ReadResourceRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<ReadResourceResult, McpError> {
+        self.inner.read_resource(request, context).await
+    }
+
+    async fn subscribe(
+        &self,
+        request: SubscribeRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<(), McpError> {
+        self.inner.subscribe(request, context).await
+    }
+
+    async fn unsubscribe(
+        &self,
+        request: UnsubscribeRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<(), McpError> {
+        self.inner.unsubscribe(request, context).await
+    }
+
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        match ClientManager::split_tool_name(&request.name) {
+            Some((server, tool)) if self.upstreams.peer(server).is_some() => {
+                self.forward_tool_call(server, tool, request.arguments, context)
+                    .await
+            }
+            _ => self.inner.call_tool(request, context).await,
+        }
+    }
+
+    /// The tools of `S`, and the tools of the upstream servers after the last page
+    async fn list_tools(
+        &self,
+        request: Option<PaginatedRequestParam>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<ListToolsResult, McpError> {
+        let mut result = self.inner.list_tools(request, context).await?;
+        if result.next_cursor.is_none() {
+            for server in self.upstreams.servers() {
+                match self.upstreams.list_tools_of(&server).await {
+                    Ok(tools) => result.tools.extend(tools),
+                    Err(error) => {
+                        tracing::warn!(%server, %error, "skipping the tools of an upstream server")
+                    }
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    async fn on_custom_request(
+        &self,
+        request: CustomRequest,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CustomResult, McpError> {
+        self.inner.on_custom_request(request, context).await
+    }
+
+    async fn on_cancelled(
+        &self,
+        notification: CancelledNotificationParam,
+        context: NotificationContext<RoleServer>,
+    ) {
+        self.inner.on_cancelled(notification, context).await
+    }
+
+    async fn on_progress(
+        &self,
+        notification: ProgressNotificationParam,
+        context: NotificationContext<RoleServer>,
+    ) {
+        self.inner.on_progress(notification, context).await
+    }
+
+    async fn on_initialized(&self, context: NotificationContext<RoleServer>) {
+        self.inner.on_initialized(context).await
+    }
+
+    async fn on_roots_list_changed(&self, context: NotificationContext<RoleServer>) {
+        self.inner.on_roots_list_changed(context).await
+    }
+
+    async fn on_session_closed(
+        &self,
+        params: SessionClosedNotificationParam,
+        context: NotificationContext<RoleServer>,
+    ) {
+        self.inner.on_session_closed(params, context).await
+    }
+
+    fn get_info(&self) -> ServerInfo {
+        Self::with_tools_capability(self.inner.get_info())
+    }
+
+    fn handles_tools(&self) -> bool {
+        true
+    }
+
+    fn handles_prompts(&self) -> bool {
+        self.inner.handles_prompts()
+    }
+}
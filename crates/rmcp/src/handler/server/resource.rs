@@ -1 +1,13 @@
+use schemars::JsonSchema;
 
+use super::common::schema_for_type;
+use crate::model::RawResourceTemplate;
+
+impl RawResourceTemplate {
+    /// Document the parameters with the JSON schema of `T`, whose fields are the template variables.
+    ///
+    /// The doc comments of the fields become the descriptions of the parameters.
+    pub fn with_parameters<T: JsonSchema>(self) -> Self {
+        self.with_parameters_schema(schema_for_type::<T>())
+    }
+}
@@ -0,0 +1,203 @@
+//! Resume a session after a server restart with a token signed by the server.
+//!
+//! The session id of a transport lives in the memory of the server, so it's lost when the server
+//! restarts. Instead, a server persisting a minimal state per session gives the client a
+//! resumption token at initialize, in the `resumption` experimental capability. A client
+//! reconnecting presents the token back in its own `resumption` experimental capability, and the
+//! server restores the persisted state of the session the token was issued for.
+//!
+//! ```rust,ignore
+//! async fn initialize(
+//!     &self,
+//!     request: InitializeRequestParam,
+//!     context: RequestContext<RoleServer>,
+//! ) -> Result<InitializeResult, ErrorData> {
+//!     let session = match self.signer.resumed_session(&request) {
+//!         Some(session) => {
+//!             self.restore(self.store.load(&session).await);
+//!             session
+//!         }
+//!         None => ResumptionSigner::new_session(),
+//!     };
+//!     let mut info = self.get_info();
+//!     info.capabilities
+//!         .set_resumption_token(self.signer.issue(&session));
+//!     Ok(info)
+//! }
+//! ```
+//!
+//! # Security
+//!
+//! A token is `<payload>.<signature>`, where the payload is the base64url encoded JSON of the
+//! session id and the time the token was issued at, and the signature is its HMAC-SHA256 with the
+//! secret of the [`ResumptionSigner`], compared in constant time.
+//!
+//! - The token is a bearer credential: whoever holds it resumes the session, so only send it over
+//!   an encrypted transport and never log it.
+//! - The payload is signed but not encrypted, the session id can be read by the client. Keep the
+//!   state itself on the server, never in the session id.
+//! - A token expires after [`ResumptionSigner::with_max_age`], 10 minutes by default, since it's
+//!   meant to survive a brief restart. It can be replayed until then; issue a new one on each
+//!   initialize so clients always hold a fresh token.
+//! - The secret must be the same across restarts and kept out of the client. Rotating it revokes
+//!   all the tokens, and deleting the persisted state of a session revokes its tokens.
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+use crate::model::ClientInfo;
+
+/// Why a resumption token is rejected
+#[derive(Debug, thiserror::Error, Clone, PartialEq, Eq)]
+pub enum ResumptionError {
+    #[error("malformed resumption token")]
+    Malformed,
+    #[error("invalid resumption token signature")]
+    InvalidSignature,
+    #[error("resumption token expired")]
+    Expired,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Claims {
+    session: String,
+    issued_at: u64,
+}
+
+/// Issue and verify the resumption tokens of the sessions, see the [module](self) documentation.
+#[derive(Clone)]
+pub struct ResumptionSigner {
+    mac: Hmac<Sha256>,
+    max_age: Duration,
+}
+
+impl std::fmt::Debug for ResumptionSigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ResumptionSigner")
+            .field("max_age", &self.max_age)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ResumptionSigner {
+    pub const DEFAULT_MAX_AGE: Duration = Duration::from_secs(600);
+
+    /// `secret` should be at least 32 random bytes, kept by the server across restarts
+    pub fn new(secret: impl AsRef<[u8]>) -> Self {
+        Self {
+            mac: Hmac::new_from_slice(secret.as_ref()).expect("HMAC accepts keys of any size"),
+            max_age: Self::DEFAULT_MAX_AGE,
+        }
+    }
+
+    /// How long a token can be used after it's issued
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    /// A new random session id
+    pub fn new_session() -> String {
+        uuid::Uuid::new_v4().to_string()
+    }
+
+    /// Issue a token resuming `session`
+    pub fn issue(&self, session: &str) -> String {
+        let claims = Claims {
+            session: session.to_string(),
+            issued_at: now(),
+        };
+        let payload =
+            URL_SAFE_NO_PAD.encode(serde_json::to_vec(&claims).expect("claims are serializable"));
+        let signature = URL_SAFE_NO_PAD.encode(self.sign(payload.as_bytes()));
+        format!("{payload}.{signature}")
+    }
+
+    /// Verify `token`, returning the session it resumes
+    pub fn verify(&self, token: &str) -> Result<String, ResumptionError> {
+        let (payload, signature) = token.split_once('.').ok_or(ResumptionError::Malformed)?;
+        let signature = URL_SAFE_NO_PAD
+            .decode(signature)
+            .map_err(|_| ResumptionError::Malformed)?;
+        let mut mac = self.mac.clone();
+        mac.update(payload.as_bytes());
+        mac.verify_slice(&signature)
+            .map_err(|_| ResumptionError::InvalidSignature)?;
+        let claims: Claims = URL_SAFE_NO_PAD
+            .decode(payload)
+            .ok()
+            .and_then(|payload| serde_json::from_slice(&payload).ok())
+            .ok_or(ResumptionError::Malformed)?;
+        if now().saturating_sub(claims.issued_at) > self.max_age.as_secs() {
+            return Err(ResumptionError::Expired);
+        }
+        Ok(claims.session)
+    }
+
+    /// The session the client resumes with the token of its `resumption` experimental capability.
+    ///
+    /// Returns `None` if the client starts a new session, or if its token is rejected, which is
+    /// logged.
+    pub fn resumed_session(&self, client: &ClientInfo) -> Option<String> {
+        let token = client.capabilities.resumption_token()?;
+        self.verify(token)
+            .inspect_err(|error| tracing::warn!(%error, "resumption token rejected"))
+            .ok()
+    }
+
+    /// HMAC-SHA256 of `message`
+    fn sign(&self, message: &[u8]) -> [u8; 32] {
+        let mut mac = self.mac.clone();
+        mac.update(message);
+        mac.finalize().into_bytes().into()
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_is_rejected_when_tampered_or_expired() {
+        let signer = ResumptionSigner::new("secret of the movie server");
+        let token = signer.issue("session-1");
+        assert_eq!(signer.verify(&token).as_deref(), Ok("session-1"));
+
+        let other = ResumptionSigner::new("another secret");
+        assert_eq!(other.verify(&token), Err(ResumptionError::InvalidSignature));
+        let (_, signature) = token.split_once('.').unwrap();
+        let forged = format!(
+            "{}.{signature}",
+            URL_SAFE_NO_PAD.encode(r#"{"session":"session-2","issued_at":0}"#)
+        );
+        assert_eq!(
+            signer.verify(&forged),
+            Err(ResumptionError::InvalidSignature)
+        );
+        assert_eq!(signer.verify("session-1"), Err(ResumptionError::Malformed));
+
+        let expired =
+            ResumptionSigner::new("secret of the movie server").with_max_age(Duration::ZERO);
+        let claims = Claims {
+            session: "session-1".to_string(),
+            issued_at: now() - 10,
+        };
+        let payload = URL_SAFE_NO_PAD.encode(serde_json::to_vec(&claims).unwrap());
+        let token = format!(
+            "{payload}.{}",
+            URL_SAFE_NO_PAD.encode(expired.sign(payload.as_bytes()))
+        );
+        assert_eq!(expired.verify(&token), Err(ResumptionError::Expired));
+        assert!(signer.verify(&token).is_ok());
+    }
+}
@@ -6,7 +6,7 @@ use tool::{IntoToolRoute, ToolRoute};
 use super::ServerHandler;
 use crate::{
     RoleServer, Service,
-    model::{ClientRequest, ListPromptsResult, ListToolsResult, ServerResult},
+    model::{ClientRequest, ListPromptsResult, ServerResult},
     service::NotificationContext,
 };
 
@@ -98,13 +98,9 @@ where
                         .await
                 }
             }
-            ClientRequest::ListToolsRequest(_) => {
-                let tools = self.tool_router.list_all();
-                Ok(ServerResult::ListToolsResult(ListToolsResult {
-                    tools,
-                    next_cursor: None,
-                }))
-            }
+            ClientRequest::ListToolsRequest(_) => Ok(ServerResult::ListToolsResult(
+                self.tool_router.list_tools(&context),
+            )),
             ClientRequest::GetPromptRequest(request) => {
                 if self.prompt_router.has_route(request.params.name.as_ref()) {
                     let prompt_context = crate::handler::server::prompt::PromptContext::new(
@@ -123,10 +119,9 @@ where
             }
             ClientRequest::ListPromptsRequest(_) => {
                 let prompts = self.prompt_router.list_all();
-                Ok(ServerResult::ListPromptsResult(ListPromptsResult {
-                    prompts,
-                    next_cursor: None,
-                }))
+                Ok(ServerResult::ListPromptsResult(
+                    ListPromptsResult::with_all_items(prompts),
+                ))
             }
             rest => self.service.handle_request(rest, context).await,
         }
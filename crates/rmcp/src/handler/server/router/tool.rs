@@ -1,19 +1,53 @@
-use std::{borrow::Cow, sync::Arc};
+use std::{
+    borrow::Cow,
+    sync::{
+        Arc, Mutex, PoisonError,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
 
 use futures::{FutureExt, future::BoxFuture};
 use schemars::JsonSchema;
 
 use crate::{
-    handler::server::tool::{
-        CallToolHandler, DynCallToolHandler, ToolCallContext, schema_for_type,
+    Peer, RoleServer,
+    handler::server::{
+        common::{canonical_json, inline_schema_refs},
+        tool::{CallToolHandler, DynCallToolHandler, ToolCallContext, schema_for_type},
+    },
+    model::{
+        CallToolRequestParam, CallToolResult, Content, ListToolsResult, Tool, ToolAnnotations,
+        ToolGroup,
     },
-    model::{CallToolResult, Tool, ToolAnnotations},
+    service::{RequestContext, WeakPeer},
 };
 
+mod cache;
+mod coalesce;
+mod describe;
+mod post_process;
+mod snapshot;
+mod validate;
+pub use cache::ToolResultCache;
+pub use coalesce::InFlightCalls;
+pub use describe::{ParameterSummary, ToolSummary};
+pub use post_process::{PostProcess, ToolPostProcessor};
+pub use snapshot::{ToolDiff, ToolSnapshot};
+
 pub struct ToolRoute<S> {
     #[allow(clippy::type_complexity)]
     pub call: Arc<DynCallToolHandler<S>>,
     pub attr: crate::model::Tool,
+    /// Successful results cached by arguments, see [`ToolRoute::with_cache`]
+    pub cache: Option<Arc<ToolResultCache>>,
+    /// The calls in flight shared by the concurrent identical calls, see [`ToolRoute::with_coalescing`]
+    pub in_flight: Option<Arc<InFlightCalls>>,
+    /// The max size of the arguments, serialized as JSON, see [`ToolRoute::with_max_arg_bytes`]
+    pub max_arg_bytes: Option<usize>,
+    /// Whether the tool is listed and callable, shared by the clones of the route,
+    /// see [`ToolRouter::set_enabled`]
+    pub enabled: Arc<AtomicBool>,
 }
 
 impl<S> std::fmt::Debug for ToolRoute<S> {
@@ -22,6 +56,10 @@ impl<S> std::fmt::Debug for ToolRoute<S> {
             .field("name", &self.attr.name)
             .field("description", &self.attr.description)
             .field("input_schema", &self.attr.input_schema)
+            .field("cache", &self.cache)
+            .field("in_flight", &self.in_flight)
+            .field("max_arg_bytes", &self.max_arg_bytes)
+            .field("enabled", &self.enabled)
             .finish()
     }
 }
@@ -31,6 +69,10 @@ impl<S> Clone for ToolRoute<S> {
         Self {
             call: self.call.clone(),
             attr: self.attr.clone(),
+            cache: self.cache.clone(),
+            in_flight: self.in_flight.clone(),
+            max_arg_bytes: self.max_arg_bytes,
+            enabled: self.enabled.clone(),
         }
     }
 }
@@ -46,6 +88,10 @@ impl<S: Send + Sync + 'static> ToolRoute<S> {
                 context.invoke(call).boxed()
             }),
             attr: attr.into(),
+            cache: None,
+            in_flight: None,
+            max_arg_bytes: None,
+            enabled: Arc::new(AtomicBool::new(true)),
         }
     }
     pub fn new_dyn<C>(attr: impl Into<Tool>, call: C) -> Self
@@ -60,11 +106,54 @@ impl<S: Send + Sync + 'static> ToolRoute<S> {
         Self {
             call: Arc::new(call),
             attr: attr.into(),
+            cache: None,
+            in_flight: None,
+            max_arg_bytes: None,
+            enabled: Arc::new(AtomicBool::new(true)),
         }
     }
     pub fn name(&self) -> &str {
         &self.attr.name
     }
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+    /// Cache successful results for `ttl`, keyed by the arguments, keeping at most `capacity` of them.
+    ///
+    /// A result is only served to the clients with the same locale and accepted types, and the
+    /// same key if the router has one, see [`ToolRouter::with_coalescing_key`].
+    /// A cache hit skips the handler entirely. Dry run calls never use the cache.
+    /// This is what `#[tool(cache_ttl_ms = ..., cache_capacity = ...)]` generates under `#[tool_router]`.
+    pub fn with_cache(mut self, ttl: Duration, capacity: usize) -> Self {
+        self.cache = Some(Arc::new(ToolResultCache::new(ttl, capacity)));
+        self
+    }
+    /// Coalesce the concurrent calls with the same arguments: the first one runs the handler, the
+    /// others wait for its result instead of running it again.
+    ///
+    /// The calls are only shared by the clients with the same locale and accepted types, and the
+    /// same key if the router has one, see [`ToolRouter::with_coalescing_key`]. A tool whose
+    /// result depends on anything else in the request context needs such a key.
+    ///
+    /// Only the calls in flight are shared, see [`ToolRoute::with_cache`] to keep the results.
+    /// If the first call is cancelled, the waiting ones run the handler on their own.
+    /// Dry run calls are never coalesced.
+    /// This is what `#[tool(coalesce)]` generates under `#[tool_router]`.
+    pub fn with_coalescing(mut self) -> Self {
+        self.in_flight = Some(Arc::new(InFlightCalls::new()));
+        self
+    }
+    /// Reject the calls whose arguments are larger than `max_arg_bytes` once serialized as JSON
+    /// with `invalid_params`, before the tool runs.
+    ///
+    /// The arguments were already parsed by then, so it doesn't protect the server against large
+    /// messages, bound them with the `json_limits` of the transport.
+    ///
+    /// This is what `#[tool(max_arg_bytes = ...)]` generates under `#[tool_router]`.
+    pub fn with_max_arg_bytes(mut self, max_arg_bytes: usize) -> Self {
+        self.max_arg_bytes = Some(max_arg_bytes);
+        self
+    }
 }
 
 pub trait IntoToolRoute<S, A> {
@@ -166,12 +255,69 @@ where
         self
     }
 }
+fn inline_tool_schemas(tool: &mut Tool) {
+    tool.input_schema = Arc::new(inline_schema_refs(&tool.input_schema));
+    if let Some(output_schema) = &tool.output_schema {
+        tool.output_schema = Some(Arc::new(inline_schema_refs(output_schema)));
+    }
+}
+
+/// Whether a tool is visible to the client of a request, see [`ToolRouter::with_visibility`]
+#[derive(Clone)]
+pub struct ToolVisibility(
+    #[allow(clippy::type_complexity)]
+    pub  Arc<dyn Fn(&Tool, &RequestContext<RoleServer>) -> bool + Send + Sync>,
+);
+
+impl std::fmt::Debug for ToolVisibility {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("ToolVisibility").finish_non_exhaustive()
+    }
+}
+
+/// The part of the request context a coalesced call depends on, see [`ToolRouter::with_coalescing_key`]
+#[derive(Clone)]
+pub struct CoalescingKey(
+    #[allow(clippy::type_complexity)]
+    pub  Arc<dyn Fn(&RequestContext<RoleServer>) -> String + Send + Sync>,
+);
+
+impl std::fmt::Debug for CoalescingKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("CoalescingKey").finish_non_exhaustive()
+    }
+}
+
 #[derive(Debug)]
 pub struct ToolRouter<S> {
     #[allow(clippy::type_complexity)]
     pub map: std::collections::HashMap<Cow<'static, str>, ToolRoute<S>>,
 
     pub transparent_when_not_found: bool,
+
+    /// Suggest the closest tool name when a tool isn't found, see [`ToolRouter::with_suggestions`]
+    pub suggest_when_not_found: bool,
+
+    /// Return the errors of the tools as error results, see [`ToolRouter::with_errors_as_results`]
+    pub errors_as_results: bool,
+
+    /// Inline the `$ref`s of the tool schemas, see [`ToolRouter::with_inlined_schemas`]
+    pub inline_schemas: bool,
+
+    /// Hide tools from some clients, see [`ToolRouter::with_visibility`]
+    pub visibility: Option<ToolVisibility>,
+
+    /// Separate the coalesced calls of different callers, see [`ToolRouter::with_coalescing_key`]
+    pub coalescing_key: Option<CoalescingKey>,
+
+    /// Transform the results of the tools in order, see [`ToolRouter::with_post_processor`]
+    pub post_processors: Vec<ToolPostProcessor>,
+
+    /// The groups of the tools, see [`ToolRouter::with_group`]
+    pub groups: Vec<ToolGroup>,
+
+    /// The peers notified when the tool list changes, see [`ToolRouter::notify_list_changed_to`]
+    list_changed_peers: Arc<Mutex<Vec<WeakPeer<RoleServer>>>>,
 }
 
 impl<S> Default for ToolRouter<S> {
@@ -179,6 +325,14 @@ impl<S> Default for ToolRouter<S> {
         Self {
             map: std::collections::HashMap::new(),
             transparent_when_not_found: false,
+            suggest_when_not_found: false,
+            errors_as_results: false,
+            inline_schemas: false,
+            visibility: None,
+            coalescing_key: None,
+            post_processors: Vec::new(),
+            groups: Vec::new(),
+            list_changed_peers: Default::default(),
         }
     }
 }
@@ -187,6 +341,14 @@ impl<S> Clone for ToolRouter<S> {
         Self {
             map: self.map.clone(),
             transparent_when_not_found: self.transparent_when_not_found,
+            suggest_when_not_found: self.suggest_when_not_found,
+            errors_as_results: self.errors_as_results,
+            inline_schemas: self.inline_schemas,
+            visibility: self.visibility.clone(),
+            coalescing_key: self.coalescing_key.clone(),
+            post_processors: self.post_processors.clone(),
+            groups: self.groups.clone(),
+            list_changed_peers: self.list_changed_peers.clone(),
         }
     }
 }
@@ -208,6 +370,14 @@ where
         Self {
             map: std::collections::HashMap::new(),
             transparent_when_not_found: false,
+            suggest_when_not_found: false,
+            errors_as_results: false,
+            inline_schemas: false,
+            visibility: None,
+            coalescing_key: None,
+            post_processors: Vec::new(),
+            groups: Vec::new(),
+            list_changed_peers: Default::default(),
         }
     }
     pub fn with_route<R, A>(mut self, route: R) -> Self
@@ -218,7 +388,153 @@ where
         self
     }
 
-    pub fn add_route(&mut self, item: ToolRoute<S>) {
+    /// Include the closest registered tool name in the error when a tool isn't found,
+    /// e.g. `get_cinemalist` suggests `get_cinema_list`.
+    ///
+    /// It's off by default, since it reveals the names of the tools.
+    pub fn with_suggestions(mut self) -> Self {
+        self.suggest_when_not_found = true;
+        self
+    }
+
+    /// Return the errors of the tools, like a failed upstream request or invalid arguments, as
+    /// error results with the message as text, instead of JSON-RPC errors.
+    ///
+    /// The model never sees a JSON-RPC error, only the client does, while it reads an error
+    /// result and may recover from it, e.g. by calling the tool again with other arguments.
+    /// Calling a tool which doesn't exist is still a JSON-RPC error.
+    pub fn with_errors_as_results(mut self) -> Self {
+        self.errors_as_results = true;
+        self
+    }
+
+    /// Inline the `$ref`s of the input and output schemas of the tools, so each schema is
+    /// self-contained, see [`inline_schema_refs`](crate::handler::server::common::inline_schema_refs).
+    ///
+    /// `schemars` refers to the nested types of the parameters with `$ref`s into `definitions`,
+    /// which some clients can't resolve. It's off by default, since the inlined schemas are
+    /// larger, a type used twice is repeated. It applies to the routes added later too.
+    pub fn with_inlined_schemas(mut self) -> Self {
+        self.inline_schemas = true;
+        for item in self.map.values_mut() {
+            inline_tool_schemas(&mut item.attr);
+        }
+        self
+    }
+
+    /// Show a tool only to the clients `visible` returns `true` for, based on the request
+    /// context, like the client info or the extensions set by an authentication layer:
+    ///
+    /// ```rust,ignore
+    /// Self::tool_router().with_visibility(|tool, context| {
+    ///     !tool.name.starts_with("admin_") || context.extensions.get::<AdminToken>().is_some()
+    /// })
+    /// ```
+    ///
+    /// A hidden tool isn't listed by [`ToolRouter::list_visible`], and calling it fails as if it
+    /// didn't exist, it's never suggested either.
+    ///
+    /// The tools caching or coalescing their calls then need a [`ToolRouter::with_coalescing_key`],
+    /// or they reject the calls: a client must not get the result computed for another one.
+    pub fn with_visibility<F>(mut self, visible: F) -> Self
+    where
+        F: Fn(&Tool, &RequestContext<RoleServer>) -> bool + Send + Sync + 'static,
+    {
+        self.visibility = Some(ToolVisibility(Arc::new(visible)));
+        self
+    }
+
+    /// Share the cached and coalesced results only between the calls whose request context gives
+    /// the same `key`, like the identity set by an authentication layer, see
+    /// [`ToolRoute::with_cache`] and [`ToolRoute::with_coalescing`]:
+    ///
+    /// ```rust,ignore
+    /// Self::tool_router().with_coalescing_key(|context| {
+    ///     context.extensions.get::<User>().map(|user| user.id.clone()).unwrap_or_default()
+    /// })
+    /// ```
+    ///
+    /// The locale and the accepted types of the caller are always part of the key.
+    pub fn with_coalescing_key<F>(mut self, key: F) -> Self
+    where
+        F: Fn(&RequestContext<RoleServer>) -> String + Send + Sync + 'static,
+    {
+        self.coalescing_key = Some(CoalescingKey(Arc::new(key)));
+        self
+    }
+
+    /// Append `post_processor` to the hooks transforming the results of the tools, like a
+    /// redaction, a truncation or a localization:
+    ///
+    /// ```rust,ignore
+    /// Self::tool_router()
+    ///     .with_post_processor(|_tool, _context, result| PostProcess::Continue(redact(result)))
+    ///     .with_post_processor(|_tool, context, result| {
+    ///         PostProcess::Continue(localize(result, context))
+    ///     })
+    /// ```
+    ///
+    /// They run in the order they are added, each one is given the result of the previous one,
+    /// and may return [`PostProcess::Stop`] to send its result as is, skipping the next ones.
+    /// They apply to the error results too, including the errors turned into results by
+    /// [`ToolRouter::with_errors_as_results`], but not to the JSON-RPC errors.
+    pub fn with_post_processor<F>(mut self, post_processor: F) -> Self
+    where
+        F: Fn(&Tool, &RequestContext<RoleServer>, CallToolResult) -> PostProcess
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.post_processors
+            .push(ToolPostProcessor(Arc::new(post_processor)));
+        self
+    }
+
+    /// Whether `tool` is visible to the client of `context`, see [`ToolRouter::with_visibility`]
+    pub fn is_visible(&self, tool: &Tool, context: &RequestContext<RoleServer>) -> bool {
+        self.visibility
+            .as_ref()
+            .is_none_or(|visibility| (visibility.0)(tool, context))
+    }
+
+    /// Suggest clients show `tools` together under `label`, without changing the tools:
+    ///
+    /// ```rust,ignore
+    /// Self::tool_router()
+    ///     .with_group("Location", ["get_cinema_list"])
+    ///     .with_group("Movies", ["get_movie_list", "get_movie_detail_info"])
+    /// ```
+    ///
+    /// The groups are sent in the `_meta` of `tools/list` by [`ToolRouter::list_tools`], in the
+    /// order they are added. A tool is in one group at most, adding it to another group moves it.
+    pub fn with_group<I, T>(mut self, label: impl Into<String>, tools: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        self.add_group(ToolGroup::new(label, tools));
+        self
+    }
+
+    pub fn add_group(&mut self, group: ToolGroup) {
+        for other in &mut self.groups {
+            other.tools.retain(|tool| !group.tools.contains(tool));
+        }
+        match self
+            .groups
+            .iter_mut()
+            .find(|other| other.label == group.label)
+        {
+            Some(other) => other.tools.extend(group.tools),
+            None => self.groups.push(group),
+        }
+        self.groups.retain(|group| !group.tools.is_empty());
+    }
+
+    pub fn add_route(&mut self, mut item: ToolRoute<S>) {
+        if self.inline_schemas {
+            inline_tool_schemas(&mut item.attr);
+        }
         self.map.insert(item.attr.name.clone(), item);
     }
 
@@ -226,6 +542,9 @@ where
         for item in other.map.into_values() {
             self.add_route(item);
         }
+        for group in other.groups {
+            self.add_group(group);
+        }
     }
 
     pub fn remove_route(&mut self, name: &str) {
@@ -234,6 +553,89 @@ where
     pub fn has_route(&self, name: &str) -> bool {
         self.map.contains_key(name)
     }
+
+    /// Disable a tool without removing it, or enable it again.
+    ///
+    /// A disabled tool isn't listed, and calling it fails with a retryable
+    /// [`ErrorData::unavailable`](crate::ErrorData::unavailable). The
+    /// state is shared by the clones of the router, so a server can be cloned for each session
+    /// and still disable a tool for all of them. When the state changes, the peers registered
+    /// with [`ToolRouter::notify_list_changed_to`] are sent a `notifications/tools/list_changed`.
+    ///
+    /// Return `false` if the tool doesn't exist.
+    pub async fn set_enabled(&self, name: &str, enabled: bool) -> bool {
+        let Some(item) = self.map.get(name) else {
+            return false;
+        };
+        if item.enabled.swap(enabled, Ordering::Relaxed) != enabled {
+            self.notify_list_changed().await;
+        }
+        true
+    }
+
+    /// Send `notifications/tools/list_changed` to `peer` whenever [`ToolRouter::set_enabled`]
+    /// changes the tool list, e.g. from [`ServerHandler::on_initialized`](crate::ServerHandler::on_initialized).
+    ///
+    /// The router only keeps a [`WeakPeer`], so it doesn't keep the session alive: the peer is
+    /// dropped once its session is gone. Registering the same peer again has no effect.
+    pub fn notify_list_changed_to(&self, peer: Peer<RoleServer>) {
+        let peer = peer.downgrade();
+        let mut peers = self
+            .list_changed_peers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        // the gone sessions are dropped here too, the list may rarely change
+        peers.retain(|known| known.upgrade().is_some());
+        if !peers.iter().any(|known| known.ptr_eq(&peer)) {
+            peers.push(peer);
+        }
+    }
+
+    async fn notify_list_changed(&self) {
+        let peers = {
+            let mut peers = self
+                .list_changed_peers
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            let mut alive = Vec::with_capacity(peers.len());
+            peers.retain(|peer| match peer.upgrade() {
+                Some(peer) => {
+                    alive.push(peer);
+                    true
+                }
+                None => false,
+            });
+            alive
+        };
+        for peer in peers {
+            if let Err(error) = peer.notify_tool_list_changed().await {
+                tracing::warn!(%error, "fail to notify the tool list change");
+            }
+        }
+    }
+    /// Drop the cached results of a tool, or only the one cached for `arguments` if given.
+    ///
+    /// Return `false` if the tool doesn't exist or has no cache.
+    pub fn invalidate_cache(
+        &self,
+        name: &str,
+        arguments: Option<&crate::model::JsonObject>,
+    ) -> bool {
+        let Some(cache) = self.map.get(name).and_then(|item| item.cache.as_ref()) else {
+            return false;
+        };
+        match arguments {
+            Some(arguments) => cache.invalidate(Some(arguments)),
+            None => cache.clear(),
+        }
+        true
+    }
+    /// Call the tool named in `context`.
+    ///
+    /// The arguments are first checked against the size limit of the tool, see
+    /// [`ToolRoute::with_max_arg_bytes`], then against the bounds declared in the tool's input schema,
+    /// e.g. by `#[validate(range(min = -90.0, max = 90.0))]` on a parameter field, and rejected
+    /// with `invalid_params` if out of them.
     pub async fn call(
         &self,
         context: ToolCallContext<'_, S>,
@@ -241,16 +643,298 @@ where
         let item = self
             .map
             .get(context.name())
-            .ok_or_else(|| crate::ErrorData::invalid_params("tool not found", None))?;
+            .filter(|item| self.is_visible(&item.attr, context.request_context()))
+            .ok_or_else(|| self.not_found_error(context.name(), context.request_context()))?;
+        let request_context =
+            (!self.post_processors.is_empty()).then(|| context.request_context().clone());
+        let result = match self.call_route(item, context).await {
+            Err(error) if self.errors_as_results => {
+                Ok(CallToolResult::error(vec![Content::text(error.message)]))
+            }
+            result => result,
+        };
+        match request_context {
+            Some(request_context) => result.map(|result| {
+                post_process::post_process(
+                    &self.post_processors,
+                    &item.attr,
+                    &request_context,
+                    result,
+                )
+            }),
+            None => result,
+        }
+    }
 
-        let result = (item.call)(context).await?;
+    async fn call_route(
+        &self,
+        item: &ToolRoute<S>,
+        context: ToolCallContext<'_, S>,
+    ) -> Result<CallToolResult, crate::ErrorData> {
+        if !item.is_enabled() {
+            return Err(crate::ErrorData::unavailable(
+                format!("tool {} is temporarily unavailable", context.name()),
+                None,
+            ));
+        }
+        if let Some(deprecation) = item.attr.deprecation() {
+            tracing::warn!(tool = %context.name(), %deprecation, "deprecated tool called");
+        }
+        if let (Some(max_arg_bytes), Some(arguments)) = (item.max_arg_bytes, &context.arguments) {
+            let arg_bytes = crate::model::serialized_len(arguments);
+            if arg_bytes > max_arg_bytes {
+                return Err(crate::ErrorData::invalid_params(
+                    format!(
+                        "arguments of tool {} are too large: {arg_bytes} bytes, the limit is {max_arg_bytes}",
+                        context.name()
+                    ),
+                    Some(serde_json::json!({ "size": arg_bytes, "limit": max_arg_bytes })),
+                ));
+            }
+        }
+        validate::validate_arguments(&item.attr.input_schema, context.arguments.as_ref())?;
 
+        let dry_run = context.request_context().is_dry_run();
+        let cache = item.cache.as_ref().filter(|_| !dry_run);
+        let in_flight = item.in_flight.as_ref().filter(|_| !dry_run);
+        if (cache.is_some() || in_flight.is_some())
+            && self.visibility.is_some()
+            && self.coalescing_key.is_none()
+        {
+            return Err(crate::ErrorData::internal_error(
+                format!(
+                    "tool {} shares its results, but the router hides tools from some clients without a coalescing key",
+                    context.name()
+                ),
+                None,
+            ));
+        }
+        let key = (cache.is_some() || in_flight.is_some()).then(|| self.result_key(&context));
+
+        let cache = cache.zip(key.clone());
+        if let Some((cache, key)) = &cache {
+            if let Some(result) = cache.get(key) {
+                return Ok(result);
+            }
+        }
+
+        let flight = in_flight
+            .zip(key)
+            .map(|(in_flight, key)| in_flight.join(key));
+        let leader = match flight {
+            Some(coalesce::Flight::Follower(receiver)) => match coalesce::follow(receiver).await {
+                Some(result) => return result,
+                None => None,
+            },
+            Some(coalesce::Flight::Leader(leader)) => Some(leader),
+            None => None,
+        };
+
+        let result = (item.call)(context).await;
+        if let Some(leader) = leader {
+            leader.finish(&result);
+        }
+        let result = result?;
+
+        if let Some((cache, key)) = cache {
+            if result.is_error != Some(true) {
+                cache.insert(key, result.clone());
+            }
+        }
         Ok(result)
     }
 
+    /// The key of a cached or coalesced result: the arguments of the call and the request context
+    /// the result depends on
+    fn result_key(&self, context: &ToolCallContext<'_, S>) -> String {
+        let request_context = context.request_context();
+        let caller = self
+            .coalescing_key
+            .as_ref()
+            .map(|key| (key.0)(request_context));
+        let scope =
+            serde_json::json!([request_context.locale(), request_context.accept(), caller,]);
+        ToolResultCache::scoped_key(&scope.to_string(), context.arguments.as_ref())
+    }
+
+    /// Call the tool `name` with JSON `arguments`, without going through JSON-RPC.
+    ///
+    /// It takes the same path as a `tools/call` request, result cache included, so a tool can
+    /// call another tool of its router with its own request context, and tests can drive tools
+    /// directly. The `arguments` must be an object, or `null` for no arguments.
+    pub async fn call_tool(
+        &self,
+        service: &S,
+        name: impl Into<Cow<'static, str>>,
+        arguments: serde_json::Value,
+        request_context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, crate::ErrorData> {
+        let arguments = match arguments {
+            serde_json::Value::Object(arguments) => Some(arguments),
+            serde_json::Value::Null => None,
+            _ => {
+                return Err(crate::ErrorData::invalid_params(
+                    "tool arguments must be an object",
+                    None,
+                ));
+            }
+        };
+        let param = CallToolRequestParam {
+            name: name.into(),
+            arguments,
+        };
+        self.call(ToolCallContext::new(service, param, request_context))
+            .await
+    }
+
+    fn not_found_error(
+        &self,
+        name: &str,
+        context: &RequestContext<RoleServer>,
+    ) -> crate::ErrorData {
+        let suggestion = self
+            .suggest_when_not_found
+            .then(|| self.closest_name(name, context))
+            .flatten();
+        match suggestion {
+            Some(suggestion) => crate::ErrorData::invalid_params(
+                format!("tool not found, did you mean `{suggestion}`?"),
+                Some(serde_json::json!({ "suggestion": suggestion })),
+            ),
+            None => crate::ErrorData::invalid_params("tool not found", None),
+        }
+    }
+
+    /// The name closest to `name` among the tools listed to the client of `context`, if it's within
+    /// a third of its length in edit distance, so a hidden or disabled tool is never suggested
+    fn closest_name(&self, name: &str, context: &RequestContext<RoleServer>) -> Option<&str> {
+        let max_distance = (name.chars().count() / 3).max(1);
+        self.map
+            .iter()
+            .filter(|(_, item)| item.is_enabled() && self.is_visible(&item.attr, context))
+            .map(|(candidate, _)| (levenshtein(name, candidate), candidate))
+            .filter(|(distance, _)| *distance <= max_distance)
+            .min()
+            .map(|(_, candidate)| candidate.as_ref())
+    }
+
+    /// The enabled tools, see [`ToolRouter::set_enabled`]
     pub fn list_all(&self) -> Vec<crate::model::Tool> {
-        self.map.values().map(|item| item.attr.clone()).collect()
+        self.map
+            .values()
+            .filter(|item| item.is_enabled())
+            .map(|item| item.attr.clone())
+            .collect()
+    }
+
+    /// The enabled tools visible to the client of `context`, see [`ToolRouter::with_visibility`]
+    pub fn list_visible(&self, context: &RequestContext<RoleServer>) -> Vec<crate::model::Tool> {
+        let mut tools = self.list_all();
+        tools.retain(|tool| self.is_visible(tool, context));
+        tools
+    }
+
+    /// The result of `tools/list`: the tools visible to the client of `context`, with the groups
+    /// of these tools in its `_meta`, see [`ToolRouter::with_group`]
+    pub fn list_tools(&self, context: &RequestContext<RoleServer>) -> ListToolsResult {
+        let tools = self.list_visible(context);
+        let groups: Vec<_> = self
+            .groups
+            .iter()
+            .map(|group| ToolGroup {
+                label: group.label.clone(),
+                tools: group
+                    .tools
+                    .iter()
+                    .filter(|name| tools.iter().any(|tool| tool.name == name.as_str()))
+                    .cloned()
+                    .collect(),
+            })
+            .filter(|group| !group.tools.is_empty())
+            .collect();
+        let mut result = ListToolsResult::with_all_items(tools);
+        if !groups.is_empty() {
+            result.meta.get_or_insert_default().set_tool_groups(groups);
+        }
+        result
+    }
+
+    /// The enabled tools, to find what changed when they change at runtime, see [`ToolSnapshot::diff`]
+    pub fn snapshot(&self) -> ToolSnapshot {
+        self.list_all().into_iter().collect()
+    }
+
+    /// A summary of each enabled tool, sorted by name, like [`ToolRouter::list_all`]
+    pub fn summaries(&self) -> Vec<ToolSummary> {
+        let mut summaries: Vec<ToolSummary> = self
+            .map
+            .values()
+            .filter(|item| item.is_enabled())
+            .map(|item| ToolSummary::from(&item.attr))
+            .collect();
+        summaries.sort_by(|a, b| a.name.cmp(&b.name));
+        summaries
+    }
+
+    /// A human-readable index of the tools, for CLIs and debugging, with their descriptions and
+    /// parameters, see [`ToolSummary`]
+    pub fn describe(&self) -> String {
+        self.summaries()
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// The input and output schemas of all tools, keyed by tool name, for snapshot testing.
+    ///
+    /// ```json
+    /// { "get_movie": { "inputSchema": { ... }, "outputSchema": null } }
+    /// ```
+    pub fn schema_snapshot(&self) -> serde_json::Value {
+        let tools = self
+            .map
+            .values()
+            .map(|item| {
+                let schemas = serde_json::json!({
+                    "inputSchema": item.attr.input_schema.as_ref(),
+                    "outputSchema": item.attr.output_schema.as_deref(),
+                });
+                (item.attr.name.to_string(), schemas)
+            })
+            .collect();
+        serde_json::Value::Object(tools)
+    }
+
+    /// A stable hash of [`ToolRouter::schema_snapshot`], it changes whenever a tool schema changes.
+    ///
+    /// The hash is 64-bit FNV-1a over the snapshot serialized with its keys sorted at every level,
+    /// like the key of a [`ToolResultCache`], so it's the same across runs, platforms and
+    /// compiler versions.
+    pub fn schema_fingerprint(&self) -> String {
+        const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+        const FNV_PRIME: u64 = 0x100000001b3;
+        let canonical = canonical_json(self.schema_snapshot()).to_string();
+        let hash = canonical.bytes().fold(FNV_OFFSET_BASIS, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME)
+        });
+        format!("{hash:016x}")
+    }
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, a) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, b) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(a != *b);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(row[j + 1] + 1);
+        }
     }
+    row[b.len()]
 }
 
 impl<S> std::ops::Add<ToolRouter<S>> for ToolRouter<S>
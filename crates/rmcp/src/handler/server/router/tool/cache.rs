@@ -0,0 +1,142 @@
+use std::{
+    collections::HashMap,
+    sync::{Mutex, PoisonError},
+    time::Duration,
+};
+
+use tokio::time::Instant;
+
+use crate::{
+    handler::server::common::canonical_json,
+    model::{CallToolResult, JsonObject},
+};
+
+/// An LRU cache of successful tool results, keyed by the serialized arguments and the scope of
+/// the caller, see [`ToolResultCache::scoped_key`].
+#[derive(Debug)]
+pub struct ToolResultCache {
+    ttl: Duration,
+    capacity: usize,
+    state: Mutex<CacheState>,
+}
+
+#[derive(Debug, Default)]
+struct CacheState {
+    entries: HashMap<String, CacheEntry>,
+    /// Increased on every access, the entry with the smallest `last_used` is the least recently used
+    clock: u64,
+}
+
+#[derive(Debug)]
+struct CacheEntry {
+    result: CallToolResult,
+    expires_at: Instant,
+    last_used: u64,
+}
+
+impl ToolResultCache {
+    pub const DEFAULT_CAPACITY: usize = 128;
+
+    pub fn new(ttl: Duration, capacity: usize) -> Self {
+        Self {
+            ttl,
+            capacity: capacity.max(1),
+            state: Mutex::new(CacheState::default()),
+        }
+    }
+
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn key(arguments: Option<&JsonObject>) -> String {
+        // keys sorted at every level, so equal arguments always serialize the same way
+        arguments
+            .map(|arguments| {
+                canonical_json(serde_json::Value::Object(arguments.clone())).to_string()
+            })
+            .unwrap_or_default()
+    }
+
+    /// The key of the result for `arguments`, shared only by the callers with the same `scope`,
+    /// i.e. the part of the request context the result depends on.
+    pub fn scoped_key(scope: &str, arguments: Option<&JsonObject>) -> String {
+        // serialized JSON never contains a raw NUL, so the arguments are always after the last one
+        format!("{scope}\0{}", Self::key(arguments))
+    }
+
+    pub fn get(&self, key: &str) -> Option<CallToolResult> {
+        let mut state = self.lock();
+        let now = Instant::now();
+        state.clock += 1;
+        let clock = state.clock;
+        match state.entries.get_mut(key) {
+            Some(entry) if entry.expires_at > now => {
+                entry.last_used = clock;
+                Some(entry.result.clone())
+            }
+            Some(_) => {
+                state.entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    pub fn insert(&self, key: String, result: CallToolResult) {
+        let mut state = self.lock();
+        let now = Instant::now();
+        state.clock += 1;
+        let clock = state.clock;
+        if !state.entries.contains_key(&key) && state.entries.len() >= self.capacity {
+            state.entries.retain(|_, entry| entry.expires_at > now);
+            if state.entries.len() >= self.capacity {
+                let lru = state
+                    .entries
+                    .iter()
+                    .min_by_key(|(_, entry)| entry.last_used)
+                    .map(|(key, _)| key.clone());
+                if let Some(lru) = lru {
+                    state.entries.remove(&lru);
+                }
+            }
+        }
+        state.entries.insert(
+            key,
+            CacheEntry {
+                result,
+                expires_at: now + self.ttl,
+                last_used: clock,
+            },
+        );
+    }
+
+    /// Remove the results cached for these arguments, whatever their scope
+    pub fn invalidate(&self, arguments: Option<&JsonObject>) {
+        let key = Self::key(arguments);
+        let scoped = format!("\0{key}");
+        self.lock()
+            .entries
+            .retain(|entry_key, _| *entry_key != key && !entry_key.ends_with(&scoped));
+    }
+
+    pub fn clear(&self) {
+        self.lock().entries.clear();
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, CacheState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
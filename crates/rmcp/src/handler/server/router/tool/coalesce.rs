@@ -0,0 +1,92 @@
+use std::{
+    collections::HashMap,
+    sync::{Mutex, PoisonError},
+};
+
+use tokio::sync::watch;
+
+use crate::model::CallToolResult;
+
+type CallResult = Result<CallToolResult, crate::ErrorData>;
+
+/// The calls of a tool in flight, keyed by the serialized arguments, so concurrent calls with the
+/// same arguments share the execution of the first one.
+///
+/// Unlike [`ToolResultCache`](super::ToolResultCache), nothing is kept once the call is done:
+/// a call starting after it runs the tool again.
+#[derive(Debug, Default)]
+pub struct InFlightCalls {
+    calls: Mutex<HashMap<String, watch::Receiver<Option<CallResult>>>>,
+}
+
+/// Whether a call runs the tool or waits for the call already running it
+pub(crate) enum Flight<'a> {
+    Leader(Leader<'a>),
+    Follower(watch::Receiver<Option<CallResult>>),
+}
+
+/// The call running the tool, its result is shared with the followers
+pub(crate) struct Leader<'a> {
+    in_flight: &'a InFlightCalls,
+    key: String,
+    sender: watch::Sender<Option<CallResult>>,
+}
+
+impl InFlightCalls {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of distinct calls in flight
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub(crate) fn join(&self, key: String) -> Flight<'_> {
+        let mut calls = self.lock();
+        if let Some(receiver) = calls.get(&key) {
+            return Flight::Follower(receiver.clone());
+        }
+        let (sender, receiver) = watch::channel(None);
+        calls.insert(key.clone(), receiver);
+        Flight::Leader(Leader {
+            in_flight: self,
+            key,
+            sender,
+        })
+    }
+
+    fn lock(
+        &self,
+    ) -> std::sync::MutexGuard<'_, HashMap<String, watch::Receiver<Option<CallResult>>>> {
+        self.calls.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Leader<'_> {
+    pub(crate) fn finish(self, result: &CallResult) {
+        self.sender.send_replace(Some(result.clone()));
+    }
+}
+
+impl Drop for Leader<'_> {
+    fn drop(&mut self) {
+        // the next call runs the tool again, even if this one was cancelled
+        self.in_flight.lock().remove(&self.key);
+    }
+}
+
+/// Wait for the result of the leader, `None` if it was cancelled before it's done
+pub(crate) async fn follow(
+    mut receiver: watch::Receiver<Option<CallResult>>,
+) -> Option<CallResult> {
+    receiver
+        .wait_for(Option::is_some)
+        .await
+        .ok()
+        .and_then(|result| result.clone())
+}
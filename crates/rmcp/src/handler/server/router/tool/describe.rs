@@ -0,0 +1,133 @@
+use std::fmt;
+
+use serde_json::Value;
+
+use crate::{
+    handler::server::common::resolve_ref,
+    model::{JsonObject, Tool},
+};
+
+/// A short description of a tool, see [`ToolRouter::summaries`](super::ToolRouter::summaries)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolSummary {
+    pub name: String,
+    pub description: Option<String>,
+    pub parameters: Vec<ParameterSummary>,
+}
+
+/// A parameter of a [`ToolSummary`], as found in the input schema of the tool
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParameterSummary {
+    pub name: String,
+    /// The JSON type, like `string`, `integer | string` or `string[]`, `None` if the schema
+    /// doesn't say
+    pub type_name: Option<String>,
+    pub required: bool,
+    pub description: Option<String>,
+}
+
+impl From<&Tool> for ToolSummary {
+    fn from(tool: &Tool) -> Self {
+        let schema = tool.input_schema.as_ref();
+        let required: Vec<&str> = schema
+            .get("required")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+            .collect();
+        let parameters = schema
+            .get("properties")
+            .and_then(Value::as_object)
+            .into_iter()
+            .flatten()
+            .map(|(name, property)| ParameterSummary {
+                name: name.clone(),
+                type_name: type_name(schema, property),
+                required: required.contains(&name.as_str()),
+                description: property
+                    .get("description")
+                    .and_then(Value::as_str)
+                    .map(str::to_owned),
+            })
+            .collect();
+        Self {
+            name: tool.name.to_string(),
+            description: tool.description.as_deref().map(str::to_owned),
+            parameters,
+        }
+    }
+}
+
+/// One line for the tool, followed by an indented line per parameter:
+///
+/// ```text
+/// get_movie_detail_info - Get movie details based on the movie ID
+///     movie_id: integer (required) - The ID of the movie
+/// ```
+impl fmt::Display for ToolSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        if let Some(description) = &self.description {
+            write!(f, " - {description}")?;
+        }
+        for parameter in &self.parameters {
+            write!(f, "\n    {parameter}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ParameterSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        if let Some(type_name) = &self.type_name {
+            write!(f, ": {type_name}")?;
+        }
+        if self.required {
+            write!(f, " (required)")?;
+        }
+        if let Some(description) = &self.description {
+            write!(f, " - {description}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The type of a property, `null` left out as it only marks the optional ones
+fn type_name(root: &JsonObject, property: &Value) -> Option<String> {
+    if let Some(reference) = property.get("$ref").and_then(Value::as_str) {
+        let name = reference.rsplit('/').next().unwrap_or(reference);
+        // a local definition is named after the rust type, prefer its JSON type if it's simple
+        return resolve_ref(root, reference)
+            .and_then(|definition| type_name(root, definition))
+            .filter(|type_name| type_name != "object")
+            .or_else(|| Some(name.to_owned()));
+    }
+    let types: Vec<String> = match property.get("type") {
+        Some(Value::String(kind)) => vec![kind.clone()],
+        Some(Value::Array(kinds)) => kinds
+            .iter()
+            .filter_map(Value::as_str)
+            .map(str::to_owned)
+            .collect(),
+        _ => ["anyOf", "oneOf"]
+            .iter()
+            .filter_map(|key| property.get(*key).and_then(Value::as_array))
+            .flatten()
+            .filter_map(|variant| type_name(root, variant))
+            .collect(),
+    };
+    let types: Vec<String> = types
+        .into_iter()
+        .filter(|kind| kind != "null")
+        .map(|kind| match (kind.as_str(), property.get("items")) {
+            ("array", Some(items)) => match type_name(root, items) {
+                Some(item) if !item.contains(' ') => format!("{item}[]"),
+                _ => kind,
+            },
+            _ => kind,
+        })
+        .collect();
+    (!types.is_empty()).then(|| types.join(" | "))
+}
@@ -0,0 +1,48 @@
+use std::sync::Arc;
+
+use crate::{
+    RoleServer,
+    model::{CallToolResult, Tool},
+    service::RequestContext,
+};
+
+/// What a [`ToolPostProcessor`] returns, the result passed to the next one or sent as is
+#[derive(Debug, Clone, PartialEq)]
+pub enum PostProcess {
+    /// Pass the result to the next post-processor
+    Continue(CallToolResult),
+    /// Send the result to the client, skipping the remaining post-processors
+    Stop(CallToolResult),
+}
+
+/// A hook transforming the results of the tools, like a redaction, a truncation or a
+/// localization, see [`ToolRouter::with_post_processor`](super::ToolRouter::with_post_processor)
+#[derive(Clone)]
+pub struct ToolPostProcessor(
+    #[allow(clippy::type_complexity)]
+    pub  Arc<
+        dyn Fn(&Tool, &RequestContext<RoleServer>, CallToolResult) -> PostProcess + Send + Sync,
+    >,
+);
+
+impl std::fmt::Debug for ToolPostProcessor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("ToolPostProcessor").finish_non_exhaustive()
+    }
+}
+
+/// Run `result` through `post_processors` in order, until one of them stops
+pub(crate) fn post_process(
+    post_processors: &[ToolPostProcessor],
+    tool: &Tool,
+    context: &RequestContext<RoleServer>,
+    mut result: CallToolResult,
+) -> CallToolResult {
+    for post_processor in post_processors {
+        match (post_processor.0)(tool, context, result) {
+            PostProcess::Continue(next) => result = next,
+            PostProcess::Stop(last) => return last,
+        }
+    }
+    result
+}
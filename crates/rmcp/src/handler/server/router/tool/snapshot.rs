@@ -0,0 +1,110 @@
+use std::{collections::BTreeMap, fmt};
+
+use crate::model::Tool;
+
+/// The enabled tools of a router at some point, see [`ToolRouter::snapshot`](super::ToolRouter::snapshot)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ToolSnapshot {
+    tools: BTreeMap<String, Tool>,
+}
+
+impl ToolSnapshot {
+    /// The tools, sorted by name
+    pub fn tools(&self) -> impl Iterator<Item = &Tool> {
+        self.tools.values()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Tool> {
+        self.tools.get(name)
+    }
+
+    pub fn len(&self) -> usize {
+        self.tools.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tools.is_empty()
+    }
+
+    /// What changed from this snapshot to `new`, a tool is modified when any of its fields
+    /// changed, like its description, schemas or annotations.
+    ///
+    /// ```rust,ignore
+    /// let old = router.snapshot();
+    /// router.remove_route("get_movie_list");
+    /// router.add_route(get_cinema_list_route());
+    /// let diff = old.diff(&router.snapshot());
+    /// tracing::info!(%diff, "tools changed"); // added: get_cinema_list; removed: get_movie_list
+    /// ```
+    pub fn diff(&self, new: &ToolSnapshot) -> ToolDiff {
+        let mut diff = ToolDiff::default();
+        for (name, tool) in &new.tools {
+            match self.tools.get(name) {
+                None => diff.added.push(tool.clone()),
+                Some(old) if old != tool => diff.modified.push(tool.clone()),
+                Some(_) => {}
+            }
+        }
+        diff.removed = self
+            .tools
+            .keys()
+            .filter(|name| !new.tools.contains_key(*name))
+            .cloned()
+            .collect();
+        diff
+    }
+}
+
+impl FromIterator<Tool> for ToolSnapshot {
+    fn from_iter<I: IntoIterator<Item = Tool>>(tools: I) -> Self {
+        Self {
+            tools: tools
+                .into_iter()
+                .map(|tool| (tool.name.to_string(), tool))
+                .collect(),
+        }
+    }
+}
+
+/// The tools changed between two [`ToolSnapshot`]s, each list sorted by name
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ToolDiff {
+    pub added: Vec<Tool>,
+    /// The names of the removed tools
+    pub removed: Vec<String>,
+    /// The new version of the modified tools
+    pub modified: Vec<Tool>,
+}
+
+impl ToolDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+}
+
+/// The names of the changed tools, for logs:
+///
+/// ```text
+/// added: get_cinema_list; removed: get_movie_list; modified: get_movie_detail_info
+/// ```
+impl fmt::Display for ToolDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "unchanged");
+        }
+        let changes = [
+            ("added", self.added.iter().map(|tool| &*tool.name).collect()),
+            ("removed", self.removed.iter().map(String::as_str).collect()),
+            (
+                "modified",
+                self.modified.iter().map(|tool| &*tool.name).collect(),
+            ),
+        ];
+        let changes: Vec<String> = changes
+            .into_iter()
+            .filter(|(_, names): &(_, Vec<&str>)| !names.is_empty())
+            .map(|(change, names)| format!("{change}: {}", names.join(", ")))
+            .collect();
+        write!(f, "{}", changes.join("; "))
+    }
+}
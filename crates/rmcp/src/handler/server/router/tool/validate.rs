@@ -0,0 +1,180 @@
+use serde_json::Value;
+
+use crate::{ErrorData, handler::server::common::resolve_ref, model::JsonObject};
+
+/// Check the arguments of a tool call against the constraints of its input schema.
+///
+/// Only the constraints `schemars` derives from `#[validate(...)]` and `#[schemars(...)]`
+/// attributes are checked: `minimum`, `maximum`, `exclusiveMinimum`, `exclusiveMaximum`,
+/// `minLength`, `maxLength`, `pattern`, `minItems` and `maxItems`, following `properties`,
+/// `items`, `anyOf` and local `$ref`s. Types and required fields are left to deserialization.
+pub(crate) fn validate_arguments(
+    schema: &JsonObject,
+    arguments: Option<&JsonObject>,
+) -> Result<(), ErrorData> {
+    let Some(arguments) = arguments else {
+        return Ok(());
+    };
+    let mut path = Vec::new();
+    check_object(schema, schema, arguments, &mut path).map_err(|message| {
+        let path = path.join(".");
+        ErrorData::invalid_params(
+            format!("invalid argument `{path}`: {message}"),
+            Some(serde_json::json!({ "path": path })),
+        )
+    })
+}
+
+fn check_object(
+    root: &JsonObject,
+    schema: &JsonObject,
+    object: &JsonObject,
+    path: &mut Vec<String>,
+) -> Result<(), String> {
+    let Some(properties) = schema.get("properties").and_then(Value::as_object) else {
+        return Ok(());
+    };
+    for (name, value) in object {
+        if let Some(property) = properties.get(name).and_then(Value::as_object) {
+            path.push(name.clone());
+            check_value(root, property, value, path)?;
+            path.pop();
+        }
+    }
+    Ok(())
+}
+
+fn check_value(
+    root: &JsonObject,
+    schema: &JsonObject,
+    value: &Value,
+    path: &mut Vec<String>,
+) -> Result<(), String> {
+    let schema = resolve(root, schema);
+    let bound = |keyword: &str| schema.get(keyword).and_then(Value::as_f64);
+    let count = |keyword: &str| schema.get(keyword).and_then(Value::as_u64);
+    match value {
+        Value::Number(number) => {
+            let number = number.as_f64().unwrap_or_default();
+            if let Some(minimum) = bound("minimum").filter(|minimum| number < *minimum) {
+                return Err(format!("must be at least {minimum}"));
+            }
+            if let Some(maximum) = bound("maximum").filter(|maximum| number > *maximum) {
+                return Err(format!("must be at most {maximum}"));
+            }
+            if let Some(minimum) = bound("exclusiveMinimum").filter(|minimum| number <= *minimum) {
+                return Err(format!("must be greater than {minimum}"));
+            }
+            if let Some(maximum) = bound("exclusiveMaximum").filter(|maximum| number >= *maximum) {
+                return Err(format!("must be less than {maximum}"));
+            }
+        }
+        Value::String(string) => {
+            let length = string.chars().count() as u64;
+            if let Some(min) = count("minLength").filter(|min| length < *min) {
+                return Err(format!("must be at least {min} characters long"));
+            }
+            if let Some(max) = count("maxLength").filter(|max| length > *max) {
+                return Err(format!("must be at most {max} characters long"));
+            }
+            if let Some(pattern) = schema.get("pattern").and_then(Value::as_str) {
+                // a pattern the server got wrong can't be enforced, the call goes on
+                match regex::Regex::new(pattern) {
+                    Ok(regex) if !regex.is_match(string) => {
+                        return Err(format!("must match the pattern `{pattern}`"));
+                    }
+                    Ok(_) => {}
+                    Err(error) => {
+                        tracing::warn!(%error, pattern, "invalid pattern in a tool schema")
+                    }
+                }
+            }
+        }
+        Value::Array(items) => {
+            let length = items.len() as u64;
+            if let Some(min) = count("minItems").filter(|min| length < *min) {
+                return Err(format!("must have at least {min} items"));
+            }
+            if let Some(max) = count("maxItems").filter(|max| length > *max) {
+                return Err(format!("must have at most {max} items"));
+            }
+            if let Some(item_schema) = schema.get("items").and_then(Value::as_object) {
+                for (index, item) in items.iter().enumerate() {
+                    path.push(index.to_string());
+                    check_value(root, item_schema, item, path)?;
+                    path.pop();
+                }
+            }
+        }
+        Value::Object(object) => check_object(root, schema, object, path)?,
+        Value::Null | Value::Bool(_) => {}
+    }
+    if let Some(variants) = schema.get("anyOf").and_then(Value::as_array) {
+        check_any_of(root, variants, value, path)?;
+    }
+    Ok(())
+}
+
+/// Check that `value` matches one of the `variants` of its kind, or fail with the error of the
+/// first one. When no variant is of its kind, it's left to deserialization.
+fn check_any_of(
+    root: &JsonObject,
+    variants: &[Value],
+    value: &Value,
+    path: &mut Vec<String>,
+) -> Result<(), String> {
+    let depth = path.len();
+    let mut first_error = None;
+    let variants = variants
+        .iter()
+        .filter_map(Value::as_object)
+        .filter(|variant| is_of_kind(resolve(root, variant), value));
+    for variant in variants {
+        match check_value(root, variant, value, path) {
+            Ok(()) => {
+                path.truncate(depth);
+                return Ok(());
+            }
+            Err(error) => {
+                first_error.get_or_insert_with(|| (error, path.clone()));
+                path.truncate(depth);
+            }
+        }
+    }
+    match first_error {
+        Some((error, error_path)) => {
+            *path = error_path;
+            Err(error)
+        }
+        None => Ok(()),
+    }
+}
+
+/// Whether `value` has the `type` and `const` of `schema`, if it says
+fn is_of_kind(schema: &JsonObject, value: &Value) -> bool {
+    if let Some(constant) = schema.get("const") {
+        return constant == value;
+    }
+    let is_type = |kind: &str| match (kind, value) {
+        ("null", Value::Null) | ("boolean", Value::Bool(_)) | ("number", Value::Number(_)) => true,
+        ("integer", Value::Number(number)) => number.is_i64() || number.is_u64(),
+        ("string", Value::String(_)) | ("array", Value::Array(_)) => true,
+        ("object", Value::Object(_)) => true,
+        _ => false,
+    };
+    match schema.get("type") {
+        Some(Value::String(kind)) => is_type(kind),
+        Some(Value::Array(kinds)) => kinds.iter().filter_map(Value::as_str).any(is_type),
+        _ => true,
+    }
+}
+
+/// Follow a local `$ref` like `#/definitions/Location`, or return the schema as is
+fn resolve<'a>(root: &'a JsonObject, schema: &'a JsonObject) -> &'a JsonObject {
+    schema
+        .get("$ref")
+        .and_then(Value::as_str)
+        .and_then(|reference| resolve_ref(root, reference))
+        .and_then(Value::as_object)
+        .unwrap_or(schema)
+}
@@ -4,18 +4,27 @@ use std::{
     marker::PhantomData,
 };
 
-use futures::future::{BoxFuture, FutureExt};
+use futures::{
+    Stream, StreamExt,
+    future::{BoxFuture, FutureExt},
+};
 use serde::de::DeserializeOwned;
 
 use super::common::{AsRequestContext, FromContextPart};
 pub use super::{
-    common::{Extension, RequestId, cached_schema_for_type, schema_for_type},
+    common::{
+        Accept, DryRun, Extension, FromRequestContext, Locale, RequestId, cached_schema_for_type,
+        schema_for_type,
+    },
     router::tool::{ToolRoute, ToolRouter},
 };
 use crate::{
-    RoleServer,
+    Peer, RoleServer,
     handler::server::wrapper::Parameters,
-    model::{CallToolRequestParam, CallToolResult, IntoContents, JsonObject},
+    model::{
+        CallToolRequestParam, CallToolResult, Content, IntoContents, JsonObject, ProgressToken,
+        ToolPartialResultNotificationParam,
+    },
     service::RequestContext,
 };
 
@@ -85,6 +94,18 @@ impl<T: IntoContents, E: IntoContents> IntoCallToolResult for Result<T, E> {
     }
 }
 
+impl IntoCallToolResult for crate::model::PartialResult {
+    fn into_call_tool_result(self) -> Result<CallToolResult, crate::ErrorData> {
+        Ok(self.into())
+    }
+}
+
+impl IntoCallToolResult for crate::model::Table {
+    fn into_call_tool_result(self) -> Result<CallToolResult, crate::ErrorData> {
+        Ok(self.into())
+    }
+}
+
 impl<T: IntoCallToolResult> IntoCallToolResult for Result<T, crate::ErrorData> {
     fn into_call_tool_result(self) -> Result<CallToolResult, crate::ErrorData> {
         match self {
@@ -181,6 +202,74 @@ impl<S> FromContextPart<ToolCallContext<'_, S>> for JsonObject {
     }
 }
 
+/// Experimental: drives the content stream of a `#[tool(streaming)]` tool.
+///
+/// If the client opted in with [`Meta::set_partial_results`](crate::model::Meta::set_partial_results),
+/// every chunk is sent as a partial result notification, followed by one marked `done`.
+/// The aggregated content is always returned as the tool result.
+pub struct StreamingToolContext {
+    peer: Peer<RoleServer>,
+    progress_token: Option<ProgressToken>,
+}
+
+impl StreamingToolContext {
+    pub async fn collect<St>(self, stream: St) -> Result<CallToolResult, crate::ErrorData>
+    where
+        St: Stream<Item = Content> + Send,
+    {
+        let Self {
+            peer,
+            mut progress_token,
+        } = self;
+        let mut stream = std::pin::pin!(stream);
+        let mut contents = Vec::new();
+        while let Some(content) = stream.next().await {
+            if let Some(token) = &progress_token {
+                let result = peer
+                    .notify_tool_partial_result(ToolPartialResultNotificationParam {
+                        progress_token: token.clone(),
+                        content: vec![content.clone()],
+                        done: false,
+                    })
+                    .await;
+                if let Err(error) = result {
+                    tracing::warn!(%error, "fail to send partial tool result, stop streaming");
+                    progress_token = None;
+                }
+            }
+            contents.push(content);
+        }
+        if let Some(progress_token) = progress_token {
+            let result = peer
+                .notify_tool_partial_result(ToolPartialResultNotificationParam {
+                    progress_token,
+                    content: Vec::new(),
+                    done: true,
+                })
+                .await;
+            if let Err(error) = result {
+                tracing::warn!(%error, "fail to send partial tool result completion");
+            }
+        }
+        Ok(CallToolResult::success(contents))
+    }
+}
+
+impl<S> FromContextPart<ToolCallContext<'_, S>> for StreamingToolContext {
+    fn from_context_part(context: &mut ToolCallContext<S>) -> Result<Self, crate::ErrorData> {
+        let request_context = context.request_context();
+        let progress_token = request_context
+            .meta
+            .partial_results()
+            .then(|| request_context.meta.get_progress_token())
+            .flatten();
+        Ok(Self {
+            peer: request_context.peer.clone(),
+            progress_token,
+        })
+    }
+}
+
 impl<'s, S> ToolCallContext<'s, S> {
     pub fn invoke<H, A>(self, h: H) -> BoxFuture<'s, Result<CallToolResult, crate::ErrorData>>
     where
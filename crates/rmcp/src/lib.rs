@@ -163,6 +163,9 @@ pub use service::{RoleServer, serve_server};
 
 pub mod handler;
 pub mod transport;
+#[cfg(feature = "transport-auto")]
+#[cfg_attr(docsrs, doc(cfg(feature = "transport-auto")))]
+pub use transport::auto::serve_auto;
 
 // re-export
 #[cfg(all(feature = "macros", feature = "server"))]
@@ -2,21 +2,29 @@ use std::{borrow::Cow, sync::Arc};
 mod annotated;
 mod capabilities;
 mod content;
+mod envelope;
 mod extension;
 mod meta;
+mod partial_result;
 mod prompt;
+mod representation;
 mod resource;
 mod serde_impl;
+mod table;
 mod tool;
 pub use annotated::*;
 pub use capabilities::*;
 pub use content::*;
+pub use envelope::*;
 pub use extension::*;
 pub use meta::*;
+pub use partial_result::*;
 pub use prompt::*;
+pub use representation::*;
 pub use resource::*;
 use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use serde_json::Value;
+pub use table::*;
 pub use tool::*;
 
 /// A JSON object type alias for convenient handling of JSON data.
@@ -271,6 +279,9 @@ impl schemars::JsonSchema for NumberOrString {
 }
 
 /// Type alias for request identifiers used in JSON-RPC communication.
+///
+/// The id of a request is echoed as received in its response: a string id stays a string,
+/// even a numeric one like `"42"`, so that strict clients can match the response.
 pub type RequestId = NumberOrString;
 
 /// A token used to track the progress of long-running operations.
@@ -442,7 +453,10 @@ pub struct JsonRpcNotification<N = Notification> {
 pub struct ErrorCode(pub i32);
 
 impl ErrorCode {
+    pub const REQUEST_TIMEOUT: Self = Self(-32001);
     pub const RESOURCE_NOT_FOUND: Self = Self(-32002);
+    /// The request can't be handled for now, see [`ErrorData::unavailable`]
+    pub const UNAVAILABLE: Self = Self(-32003);
     pub const INVALID_REQUEST: Self = Self(-32600);
     pub const METHOD_NOT_FOUND: Self = Self(-32601);
     pub const INVALID_PARAMS: Self = Self(-32602);
@@ -450,6 +464,12 @@ impl ErrorCode {
     pub const PARSE_ERROR: Self = Self(-32700);
 }
 
+impl From<i32> for ErrorCode {
+    fn from(code: i32) -> Self {
+        Self(code)
+    }
+}
+
 /// Error information for JSON-RPC error responses.
 ///
 /// This structure follows the JSON-RPC 2.0 specification for error reporting,
@@ -499,6 +519,14 @@ impl ErrorData {
     pub fn internal_error(message: impl Into<Cow<'static, str>>, data: Option<Value>) -> Self {
         Self::new(ErrorCode::INTERNAL_ERROR, message, data)
     }
+    pub fn request_timeout(message: impl Into<Cow<'static, str>>, data: Option<Value>) -> Self {
+        Self::new(ErrorCode::REQUEST_TIMEOUT, message, data)
+    }
+    /// The request can't be handled for now but may be later, like when a service the server
+    /// depends on is temporarily down. It's marked retryable, see [`ErrorData::is_retryable`].
+    pub fn unavailable(message: impl Into<Cow<'static, str>>, data: Option<Value>) -> Self {
+        Self::new(ErrorCode::UNAVAILABLE, message, data).with_retryable(true)
+    }
 }
 
 /// Represents any JSON-RPC message that can be sent or received.
@@ -625,6 +653,22 @@ const_string!(CancelledNotificationMethod = "notifications/cancelled");
 pub type CancelledNotification =
     Notification<CancelledNotificationMethod, CancelledNotificationParam>;
 
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct SessionClosedNotificationParam {
+    pub reason: String,
+}
+
+const_string!(SessionClosedNotificationMethod = "notifications/rmcp/session_closed");
+
+/// # Session Closed
+/// This notification can be sent by either side right before closing the session, to tell the other side why.
+///
+/// Requests still in-flight on either side fail with [`ServiceError::SessionClosed`](crate::ServiceError::SessionClosed).
+pub type SessionClosedNotification =
+    Notification<SessionClosedNotificationMethod, SessionClosedNotificationParam>;
+
 const_string!(InitializeResultMethod = "initialize");
 /// # Initialization
 /// This request is sent from the client to the server when it first connects, asking it to begin initialization.
@@ -695,6 +739,22 @@ impl Default for ClientInfo {
     }
 }
 
+impl ClientInfo {
+    /// Advertise the `capabilities` of the client, like sampling or elicitation, so that servers
+    /// know which requests it answers:
+    ///
+    /// ```rust
+    /// # use rmcp::model::{ClientCapabilities, ClientInfo};
+    /// let info = ClientInfo::default()
+    ///     .with_capabilities(ClientCapabilities::builder().enable_sampling());
+    /// assert!(info.capabilities.sampling.is_some());
+    /// ```
+    pub fn with_capabilities(mut self, capabilities: impl Into<ClientCapabilities>) -> Self {
+        self.capabilities = capabilities.into();
+        self
+    }
+}
+
 /// A URL pointing to an icon resource or a base64-encoded data URI.
 ///
 /// Clients that support rendering icons MUST support at least the following MIME types:
@@ -795,6 +855,9 @@ macro_rules! paginated_result {
             #[serde(skip_serializing_if = "Option::is_none")]
             pub next_cursor: Option<Cursor>,
             pub $i_item: $t_item,
+            /// Optional protocol-level metadata for this result
+            #[serde(rename = "_meta", skip_serializing_if = "Option::is_none")]
+            pub meta: Option<Meta>,
         }
 
         impl $t {
@@ -804,6 +867,7 @@ macro_rules! paginated_result {
                 Self {
                     next_cursor: None,
                     $i_item: items,
+                    meta: None,
                 }
             }
         }
@@ -843,6 +907,12 @@ pub struct ReadResourceRequestParam {
 }
 
 /// Result containing the contents of a read resource
+///
+/// A single URI can map to several contents, e.g. a cinema whose metadata is a JSON text and
+/// whose poster is a blob. They are alternative or complementary views of the same resource, not
+/// an ordered sequence: clients should pick the ones they need by mime type (and by `uri`, which
+/// may name a sub-resource of the requested one) rather than by position. Chunked blobs are the
+/// exception, see [`RequestContext::stream_blob`](crate::service::RequestContext::stream_blob).
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ReadResourceResult {
@@ -850,6 +920,18 @@ pub struct ReadResourceResult {
     pub contents: Vec<ResourceContents>,
 }
 
+impl ReadResourceResult {
+    /// The contents with the given mime type
+    pub fn contents_with_mime_type<'a>(
+        &'a self,
+        mime_type: &'a str,
+    ) -> impl Iterator<Item = &'a ResourceContents> + 'a {
+        self.contents
+            .iter()
+            .filter(move |contents| contents.mime_type() == Some(mime_type))
+    }
+}
+
 /// Request to read a specific resource
 pub type ReadResourceRequest = Request<ReadResourceRequestMethod, ReadResourceRequestParam>;
 
@@ -928,12 +1010,85 @@ const_string!(ToolListChangedNotificationMethod = "notifications/tools/list_chan
 /// Notification sent when the list of available tools changes
 pub type ToolListChangedNotification = NotificationNoParam<ToolListChangedNotificationMethod>;
 
+const_string!(ToolPartialResultNotificationMethod = "notifications/rmcp/tool_partial_result");
+/// Experimental: a chunk of content produced by a streaming tool.
+///
+/// Only sent when the client opted in with [`Meta::set_partial_results`] on the `tools/call` request.
+/// The chunks are correlated with the request by its progress token, and the aggregated result is
+/// still sent as the response after the notification marked `done`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ToolPartialResultNotificationParam {
+    pub progress_token: ProgressToken,
+    /// The content produced since the previous notification
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub content: Vec<Content>,
+    /// Whether this is the final notification of the stream
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub done: bool,
+}
+/// Experimental notification carrying partial content of a streaming tool call
+pub type ToolPartialResultNotification =
+    Notification<ToolPartialResultNotificationMethod, ToolPartialResultNotificationParam>;
+
+const_string!(ResourceChunkNotificationMethod = "notifications/rmcp/resource_chunk");
+/// Experimental: a chunk of a blob resource, sent while it's read.
+///
+/// Only sent when the client opted in with [`Meta::set_partial_results`] on the `resources/read`
+/// request. The chunks are correlated with the request by its progress token, and sent in order
+/// before the response, see [`RequestContext::stream_blob`](crate::service::RequestContext::stream_blob).
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ResourceChunkNotificationParam {
+    pub progress_token: ProgressToken,
+    pub uri: String,
+    /// The position of the chunk in the blob, starting at 0
+    pub index: usize,
+    /// The bytes of the chunk, base64 encoded
+    pub blob: String,
+}
+/// Experimental notification carrying a chunk of a blob resource
+pub type ResourceChunkNotification =
+    Notification<ResourceChunkNotificationMethod, ResourceChunkNotificationParam>;
+
+const_string!(ConfigChangedNotificationMethod = "notifications/rmcp/config_changed");
+/// Experimental: a setting of the server changed, like a default city the results depend on.
+///
+/// This is an extension of rmcp, not a method of the MCP specification, hence the `rmcp`
+/// namespace in the method. The shape of `config` is up to the server, see
+/// [`ConfigChangedNotificationParam::config_as`] to read it as a typed value.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ConfigChangedNotificationParam {
+    /// The changed settings
+    pub config: Value,
+}
+
+impl ConfigChangedNotificationParam {
+    pub fn new(config: impl Into<Value>) -> Self {
+        Self {
+            config: config.into(),
+        }
+    }
+
+    /// Deserialize the changed settings into `T`
+    pub fn config_as<T: DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        T::deserialize(&self.config)
+    }
+}
+/// Experimental notification telling the client that a setting of the server changed
+pub type ConfigChangedNotification =
+    Notification<ConfigChangedNotificationMethod, ConfigChangedNotificationParam>;
+
 // =============================================================================
 // LOGGING
 // =============================================================================
 
-/// Logging levels supported by the MCP protocol
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Copy)]
+/// Logging levels supported by the MCP protocol, ordered by severity from `Debug` to `Emergency`
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Copy)]
 #[serde(rename_all = "lowercase")] //match spec
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub enum LoggingLevel {
@@ -1012,6 +1167,22 @@ pub struct SamplingMessage {
     pub content: Content,
 }
 
+impl SamplingMessage {
+    pub fn user_text(text: impl Into<String>) -> Self {
+        Self {
+            role: Role::User,
+            content: Content::text(text),
+        }
+    }
+
+    pub fn assistant_text(text: impl Into<String>) -> Self {
+        Self {
+            role: Role::Assistant,
+            content: Content::text(text),
+        }
+    }
+}
+
 /// Specifies how much context should be included in sampling requests.
 ///
 /// This allows clients to control what additional context information
@@ -1063,12 +1234,87 @@ pub struct CreateMessageRequestParam {
     pub metadata: Option<Value>,
 }
 
+impl CreateMessageRequestParam {
+    /// Ask the client to sample `messages`, generating at most `max_tokens`, the other fields
+    /// are set with the `with_*` methods:
+    ///
+    /// ```rust
+    /// # use rmcp::model::*;
+    /// let params = CreateMessageRequestParam::new(
+    ///     vec![SamplingMessage::user_text("Summarize The Wandering Earth")],
+    ///     100,
+    /// )
+    /// .with_system_prompt("Answer in at most three sentences, without spoilers.")
+    /// .with_model_preferences(
+    ///     ModelPreferences::new()
+    ///         .with_hint("claude")
+    ///         .with_speed_priority(0.8),
+    /// )
+    /// .with_stop_sequences(["\n\n"]);
+    /// assert_eq!(params.stop_sequences, Some(vec!["\n\n".to_string()]));
+    /// ```
+    pub fn new(messages: Vec<SamplingMessage>, max_tokens: u32) -> Self {
+        Self {
+            messages,
+            model_preferences: None,
+            system_prompt: None,
+            include_context: None,
+            temperature: None,
+            max_tokens,
+            stop_sequences: None,
+            metadata: None,
+        }
+    }
+
+    pub fn with_model_preferences(mut self, model_preferences: ModelPreferences) -> Self {
+        self.model_preferences = Some(model_preferences);
+        self
+    }
+
+    pub fn with_system_prompt(mut self, system_prompt: impl Into<String>) -> Self {
+        self.system_prompt = Some(system_prompt.into());
+        self
+    }
+
+    pub fn with_include_context(mut self, include_context: ContextInclusion) -> Self {
+        self.include_context = Some(include_context);
+        self
+    }
+
+    pub fn with_temperature(mut self, temperature: f32) -> Self {
+        self.temperature = Some(temperature);
+        self
+    }
+
+    pub fn with_max_tokens(mut self, max_tokens: u32) -> Self {
+        self.max_tokens = max_tokens;
+        self
+    }
+
+    /// Add sequences stopping the generation, after the ones already set
+    pub fn with_stop_sequences<I>(mut self, stop_sequences: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.stop_sequences
+            .get_or_insert_default()
+            .extend(stop_sequences.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn with_metadata(mut self, metadata: impl Into<Value>) -> Self {
+        self.metadata = Some(metadata.into());
+        self
+    }
+}
+
 /// Preferences for model selection and behavior in sampling requests.
 ///
 /// This allows servers to express their preferences for which model to use
 /// and how to balance different priorities when the client has multiple
 /// model options available.
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ModelPreferences {
@@ -1086,6 +1332,35 @@ pub struct ModelPreferences {
     pub intelligence_priority: Option<f32>,
 }
 
+impl ModelPreferences {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a hint, the hints are evaluated in order by the client
+    pub fn with_hint(mut self, name: impl Into<String>) -> Self {
+        self.hints
+            .get_or_insert_default()
+            .push(ModelHint::new(name));
+        self
+    }
+
+    pub fn with_cost_priority(mut self, cost_priority: f32) -> Self {
+        self.cost_priority = Some(cost_priority);
+        self
+    }
+
+    pub fn with_speed_priority(mut self, speed_priority: f32) -> Self {
+        self.speed_priority = Some(speed_priority);
+        self
+    }
+
+    pub fn with_intelligence_priority(mut self, intelligence_priority: f32) -> Self {
+        self.intelligence_priority = Some(intelligence_priority);
+        self
+    }
+}
+
 /// A hint suggesting a preferred model name or family.
 ///
 /// Model hints are advisory suggestions that help clients choose appropriate
@@ -1098,6 +1373,14 @@ pub struct ModelHint {
     pub name: Option<String>,
 }
 
+impl ModelHint {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: Some(name.into()),
+        }
+    }
+}
+
 // =============================================================================
 // COMPLETION AND AUTOCOMPLETE
 // =============================================================================
@@ -1441,6 +1724,24 @@ pub struct CallToolResult {
 }
 
 impl CallToolResult {
+    /// Build a result by adding its content one part at a time.
+    ///
+    /// ```
+    /// use rmcp::model::{CallToolResult, Content};
+    /// use serde_json::json;
+    ///
+    /// let sold_out = true;
+    /// let result = CallToolResult::builder()
+    ///     .text("万达影城: 19:30")
+    ///     .json(json!({ "cinema": "万达影城", "time": "19:30" }))
+    ///     .error_if(sold_out)
+    ///     .build();
+    /// assert_eq!(result.content.len(), 2);
+    /// assert_eq!(result.is_error, Some(true));
+    /// ```
+    pub fn builder() -> CallToolResultBuilder {
+        CallToolResultBuilder::default()
+    }
     /// Create a successful tool result with unstructured content
     pub fn success(content: Vec<Content>) -> Self {
         CallToolResult {
@@ -1450,6 +1751,15 @@ impl CallToolResult {
             meta: None,
         }
     }
+    /// Create a successful tool result without any output, for the tools only performing an
+    /// action, like refreshing a cache.
+    ///
+    /// It's serialized as `{"content": [], "isError": false}`, and tells the model the action
+    /// succeeded. If the action failed, return an [error](CallToolResult::error) explaining why
+    /// instead, as an empty result is never one.
+    pub fn empty() -> Self {
+        CallToolResult::success(Vec::new())
+    }
     /// Create an error tool result with unstructured content
     pub fn error(content: Vec<Content>) -> Self {
         CallToolResult {
@@ -1475,7 +1785,7 @@ impl CallToolResult {
     /// ```
     pub fn structured(value: Value) -> Self {
         CallToolResult {
-            content: vec![Content::text(value.to_string())],
+            content: vec![RawContent::json_text(value.to_string()).no_annotation()],
             structured_content: Some(value),
             is_error: Some(false),
             meta: None,
@@ -1501,13 +1811,56 @@ impl CallToolResult {
     /// ```
     pub fn structured_error(value: Value) -> Self {
         CallToolResult {
-            content: vec![Content::text(value.to_string())],
+            content: vec![RawContent::json_text(value.to_string()).no_annotation()],
             structured_content: Some(value),
             is_error: Some(true),
             meta: None,
         }
     }
 
+    /// Create an error tool result with a machine readable code, like `NO_CINEMAS_NEARBY`, along with
+    /// the content explaining it.
+    ///
+    /// Unlike the code of a JSON-RPC error, it's a business error of the tool, so the result still
+    /// goes to the model. The code is carried in the `rmcp/errorCode` field of `_meta`, see
+    /// [`CallToolResult::error_code`].
+    pub fn error_with_code(code: impl Into<String>, content: Vec<Content>) -> Self {
+        let mut meta = Meta::new();
+        meta.set_error_code(code);
+        CallToolResult::error(content).with_meta(meta)
+    }
+
+    /// The machine readable code of an error result, see [`CallToolResult::error_with_code`].
+    ///
+    /// Returns `None` for a successful result, even if it has a code.
+    pub fn error_code(&self) -> Option<&str> {
+        if self.is_error != Some(true) {
+            return None;
+        }
+        self.meta.as_ref()?.error_code()
+    }
+
+    /// Whether this is a successful result without any output, see [`CallToolResult::empty`]
+    pub fn is_empty(&self) -> bool {
+        self.is_error != Some(true) && self.content.is_empty() && self.structured_content.is_none()
+    }
+
+    /// The length in bytes of this result serialized as JSON, to check it against an output budget
+    /// before returning it.
+    ///
+    /// The bytes are counted as they are serialized, without building the JSON string.
+    pub fn byte_len(&self) -> usize {
+        content::serialized_len(self)
+    }
+
+    /// Attach protocol-level metadata to this result, e.g. cache hints or upstream request ids.
+    ///
+    /// The fields are merged into the existing `_meta`, replacing the ones with the same key.
+    pub fn with_meta(mut self, meta: Meta) -> Self {
+        self.meta.get_or_insert_with(Meta::new).extend(meta);
+        self
+    }
+
     /// Convert the `structured_content` part of response into a certain type.
     ///
     /// # About json schema validation
@@ -1537,6 +1890,64 @@ impl CallToolResult {
     }
 }
 
+/// A builder of [`CallToolResult`], see [`CallToolResult::builder`].
+///
+/// The result is successful unless [`error_if`](CallToolResultBuilder::error_if) is given `true`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CallToolResultBuilder {
+    content: Vec<Content>,
+    is_error: bool,
+    meta: Option<Meta>,
+}
+
+impl CallToolResultBuilder {
+    /// Add a text content
+    pub fn text(mut self, text: impl Into<String>) -> Self {
+        self.content.push(Content::text(text));
+        self
+    }
+
+    /// Add a JSON content, a text with the `application/json` mime type
+    pub fn json(mut self, value: Value) -> Self {
+        self.content
+            .push(RawContent::json_text(value.to_string()).no_annotation());
+        self
+    }
+
+    /// Add any content, like an image or a resource link
+    pub fn content(mut self, content: Content) -> Self {
+        self.content.push(content);
+        self
+    }
+
+    /// Mark the result as an error if `is_error` is `true`, the result stays an error once marked.
+    pub fn error_if(mut self, is_error: bool) -> Self {
+        self.is_error |= is_error;
+        self
+    }
+
+    /// Attach protocol-level metadata, see [`CallToolResult::with_meta`]
+    pub fn meta(mut self, meta: Meta) -> Self {
+        self.meta.get_or_insert_with(Meta::new).extend(meta);
+        self
+    }
+
+    pub fn build(self) -> CallToolResult {
+        CallToolResult {
+            content: self.content,
+            structured_content: None,
+            is_error: Some(self.is_error),
+            meta: self.meta,
+        }
+    }
+}
+
+impl From<CallToolResultBuilder> for CallToolResult {
+    fn from(builder: CallToolResultBuilder) -> Self {
+        builder.build()
+    }
+}
+
 // Custom deserialize implementation to validate mutual exclusivity
 impl<'de> Deserialize<'de> for CallToolResult {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
@@ -1558,21 +1969,20 @@ impl<'de> Deserialize<'de> for CallToolResult {
         }
 
         let helper = CallToolResultHelper::deserialize(deserializer)?;
-        let result = CallToolResult {
-            content: helper.content.unwrap_or_default(),
-            structured_content: helper.structured_content,
-            is_error: helper.is_error,
-            meta: helper.meta,
-        };
 
-        // Validate mutual exclusivity
-        if result.content.is_empty() && result.structured_content.is_none() {
+        // Validate mutual exclusivity, an empty content is a result without output
+        if helper.content.is_none() && helper.structured_content.is_none() {
             return Err(serde::de::Error::custom(
                 "CallToolResult must have either content or structured_content",
             ));
         }
 
-        Ok(result)
+        Ok(CallToolResult {
+            content: helper.content.unwrap_or_default(),
+            structured_content: helper.structured_content,
+            is_error: helper.is_error,
+            meta: helper.meta,
+        })
     }
 }
 
@@ -1690,6 +2100,121 @@ macro_rules! ts_union {
     (@impl_from $U: ident  { }) => {};
 }
 
+// =============================================================================
+// CUSTOM REQUESTS
+// =============================================================================
+
+/// The method of a [`CustomRequest`], like `x-movie/refresh-cache`.
+///
+/// It never deserializes from a [core method](CustomRequestMethod::CORE_METHODS), so a core
+/// request, even a malformed one, never reaches
+/// [`ServerHandler::on_custom_request`](crate::ServerHandler::on_custom_request).
+#[derive(Debug, Serialize, Clone, PartialEq, Eq, Hash)]
+#[serde(transparent)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct CustomRequestMethod(Cow<'static, str>);
+
+impl CustomRequestMethod {
+    /// The methods of the requests of the MCP specification
+    pub const CORE_METHODS: &[&str] = &[
+        PingRequestMethod::VALUE,
+        InitializeResultMethod::VALUE,
+        CompleteRequestMethod::VALUE,
+        SetLevelRequestMethod::VALUE,
+        GetPromptRequestMethod::VALUE,
+        ListPromptsRequestMethod::VALUE,
+        ListResourcesRequestMethod::VALUE,
+        ListResourceTemplatesRequestMethod::VALUE,
+        ReadResourceRequestMethod::VALUE,
+        SubscribeRequestMethod::VALUE,
+        UnsubscribeRequestMethod::VALUE,
+        CallToolRequestMethod::VALUE,
+        ListToolsRequestMethod::VALUE,
+        CreateMessageRequestMethod::VALUE,
+        ListRootsRequestMethod::VALUE,
+        ElicitationCreateRequestMethod::VALUE,
+    ];
+
+    pub fn new(method: impl Into<Cow<'static, str>>) -> Self {
+        Self(method.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn is_core(method: &str) -> bool {
+        Self::CORE_METHODS.contains(&method)
+    }
+}
+
+impl<'de> Deserialize<'de> for CustomRequestMethod {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let method = String::deserialize(deserializer)?;
+        if Self::is_core(&method) {
+            return Err(serde::de::Error::custom(format!(
+                "{method} is a core method, not a custom one"
+            )));
+        }
+        Ok(Self(method.into()))
+    }
+}
+
+/// Experimental: a request of a method outside the MCP specification, like a vendor method
+/// `x-movie/refresh-cache`, handled by
+/// [`ServerHandler::on_custom_request`](crate::ServerHandler::on_custom_request).
+pub type CustomRequest = RequestOptionalParam<CustomRequestMethod, JsonObject>;
+
+impl CustomRequest {
+    pub fn new(method: impl Into<Cow<'static, str>>, params: Option<JsonObject>) -> Self {
+        Self {
+            method: CustomRequestMethod::new(method),
+            params,
+            extensions: Extensions::default(),
+        }
+    }
+}
+
+/// The result of a [`CustomRequest`], any JSON value.
+///
+/// An object without fields besides `_meta` is an [`EmptyResult`] rather than a custom result.
+/// A malformed result of a core request parses as a custom one too, the session decodes it again
+/// as the expected type to fail with [`ServiceError::InvalidResponse`](crate::ServiceError::InvalidResponse).
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(transparent)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct CustomResult(pub Value);
+
+impl CustomResult {
+    pub fn new(result: impl Into<Value>) -> Self {
+        Self(result.into())
+    }
+
+    /// Deserialize the result into `T`
+    pub fn result_as<T: DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        T::deserialize(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for CustomResult {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let result = Value::deserialize(deserializer)?;
+        let is_empty = result
+            .as_object()
+            .is_some_and(|object| object.keys().all(|key| key == "_meta"));
+        if is_empty {
+            return Err(serde::de::Error::custom("an empty result"));
+        }
+        Ok(Self(result))
+    }
+}
+
 ts_union!(
     export type ClientRequest =
     | PingRequest
@@ -1704,11 +2229,12 @@ ts_union!(
     | SubscribeRequest
     | UnsubscribeRequest
     | CallToolRequest
-    | ListToolsRequest;
+    | ListToolsRequest
+    | CustomRequest;
 );
 
 impl ClientRequest {
-    pub fn method(&self) -> &'static str {
+    pub fn method(&self) -> &str {
         match &self {
             ClientRequest::PingRequest(r) => r.method.as_str(),
             ClientRequest::InitializeRequest(r) => r.method.as_str(),
@@ -1723,6 +2249,7 @@ impl ClientRequest {
             ClientRequest::UnsubscribeRequest(r) => r.method.as_str(),
             ClientRequest::CallToolRequest(r) => r.method.as_str(),
             ClientRequest::ListToolsRequest(r) => r.method.as_str(),
+            ClientRequest::CustomRequest(r) => r.method.as_str(),
         }
     }
 }
@@ -1732,9 +2259,22 @@ ts_union!(
     | CancelledNotification
     | ProgressNotification
     | InitializedNotification
-    | RootsListChangedNotification;
+    | RootsListChangedNotification
+    | SessionClosedNotification;
 );
 
+impl ClientNotification {
+    pub fn method(&self) -> &str {
+        match &self {
+            ClientNotification::CancelledNotification(n) => n.method.as_str(),
+            ClientNotification::ProgressNotification(n) => n.method.as_str(),
+            ClientNotification::InitializedNotification(n) => n.method.as_str(),
+            ClientNotification::RootsListChangedNotification(n) => n.method.as_str(),
+            ClientNotification::SessionClosedNotification(n) => n.method.as_str(),
+        }
+    }
+}
+
 ts_union!(
     export type ClientResult = box CreateMessageResult | ListRootsResult | CreateElicitationResult | EmptyResult;
 );
@@ -1755,6 +2295,17 @@ ts_union!(
     | CreateElicitationRequest;
 );
 
+impl ServerRequest {
+    pub fn method(&self) -> &str {
+        match &self {
+            ServerRequest::PingRequest(r) => r.method.as_str(),
+            ServerRequest::CreateMessageRequest(r) => r.method.as_str(),
+            ServerRequest::ListRootsRequest(r) => r.method.as_str(),
+            ServerRequest::CreateElicitationRequest(r) => r.method.as_str(),
+        }
+    }
+}
+
 ts_union!(
     export type ServerNotification =
     | CancelledNotification
@@ -1763,9 +2314,31 @@ ts_union!(
     | ResourceUpdatedNotification
     | ResourceListChangedNotification
     | ToolListChangedNotification
-    | PromptListChangedNotification;
+    | PromptListChangedNotification
+    | ToolPartialResultNotification
+    | ResourceChunkNotification
+    | ConfigChangedNotification
+    | SessionClosedNotification;
 );
 
+impl ServerNotification {
+    pub fn method(&self) -> &str {
+        match &self {
+            ServerNotification::CancelledNotification(n) => n.method.as_str(),
+            ServerNotification::ProgressNotification(n) => n.method.as_str(),
+            ServerNotification::LoggingMessageNotification(n) => n.method.as_str(),
+            ServerNotification::ResourceUpdatedNotification(n) => n.method.as_str(),
+            ServerNotification::ResourceListChangedNotification(n) => n.method.as_str(),
+            ServerNotification::ToolListChangedNotification(n) => n.method.as_str(),
+            ServerNotification::PromptListChangedNotification(n) => n.method.as_str(),
+            ServerNotification::ToolPartialResultNotification(n) => n.method.as_str(),
+            ServerNotification::ResourceChunkNotification(n) => n.method.as_str(),
+            ServerNotification::ConfigChangedNotification(n) => n.method.as_str(),
+            ServerNotification::SessionClosedNotification(n) => n.method.as_str(),
+        }
+    }
+}
+
 ts_union!(
     export type ServerResult =
     | InitializeResult
@@ -1778,6 +2351,7 @@ ts_union!(
     | CallToolResult
     | ListToolsResult
     | CreateElicitationResult
+    | CustomResult
     | EmptyResult
     ;
 );
@@ -1812,6 +2386,28 @@ impl TryInto<CancelledNotification> for ClientNotification {
     }
 }
 
+impl TryInto<SessionClosedNotification> for ServerNotification {
+    type Error = ServerNotification;
+    fn try_into(self) -> Result<SessionClosedNotification, Self::Error> {
+        if let ServerNotification::SessionClosedNotification(t) = self {
+            Ok(t)
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl TryInto<SessionClosedNotification> for ClientNotification {
+    type Error = ClientNotification;
+    fn try_into(self) -> Result<SessionClosedNotification, Self::Error> {
+        if let ClientNotification::SessionClosedNotification(t) = self {
+            Ok(t)
+        } else {
+            Err(self)
+        }
+    }
+}
+
 // =============================================================================
 // TESTS
 // =============================================================================
@@ -1822,6 +2418,51 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_call_tool_result_builder() {
+        let result = CallToolResult::builder()
+            .text("万达影城")
+            .json(json!({ "cinema": "万达影城", "seats": 42 }))
+            .content(Content::image("aGVsbG8=", "image/png"))
+            .build();
+        assert_eq!(
+            result,
+            CallToolResult::success(vec![
+                Content::text("万达影城"),
+                Content::json(json!({ "cinema": "万达影城", "seats": 42 })).unwrap(),
+                Content::image("aGVsbG8=", "image/png"),
+            ])
+        );
+
+        // an error stays an error
+        let result = CallToolResult::builder()
+            .error_if(true)
+            .text("sold out")
+            .error_if(false)
+            .build();
+        assert_eq!(
+            result,
+            CallToolResult::error(vec![Content::text("sold out")])
+        );
+
+        let mut meta = Meta::new();
+        meta.set_error_code("SOLD_OUT");
+        let result = CallToolResult::builder()
+            .text("sold out")
+            .error_if(true)
+            .meta(meta)
+            .build();
+        assert_eq!(result.error_code(), Some("SOLD_OUT"));
+        assert_eq!(
+            serde_json::to_value(&result).unwrap(),
+            json!({
+                "content": [{ "type": "text", "text": "sold out" }],
+                "isError": true,
+                "_meta": { "rmcp/errorCode": "SOLD_OUT" }
+            })
+        );
+    }
+
     #[test]
     fn test_notification_serde() {
         let raw = json!( {
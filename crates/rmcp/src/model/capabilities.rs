@@ -267,6 +267,98 @@ impl<const E: bool, const L: bool, const C: bool, const P: bool, const T: bool>
     }
 }
 
+impl ClientCapabilities {
+    /// The experimental capability holding the preferred locale, as `{"tag": "fr-FR"}`.
+    pub const LOCALE: &str = "locale";
+
+    /// The preferred locale of the client for the whole session, as a BCP 47 language tag.
+    pub fn locale(&self) -> Option<&str> {
+        self.experimental
+            .as_ref()?
+            .get(Self::LOCALE)?
+            .get("tag")?
+            .as_str()
+    }
+
+    pub fn set_locale(&mut self, locale: impl Into<String>) {
+        let mut capability = JsonObject::new();
+        capability.insert("tag".to_string(), locale.into().into());
+        self.experimental
+            .get_or_insert_with(Default::default)
+            .insert(Self::LOCALE.to_string(), capability);
+    }
+
+    /// The experimental capability holding the MIME types the client prefers for tool results,
+    /// as `{"types": ["text/markdown", "application/json"]}`.
+    pub const ACCEPT: &str = "accept";
+
+    /// The MIME types the client prefers for the whole session, most preferred first, see
+    /// [`Representations`](super::Representations).
+    pub fn accept(&self) -> Option<Vec<String>> {
+        serde_json::from_value(
+            self.experimental
+                .as_ref()?
+                .get(Self::ACCEPT)?
+                .get("types")?
+                .clone(),
+        )
+        .ok()
+    }
+
+    pub fn set_accept(&mut self, mime_types: impl IntoIterator<Item = impl Into<String>>) {
+        let types = mime_types
+            .into_iter()
+            .map(|mime_type| serde_json::Value::String(mime_type.into()))
+            .collect();
+        let mut capability = JsonObject::new();
+        capability.insert("types".to_string(), serde_json::Value::Array(types));
+        self.experimental
+            .get_or_insert_with(Default::default)
+            .insert(Self::ACCEPT.to_string(), capability);
+    }
+
+    /// The token the client resumes a session with, see [`ServerCapabilities::resumption_token`].
+    pub fn resumption_token(&self) -> Option<&str> {
+        resumption_token(self.experimental.as_ref())
+    }
+
+    pub fn set_resumption_token(&mut self, token: impl Into<String>) {
+        set_resumption_token(&mut self.experimental, token.into());
+    }
+}
+
+impl ServerCapabilities {
+    /// The experimental capability holding a resumption token, as `{"token": "..."}`.
+    pub const RESUMPTION: &str = "resumption";
+
+    /// The token issued by the server to resume the session after a restart.
+    ///
+    /// The client presents it back in its own capabilities when it reconnects, see
+    /// [`ClientCapabilities::set_resumption_token`].
+    pub fn resumption_token(&self) -> Option<&str> {
+        resumption_token(self.experimental.as_ref())
+    }
+
+    pub fn set_resumption_token(&mut self, token: impl Into<String>) {
+        set_resumption_token(&mut self.experimental, token.into());
+    }
+}
+
+fn resumption_token(experimental: Option<&ExperimentalCapabilities>) -> Option<&str> {
+    experimental?
+        .get(ServerCapabilities::RESUMPTION)?
+        .get("token")?
+        .as_str()
+}
+
+fn set_resumption_token(experimental: &mut Option<ExperimentalCapabilities>, token: String) {
+    let mut capability = JsonObject::new();
+    capability.insert("token".to_string(), token.into());
+    experimental
+        .get_or_insert_with(Default::default)
+        .insert(ServerCapabilities::RESUMPTION.to_string(), capability);
+}
+
 builder! {
     ClientCapabilities{
         experimental: ExperimentalCapabilities,
@@ -16,6 +16,16 @@ pub struct RawTextContent {
     pub meta: Option<super::Meta>,
 }
 pub type TextContent = Annotated<RawTextContent>;
+
+impl RawTextContent {
+    /// The MIME type of the text, e.g. `application/json` for machine-parseable payloads.
+    ///
+    /// The spec defines none for text content, it's carried as the content type of the `_meta`,
+    /// see [`Meta::content_type`](super::Meta::content_type).
+    pub fn mime_type(&self) -> Option<&str> {
+        self.meta.as_ref()?.content_type()
+    }
+}
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
@@ -72,6 +82,9 @@ pub enum RawContent {
 
 pub type Content = Annotated<RawContent>;
 
+/// The MIME type of the text content created by [`RawContent::json`]
+pub const JSON_MIME_TYPE: &str = "application/json";
+
 impl RawContent {
     pub fn json<S: Serialize>(json: S) -> Result<Self, crate::ErrorData> {
         let json = serde_json::to_string(&json).map_err(|e| {
@@ -82,7 +95,37 @@ impl RawContent {
                 )),
             )
         })?;
-        Ok(RawContent::text(json))
+        Ok(RawContent::json_text(json))
+    }
+
+    /// Text or JSON content, depending on what `value` serializes to:
+    ///
+    /// - a string, like a `String` or a unit enum variant, is plain text, passed through
+    ///   unchanged, without the quotes JSON would add
+    /// - anything else, like a struct, a map, a sequence or a number, is JSON, see [`RawContent::json`]
+    pub fn from_serializable<S: Serialize + ?Sized>(value: &S) -> Result<Self, crate::ErrorData> {
+        let value = serde_json::to_value(value).map_err(|e| {
+            crate::ErrorData::internal_error(
+                "fail to serialize response to json",
+                Some(json!(
+                    {"reason": e.to_string()}
+                )),
+            )
+        })?;
+        Ok(match value {
+            serde_json::Value::String(text) => RawContent::text(text),
+            value => RawContent::json_text(value.to_string()),
+        })
+    }
+
+    /// Text content holding already serialized json
+    pub(crate) fn json_text(json: String) -> Self {
+        let mut meta = super::Meta::new();
+        meta.set_content_type(JSON_MIME_TYPE);
+        RawContent::Text(RawTextContent {
+            text: json,
+            meta: Some(meta),
+        })
     }
 
     pub fn text<S: Into<String>>(text: S) -> Self {
@@ -155,6 +198,20 @@ impl RawContent {
     pub fn resource_link(resource: super::resource::RawResource) -> Self {
         RawContent::ResourceLink(resource)
     }
+
+    /// Create a resource link content from its uri, name and description
+    ///
+    /// The link references the resource without inlining its contents, a client can show it
+    /// as a link or read it later with `resources/read`.
+    pub fn link(
+        uri: impl Into<String>,
+        name: impl Into<String>,
+        description: impl Into<String>,
+    ) -> Self {
+        let mut resource = super::resource::RawResource::new(uri, name);
+        resource.description = Some(description.into());
+        RawContent::ResourceLink(resource)
+    }
 }
 
 impl Content {
@@ -178,10 +235,47 @@ impl Content {
         RawContent::json(json).map(|c| c.no_annotation())
     }
 
+    /// Text or JSON content, see [`RawContent::from_serializable`]
+    pub fn from_serializable<S: Serialize + ?Sized>(value: &S) -> Result<Self, crate::ErrorData> {
+        RawContent::from_serializable(value).map(|c| c.no_annotation())
+    }
+
     /// Create a resource link content
     pub fn resource_link(resource: super::resource::RawResource) -> Self {
         RawContent::resource_link(resource).no_annotation()
     }
+
+    /// Create a resource link content from its uri, name and description, see [`RawContent::link`]
+    pub fn link(
+        uri: impl Into<String>,
+        name: impl Into<String>,
+        description: impl Into<String>,
+    ) -> Self {
+        RawContent::link(uri, name, description).no_annotation()
+    }
+
+    /// The length in bytes of this content serialized as JSON, see [`CallToolResult::byte_len`](super::CallToolResult::byte_len).
+    pub fn byte_len(&self) -> usize {
+        serialized_len(self)
+    }
+}
+
+/// The length of `value` serialized as compact JSON, counted as it's written without buffering it
+pub(crate) fn serialized_len<T: Serialize + ?Sized>(value: &T) -> usize {
+    struct Counter(usize);
+    impl std::io::Write for Counter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0 += buf.len();
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+    let mut counter = Counter(0);
+    // writing to the counter can't fail, and our models always serialize
+    let _ = serde_json::to_writer(&mut counter, value);
+    counter.0
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -215,6 +309,58 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_from_serializable_struct_is_json() {
+        #[derive(Serialize)]
+        struct Movie {
+            id: i32,
+            name: &'static str,
+        }
+        let content = Content::from_serializable(&Movie {
+            id: 1297,
+            name: "哪吒之魔童闹海",
+        })
+        .unwrap();
+        let text = content.as_text().unwrap();
+        assert_eq!(text.mime_type(), Some(JSON_MIME_TYPE));
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&text.text).unwrap(),
+            json!({ "id": 1297, "name": "哪吒之魔童闹海" })
+        );
+
+        let content = Content::from_serializable(&[1, 2]).unwrap();
+        assert_eq!(content.as_text().unwrap().text, "[1,2]");
+    }
+
+    #[test]
+    fn test_from_serializable_string_is_text() {
+        let content = Content::from_serializable("哪吒之魔童闹海").unwrap();
+        assert_eq!(content, Content::text("哪吒之魔童闹海"));
+        // not quoted, even if it looks like JSON
+        let content = Content::from_serializable(&String::from("{\"id\": 1297}")).unwrap();
+        assert_eq!(content, Content::text("{\"id\": 1297}"));
+    }
+
+    #[test]
+    fn test_byte_len_matches_serialization() {
+        let contents = vec![
+            Content::text("热门电影 \"quoted\"\n"),
+            Content::image("base64data", "image/png"),
+            Content::link("cinema://42", "Cinema 42", "A cinema"),
+        ];
+        for content in &contents {
+            assert_eq!(
+                content.byte_len(),
+                serde_json::to_string(content).unwrap().len()
+            );
+        }
+        let result = crate::model::CallToolResult::success(contents);
+        assert_eq!(
+            result.byte_len(),
+            serde_json::to_string(&result).unwrap().len()
+        );
+    }
+
     #[test]
     fn test_image_content_serialization() {
         let image_content = RawImageContent {
@@ -246,6 +392,39 @@ mod tests {
         assert!(!json.contains("mime_type"));
     }
 
+    #[test]
+    fn test_json_content_serialization() {
+        #[derive(Serialize)]
+        struct City {
+            name: &'static str,
+            id: u32,
+        }
+
+        let content = Content::json(City {
+            name: "Beijing",
+            id: 1,
+        })
+        .unwrap();
+        let json = serde_json::to_value(&content).unwrap();
+        assert_eq!(
+            json,
+            json!({
+                "type": "text",
+                "text": r#"{"name":"Beijing","id":1}"#,
+                "_meta": { "rmcp/contentType": "application/json" },
+            })
+        );
+
+        let content = Content::json(json!({"name": "Beijing"})).unwrap();
+        let text = content.as_text().unwrap();
+        assert_eq!(text.mime_type(), Some(JSON_MIME_TYPE));
+        assert_eq!(text.text, r#"{"name":"Beijing"}"#);
+
+        // plain text doesn't declare a mime type
+        let json = serde_json::to_value(Content::text("hello")).unwrap();
+        assert!(json.get("_meta").is_none());
+    }
+
     #[test]
     fn test_resource_link_serialization() {
         use super::super::resource::RawResource;
@@ -269,6 +448,29 @@ mod tests {
         assert!(json.contains("\"name\":\"test.txt\""));
     }
 
+    #[test]
+    fn test_link_serialization() {
+        let link = Content::link(
+            "https://example.com/cinemas/1/booking",
+            "booking",
+            "Book a ticket at Wanda Cinema",
+        );
+        let json = serde_json::to_value(&link).unwrap();
+        assert_eq!(
+            json,
+            json!({
+                "type": "resource_link",
+                "uri": "https://example.com/cinemas/1/booking",
+                "name": "booking",
+                "description": "Book a ticket at Wanda Cinema",
+            })
+        );
+
+        let back: Content = serde_json::from_value(json).unwrap();
+        assert_eq!(back, link);
+        assert_eq!(back.as_resource_link().unwrap().name, "booking");
+    }
+
     #[test]
     fn test_resource_link_deserialization() {
         let json = r#"{
@@ -0,0 +1,144 @@
+//! Validation of the JSON-RPC envelope of incoming messages, see [`JsonRpcParseMode`] and [`JsonLimits`]
+use serde_json::Value;
+
+/// How strictly the JSON-RPC envelope of incoming messages is checked before parsing them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum JsonRpcParseMode {
+    /// Reject envelopes which don't follow JSON-RPC 2.0, with a precise [`JsonRpcEnvelopeError`].
+    #[default]
+    Strict,
+    /// Fix the common deviations of non-conformant peers before parsing:
+    /// - a missing or wrong `jsonrpc` version is set to `"2.0"`
+    /// - `"params": null` is removed
+    /// - the `id` of a response or an error is turned into a number if it's a numeric string
+    ///   or an integral float, to be matched with the request we sent
+    ///
+    /// The `id` of an incoming request is never changed, its response echoes it as received.
+    Lenient,
+}
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum JsonRpcEnvelopeError {
+    #[error("message is not a JSON object")]
+    NotAnObject,
+    #[error("missing `jsonrpc` field, expected \"2.0\"")]
+    MissingVersion,
+    #[error("unsupported `jsonrpc` version {0}, expected \"2.0\"")]
+    UnsupportedVersion(Value),
+    #[error("invalid `id` {0}, expected a string or an integer")]
+    InvalidId(Value),
+    #[error("message larger than {max_size} bytes")]
+    TooLarge { max_size: usize },
+    #[error("message nested deeper than {max_depth} levels")]
+    TooDeep { max_depth: usize },
+}
+
+/// Limits on the incoming JSON, checked on the raw bytes before they are parsed.
+///
+/// The depth is the nesting of arrays and objects, the message itself being at depth 1. It's
+/// checked without recursing, so a deeply nested message is rejected before it can exhaust the
+/// stack. Note that `serde_json` refuses to parse more than 128 levels anyway.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JsonLimits {
+    pub max_depth: usize,
+    /// The max size of a message in bytes, `None` for no limit
+    pub max_size: Option<usize>,
+}
+
+impl JsonLimits {
+    pub const DEFAULT_MAX_DEPTH: usize = 128;
+
+    pub fn check(&self, json: &[u8]) -> Result<(), JsonRpcEnvelopeError> {
+        if let Some(max_size) = self.max_size.filter(|max_size| json.len() > *max_size) {
+            return Err(JsonRpcEnvelopeError::TooLarge { max_size });
+        }
+        let mut depth = 0usize;
+        let mut in_string = false;
+        let mut escaped = false;
+        for byte in json {
+            if in_string {
+                match byte {
+                    _ if escaped => escaped = false,
+                    b'\\' => escaped = true,
+                    b'"' => in_string = false,
+                    _ => {}
+                }
+                continue;
+            }
+            match byte {
+                b'"' => in_string = true,
+                b'[' | b'{' => {
+                    depth += 1;
+                    if depth > self.max_depth {
+                        return Err(JsonRpcEnvelopeError::TooDeep {
+                            max_depth: self.max_depth,
+                        });
+                    }
+                }
+                b']' | b'}' => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for JsonLimits {
+    /// No size limit, and the depth `serde_json` can parse
+    fn default() -> Self {
+        Self {
+            max_depth: Self::DEFAULT_MAX_DEPTH,
+            max_size: None,
+        }
+    }
+}
+
+impl JsonRpcParseMode {
+    /// Check the envelope of a message, or of every message of a batch. In lenient mode, it's fixed in place.
+    pub fn check(self, message: &mut Value) -> Result<(), JsonRpcEnvelopeError> {
+        match message {
+            Value::Array(batch) => batch.iter_mut().try_for_each(|message| self.check(message)),
+            message => self.check_one(message),
+        }
+    }
+
+    fn check_one(self, message: &mut Value) -> Result<(), JsonRpcEnvelopeError> {
+        let Value::Object(object) = message else {
+            return Err(JsonRpcEnvelopeError::NotAnObject);
+        };
+        let lenient = self == Self::Lenient;
+        match object.get("jsonrpc") {
+            Some(Value::String(version)) if version == "2.0" => {}
+            _ if lenient => {
+                object.insert("jsonrpc".to_string(), Value::String("2.0".to_string()));
+            }
+            None => return Err(JsonRpcEnvelopeError::MissingVersion),
+            Some(version) => return Err(JsonRpcEnvelopeError::UnsupportedVersion(version.clone())),
+        }
+        if lenient && object.get("params").is_some_and(Value::is_null) {
+            object.remove("params");
+        }
+        let is_answer = object.contains_key("result") || object.contains_key("error");
+        let Some(id) = object.get_mut("id") else {
+            return Ok(());
+        };
+        if lenient && is_answer {
+            let number = match &*id {
+                Value::String(s) => s.parse::<i64>().ok(),
+                Value::Number(n) if n.as_i64().is_none() => n
+                    .as_f64()
+                    .filter(|f| f.fract() == 0.0 && f.abs() < i64::MAX as f64)
+                    .map(|f| f as i64),
+                _ => None,
+            };
+            if let Some(number) = number {
+                *id = Value::from(number);
+            }
+        }
+        match id {
+            Value::String(_) => Ok(()),
+            Value::Number(n) if n.is_i64() => Ok(()),
+            id => Err(JsonRpcEnvelopeError::InvalidId(id.clone())),
+        }
+    }
+}
@@ -4,8 +4,8 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use super::{
-    ClientNotification, ClientRequest, Extensions, JsonObject, JsonRpcMessage, NumberOrString,
-    ProgressToken, ServerNotification, ServerRequest,
+    ByteRange, ClientNotification, ClientRequest, Extensions, JsonObject, JsonRpcMessage,
+    NumberOrString, ProgressToken, ServerNotification, ServerRequest, ToolGroup,
 };
 
 pub trait GetMeta {
@@ -66,6 +66,7 @@ variant_extension! {
         UnsubscribeRequest
         CallToolRequest
         ListToolsRequest
+        CustomRequest
     }
 }
 
@@ -84,6 +85,7 @@ variant_extension! {
         ProgressNotification
         InitializedNotification
         RootsListChangedNotification
+        SessionClosedNotification
     }
 }
 
@@ -96,6 +98,10 @@ variant_extension! {
         ResourceListChangedNotification
         ToolListChangedNotification
         PromptListChangedNotification
+        ToolPartialResultNotification
+        ResourceChunkNotification
+        ConfigChangedNotification
+        SessionClosedNotification
     }
 }
 #[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
@@ -103,7 +109,20 @@ variant_extension! {
 #[serde(transparent)]
 pub struct Meta(pub JsonObject);
 const PROGRESS_TOKEN_FIELD: &str = "progressToken";
+const PARTIAL_RESULTS_FIELD: &str = "rmcp/partialResults";
+const DRY_RUN_FIELD: &str = "rmcp/dryRun";
+const LOCALE_FIELD: &str = "rmcp/locale";
+const TIMEOUT_FIELD: &str = "rmcp/timeoutMs";
+const ERROR_CODE_FIELD: &str = "rmcp/errorCode";
+const TOOL_GROUPS_FIELD: &str = "rmcp/toolGroups";
+const BYTE_RANGE_FIELD: &str = "rmcp/byteRange";
+const ACCEPT_FIELD: &str = "rmcp/accept";
+const CONTENT_TYPE_FIELD: &str = "rmcp/contentType";
+const DEPRECATED_FIELD: &str = "rmcp/deprecated";
 impl Meta {
+    /// The longest timeout read from a `rmcp/timeoutMs` hint, longer hints are clamped to it.
+    pub const MAX_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(24 * 60 * 60);
+
     pub fn new() -> Self {
         Self(JsonObject::new())
     }
@@ -133,6 +152,136 @@ impl Meta {
         })
     }
 
+    /// Whether the requester accepts partial results of a streaming tool or a chunked blob,
+    /// see [`ToolPartialResultNotificationParam`](super::ToolPartialResultNotificationParam) and
+    /// [`ResourceChunkNotificationParam`](super::ResourceChunkNotificationParam).
+    pub fn partial_results(&self) -> bool {
+        self.0
+            .get(PARTIAL_RESULTS_FIELD)
+            .and_then(Value::as_bool)
+            .unwrap_or_default()
+    }
+
+    pub fn set_partial_results(&mut self, accept: bool) {
+        self.0
+            .insert(PARTIAL_RESULTS_FIELD.to_string(), Value::Bool(accept));
+    }
+
+    /// Whether the requester asks for a dry run, see [`RequestContext::is_dry_run`](crate::service::RequestContext::is_dry_run).
+    pub fn dry_run(&self) -> bool {
+        self.0
+            .get(DRY_RUN_FIELD)
+            .and_then(Value::as_bool)
+            .unwrap_or_default()
+    }
+
+    pub fn set_dry_run(&mut self, dry_run: bool) {
+        self.0
+            .insert(DRY_RUN_FIELD.to_string(), Value::Bool(dry_run));
+    }
+
+    /// The preferred locale of the requester, as a BCP 47 language tag like `fr-FR`.
+    pub fn locale(&self) -> Option<&str> {
+        self.0.get(LOCALE_FIELD).and_then(Value::as_str)
+    }
+
+    pub fn set_locale(&mut self, locale: impl Into<String>) {
+        self.0
+            .insert(LOCALE_FIELD.to_string(), Value::String(locale.into()));
+    }
+
+    /// How long the requester is willing to wait, see [`Peer::handler_timeout`](crate::Peer::handler_timeout).
+    ///
+    /// The hint comes from the remote peer, so it's clamped to [`Meta::MAX_TIMEOUT`].
+    pub fn timeout(&self) -> Option<std::time::Duration> {
+        self.0
+            .get(TIMEOUT_FIELD)
+            .and_then(Value::as_u64)
+            .map(|millis| std::time::Duration::from_millis(millis).min(Self::MAX_TIMEOUT))
+    }
+
+    pub fn set_timeout(&mut self, timeout: std::time::Duration) {
+        self.0.insert(
+            TIMEOUT_FIELD.to_string(),
+            Value::Number((timeout.as_millis() as u64).into()),
+        );
+    }
+
+    /// The machine readable code of a tool error, see [`CallToolResult::error_code`](super::CallToolResult::error_code).
+    pub fn error_code(&self) -> Option<&str> {
+        self.0.get(ERROR_CODE_FIELD).and_then(Value::as_str)
+    }
+
+    pub fn set_error_code(&mut self, code: impl Into<String>) {
+        self.0
+            .insert(ERROR_CODE_FIELD.to_string(), Value::String(code.into()));
+    }
+
+    /// The groups the server suggests to show the listed tools in, see [`ToolGroup`].
+    ///
+    /// Returns `None` if the server sends no groups, or malformed ones.
+    pub fn tool_groups(&self) -> Option<Vec<ToolGroup>> {
+        serde_json::from_value(self.0.get(TOOL_GROUPS_FIELD)?.clone()).ok()
+    }
+
+    pub fn set_tool_groups(&mut self, groups: Vec<ToolGroup>) {
+        self.0.insert(
+            TOOL_GROUPS_FIELD.to_string(),
+            serde_json::to_value(groups).expect("tool groups are serializable"),
+        );
+    }
+
+    /// The deprecation message of a tool, see [`Tool::deprecation`](super::Tool::deprecation).
+    pub fn deprecated(&self) -> Option<&str> {
+        self.0.get(DEPRECATED_FIELD).and_then(Value::as_str)
+    }
+
+    pub fn set_deprecated(&mut self, message: impl Into<String>) {
+        self.0
+            .insert(DEPRECATED_FIELD.to_string(), Value::String(message.into()));
+    }
+
+    /// The range of bytes the requester asks for when reading a blob resource, see [`ByteRange`].
+    pub fn byte_range(&self) -> Option<ByteRange> {
+        serde_json::from_value(self.0.get(BYTE_RANGE_FIELD)?.clone()).ok()
+    }
+
+    pub fn set_byte_range(&mut self, range: ByteRange) {
+        self.0.insert(
+            BYTE_RANGE_FIELD.to_string(),
+            serde_json::to_value(range).expect("byte range is serializable"),
+        );
+    }
+
+    /// The MIME types the requester prefers for the result, most preferred first, see [`Representations`](super::Representations).
+    pub fn accept(&self) -> Option<Vec<String>> {
+        serde_json::from_value(self.0.get(ACCEPT_FIELD)?.clone()).ok()
+    }
+
+    pub fn set_accept(&mut self, mime_types: impl IntoIterator<Item = impl Into<String>>) {
+        self.0.insert(
+            ACCEPT_FIELD.to_string(),
+            Value::Array(
+                mime_types
+                    .into_iter()
+                    .map(|mime_type| Value::String(mime_type.into()))
+                    .collect(),
+            ),
+        );
+    }
+
+    /// The MIME type of the representation picked for a result, see [`Representations::negotiate`](super::Representations::negotiate).
+    pub fn content_type(&self) -> Option<&str> {
+        self.0.get(CONTENT_TYPE_FIELD).and_then(Value::as_str)
+    }
+
+    pub fn set_content_type(&mut self, mime_type: impl Into<String>) {
+        self.0.insert(
+            CONTENT_TYPE_FIELD.to_string(),
+            Value::String(mime_type.into()),
+        );
+    }
+
     pub fn set_progress_token(&mut self, token: ProgressToken) {
         match token.0 {
             NumberOrString::String(ref s) => self.0.insert(
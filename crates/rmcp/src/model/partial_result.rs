@@ -0,0 +1,123 @@
+//! A convention for tool results made of parts which may fail independently
+use serde::{Deserialize, Serialize};
+
+use super::{CallToolResult, Content, Meta};
+
+/// The status of one part of a [`PartialResult`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PartStatus {
+    pub name: String,
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Build a [`CallToolResult`] from parts which may fail independently, instead of failing the whole tool.
+///
+/// The contents of the successful parts are returned in order, a failed part is replaced by a
+/// text note like `movies failed: timeout`. The status of each part is recorded in the `_meta` of
+/// the result as `{"rmcp/partialResult": {"parts": [{"name": "movies", "ok": false, "error": "timeout"}]}}`,
+/// read it back with [`PartialResult::parts_of`]. The result is an error only if every part failed.
+///
+/// ```rust
+/// # use rmcp::model::PartialResult;
+/// let result = PartialResult::new()
+///     .text_part("cinema", Ok::<_, String>("{\"id\": 42}"))
+///     .text_part("movies", Err::<String, _>("timeout"))
+///     .into_call_tool_result();
+/// assert_eq!(result.is_error, Some(false));
+/// assert!(!PartialResult::parts_of(&result).unwrap()[1].ok);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct PartialResult {
+    content: Vec<Content>,
+    parts: Vec<PartStatus>,
+}
+
+impl PartialResult {
+    /// The `_meta` field holding the status of the parts
+    pub const META_FIELD: &str = "rmcp/partialResult";
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn part<E: std::fmt::Display>(
+        mut self,
+        name: impl Into<String>,
+        result: Result<Vec<Content>, E>,
+    ) -> Self {
+        self.push(name, result);
+        self
+    }
+
+    /// A part made of a single text content
+    pub fn text_part<T: Into<String>, E: std::fmt::Display>(
+        self,
+        name: impl Into<String>,
+        result: Result<T, E>,
+    ) -> Self {
+        self.part(name, result.map(|text| vec![Content::text(text)]))
+    }
+
+    pub fn push<E: std::fmt::Display>(
+        &mut self,
+        name: impl Into<String>,
+        result: Result<Vec<Content>, E>,
+    ) {
+        let name = name.into();
+        match result {
+            Ok(content) => {
+                self.content.extend(content);
+                self.parts.push(PartStatus {
+                    name,
+                    ok: true,
+                    error: None,
+                });
+            }
+            Err(error) => {
+                let error = error.to_string();
+                self.content
+                    .push(Content::text(format!("{name} failed: {error}")));
+                self.parts.push(PartStatus {
+                    name,
+                    ok: false,
+                    error: Some(error),
+                });
+            }
+        }
+    }
+
+    pub fn parts(&self) -> &[PartStatus] {
+        &self.parts
+    }
+
+    pub fn into_call_tool_result(self) -> CallToolResult {
+        let all_failed = !self.parts.is_empty() && self.parts.iter().all(|part| !part.ok);
+        let mut result = if all_failed {
+            CallToolResult::error(self.content)
+        } else {
+            CallToolResult::success(self.content)
+        };
+        let mut meta = Meta::new();
+        meta.insert(
+            Self::META_FIELD.to_string(),
+            serde_json::json!({ "parts": self.parts }),
+        );
+        result.meta = Some(meta);
+        result
+    }
+
+    /// The status of the parts recorded in a result, `None` if it isn't a partial result
+    pub fn parts_of(result: &CallToolResult) -> Option<Vec<PartStatus>> {
+        let parts = result.meta.as_ref()?.get(Self::META_FIELD)?.get("parts")?;
+        serde_json::from_value(parts.clone()).ok()
+    }
+}
+
+impl From<PartialResult> for CallToolResult {
+    fn from(value: PartialResult) -> Self {
+        value.into_call_tool_result()
+    }
+}
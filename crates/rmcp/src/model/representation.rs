@@ -0,0 +1,67 @@
+use super::{CallToolResult, Content, IntoContents, Meta};
+
+/// Alternative representations of the same tool output, like a markdown summary and the raw
+/// JSON, keyed by MIME type.
+///
+/// [`Representations::negotiate`] picks the one the client prefers, similar to the HTTP `Accept`
+/// header, see [`Meta::set_accept`] and [`ClientCapabilities::set_accept`](super::ClientCapabilities::set_accept).
+#[derive(Debug, Clone, Default)]
+pub struct Representations {
+    offered: Vec<(String, Vec<Content>)>,
+}
+
+impl Representations {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Offer `contents` as `mime_type`, the first one offered is the default.
+    pub fn with(mut self, mime_type: impl Into<String>, contents: impl IntoContents) -> Self {
+        self.offered
+            .push((mime_type.into(), contents.into_contents()));
+        self
+    }
+
+    /// The MIME types offered, in order
+    pub fn mime_types(&self) -> impl Iterator<Item = &str> {
+        self.offered.iter().map(|(mime_type, _)| mime_type.as_str())
+    }
+
+    /// Pick the representation matching the first of the `accept`ed MIME types, which may be
+    /// wildcards like `text/*` or `*/*`, or the default one if none matches.
+    ///
+    /// The MIME type picked is set as `rmcp/contentType` in the `_meta` of the result, see
+    /// [`Meta::content_type`].
+    pub fn negotiate(self, accept: &[impl AsRef<str>]) -> CallToolResult {
+        let index = accept
+            .iter()
+            .find_map(|accepted| {
+                self.offered
+                    .iter()
+                    .position(|(mime_type, _)| media_type_matches(accepted.as_ref(), mime_type))
+            })
+            .unwrap_or_default();
+        let Some((mime_type, contents)) = self.offered.into_iter().nth(index) else {
+            return CallToolResult::empty();
+        };
+        let mut meta = Meta::new();
+        meta.set_content_type(mime_type);
+        CallToolResult {
+            meta: Some(meta),
+            ..CallToolResult::success(contents)
+        }
+    }
+}
+
+/// Whether the `accepted` media range, without its parameters, matches `mime_type`
+fn media_type_matches(accepted: &str, mime_type: &str) -> bool {
+    let accepted = accepted.split(';').next().unwrap_or_default().trim();
+    let mime_type = mime_type.split(';').next().unwrap_or_default().trim();
+    match accepted.split_once('/') {
+        Some(("*", "*")) => true,
+        Some((kind, "*")) => mime_type
+            .split_once('/')
+            .is_some_and(|(offered, _)| offered.eq_ignore_ascii_case(kind)),
+        _ => accepted.eq_ignore_ascii_case(mime_type),
+    }
+}
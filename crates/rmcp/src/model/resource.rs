@@ -1,6 +1,8 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
-use super::{Annotated, Icon, Meta};
+use super::{Annotated, Icon, JsonObject, Meta};
 
 /// Represents a resource in the extension with metadata
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
@@ -37,6 +39,7 @@ pub type Resource = Annotated<RawResource>;
 #[serde(rename_all = "camelCase")]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct RawResourceTemplate {
+    /// A URI template (RFC 6570), e.g. `cinema://{cinema_id}/shows`
     pub uri_template: String,
     pub name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -45,6 +48,9 @@ pub struct RawResourceTemplate {
     pub description: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub mime_type: Option<String>,
+    /// The documented parameters are stored here, see [`RawResourceTemplate::parameters`]
+    #[serde(rename = "_meta", skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Meta>,
 }
 
 pub type ResourceTemplate = Annotated<RawResourceTemplate>;
@@ -74,6 +80,13 @@ pub enum ResourceContents {
 }
 
 impl ResourceContents {
+    /// The `_meta` field holding the position of a blob chunk, `{ "index": 0, "count": 3 }`
+    pub const CHUNK_META_FIELD: &str = "rmcp/chunk";
+    /// The `_meta` field holding the bytes of a blob slice, `{ "start": 0, "end": 1024, "total": 4096 }`
+    pub const RANGE_META_FIELD: &str = "rmcp/range";
+    /// The default chunk size of [`RequestContext::stream_blob`](crate::service::RequestContext::stream_blob), before base64 encoding
+    pub const DEFAULT_BLOB_CHUNK_SIZE: usize = 64 * 1024;
+
     pub fn text(text: impl Into<String>, uri: impl Into<String>) -> Self {
         Self::TextResourceContents {
             uri: uri.into(),
@@ -82,6 +95,272 @@ impl ResourceContents {
             meta: None,
         }
     }
+
+    /// A chunk of a blob returned in a single `resources/read` result, with its position in
+    /// [`ResourceContents::CHUNK_META_FIELD`], see [`RequestContext::stream_blob`](crate::service::RequestContext::stream_blob).
+    ///
+    /// `blob` is the base64 encoded chunk.
+    pub fn blob_chunk(
+        uri: impl Into<String>,
+        mime_type: Option<String>,
+        blob: impl Into<String>,
+        index: usize,
+        count: usize,
+    ) -> Self {
+        let mut meta = Meta::new();
+        meta.insert(
+            Self::CHUNK_META_FIELD.to_string(),
+            serde_json::json!({ "index": index, "count": count }),
+        );
+        Self::BlobResourceContents {
+            uri: uri.into(),
+            mime_type,
+            blob: blob.into(),
+            meta: Some(meta),
+        }
+    }
+
+    /// An empty blob standing for the `count` chunks sent before the result, as
+    /// `notifications/rmcp/resource_chunk`, see [`RequestContext::stream_blob`](crate::service::RequestContext::stream_blob).
+    pub fn streamed_blob(uri: impl Into<String>, mime_type: Option<String>, count: usize) -> Self {
+        let mut meta = Meta::new();
+        meta.insert(
+            Self::CHUNK_META_FIELD.to_string(),
+            serde_json::json!({ "count": count, "streamed": true }),
+        );
+        Self::BlobResourceContents {
+            uri: uri.into(),
+            mime_type,
+            blob: String::new(),
+            meta: Some(meta),
+        }
+    }
+
+    pub fn uri(&self) -> &str {
+        match self {
+            Self::TextResourceContents { uri, .. } | Self::BlobResourceContents { uri, .. } => uri,
+        }
+    }
+
+    pub fn mime_type(&self) -> Option<&str> {
+        match self {
+            Self::TextResourceContents { mime_type, .. }
+            | Self::BlobResourceContents { mime_type, .. } => mime_type.as_deref(),
+        }
+    }
+
+    /// The `(index, count)` of a blob chunk, see [`ResourceContents::blob_chunk`]
+    pub fn chunk(&self) -> Option<(usize, usize)> {
+        let chunk = self.chunk_meta()?;
+        let index = chunk.get("index")?.as_u64()?;
+        let count = chunk.get("count")?.as_u64()?;
+        Some((index as usize, count as usize))
+    }
+
+    /// The number of chunks sent before the result, see [`ResourceContents::streamed_blob`]
+    pub fn streamed_chunks(&self) -> Option<usize> {
+        let chunk = self.chunk_meta()?;
+        if chunk.get("streamed")?.as_bool()? {
+            Some(chunk.get("count")?.as_u64()? as usize)
+        } else {
+            None
+        }
+    }
+
+    fn chunk_meta(&self) -> Option<&serde_json::Value> {
+        let Self::BlobResourceContents {
+            meta: Some(meta), ..
+        } = self
+        else {
+            return None;
+        };
+        meta.get(Self::CHUNK_META_FIELD)
+    }
+
+    /// Take the `range` of a blob, the whole blob when `None`, and record which bytes it holds
+    /// in [`ResourceContents::RANGE_META_FIELD`], along with the size of the whole blob.
+    ///
+    /// The range is clamped to the blob, a range starting past its end gives an empty slice.
+    /// The client reads it with [`Peer::read_resource_range`](crate::service::Peer::read_resource_range).
+    #[cfg(feature = "base64")]
+    pub fn blob_slice(
+        uri: impl Into<String>,
+        mime_type: Option<String>,
+        data: &[u8],
+        range: Option<ByteRange>,
+    ) -> Self {
+        use base64::engine::{Engine, general_purpose::STANDARD};
+
+        let total = data.len() as u64;
+        let range = range.unwrap_or(ByteRange::starting_at(0));
+        let start = range.start.min(total);
+        let end = range.end.unwrap_or(total).clamp(start, total);
+        let mut meta = Meta::new();
+        meta.insert(
+            Self::RANGE_META_FIELD.to_string(),
+            serde_json::to_value(ContentRange { start, end, total })
+                .expect("content range is serializable"),
+        );
+        Self::BlobResourceContents {
+            uri: uri.into(),
+            mime_type,
+            blob: STANDARD.encode(&data[start as usize..end as usize]),
+            meta: Some(meta),
+        }
+    }
+
+    /// The bytes held by a blob slice, see [`ResourceContents::blob_slice`]
+    pub fn range(&self) -> Option<ContentRange> {
+        let Self::BlobResourceContents {
+            meta: Some(meta), ..
+        } = self
+        else {
+            return None;
+        };
+        serde_json::from_value(meta.get(Self::RANGE_META_FIELD)?.clone()).ok()
+    }
+}
+
+/// A range of bytes of a blob resource, asked with [`Meta::set_byte_range`] when reading it.
+///
+/// `end` is excluded, a range without `end` goes to the end of the blob.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ByteRange {
+    pub start: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end: Option<u64>,
+}
+
+impl ByteRange {
+    pub fn new(start: u64, end: u64) -> Self {
+        Self {
+            start,
+            end: Some(end),
+        }
+    }
+
+    /// The bytes from `start` to the end of the blob
+    pub fn starting_at(start: u64) -> Self {
+        Self { start, end: None }
+    }
+}
+
+/// The range of bytes held by a blob slice, see [`ResourceContents::blob_slice`].
+///
+/// `end` is excluded, `total` is the size of the whole blob.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContentRange {
+    pub start: u64,
+    pub end: u64,
+    pub total: u64,
+}
+
+impl RawResourceTemplate {
+    /// The `_meta` field holding the JSON schema of the template parameters
+    pub const PARAMETERS_META_FIELD: &str = "rmcp/parameters";
+
+    pub fn new(uri_template: impl Into<String>, name: impl Into<String>) -> Self {
+        Self {
+            uri_template: uri_template.into(),
+            name: name.into(),
+            title: None,
+            description: None,
+            mime_type: None,
+            meta: None,
+        }
+    }
+
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn with_mime_type(mut self, mime_type: impl Into<String>) -> Self {
+        self.mime_type = Some(mime_type.into());
+        self
+    }
+
+    /// Document the parameters with a JSON schema object, whose properties are the template variables.
+    ///
+    /// With the `server` feature, [`RawResourceTemplate::with_parameters`] generates it from a type.
+    pub fn with_parameters_schema(mut self, schema: JsonObject) -> Self {
+        self.meta.get_or_insert_with(Meta::new).insert(
+            Self::PARAMETERS_META_FIELD.to_string(),
+            serde_json::Value::Object(schema),
+        );
+        self
+    }
+
+    /// The JSON schema of the parameters, if documented
+    pub fn parameters(&self) -> Option<&JsonObject> {
+        self.meta
+            .as_ref()?
+            .get(Self::PARAMETERS_META_FIELD)?
+            .as_object()
+    }
+
+    /// The names of the variables in the template, e.g. `["cinema_id"]` for `cinema://{cinema_id}/shows`
+    pub fn variables(&self) -> Vec<&str> {
+        template_parts(&self.uri_template)
+            .filter_map(|part| match part {
+                TemplatePart::Variable(name) => Some(name),
+                TemplatePart::Literal(_) => None,
+            })
+            .collect()
+    }
+
+    /// Extract the variables from a URI matching the template.
+    ///
+    /// Only simple `{name}` expressions are supported, a variable matches a non-empty
+    /// string without `/`. Return `None` if the URI doesn't match.
+    pub fn match_uri(&self, uri: &str) -> Option<HashMap<String, String>> {
+        let mut variables = HashMap::new();
+        let mut rest = uri;
+        let mut parts = template_parts(&self.uri_template).peekable();
+        while let Some(part) = parts.next() {
+            match part {
+                TemplatePart::Literal(literal) => rest = rest.strip_prefix(literal)?,
+                TemplatePart::Variable(name) => {
+                    let end = match parts.peek() {
+                        Some(TemplatePart::Literal(next)) => rest.find(next)?,
+                        _ => rest.len(),
+                    };
+                    let value = &rest[..end];
+                    if value.is_empty() || value.contains('/') {
+                        return None;
+                    }
+                    variables.insert(name.to_string(), value.to_string());
+                    rest = &rest[end..];
+                }
+            }
+        }
+        rest.is_empty().then_some(variables)
+    }
+}
+
+enum TemplatePart<'a> {
+    Literal(&'a str),
+    Variable(&'a str),
+}
+
+fn template_parts(template: &str) -> impl Iterator<Item = TemplatePart<'_>> {
+    let mut rest = template;
+    std::iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+        if let Some(expression) = rest.strip_prefix('{') {
+            if let Some(end) = expression.find('}') {
+                rest = &expression[end + 1..];
+                return Some(TemplatePart::Variable(&expression[..end]));
+            }
+        }
+        // a `{` without `}` is kept as a literal
+        let end = rest[1..].find('{').map_or(rest.len(), |end| end + 1);
+        let literal = &rest[..end];
+        rest = &rest[end..];
+        Some(TemplatePart::Literal(literal))
+    })
 }
 
 impl RawResource {
@@ -125,6 +404,24 @@ mod tests {
         assert!(!json.contains("mime_type"));
     }
 
+    #[test]
+    fn test_resource_template_match_uri() {
+        let template = RawResourceTemplate::new("cinema://{cinema_id}/shows/{day}", "shows");
+        assert_eq!(template.variables(), ["cinema_id", "day"]);
+        let variables = template
+            .match_uri("cinema://42/shows/monday")
+            .expect("match");
+        assert_eq!(variables["cinema_id"], "42");
+        assert_eq!(variables["day"], "monday");
+        assert!(template.match_uri("cinema://42/shows").is_none());
+        assert!(template.match_uri("cinema://42/43/shows/monday").is_none());
+        assert!(
+            template
+                .match_uri("cinema://42/shows/monday/extra")
+                .is_none()
+        );
+    }
+
     #[test]
     fn test_resource_contents_serialization() {
         let text_contents = ResourceContents::TextResourceContents {
@@ -0,0 +1,154 @@
+//! Experimental: a convention for tabular tool results, for clients rendering them as tables
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{CallToolResult, Content, Meta};
+
+/// A column of a [`Table`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct TableColumn {
+    /// The key of the column, like `address`
+    pub name: String,
+    /// The header to display, the name if absent
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+}
+
+impl TableColumn {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            title: None,
+        }
+    }
+
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// The header to display
+    pub fn title(&self) -> &str {
+        self.title.as_deref().unwrap_or(&self.name)
+    }
+}
+
+impl From<&str> for TableColumn {
+    fn from(name: &str) -> Self {
+        Self::new(name)
+    }
+}
+
+impl From<String> for TableColumn {
+    fn from(name: String) -> Self {
+        Self::new(name)
+    }
+}
+
+/// Experimental: tabular data, as columns and rows of JSON values, each row holding one value per
+/// column.
+///
+/// It's returned as the structured content of the result, serialized as
+/// `{"columns": [{"name": "name", "title": "Cinema"}], "rows": [["万达影城"]]}`, and hinted in the
+/// `_meta` of the result as `{"rmcp/experimental/ui": "table"}` so that clients may render it as a
+/// table. The text content is the same table in markdown, for the clients which don't.
+///
+/// ```rust
+/// # use rmcp::model::{Table, TableColumn};
+/// let result = Table::new([TableColumn::new("name").with_title("Cinema"), "distance".into()])
+///     .with_row(["万达影城".into(), 1.2.into()])
+///     .into_call_tool_result();
+/// assert_eq!(Table::from_result(&result).unwrap().rows.len(), 1);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Table {
+    pub columns: Vec<TableColumn>,
+    pub rows: Vec<Vec<Value>>,
+}
+
+impl Table {
+    /// The `_meta` field hinting how to render the structured content
+    pub const UI_HINT_META_FIELD: &str = "rmcp/experimental/ui";
+    pub const UI_HINT: &str = "table";
+
+    pub fn new(columns: impl IntoIterator<Item = impl Into<TableColumn>>) -> Self {
+        Self {
+            columns: columns.into_iter().map(Into::into).collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn with_row(mut self, row: impl IntoIterator<Item = Value>) -> Self {
+        self.push_row(row);
+        self
+    }
+
+    pub fn push_row(&mut self, row: impl IntoIterator<Item = Value>) {
+        self.rows.push(row.into_iter().collect());
+    }
+
+    /// The table in markdown, the strings unquoted and the missing values left empty
+    pub fn to_markdown(&self) -> String {
+        let line = |cells: Vec<String>| format!("| {} |", cells.join(" | "));
+        let mut lines = vec![
+            line(
+                self.columns
+                    .iter()
+                    .map(|column| escape_cell(column.title()))
+                    .collect(),
+            ),
+            line(self.columns.iter().map(|_| "---".to_string()).collect()),
+        ];
+        lines.extend(self.rows.iter().map(|row| {
+            line(
+                (0..self.columns.len())
+                    .map(|index| match row.get(index) {
+                        None | Some(Value::Null) => String::new(),
+                        Some(Value::String(text)) => escape_cell(text),
+                        Some(value) => escape_cell(&value.to_string()),
+                    })
+                    .collect(),
+            )
+        }));
+        lines.join("\n")
+    }
+
+    /// Set the table as the structured content of `result`, along with the hint to render it
+    pub fn add_to(self, result: &mut CallToolResult) {
+        result.structured_content =
+            Some(serde_json::to_value(self).expect("table is serializable"));
+        result
+            .meta
+            .get_or_insert_with(Meta::new)
+            .insert(Self::UI_HINT_META_FIELD.to_string(), Self::UI_HINT.into());
+    }
+
+    pub fn into_call_tool_result(self) -> CallToolResult {
+        let mut result = CallToolResult::success(vec![Content::text(self.to_markdown())]);
+        self.add_to(&mut result);
+        result
+    }
+
+    /// The table of a result, `None` if it isn't hinted as a table
+    pub fn from_result(result: &CallToolResult) -> Option<Table> {
+        let hint = result.meta.as_ref()?.get(Self::UI_HINT_META_FIELD)?;
+        if hint != Self::UI_HINT {
+            return None;
+        }
+        serde_json::from_value(result.structured_content.clone()?).ok()
+    }
+}
+
+impl From<Table> for CallToolResult {
+    fn from(value: Table) -> Self {
+        value.into_call_tool_result()
+    }
+}
+
+fn escape_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
@@ -6,7 +6,7 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use super::{Icon, JsonObject};
+use super::{Icon, JsonObject, Meta};
 
 /// A tool that can be used by a model.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -32,6 +32,37 @@ pub struct Tool {
     /// Optional list of icons for the tool
     #[serde(skip_serializing_if = "Option::is_none")]
     pub icons: Option<Vec<Icon>>,
+    /// Optional protocol-level metadata for this tool, like its deprecation, see [`Tool::deprecation`]
+    #[serde(rename = "_meta", skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Meta>,
+}
+
+/// A group of tools the server suggests to show together, like "Location" or "Movies".
+///
+/// The groups of the listed tools are sent in the `_meta` of `tools/list`, see
+/// [`Meta::tool_groups`](super::Meta::tool_groups). They are listed in the suggested order,
+/// as are the tools of each group, and the tools in no group are shown after them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ToolGroup {
+    /// A human-readable label of the group
+    pub label: String,
+    /// The names of the tools in the group
+    pub tools: Vec<String>,
+}
+
+impl ToolGroup {
+    pub fn new<I, T>(label: impl Into<String>, tools: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        Self {
+            label: label.into(),
+            tools: tools.into_iter().map(Into::into).collect(),
+        }
+    }
 }
 
 /// Additional properties describing a Tool to clients.
@@ -150,9 +181,25 @@ impl Tool {
             output_schema: None,
             annotations: None,
             icons: None,
+            meta: None,
         }
     }
 
+    /// Mark the tool deprecated, with a message telling the clients how to migrate, like
+    /// "use get_cinema_list instead". It's sent in the `_meta` of the tool, and each call of the
+    /// tool logs a warning.
+    pub fn with_deprecation(mut self, message: impl Into<String>) -> Self {
+        self.meta
+            .get_or_insert_with(Meta::new)
+            .set_deprecated(message);
+        self
+    }
+
+    /// The deprecation message of the tool, `None` if it isn't deprecated, see [`Tool::with_deprecation`]
+    pub fn deprecation(&self) -> Option<&str> {
+        self.meta.as_ref()?.deprecated()
+    }
+
     pub fn annotate(self, annotations: ToolAnnotations) -> Self {
         Tool {
             annotations: Some(annotations),
@@ -4,9 +4,10 @@ use thiserror::Error;
 use crate::{
     error::ErrorData as McpError,
     model::{
-        CancelledNotification, CancelledNotificationParam, Extensions, GetExtensions, GetMeta,
-        JsonRpcError, JsonRpcMessage, JsonRpcNotification, JsonRpcRequest, JsonRpcResponse, Meta,
-        NumberOrString, ProgressToken, RequestId, ServerJsonRpcMessage,
+        CancelledNotification, CancelledNotificationParam, ConstString, ErrorCode, Extensions,
+        GetExtensions, GetMeta, InitializeResultMethod, JsonRpcError, JsonRpcMessage,
+        JsonRpcNotification, JsonRpcRequest, JsonRpcResponse, Meta, NumberOrString, ProgressToken,
+        RequestId, ServerJsonRpcMessage, SessionClosedNotification, SessionClosedNotificationParam,
     },
     transport::{DynamicTransportError, IntoTransport, Transport},
 };
@@ -16,12 +17,40 @@ mod client;
 #[cfg(feature = "client")]
 #[cfg_attr(docsrs, doc(cfg(feature = "client")))]
 pub use client::*;
+#[cfg(feature = "client")]
+#[cfg_attr(docsrs, doc(cfg(feature = "client")))]
+mod client_manager;
+#[cfg(feature = "client")]
+#[cfg_attr(docsrs, doc(cfg(feature = "client")))]
+pub use client_manager::{ClientManager, ClientManagerError};
 #[cfg(feature = "server")]
 #[cfg_attr(docsrs, doc(cfg(feature = "server")))]
 mod server;
 #[cfg(feature = "server")]
 #[cfg_attr(docsrs, doc(cfg(feature = "server")))]
 pub use server::*;
+mod lazy_init;
+pub use lazy_init::{LazyInit, LazyInitError};
+mod shared_state;
+pub use shared_state::SharedState;
+mod shutdown;
+pub use shutdown::Shutdown;
+mod notification_queue;
+use notification_queue::{CoalescibleNotification, NotificationQueue};
+pub use notification_queue::{NotificationOverflowPolicy, NotificationQueueConfig};
+mod progress;
+use progress::ProgressTracker;
+pub use progress::{Progress, ProgressStream};
+mod resource_chunks;
+use resource_chunks::ChunkNotification;
+#[cfg(feature = "client")]
+use resource_chunks::ChunkTracker;
+mod metrics;
+pub use metrics::{MetricsRecorder, RequestOutcome};
+mod redaction;
+pub use redaction::{REDACTED, Redactor, redact};
+mod error_policy;
+pub use error_policy::{CloseOnError, ErrorAction, ErrorCategory};
 #[cfg(feature = "tower")]
 #[cfg_attr(docsrs, doc(cfg(feature = "tower")))]
 mod tower;
@@ -45,6 +74,24 @@ pub enum ServiceError {
     Cancelled { reason: Option<String> },
     #[error("request timeout after {}", chrono::Duration::from_std(*timeout).unwrap_or_default())]
     Timeout { timeout: Duration },
+    #[error("pagination doesn't advance, cursor {cursor:?} was already visited")]
+    PaginationStalled { cursor: String },
+    #[error("session closed: {reason}")]
+    SessionClosed { reason: String },
+    #[error("transport timed out: {0}")]
+    TransportTimeout(crate::transport::TransportTimeout),
+    #[error("invalid contents of resource {uri}: {reason}")]
+    InvalidResourceContents { uri: String, reason: String },
+    #[error("fail to write the contents of resource {uri}: {source}")]
+    WriteResourceContents {
+        uri: String,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("invalid arguments of prompt {name}: {reason}")]
+    InvalidPromptArguments { name: String, reason: String },
+    #[error("invalid response: {0}")]
+    InvalidResponse(#[source] serde_json::Error),
 }
 
 trait TransferObject:
@@ -52,6 +99,120 @@ trait TransferObject:
 {
 }
 
+/// The method of a request or a notification, e.g. to label its metrics or log it
+trait GetMethod {
+    fn method(&self) -> &str;
+}
+
+impl GetMethod for crate::model::ClientRequest {
+    fn method(&self) -> &str {
+        crate::model::ClientRequest::method(self)
+    }
+}
+
+impl GetMethod for crate::model::ServerRequest {
+    fn method(&self) -> &str {
+        crate::model::ServerRequest::method(self)
+    }
+}
+
+impl GetMethod for crate::model::ClientNotification {
+    fn method(&self) -> &str {
+        crate::model::ClientNotification::method(self)
+    }
+}
+
+impl GetMethod for crate::model::ServerNotification {
+    fn method(&self) -> &str {
+        crate::model::ServerNotification::method(self)
+    }
+}
+
+/// Decode the typed result of a request, see [`CustomResultFallback`]
+type ResultDecoder<Resp> = fn(&serde_json::Value) -> Result<Resp, serde_json::Error>;
+
+/// The results which may have been taken for the result of a custom request.
+///
+/// Any result which doesn't parse as a typed one parses as a custom one, so the result of a
+/// request which isn't custom is decoded again as the expected type, see [`DecodeResult`].
+trait CustomResultFallback {
+    fn custom_result(&self) -> Option<&serde_json::Value>;
+}
+
+impl CustomResultFallback for crate::model::ServerResult {
+    fn custom_result(&self) -> Option<&serde_json::Value> {
+        match self {
+            crate::model::ServerResult::CustomResult(result) => Some(&result.0),
+            _ => None,
+        }
+    }
+}
+
+impl CustomResultFallback for crate::model::ClientResult {
+    fn custom_result(&self) -> Option<&serde_json::Value> {
+        None
+    }
+}
+
+/// The decoder of the expected result of a request, `None` when any result is expected
+trait DecodeResult<Resp> {
+    fn result_decoder(&self) -> Option<ResultDecoder<Resp>>;
+}
+
+impl DecodeResult<crate::model::ServerResult> for crate::model::ClientRequest {
+    fn result_decoder(&self) -> Option<ResultDecoder<crate::model::ServerResult>> {
+        use crate::model::{ClientRequest, ServerResult};
+        fn decode<T: serde::de::DeserializeOwned>(
+            result: &serde_json::Value,
+            variant: fn(T) -> ServerResult,
+        ) -> Result<ServerResult, serde_json::Error> {
+            T::deserialize(result).map(variant)
+        }
+        Some(match self {
+            ClientRequest::PingRequest(_)
+            | ClientRequest::SetLevelRequest(_)
+            | ClientRequest::SubscribeRequest(_)
+            | ClientRequest::UnsubscribeRequest(_) => {
+                |result| decode(result, ServerResult::EmptyResult)
+            }
+            ClientRequest::InitializeRequest(_) => {
+                |result| decode(result, ServerResult::InitializeResult)
+            }
+            ClientRequest::CompleteRequest(_) => {
+                |result| decode(result, ServerResult::CompleteResult)
+            }
+            ClientRequest::GetPromptRequest(_) => {
+                |result| decode(result, ServerResult::GetPromptResult)
+            }
+            ClientRequest::ListPromptsRequest(_) => {
+                |result| decode(result, ServerResult::ListPromptsResult)
+            }
+            ClientRequest::ListResourcesRequest(_) => {
+                |result| decode(result, ServerResult::ListResourcesResult)
+            }
+            ClientRequest::ListResourceTemplatesRequest(_) => {
+                |result| decode(result, ServerResult::ListResourceTemplatesResult)
+            }
+            ClientRequest::ReadResourceRequest(_) => {
+                |result| decode(result, ServerResult::ReadResourceResult)
+            }
+            ClientRequest::CallToolRequest(_) => {
+                |result| decode(result, ServerResult::CallToolResult)
+            }
+            ClientRequest::ListToolsRequest(_) => {
+                |result| decode(result, ServerResult::ListToolsResult)
+            }
+            ClientRequest::CustomRequest(_) => return None,
+        })
+    }
+}
+
+impl DecodeResult<crate::model::ClientResult> for crate::model::ServerRequest {
+    fn result_decoder(&self) -> Option<ResultDecoder<crate::model::ClientResult>> {
+        None
+    }
+}
+
 impl<T> TransferObject for T where
     T: std::fmt::Debug
         + serde::Serialize
@@ -65,18 +226,25 @@ impl<T> TransferObject for T where
 
 #[allow(private_bounds, reason = "there's no the third implementation")]
 pub trait ServiceRole: std::fmt::Debug + Send + Sync + 'static + Copy + Clone {
-    type Req: TransferObject + GetMeta + GetExtensions;
+    type Req: TransferObject + GetMeta + GetExtensions + DecodeResult<Self::PeerResp>;
     type Resp: TransferObject;
     type Not: TryInto<CancelledNotification, Error = Self::Not>
         + From<CancelledNotification>
+        + From<SessionClosedNotification>
+        + CoalescibleNotification
         + TransferObject;
-    type PeerReq: TransferObject + GetMeta + GetExtensions;
-    type PeerResp: TransferObject;
+    type PeerReq: TransferObject + GetMeta + GetExtensions + GetMethod;
+    type PeerResp: TransferObject + CustomResultFallback;
     type PeerNot: TryInto<CancelledNotification, Error = Self::PeerNot>
         + From<CancelledNotification>
+        + TryInto<SessionClosedNotification, Error = Self::PeerNot>
+        + From<SessionClosedNotification>
+        + CoalescibleNotification
+        + ChunkNotification
         + TransferObject
         + GetMeta
-        + GetExtensions;
+        + GetExtensions
+        + GetMethod;
     type InitializeError;
     const IS_CLIENT: bool;
     type Info: TransferObject;
@@ -193,7 +361,7 @@ impl<R: ServiceRole, S: Service<R>> DynService<R> for S {
 use std::{
     collections::{HashMap, VecDeque},
     ops::Deref,
-    sync::{Arc, atomic::AtomicU64},
+    sync::{Arc, Weak, atomic::AtomicU64},
     time::Duration,
 };
 
@@ -248,14 +416,21 @@ pub struct RequestHandle<R: ServiceRole> {
 
 impl<R: ServiceRole> RequestHandle<R> {
     pub const REQUEST_TIMEOUT_REASON: &str = "request timeout";
+    pub const REQUEST_DROPPED_REASON: &str = "request dropped";
+    /// Wait for the response of this request.
+    ///
+    /// If the returned future is dropped before the response arrives, a cancellation
+    /// notification is sent to the peer and the pending response slot is released.
     pub async fn await_response(self) -> Result<R::PeerResp, ServiceError> {
-        if let Some(timeout) = self.options.timeout {
-            let timeout_result = tokio::time::timeout(timeout, async move {
-                self.rx.await.map_err(|_e| ServiceError::TransportClosed)?
-            })
-            .await;
+        let mut guard = CancelOnDrop {
+            peer: self.peer.clone(),
+            id: Some(self.id.clone()),
+        };
+        let response = if let Some(timeout) = self.options.timeout {
+            let timeout_result = tokio::time::timeout(timeout, self.rx).await;
+            guard.disarm();
             match timeout_result {
-                Ok(response) => response,
+                Ok(response) => response.unwrap_or(Err(ServiceError::TransportClosed)),
                 Err(_) => {
                     let error = Err(ServiceError::Timeout { timeout });
                     // cancel this request
@@ -272,8 +447,15 @@ impl<R: ServiceRole> RequestHandle<R> {
                 }
             }
         } else {
-            self.rx.await.map_err(|_e| ServiceError::TransportClosed)?
-        }
+            let response = self.rx.await;
+            guard.disarm();
+            response.unwrap_or(Err(ServiceError::TransportClosed))
+        };
+        self.peer
+            .shared
+            .progress_tracker
+            .untrack(&self.progress_token);
+        response
     }
 
     /// Cancel this request
@@ -291,17 +473,80 @@ impl<R: ServiceRole> RequestHandle<R> {
     }
 }
 
+/// Sends a cancellation for the request if the awaiting future is dropped early.
+struct CancelOnDrop<R: ServiceRole> {
+    peer: Peer<R>,
+    id: Option<RequestId>,
+}
+
+impl<R: ServiceRole> CancelOnDrop<R> {
+    fn disarm(&mut self) {
+        self.id = None;
+    }
+}
+
+impl<R: ServiceRole> Drop for CancelOnDrop<R> {
+    fn drop(&mut self) {
+        let Some(request_id) = self.id.take() else {
+            return;
+        };
+        let notification = CancelledNotification {
+            params: CancelledNotificationParam {
+                request_id,
+                reason: Some(RequestHandle::<R>::REQUEST_DROPPED_REASON.to_owned()),
+            },
+            method: crate::model::CancelledNotificationMethod,
+            extensions: Default::default(),
+        };
+        // we can't await in drop, and nobody is waiting for the result of this notification,
+        // the serve loop will also remove the responder of this request.
+        let (responder, _receiver) = tokio::sync::oneshot::channel();
+        let message = PeerSinkMessage::Notification {
+            notification: notification.into(),
+            responder,
+        };
+        match self.peer.shared.tx.try_send(message) {
+            Ok(()) => {}
+            // a busy sink is when the cancellation matters the most, wait for a slot
+            Err(mpsc::error::TrySendError::Full(message)) => {
+                match tokio::runtime::Handle::try_current() {
+                    Ok(handle) => {
+                        let tx = self.peer.shared.tx.clone();
+                        handle.spawn(async move {
+                            if tx.send(message).await.is_err() {
+                                tracing::debug!("fail to send cancellation for dropped request");
+                            }
+                        });
+                    }
+                    Err(_) => {
+                        tracing::warn!("sink full, the cancellation of a dropped request is lost");
+                    }
+                }
+            }
+            Err(error @ mpsc::error::TrySendError::Closed(_)) => {
+                tracing::debug!(%error, "fail to send cancellation for dropped request");
+            }
+        }
+    }
+}
+
 #[derive(Debug)]
 pub(crate) enum PeerSinkMessage<R: ServiceRole> {
     Request {
         request: R::Req,
         id: RequestId,
         responder: Responder<Result<R::PeerResp, ServiceError>>,
+        /// The request is evicted with [`ServiceError::Timeout`] if no response arrives in time
+        timeout: Option<Duration>,
     },
     Notification {
         notification: R::Not,
         responder: Responder<Result<(), ServiceError>>,
     },
+    Close {
+        reason: String,
+        responder: Responder<Result<(), ServiceError>>,
+    },
 }
 
 /// An interface to fetch the remote client or server
@@ -309,35 +554,155 @@ pub(crate) enum PeerSinkMessage<R: ServiceRole> {
 /// For general purpose, call [`Peer::send_request`] or [`Peer::send_notification`] to send message to remote peer.
 ///
 /// To create a cancellable request, call [`Peer::send_request_with_option`].
+///
+/// To keep it around without keeping the session alive, [`Peer::downgrade`] it to a [`WeakPeer`].
 #[derive(Clone)]
 pub struct Peer<R: ServiceRole> {
+    shared: Arc<PeerShared<R>>,
+}
+
+/// The state of a [`Peer`], shared by all its clones and by its [`WeakPeer`]s.
+struct PeerShared<R: ServiceRole> {
     tx: mpsc::Sender<PeerSinkMessage<R>>,
     request_id_provider: Arc<dyn RequestIdProvider>,
     progress_token_provider: Arc<dyn ProgressTokenProvider>,
-    info: Arc<tokio::sync::OnceCell<R::PeerInfo>>,
+    info: tokio::sync::OnceCell<R::PeerInfo>,
+    initialized: std::sync::atomic::AtomicBool,
+    notification_queue: std::sync::OnceLock<Arc<NotificationQueue<R>>>,
+    log_queue: std::sync::OnceLock<Arc<NotificationQueue<R>>>,
+    initialize_meta: std::sync::OnceLock<Meta>,
+    initialize_roots: std::sync::OnceLock<Vec<crate::model::Root>>,
+    default_locale: std::sync::OnceLock<String>,
+    request_timeout: std::sync::Mutex<Option<Duration>>,
+    handler_timeout: std::sync::Mutex<Option<Duration>>,
+    retry_policy: std::sync::Mutex<Option<RetryPolicy>>,
+    progress_tracker: ProgressTracker,
+    min_log_level: std::sync::Mutex<Option<crate::model::LoggingLevel>>,
+    message_redactor: std::sync::Mutex<Option<Arc<dyn Redactor>>>,
+    metrics_recorder: std::sync::Mutex<Option<Arc<dyn MetricsRecorder>>>,
+    close_on_error: std::sync::Mutex<CloseOnError>,
+    #[cfg(feature = "client")]
+    prompt_cache: std::sync::Mutex<PromptCache>,
+    #[cfg(feature = "client")]
+    chunk_tracker: ChunkTracker,
+}
+
+/// The prompts listed by the server, cached by the client until they change
+#[cfg(feature = "client")]
+#[derive(Debug, Default)]
+struct PromptCache {
+    // bumped by every `notifications/prompts/list_changed`, so a listing which raced one isn't kept
+    generation: u64,
+    prompts: Option<Arc<Vec<crate::model::Prompt>>>,
 }
 
 impl<R: ServiceRole> std::fmt::Debug for Peer<R> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("PeerSink")
-            .field("tx", &self.tx)
+            .field("tx", &self.shared.tx)
             .field("is_client", &R::IS_CLIENT)
             .finish()
     }
 }
 
+/// A [`Peer`] which doesn't keep its session alive, see [`Peer::downgrade`].
+///
+/// Store it in the handlers and in their background tasks instead of a [`Peer`], to send
+/// messages later without leaking the session once the remote peer is gone.
+pub struct WeakPeer<R: ServiceRole> {
+    shared: Weak<PeerShared<R>>,
+}
+
+impl<R: ServiceRole> Clone for WeakPeer<R> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<R: ServiceRole> std::fmt::Debug for WeakPeer<R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WeakPeer")
+            .field("alive", &(self.shared.strong_count() > 0))
+            .field("is_client", &R::IS_CLIENT)
+            .finish()
+    }
+}
+
+impl<R: ServiceRole> WeakPeer<R> {
+    /// Get the [`Peer`] back, as long as its session lives.
+    ///
+    /// Returns `None` once the session is gone, i.e. the service quit because the remote
+    /// peer disconnected, the session was closed or cancelled: there's no one to send to anymore.
+    pub fn upgrade(&self) -> Option<Peer<R>> {
+        let shared = self
+            .shared
+            .upgrade()
+            .filter(|shared| !shared.tx.is_closed())?;
+        Some(Peer { shared })
+    }
+
+    /// Whether both handles refer to the same session.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        self.shared.ptr_eq(&other.shared)
+    }
+}
+
 type ProxyOutbound<R> = mpsc::Receiver<PeerSinkMessage<R>>;
 
 #[derive(Debug, Default)]
 pub struct PeerRequestOptions {
+    /// How long this request waits for a response, instead of [`Peer::request_timeout`].
+    /// [`PeerRequestOptions::NO_TIMEOUT`] to wait forever.
     pub timeout: Option<Duration>,
     pub meta: Option<Meta>,
 }
 
 impl PeerRequestOptions {
+    /// A [`PeerRequestOptions::timeout`] which never expires, e.g. for the sampling and
+    /// elicitation requests waiting on the user.
+    pub const NO_TIMEOUT: Duration = Duration::MAX;
     pub fn no_options() -> Self {
         Self::default()
     }
+    /// Wait for the response forever, whatever the [`Peer::request_timeout`].
+    pub fn no_timeout() -> Self {
+        Self {
+            timeout: Some(Self::NO_TIMEOUT),
+            ..Self::default()
+        }
+    }
+}
+
+/// How [`Peer::send_request`] retries the requests failing with a retryable error, see
+/// [`ErrorData::is_retryable`](crate::ErrorData::is_retryable).
+///
+/// The delay before each retry doubles from `initial_delay`, up to `max_delay`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub max_retries: usize,
+    pub initial_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_delay: Duration::from_millis(200),
+            max_delay: Duration::from_secs(5),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// The delay before the retry number `retry`, counted from 0
+    pub fn delay(&self, retry: usize) -> Duration {
+        self.initial_delay
+            .saturating_mul(1 << retry.min(31))
+            .min(self.max_delay)
+    }
 }
 
 impl<R: ServiceRole> Peer<R> {
@@ -347,19 +712,225 @@ impl<R: ServiceRole> Peer<R> {
         peer_info: Option<R::PeerInfo>,
     ) -> (Peer<R>, ProxyOutbound<R>) {
         let (tx, rx) = mpsc::channel(Self::CLIENT_CHANNEL_BUFFER_SIZE);
+        let shared = PeerShared {
+            tx,
+            request_id_provider,
+            progress_token_provider: Arc::new(AtomicU32ProgressTokenProvider::default()),
+            info: tokio::sync::OnceCell::new_with(peer_info),
+            initialized: Default::default(),
+            notification_queue: Default::default(),
+            log_queue: Default::default(),
+            initialize_meta: Default::default(),
+            initialize_roots: Default::default(),
+            default_locale: Default::default(),
+            request_timeout: Default::default(),
+            handler_timeout: Default::default(),
+            retry_policy: Default::default(),
+            progress_tracker: Default::default(),
+            min_log_level: Default::default(),
+            message_redactor: Default::default(),
+            metrics_recorder: Default::default(),
+            close_on_error: Default::default(),
+            #[cfg(feature = "client")]
+            prompt_cache: Default::default(),
+            #[cfg(feature = "client")]
+            chunk_tracker: Default::default(),
+        };
         (
             Self {
-                tx,
-                request_id_provider,
-                progress_token_provider: Arc::new(AtomicU32ProgressTokenProvider::default()),
-                info: Arc::new(tokio::sync::OnceCell::new_with(peer_info)),
+                shared: Arc::new(shared),
             },
             rx,
         )
     }
+    /// How long a request sent to the remote peer waits for a response.
+    ///
+    /// Once expired, the awaiting future fails with [`ServiceError::Timeout`], the request is
+    /// cancelled on the remote peer and forgotten, so requests that are never answered
+    /// don't pile up. `None` by default, waiting forever, servers can set it up front with
+    /// [`HandshakeConfig::request_timeout`](crate::service::HandshakeConfig::request_timeout).
+    /// A request overrides it with its [`PeerRequestOptions::timeout`].
+    pub fn request_timeout(&self) -> Option<Duration> {
+        *self
+            .shared
+            .request_timeout
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Change [`Peer::request_timeout`] for the requests sent from now on, `None` to wait forever.
+    pub fn set_request_timeout(&self, timeout: Option<Duration>) {
+        *self
+            .shared
+            .request_timeout
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner) = timeout;
+    }
+    /// The longest a request from the remote peer is handled, `None` (the default) for no limit.
+    ///
+    /// The remote peer can ask for a shorter one with a `rmcp/timeoutMs` hint in the request `_meta`,
+    /// see [`Meta::timeout`]. Once expired, the handler is cancelled and the request answered
+    /// with a [`ErrorCode::REQUEST_TIMEOUT`](crate::model::ErrorCode::REQUEST_TIMEOUT) error.
+    pub fn handler_timeout(&self) -> Option<Duration> {
+        *self
+            .shared
+            .handler_timeout
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Change [`Peer::handler_timeout`] for the requests received from now on.
+    pub fn set_handler_timeout(&self, timeout: Option<Duration>) {
+        *self
+            .shared
+            .handler_timeout
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner) = timeout;
+    }
+    /// How [`Peer::send_request`] retries the requests failing with a retryable error, `None`
+    /// (the default) not to retry.
+    pub fn retry_policy(&self) -> Option<RetryPolicy> {
+        *self
+            .shared
+            .retry_policy
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Change [`Peer::retry_policy`] for the requests sent from now on.
+    ///
+    /// Only the requests sent with [`Peer::send_request`] and the methods built on it, like
+    /// `call_tool`, are retried, not the ones sent with [`Peer::send_request_with_option`].
+    pub fn set_retry_policy(&self, policy: Option<RetryPolicy>) {
+        *self
+            .shared
+            .retry_policy
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner) = policy;
+    }
+    /// The redactor of the logged messages, `None` (the default) when they aren't logged,
+    /// see [`Peer::set_message_logging`].
+    pub fn message_logging(&self) -> Option<Arc<dyn Redactor>> {
+        self.shared
+            .message_redactor
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Log the requests, responses and errors exchanged with the remote peer in full, as JSON
+    /// at debug level, with the fields picked by the `redactor` masked. `None` to stop.
+    pub fn set_message_logging(&self, redactor: Option<Arc<dyn Redactor>>) {
+        *self
+            .shared
+            .message_redactor
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner) = redactor;
+    }
+
+    /// The recorder of the outcomes of the handled requests, see [`Peer::set_metrics_recorder`].
+    pub fn metrics_recorder(&self) -> Option<Arc<dyn MetricsRecorder>> {
+        self.shared
+            .metrics_recorder
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Report the outcome of every request received from the remote peer to the `recorder`: whether
+    /// it completed, failed, timed out or was cancelled by the peer. `None` (the default) to stop.
+    ///
+    /// The requests left unanswered when the service stops aren't reported.
+    pub fn set_metrics_recorder(&self, recorder: Option<Arc<dyn MetricsRecorder>>) {
+        *self
+            .shared
+            .metrics_recorder
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner) = recorder;
+    }
+
+    /// Which errors answered to the remote peer close the session, see [`Peer::set_close_on_error`].
+    pub fn close_on_error(&self) -> CloseOnError {
+        *self
+            .shared
+            .close_on_error
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Close the session once an error of a category the `policy` maps to [`ErrorAction::Close`]
+    /// is answered to the remote peer, e.g. with [`CloseOnError::strict`] a client calling a
+    /// method which doesn't exist is disconnected, while a failed tool call goes on.
+    ///
+    /// The error is sent first, then the session is closed as by [`Peer::close`]. Every error
+    /// continues by default. The messages the transport can't decode, like a malformed envelope
+    /// over stdio, always close it.
+    pub fn set_close_on_error(&self, policy: CloseOnError) {
+        *self
+            .shared
+            .close_on_error
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner) = policy;
+    }
+
+    fn record_request(&self, method: &str, outcome: RequestOutcome) {
+        if let Some(recorder) = self.metrics_recorder() {
+            recorder.record_request(method, &outcome);
+        }
+    }
+
+    fn log_message<Req, Resp, Not>(
+        &self,
+        direction: &'static str,
+        message: &JsonRpcMessage<Req, Resp, Not>,
+    ) where
+        JsonRpcMessage<Req, Resp, Not>: serde::Serialize,
+    {
+        if matches!(message, JsonRpcMessage::Notification(_))
+            || !tracing::enabled!(tracing::Level::DEBUG)
+        {
+            return;
+        }
+        let Some(redactor) = self.message_logging() else {
+            return;
+        };
+        match serde_json::to_value(message) {
+            Ok(mut message) => {
+                redact(&mut message, redactor.as_ref());
+                tracing::debug!(direction, %message, "json-rpc message");
+            }
+            Err(error) => tracing::warn!(direction, %error, "fail to serialize message to log"),
+        }
+    }
+    /// Send outgoing notifications through a bounded queue, see [`NotificationQueueConfig`].
+    ///
+    /// Once enabled, [`Peer::send_notification`] returns when the notification is queued instead of sent,
+    /// and transport errors are only logged. Returns `false` if the queue has already been enabled.
+    pub fn enable_notification_queue(&self, config: NotificationQueueConfig) -> bool {
+        let mut enabled = false;
+        self.shared.notification_queue.get_or_init(|| {
+            enabled = true;
+            NotificationQueue::spawn(config, self.shared.tx.clone())
+        });
+        enabled
+    }
+
+    /// The number of notifications waiting in the notification queue
+    pub fn queued_notifications(&self) -> usize {
+        self.shared
+            .notification_queue
+            .get()
+            .map(|queue| queue.len())
+            .unwrap_or_default()
+    }
+
     pub async fn send_notification(&self, notification: R::Not) -> Result<(), ServiceError> {
+        if let Some(queue) = self.shared.notification_queue.get() {
+            return queue.push(notification).await;
+        }
         let (responder, receiver) = tokio::sync::oneshot::channel();
-        self.tx
+        self.shared
+            .tx
             .send(PeerSinkMessage::Notification {
                 notification,
                 responder,
@@ -368,11 +939,46 @@ impl<R: ServiceRole> Peer<R> {
             .map_err(|_m| ServiceError::TransportClosed)?;
         receiver.await.map_err(|_e| ServiceError::TransportClosed)?
     }
-    pub async fn send_request(&self, request: R::Req) -> Result<R::PeerResp, ServiceError> {
-        self.send_request_with_option(request, PeerRequestOptions::no_options())
-            .await?
-            .await_response()
+    /// Close the session, telling the remote peer the reason with a [`SessionClosedNotification`].
+    ///
+    /// The requests still waiting for a response fail with [`ServiceError::SessionClosed`],
+    /// and the service quits with [`QuitReason::SessionClosed`] on both sides.
+    pub async fn close(&self, reason: impl Into<String>) -> Result<(), ServiceError> {
+        let (responder, receiver) = tokio::sync::oneshot::channel();
+        self.shared
+            .tx
+            .send(PeerSinkMessage::Close {
+                reason: reason.into(),
+                responder,
+            })
             .await
+            .map_err(|_m| ServiceError::TransportClosed)?;
+        receiver.await.map_err(|_e| ServiceError::TransportClosed)?
+    }
+    pub async fn send_request(&self, request: R::Req) -> Result<R::PeerResp, ServiceError> {
+        let send = |request| async move {
+            self.send_request_with_option(request, PeerRequestOptions::no_options())
+                .await?
+                .await_response()
+                .await
+        };
+        let Some(policy) = self.retry_policy() else {
+            return send(request).await;
+        };
+        let mut retry = 0;
+        loop {
+            match send(request.clone()).await {
+                Err(ServiceError::McpError(error))
+                    if error.is_retryable() && retry < policy.max_retries =>
+                {
+                    let delay = policy.delay(retry);
+                    tracing::debug!(%error, ?delay, "retry the request");
+                    tokio::time::sleep(delay).await;
+                    retry += 1;
+                }
+                result => return result,
+            }
+        }
     }
 
     pub async fn send_cancellable_request(
@@ -388,20 +994,27 @@ impl<R: ServiceRole> Peer<R> {
         mut request: R::Req,
         options: PeerRequestOptions,
     ) -> Result<RequestHandle<R>, ServiceError> {
-        let id = self.request_id_provider.next_request_id();
-        let progress_token = self.progress_token_provider.next_progress_token();
+        let id = self.shared.request_id_provider.next_request_id();
+        let progress_token = self.shared.progress_token_provider.next_progress_token();
         request
             .get_meta_mut()
             .set_progress_token(progress_token.clone());
         if let Some(meta) = options.meta.clone() {
             request.get_meta_mut().extend(meta);
         }
+        // the options may carry a token of their own
+        let progress_token = request
+            .get_meta()
+            .get_progress_token()
+            .unwrap_or(progress_token);
         let (responder, receiver) = tokio::sync::oneshot::channel();
-        self.tx
+        self.shared
+            .tx
             .send(PeerSinkMessage::Request {
                 request,
                 id: id.clone(),
                 responder,
+                timeout: options.timeout.or_else(|| self.request_timeout()),
             })
             .await
             .map_err(|_m| ServiceError::TransportClosed)?;
@@ -413,20 +1026,51 @@ impl<R: ServiceRole> Peer<R> {
             peer: self.clone(),
         })
     }
+    /// Track the progress the peer reports for a request, i.e. the `notifications/progress`
+    /// carrying `progress_token`.
+    ///
+    /// Every request carries a progress token, see [`RequestHandle::progress_token`]. Set one of
+    /// your own in the `_meta` of its [`PeerRequestOptions`] and track it before sending the
+    /// request, so no update is missed. The stream ends once the response is received by
+    /// [`RequestHandle::await_response`]. The notifications are still passed to the handler.
+    pub fn track_progress(&self, progress_token: ProgressToken) -> ProgressStream {
+        self.shared.progress_tracker.track(progress_token)
+    }
+
+    /// A [`WeakPeer`] to this peer, which doesn't keep the session alive.
+    pub fn downgrade(&self) -> WeakPeer<R> {
+        WeakPeer {
+            shared: Arc::downgrade(&self.shared),
+        }
+    }
+
     pub fn peer_info(&self) -> Option<&R::PeerInfo> {
-        self.info.get()
+        self.shared.info.get()
     }
 
     pub fn set_peer_info(&self, info: R::PeerInfo) {
-        if self.info.initialized() {
+        if self.shared.info.initialized() {
             tracing::warn!("trying to set peer info, which is already initialized");
         } else {
-            let _ = self.info.set(info);
+            let _ = self.shared.info.set(info);
         }
     }
 
+    /// Whether the peer has completed the initialization, i.e. the `initialized` notification was received.
+    pub fn is_initialized(&self) -> bool {
+        self.shared
+            .initialized
+            .load(std::sync::atomic::Ordering::Acquire)
+    }
+
+    pub(crate) fn mark_initialized(&self) {
+        self.shared
+            .initialized
+            .store(true, std::sync::atomic::Ordering::Release);
+    }
+
     pub fn is_transport_closed(&self) -> bool {
-        self.tx.is_closed()
+        self.shared.tx.is_closed()
     }
 }
 
@@ -480,16 +1124,27 @@ impl RunningServiceCancellationToken {
 }
 
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum QuitReason {
     Cancelled,
     Closed,
     JoinError(tokio::task::JoinError),
+    /// The session was closed by [`Peer::close`], on this side or by the remote peer
+    SessionClosed {
+        reason: String,
+        by_peer: bool,
+    },
+    /// The transport stalled, see [`TimeoutTransport`](crate::transport::TimeoutTransport)
+    TransportTimeout(crate::transport::TransportTimeout),
 }
 
 /// Request execution context
 #[derive(Debug, Clone)]
 pub struct RequestContext<R: ServiceRole> {
-    /// this token will be cancelled when the [`CancelledNotification`] is received.
+    /// this token will be cancelled when the [`CancelledNotification`] of this request is received.
+    ///
+    /// Cancellation is scoped to the request id: the other requests of the session, even those
+    /// running concurrently on the same service, keep going.
     pub ct: CancellationToken,
     pub id: RequestId,
     pub meta: Meta,
@@ -498,6 +1153,55 @@ pub struct RequestContext<R: ServiceRole> {
     pub peer: Peer<R>,
 }
 
+impl<R: ServiceRole> RequestContext<R> {
+    /// Whether the requester asked for a dry run by setting `rmcp/dryRun` in the request's `_meta`,
+    /// see [`Meta::set_dry_run`].
+    ///
+    /// Dry run is opt-in for handlers. A handler supporting it must not perform any side effect,
+    /// and should reply with a description of what it would have done instead. Handlers without
+    /// side effects, like read-only tools, can ignore it and reply as usual.
+    pub fn is_dry_run(&self) -> bool {
+        self.meta.dry_run()
+    }
+
+    /// When the handling of this request times out, see [`Peer::handler_timeout`].
+    ///
+    /// Returns `None` when the request has no timeout.
+    pub fn deadline(&self) -> Option<tokio::time::Instant> {
+        self.extensions
+            .get::<RequestDeadline>()
+            .map(|RequestDeadline(deadline)| *deadline)
+    }
+
+    /// How much time is left before the handling of this request times out, see [`RequestContext::deadline`].
+    ///
+    /// A handler can use it to skip a slow call it couldn't finish in time anyway.
+    pub fn time_remaining(&self) -> Option<Duration> {
+        self.deadline()
+            .map(|deadline| deadline.saturating_duration_since(tokio::time::Instant::now()))
+    }
+
+    /// Run CPU heavy work, like parsing a large JSON, on the blocking thread pool and await it,
+    /// so the runtime keeps serving the other requests meanwhile.
+    ///
+    /// A panic of `f` fails with an internal error. The work can't be interrupted, it runs to
+    /// the end even if the request is cancelled in the meantime.
+    pub async fn spawn_blocking<F, T>(&self, f: F) -> Result<T, McpError>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        tokio::task::spawn_blocking(f).await.map_err(|error| {
+            tracing::error!(id = %self.id, %error, "blocking task of the request failed");
+            McpError::internal_error(format!("blocking task failed: {error}"), None)
+        })
+    }
+}
+
+/// The deadline of a request, stored in the extensions of its [`RequestContext`]
+#[derive(Debug, Clone, Copy)]
+struct RequestDeadline(tokio::time::Instant);
+
 /// Request execution context
 #[derive(Debug, Clone)]
 pub struct NotificationContext<R: ServiceRole> {
@@ -562,9 +1266,19 @@ where
         tracing::info!(?peer_info, "Service initialized as server");
     }
 
-    let mut local_responder_pool =
-        HashMap::<RequestId, Responder<Result<R::PeerResp, ServiceError>>>::new();
-    let mut local_ct_pool = HashMap::<RequestId, CancellationToken>::new();
+    // the requests waiting for a response, with the decoder of their expected result
+    let mut local_responder_pool = HashMap::<
+        RequestId,
+        (
+            Responder<Result<R::PeerResp, ServiceError>>,
+            Option<ResultDecoder<R::PeerResp>>,
+        ),
+    >::new();
+    // the requests being handled, with their method
+    let mut local_ct_pool = HashMap::<RequestId, (CancellationToken, String)>::new();
+    // deadlines of the requests in `local_responder_pool`, the earliest first, forgotten with
+    // their responder
+    let mut request_deadlines = VecDeque::<(tokio::time::Instant, Duration, RequestId)>::new();
     let shared_service = Arc::new(service);
     // for return
     let service = shared_service.clone();
@@ -596,12 +1310,15 @@ where
             PeerMessage(RxJsonRpcMessage<R>),
             ToSink(TxJsonRpcMessage<R>),
             SendTaskResult(SendTaskResult),
+            RequestTimeout,
         }
 
+        let mut close_responder = None;
         let quit_reason = loop {
             let evt = if let Some(m) = batch_messages.pop_front() {
                 Event::PeerMessage(m)
             } else {
+                let next_deadline = request_deadlines.front().map(|(deadline, ..)| *deadline);
                 tokio::select! {
                     m = sink_proxy_rx.recv(), if !sink_proxy_rx.is_closed() => {
                         if let Some(m) = m {
@@ -613,6 +1330,8 @@ where
                     m = transport.receive() => {
                         if let Some(m) = m {
                             Event::PeerMessage(m)
+                        } else if let Some(timeout) = transport.timed_out() {
+                            break QuitReason::TransportTimeout(timeout)
                         } else {
                             // input stream closed
                             tracing::info!("input stream terminated");
@@ -641,6 +1360,9 @@ where
                             }
                         }
                     }
+                    _ = futures::future::OptionFuture::from(next_deadline.map(tokio::time::sleep_until)), if next_deadline.is_some() => {
+                        Event::RequestTimeout
+                    }
                     _ = serve_loop_ct.cancelled() => {
                         tracing::info!("task cancelled");
                         break QuitReason::Cancelled
@@ -649,10 +1371,44 @@ where
             };
 
             tracing::trace!(?evt, "new event");
+            if let Event::PeerMessage(message) = &evt {
+                peer.log_message("received", message);
+            }
             match evt {
+                Event::RequestTimeout => {
+                    let now = tokio::time::Instant::now();
+                    while let Some((_, timeout, id)) = request_deadlines
+                        .front()
+                        .filter(|(deadline, ..)| *deadline <= now)
+                        .cloned()
+                    {
+                        request_deadlines.pop_front();
+                        let Some((responder, _)) = local_responder_pool.remove(&id) else {
+                            continue;
+                        };
+                        tracing::warn!(%id, ?timeout, "request timeout, evicted");
+                        let _ = responder.send(Err(ServiceError::Timeout { timeout }));
+                        let notification = CancelledNotification {
+                            params: CancelledNotificationParam {
+                                request_id: id,
+                                reason: Some(RequestHandle::<R>::REQUEST_TIMEOUT_REASON.to_owned()),
+                            },
+                            method: crate::model::CancelledNotificationMethod,
+                            extensions: Default::default(),
+                        };
+                        let send = transport.send(JsonRpcMessage::notification(notification.into()));
+                        let current_span = tracing::Span::current();
+                        tokio::spawn(async move {
+                            if let Err(error) = send.await {
+                                tracing::warn!(%error, "fail to send cancellation of a timeout request");
+                            }
+                        }.instrument(current_span));
+                    }
+                }
                 Event::SendTaskResult(SendTaskResult::Request { id, result }) => {
                     if let Err(e) = result {
-                        if let Some(responder) = local_responder_pool.remove(&id) {
+                        if let Some((responder, _)) = local_responder_pool.remove(&id) {
+                            request_deadlines.retain(|(.., pending)| *pending != id);
                             let _ = responder.send(Err(ServiceError::TransportSend(e)));
                         }
                     }
@@ -669,7 +1425,8 @@ where
                     };
                     let _ = responder.send(response);
                     if let Some(param) = cancellation_param {
-                        if let Some(responder) = local_responder_pool.remove(&param.request_id) {
+                        if let Some((responder, _)) = local_responder_pool.remove(&param.request_id) {
+                            request_deadlines.retain(|(.., pending)| *pending != param.request_id);
                             tracing::info!(id = %param.request_id, reason = param.reason, "cancelled");
                             let _response_result = responder.send(Err(ServiceError::Cancelled {
                                 reason: param.reason.clone(),
@@ -684,10 +1441,53 @@ where
                         JsonRpcMessage::Error(error) => Some(&error.id),
                         _ => None,
                     } {
-                        if let Some(ct) = local_ct_pool.remove(id) {
+                        if let Some((ct, method)) = local_ct_pool.remove(id) {
                             ct.cancel();
+                            let outcome = match &m {
+                                JsonRpcMessage::Error(JsonRpcError { error, .. })
+                                    if error.code == ErrorCode::REQUEST_TIMEOUT => RequestOutcome::TimedOut,
+                                JsonRpcMessage::Error(JsonRpcError { error, .. }) => RequestOutcome::Failed { code: error.code },
+                                _ => RequestOutcome::Completed,
+                            };
+                            peer.record_request(&method, outcome);
                         }
+                        let close_reason = match &m {
+                            JsonRpcMessage::Error(JsonRpcError { error, .. }) => {
+                                let category = ErrorCategory::of(error.code);
+                                (peer.close_on_error().action(category) == ErrorAction::Close)
+                                    .then(|| format!("closed after a {category} error: {}", error.message))
+                            }
+                            _ => None,
+                        };
+                        peer.log_message("sent", &m);
                         let send = transport.send(m);
+                        if let Some(reason) = close_reason {
+                            let notification = SessionClosedNotification::new(SessionClosedNotificationParam {
+                                reason: reason.clone(),
+                            });
+                            let notify = transport.send(JsonRpcMessage::notification(notification.into()));
+                            let sends = async {
+                                if let Err(error) = send.await {
+                                    tracing::error!(%error, "fail to response message");
+                                }
+                                if let Err(error) = notify.await {
+                                    tracing::warn!(%error, "fail to send session closed notification");
+                                }
+                            };
+                            // a stalled transport must not hold the loop, nor its cancellation
+                            tokio::select! {
+                                result = tokio::time::timeout(CloseOnError::CLOSE_SEND_TIMEOUT, sends) => {
+                                    if result.is_err() {
+                                        tracing::warn!("timeout sending the error closing the session");
+                                    }
+                                }
+                                _ = serve_loop_ct.cancelled() => {
+                                    tracing::info!("task cancelled");
+                                    break QuitReason::Cancelled
+                                }
+                            }
+                            break QuitReason::SessionClosed { reason, by_peer: false }
+                        }
                         let current_span = tracing::Span::current();
                         tokio::spawn(async move {
                             let send_result = send.await;
@@ -701,9 +1501,21 @@ where
                     request,
                     id,
                     responder,
+                    timeout,
                 }) => {
-                    local_responder_pool.insert(id.clone(), responder);
-                    let send = transport.send(JsonRpcMessage::request(request, id.clone()));
+                    local_responder_pool.insert(id.clone(), (responder, request.result_decoder()));
+                    // no deadline for `PeerRequestOptions::NO_TIMEOUT`
+                    let deadline = timeout.and_then(|timeout| {
+                        Some((tokio::time::Instant::now().checked_add(timeout)?, timeout))
+                    });
+                    if let Some((deadline, timeout)) = deadline {
+                        // the timeout may have been changed, keep the deadlines sorted
+                        let index = request_deadlines.partition_point(|(other, ..)| *other <= deadline);
+                        request_deadlines.insert(index, (deadline, timeout, id.clone()));
+                    }
+                    let message = JsonRpcMessage::request(request, id.clone());
+                    peer.log_message("sent", &message);
+                    let send = transport.send(message);
                     {
                         let id = id.clone();
                         let current_span = tracing::Span::current();
@@ -734,24 +1546,63 @@ where
                         result: result.map_err(DynamicTransportError::new::<T, R>),
                     }).instrument(current_span));
                 }
+                Event::ProxyMessage(PeerSinkMessage::Close { reason, responder }) => {
+                    let notification = SessionClosedNotification::new(SessionClosedNotificationParam {
+                        reason: reason.clone(),
+                    });
+                    let notify = transport.send(JsonRpcMessage::notification(notification.into()));
+                    close_responder = Some(responder);
+                    // a stalled transport must not hold the loop, nor its cancellation
+                    tokio::select! {
+                        result = tokio::time::timeout(CloseOnError::CLOSE_SEND_TIMEOUT, notify) => {
+                            match result {
+                                Ok(Err(error)) => {
+                                    tracing::warn!(%error, "fail to send session closed notification");
+                                }
+                                Err(_) => tracing::warn!("timeout sending the session closed notification"),
+                                Ok(Ok(())) => {}
+                            }
+                        }
+                        _ = serve_loop_ct.cancelled() => {
+                            tracing::info!("task cancelled");
+                            break QuitReason::Cancelled
+                        }
+                    }
+                    break QuitReason::SessionClosed { reason, by_peer: false }
+                }
                 Event::PeerMessage(JsonRpcMessage::Request(JsonRpcRequest {
                     id,
                     mut request,
                     ..
                 })) => {
-                    tracing::debug!(%id, ?request, "received request");
+                    tracing::debug!(%id, "received request");
                     {
                         let service = shared_service.clone();
                         let sink = sink_proxy_tx.clone();
                         let request_ct = serve_loop_ct.child_token();
                         let context_ct = request_ct.child_token();
-                        local_ct_pool.insert(id.clone(), request_ct);
+                        let timeout_ct = request_ct.clone();
+                        local_ct_pool.insert(id.clone(), (request_ct, request.method().to_owned()));
+                        // re-negotiation is not defined by the spec, initialize must be the first interaction
+                        let reinitialize =
+                            peer.is_initialized() && request.method() == InitializeResultMethod::VALUE;
                         let mut extensions = Extensions::new();
                         let mut meta = Meta::new();
                         // avoid clone
                         // swap meta firstly, otherwise progress token will be lost
                         std::mem::swap(&mut meta, request.get_meta_mut());
                         std::mem::swap(&mut extensions, request.extensions_mut());
+                        // the hint of the remote peer can only shorten our own limit
+                        let timeout = match (meta.timeout(), peer.handler_timeout()) {
+                            (Some(hint), Some(limit)) => Some(hint.min(limit)),
+                            (hint, limit) => hint.or(limit),
+                        };
+                        // a deadline out of the clock's range is no deadline
+                        let deadline = timeout.and_then(|timeout| {
+                            let deadline = tokio::time::Instant::now().checked_add(timeout)?;
+                            extensions.insert(RequestDeadline(deadline));
+                            Some((deadline, timeout))
+                        });
                         let context = RequestContext {
                             ct: context_ct,
                             id: id.clone(),
@@ -761,12 +1612,31 @@ where
                         };
                         let current_span = tracing::Span::current();
                         tokio::spawn(async move {
-                            let result = service
-                                .handle_request(request, context)
-                                .await;
+                            let handling = async {
+                                if reinitialize {
+                                    return Err(McpError::invalid_request(
+                                        "session is already initialized",
+                                        None,
+                                    ));
+                                }
+                                service.handle_request(request, context).await
+                            };
+                            let result = match deadline {
+                                Some((deadline, timeout)) => match tokio::time::timeout_at(deadline, handling).await {
+                                    Ok(result) => result,
+                                    Err(_) => {
+                                        timeout_ct.cancel();
+                                        Err(McpError::request_timeout(
+                                            "request handling timeout",
+                                            Some(serde_json::json!({ "timeoutMs": timeout.as_millis() as u64 })),
+                                        ))
+                                    }
+                                },
+                                None => handling.await,
+                            };
                             let response = match result {
                                 Ok(result) => {
-                                    tracing::debug!(%id, ?result, "response message");
+                                    tracing::debug!(%id, "response message");
                                     JsonRpcMessage::response(result, id)
                                 }
                                 Err(error) => {
@@ -782,18 +1652,39 @@ where
                     notification,
                     ..
                 })) => {
-                    tracing::info!(?notification, "received notification");
+                    tracing::info!(method = notification.method(), "received notification");
+                    if let Some(progress) = notification.progress() {
+                        peer.shared.progress_tracker.dispatch(progress);
+                    }
+                    // in the serve loop, so the chunks of a blob are all dispatched before its response
+                    #[cfg(feature = "client")]
+                    if let Some(chunk) = notification.resource_chunk() {
+                        peer.shared.chunk_tracker.dispatch(chunk);
+                    }
                     // catch cancelled notification
-                    let mut notification = match notification.try_into() {
+                    let notification = match notification.try_into() {
                         Ok::<CancelledNotification, _>(cancelled) => {
-                            if let Some(ct) = local_ct_pool.remove(&cancelled.params.request_id) {
+                            if let Some((ct, method)) = local_ct_pool.remove(&cancelled.params.request_id) {
                                 tracing::info!(id = %cancelled.params.request_id, reason = cancelled.params.reason, "cancelled");
                                 ct.cancel();
+                                peer.record_request(&method, RequestOutcome::Cancelled {
+                                    reason: cancelled.params.reason.clone(),
+                                });
                             }
                             cancelled.into()
                         }
                         Err(notification) => notification,
                     };
+                    // catch session closed notification
+                    let mut closed_reason = None;
+                    let mut notification = match notification.try_into() {
+                        Ok::<SessionClosedNotification, _>(closed) => {
+                            tracing::info!(reason = closed.params.reason, "session closed by peer");
+                            closed_reason = Some(closed.params.reason.clone());
+                            closed.into()
+                        }
+                        Err(notification) => notification,
+                    };
                     {
                         let service = shared_service.clone();
                         let mut extensions = Extensions::new();
@@ -814,21 +1705,37 @@ where
                             }
                         }.instrument(current_span));
                     }
+                    if let Some(reason) = closed_reason {
+                        break QuitReason::SessionClosed { reason, by_peer: true }
+                    }
                 }
                 Event::PeerMessage(JsonRpcMessage::Response(JsonRpcResponse {
                     result,
                     id,
                     ..
                 })) => {
-                    if let Some(responder) = local_responder_pool.remove(&id) {
-                        let response_result = responder.send(Ok(result));
+                    if let Some((responder, decoder)) = local_responder_pool.remove(&id) {
+                        request_deadlines.retain(|(.., pending)| *pending != id);
+                        // only a custom request expects a custom result, decode the others
+                        // again to tell what's wrong with their result
+                        let result = match (result.custom_result(), decoder) {
+                            (Some(custom), Some(decode)) => {
+                                decode(custom).map_err(ServiceError::InvalidResponse)
+                            }
+                            _ => Ok(result),
+                        };
+                        if let Err(error) = &result {
+                            tracing::warn!(%id, %error, "invalid response");
+                        }
+                        let response_result = responder.send(result);
                         if let Err(_error) = response_result {
                             tracing::warn!(%id, "Error sending response");
                         }
                     }
                 }
                 Event::PeerMessage(JsonRpcMessage::Error(JsonRpcError { error, id, .. })) => {
-                    if let Some(responder) = local_responder_pool.remove(&id) {
+                    if let Some((responder, _)) = local_responder_pool.remove(&id) {
+                        request_deadlines.retain(|(.., pending)| *pending != id);
                         let _response_result = responder.send(Err(ServiceError::McpError(error)));
                         if let Err(_error) = _response_result {
                             tracing::warn!(%id, "Error sending response");
@@ -837,10 +1744,30 @@ where
                 }
             }
         };
+        let pending_error = || match &quit_reason {
+            QuitReason::SessionClosed { reason, .. } => Some(ServiceError::SessionClosed {
+                reason: reason.clone(),
+            }),
+            QuitReason::TransportTimeout(timeout) => Some(ServiceError::TransportTimeout(*timeout)),
+            _ => None,
+        };
+        if pending_error().is_some() {
+            for (_, (responder, _)) in local_responder_pool.drain() {
+                if let Some(error) = pending_error() {
+                    let _ = responder.send(Err(error));
+                }
+            }
+            for (_, (ct, _)) in local_ct_pool.drain() {
+                ct.cancel();
+            }
+        }
         let sink_close_result = transport.close().await;
         if let Err(e) = sink_close_result {
             tracing::error!(%e, "fail to close sink");
         }
+        if let Some(responder) = close_responder {
+            let _ = responder.send(Ok(()));
+        }
         tracing::info!(?quit_reason, "serve finished");
         quit_reason
     }.instrument(current_span));
@@ -852,3 +1779,56 @@ where
         dg: ct.drop_guard(),
     }
 }
+
+#[cfg(all(test, feature = "client"))]
+mod tests {
+    use super::*;
+    use crate::model::ClientNotification;
+
+    fn cancelled(request_id: i64) -> ClientNotification {
+        CancelledNotification {
+            params: CancelledNotificationParam {
+                request_id: RequestId::Number(request_id),
+                reason: None,
+            },
+            method: crate::model::CancelledNotificationMethod,
+            extensions: Default::default(),
+        }
+        .into()
+    }
+
+    #[tokio::test]
+    async fn test_cancel_on_drop_waits_for_a_full_sink() {
+        let (peer, mut rx) = Peer::<RoleClient>::new(Arc::new(AtomicU32Provider::default()), None);
+        let mut filler = 0;
+        while peer
+            .shared
+            .tx
+            .try_send(PeerSinkMessage::Notification {
+                notification: cancelled(filler),
+                responder: tokio::sync::oneshot::channel().0,
+            })
+            .is_ok()
+        {
+            filler += 1;
+        }
+        drop(CancelOnDrop {
+            peer: peer.clone(),
+            id: Some(RequestId::Number(-1)),
+        });
+
+        let mut received = None;
+        for _ in 0..=filler {
+            received = tokio::time::timeout(Duration::from_secs(1), rx.recv())
+                .await
+                .expect("the cancellation is sent once the sink has room");
+        }
+        let Some(PeerSinkMessage::Notification { notification, .. }) = received else {
+            panic!("expect a notification");
+        };
+        let Ok::<CancelledNotification, _>(notification) = notification.try_into() else {
+            panic!("expect a cancellation");
+        };
+        assert_eq!(notification.params.request_id, RequestId::Number(-1));
+    }
+}
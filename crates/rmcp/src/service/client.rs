@@ -5,18 +5,20 @@ use thiserror::Error;
 use super::*;
 use crate::{
     model::{
-        ArgumentInfo, CallToolRequest, CallToolRequestParam, CallToolResult, CancelledNotification,
-        CancelledNotificationParam, ClientInfo, ClientJsonRpcMessage, ClientNotification,
-        ClientRequest, ClientResult, CompleteRequest, CompleteRequestParam, CompleteResult,
-        CompletionContext, CompletionInfo, GetPromptRequest, GetPromptRequestParam,
-        GetPromptResult, InitializeRequest, InitializedNotification, JsonRpcResponse,
+        ArgumentInfo, ByteRange, CallToolRequest, CallToolRequestParam, CallToolResult,
+        CancelledNotification, CancelledNotificationParam, ClientInfo, ClientJsonRpcMessage,
+        ClientNotification, ClientRequest, ClientResult, CompleteRequest, CompleteRequestParam,
+        CompleteResult, CompletionContext, CompletionInfo, ContentRange, CustomRequest, ErrorData,
+        GetPromptRequest, GetPromptRequestParam, GetPromptResult, InitializeRequest,
+        InitializeResult, InitializedNotification, JsonObject, JsonRpcError, JsonRpcResponse,
         ListPromptsRequest, ListPromptsResult, ListResourceTemplatesRequest,
         ListResourceTemplatesResult, ListResourcesRequest, ListResourcesResult, ListToolsRequest,
-        ListToolsResult, PaginatedRequestParam, ProgressNotification, ProgressNotificationParam,
-        ReadResourceRequest, ReadResourceRequestParam, ReadResourceResult, Reference, RequestId,
-        RootsListChangedNotification, ServerInfo, ServerJsonRpcMessage, ServerNotification,
-        ServerRequest, ServerResult, SetLevelRequest, SetLevelRequestParam, SubscribeRequest,
-        SubscribeRequestParam, UnsubscribeRequest, UnsubscribeRequestParam,
+        ListToolsResult, LoggingLevel, Meta, PaginatedRequestParam, ProgressNotification,
+        ProgressNotificationParam, Prompt, ProtocolVersion, ReadResourceRequest,
+        ReadResourceRequestParam, ReadResourceResult, Reference, RequestId, ResourceContents,
+        RootsListChangedNotification, ServerCapabilities, ServerInfo, ServerJsonRpcMessage,
+        ServerNotification, ServerRequest, ServerResult, SetLevelRequest, SetLevelRequestParam,
+        SubscribeRequest, SubscribeRequestParam, UnsubscribeRequest, UnsubscribeRequestParam,
     },
     transport::DynamicTransportError,
 };
@@ -38,6 +40,11 @@ pub enum ClientInitializeError {
     #[error("connection closed: {0}")]
     ConnectionClosed(String),
 
+    /// The server answered the initialize request with an error, after the retry with the
+    /// protocol version it suggested if any.
+    #[error("initialize failed: {0}")]
+    InitializeFailed(ErrorData),
+
     #[error("Send message error {error}, when {context}")]
     TransportError {
         error: DynamicTransportError,
@@ -92,6 +99,9 @@ where
             ServerJsonRpcMessage::Response(JsonRpcResponse { id, result, .. }) => {
                 break Ok((result, id));
             }
+            ServerJsonRpcMessage::Error(JsonRpcError { error, .. }) => {
+                break Err(ClientInitializeError::InitializeFailed(error));
+            }
             // Server could send logging messages before handshake
             ServerJsonRpcMessage::Notification(mut notification) => {
                 let ServerNotification::LoggingMessageNotification(logging) =
@@ -205,12 +215,59 @@ where
 {
     let mut transport = transport.into_transport();
     let id_provider = <Arc<AtomicU32RequestIdProvider>>::default();
+    let (peer, peer_rx) = Peer::new(id_provider.clone(), None);
+
+    let mut info = service.get_info();
+    let initialize_result =
+        match initialize(&mut transport, &service, &peer, &id_provider, &info).await {
+            // an older server may reject the version, retry once with the one it suggests
+            Err(ClientInitializeError::InitializeFailed(error)) => {
+                let Some(protocol_version) = suggested_protocol_version(&error)
+                    .filter(|protocol_version| *protocol_version != info.protocol_version)
+                else {
+                    return Err(ClientInitializeError::InitializeFailed(error));
+                };
+                tracing::info!(
+                    rejected = %info.protocol_version,
+                    %protocol_version,
+                    "retry initialize with the protocol version suggested by the server"
+                );
+                info.protocol_version = protocol_version;
+                initialize(&mut transport, &service, &peer, &id_provider, &info).await?
+            }
+            result => result?,
+        };
+    peer.set_peer_info(initialize_result);
+
+    // send notification
+    let notification = ClientJsonRpcMessage::notification(
+        ClientNotification::InitializedNotification(InitializedNotification {
+            method: Default::default(),
+            extensions: Default::default(),
+        }),
+    );
+    transport.send(notification).await.map_err(|error| {
+        ClientInitializeError::transport::<T>(error, "send initialized notification")
+    })?;
+    Ok(serve_inner(service, transport, peer, peer_rx, ct))
+}
 
-    // service
+/// Send the initialize request, and wait for its result
+async fn initialize<T, S>(
+    transport: &mut T,
+    service: &S,
+    peer: &Peer<RoleClient>,
+    id_provider: &AtomicU32RequestIdProvider,
+    info: &ClientInfo,
+) -> Result<InitializeResult, ClientInitializeError>
+where
+    T: Transport<RoleClient> + 'static,
+    S: Service<RoleClient>,
+{
     let id = id_provider.next_request_id();
     let init_request = InitializeRequest {
         method: Default::default(),
-        params: service.get_info(),
+        params: info.clone(),
         extensions: Default::default(),
     };
     transport
@@ -224,15 +281,8 @@ where
             context: "send initialize request".into(),
         })?;
 
-    let (peer, peer_rx) = Peer::new(id_provider, None);
-
-    let (response, response_id) = expect_response(
-        &mut transport,
-        "initialize response",
-        &service,
-        peer.clone(),
-    )
-    .await?;
+    let (response, response_id) =
+        expect_response(transport, "initialize response", service, peer.clone()).await?;
 
     if id != response_id {
         return Err(ClientInitializeError::ConflictInitResponseId(
@@ -244,19 +294,17 @@ where
     let ServerResult::InitializeResult(initialize_result) = response else {
         return Err(ClientInitializeError::ExpectedInitResult(Some(response)));
     };
-    peer.set_peer_info(initialize_result);
+    Ok(initialize_result)
+}
 
-    // send notification
-    let notification = ClientJsonRpcMessage::notification(
-        ClientNotification::InitializedNotification(InitializedNotification {
-            method: Default::default(),
-            extensions: Default::default(),
-        }),
-    );
-    transport.send(notification).await.map_err(|error| {
-        ClientInitializeError::transport::<T>(error, "send initialized notification")
-    })?;
-    Ok(serve_inner(service, transport, peer, peer_rx, ct))
+/// The protocol version suggested in the `data` of an initialize error, either as
+/// `protocolVersion`, or as the first of the `supported` versions
+fn suggested_protocol_version(error: &ErrorData) -> Option<ProtocolVersion> {
+    let data = error.data.as_ref()?;
+    let protocol_version = data
+        .get("protocolVersion")
+        .or_else(|| data.get("supported")?.get(0))?;
+    serde_json::from_value(protocol_version.clone()).ok()
 }
 
 macro_rules! method {
@@ -353,6 +401,58 @@ impl Peer<RoleClient> {
     method!(peer_req subscribe SubscribeRequest(SubscribeRequestParam) );
     method!(peer_req unsubscribe UnsubscribeRequest(UnsubscribeRequestParam));
     method!(peer_req call_tool CallToolRequest(CallToolRequestParam) => CallToolResult);
+
+    /// Call a tool, following the progress it reports.
+    ///
+    /// A progress token is generated and tracked before the request is sent, so no update is
+    /// missed. The request is sent once the returned future is polled, and the stream ends once
+    /// the result is received:
+    ///
+    /// ```rust,ignore
+    /// let (mut progress, result) = client.call_tool_with_progress(params);
+    /// let result = tokio::spawn(result);
+    /// while let Some(progress) = progress.next().await {
+    ///     println!("{:.0}%", progress.fraction().unwrap_or_default() * 100.0);
+    /// }
+    /// let result = result.await??;
+    /// ```
+    ///
+    /// Updates the stream has no room for are dropped, so consume it while waiting for the result.
+    pub fn call_tool_with_progress(
+        &self,
+        params: CallToolRequestParam,
+    ) -> (
+        ProgressStream,
+        impl Future<Output = Result<CallToolResult, ServiceError>> + Send + 'static,
+    ) {
+        let progress_token = self.shared.progress_token_provider.next_progress_token();
+        let progress = self.track_progress(progress_token.clone());
+        let mut meta = Meta::new();
+        meta.set_progress_token(progress_token);
+        let peer = self.clone();
+        let result = async move {
+            let request = ClientRequest::CallToolRequest(CallToolRequest {
+                method: Default::default(),
+                params,
+                extensions: Default::default(),
+            });
+            let options = PeerRequestOptions {
+                timeout: None,
+                meta: Some(meta),
+            };
+            match peer
+                .send_request_with_option(request, options)
+                .await?
+                .await_response()
+                .await?
+            {
+                ServerResult::CallToolResult(result) => Ok(result),
+                _ => Err(ServiceError::UnexpectedResponse),
+            }
+        };
+        (progress, result)
+    }
+
     method!(peer_req list_tools ListToolsRequest(PaginatedRequestParam)? => ListToolsResult);
 
     method!(peer_not notify_cancelled CancelledNotification(CancelledNotificationParam));
@@ -361,19 +461,93 @@ impl Peer<RoleClient> {
     method!(peer_not notify_roots_list_changed RootsListChangedNotification);
 }
 
+/// Remember the cursors of a paginated listing, so a server which doesn't advance the cursor can't make us loop forever.
+#[derive(Default)]
+struct VisitedCursors(std::collections::HashSet<String>);
+
+impl VisitedCursors {
+    fn next(&mut self, cursor: Option<String>) -> Result<Option<String>, ServiceError> {
+        match cursor {
+            Some(cursor) if !self.0.insert(cursor.clone()) => {
+                Err(ServiceError::PaginationStalled { cursor })
+            }
+            cursor => Ok(cursor),
+        }
+    }
+}
+
+/// Check the arguments of a prompt against the ones it declares.
+fn check_prompt_arguments(prompt: &Prompt, arguments: &JsonObject) -> Result<(), String> {
+    let declared = prompt.arguments.as_deref().unwrap_or_default();
+    if let Some(unknown) = arguments
+        .keys()
+        .find(|key| !declared.iter().any(|argument| &argument.name == *key))
+    {
+        let expected: Vec<_> = declared
+            .iter()
+            .map(|argument| argument.name.as_str())
+            .collect();
+        return Err(format!(
+            "unknown argument `{unknown}`, expected one of [{}]",
+            expected.join(", ")
+        ));
+    }
+    if let Some(missing) = declared
+        .iter()
+        .find(|argument| argument.required == Some(true) && !arguments.contains_key(&argument.name))
+    {
+        return Err(format!("missing required argument `{}`", missing.name));
+    }
+    Ok(())
+}
+
 impl Peer<RoleClient> {
+    /// The [`InitializeResult`](crate::model::InitializeResult) the server answered the handshake with.
+    ///
+    /// It's cached when the client is initialized, so this never sends a request.
+    /// Returns `None` before the handshake is done.
+    pub fn server_info(&self) -> Option<&ServerInfo> {
+        self.peer_info()
+    }
+
+    /// The capabilities the server advertised during the handshake, see [`Peer::server_info`].
+    pub fn server_capabilities(&self) -> Option<&ServerCapabilities> {
+        self.server_info().map(|info| &info.capabilities)
+    }
+
+    /// The instructions the server advertised during the handshake, see [`Peer::server_info`].
+    pub fn server_instructions(&self) -> Option<&str> {
+        self.server_info()?.instructions.as_deref()
+    }
+
+    /// Ask the server to only send the log messages at `level` or above, with `logging/setLevel`.
+    pub async fn set_logging_level(&self, level: LoggingLevel) -> Result<(), ServiceError> {
+        self.set_level(SetLevelRequestParam { level }).await
+    }
+
+    /// The token to resume the session with after a server restart, see
+    /// [`ServerCapabilities::resumption_token`].
+    pub fn resumption_token(&self) -> Option<String> {
+        self.server_capabilities()?
+            .resumption_token()
+            .map(str::to_string)
+    }
+
     /// A wrapper method for [`Peer<RoleClient>::list_tools`].
     ///
     /// This function will call [`Peer<RoleClient>::list_tools`] multiple times until all tools are listed.
+    ///
+    /// Returns [`ServiceError::PaginationStalled`] if the server returns a cursor which was already visited.
     pub async fn list_all_tools(&self) -> Result<Vec<crate::model::Tool>, ServiceError> {
         let mut tools = Vec::new();
         let mut cursor = None;
+        let mut visited = VisitedCursors::default();
         loop {
             let result = self
                 .list_tools(Some(PaginatedRequestParam { cursor }))
                 .await?;
             tools.extend(result.tools);
-            cursor = result.next_cursor;
+            cursor = visited.next(result.next_cursor)?;
             if cursor.is_none() {
                 break;
             }
@@ -387,12 +561,13 @@ impl Peer<RoleClient> {
     pub async fn list_all_prompts(&self) -> Result<Vec<crate::model::Prompt>, ServiceError> {
         let mut prompts = Vec::new();
         let mut cursor = None;
+        let mut visited = VisitedCursors::default();
         loop {
             let result = self
                 .list_prompts(Some(PaginatedRequestParam { cursor }))
                 .await?;
             prompts.extend(result.prompts);
-            cursor = result.next_cursor;
+            cursor = visited.next(result.next_cursor)?;
             if cursor.is_none() {
                 break;
             }
@@ -406,12 +581,13 @@ impl Peer<RoleClient> {
     pub async fn list_all_resources(&self) -> Result<Vec<crate::model::Resource>, ServiceError> {
         let mut resources = Vec::new();
         let mut cursor = None;
+        let mut visited = VisitedCursors::default();
         loop {
             let result = self
                 .list_resources(Some(PaginatedRequestParam { cursor }))
                 .await?;
             resources.extend(result.resources);
-            cursor = result.next_cursor;
+            cursor = visited.next(result.next_cursor)?;
             if cursor.is_none() {
                 break;
             }
@@ -427,12 +603,13 @@ impl Peer<RoleClient> {
     ) -> Result<Vec<crate::model::ResourceTemplate>, ServiceError> {
         let mut resource_templates = Vec::new();
         let mut cursor = None;
+        let mut visited = VisitedCursors::default();
         loop {
             let result = self
                 .list_resource_templates(Some(PaginatedRequestParam { cursor }))
                 .await?;
             resource_templates.extend(result.resource_templates);
-            cursor = result.next_cursor;
+            cursor = visited.next(result.next_cursor)?;
             if cursor.is_none() {
                 break;
             }
@@ -440,6 +617,328 @@ impl Peer<RoleClient> {
         Ok(resource_templates)
     }
 
+    /// Get a prompt with the arguments serialized from `A`, instead of a hand-built arguments map.
+    ///
+    /// `A` must serialize to a JSON object, or to `null` for a prompt without arguments, and
+    /// fields which are `null` aren't sent. The arguments are checked against the ones the prompt
+    /// declares in the [`Peer::cached_prompts`]. Returns
+    /// [`ServiceError::InvalidPromptArguments`] if the prompt isn't listed, if an argument isn't
+    /// declared by the prompt, or if a required one is missing.
+    pub async fn get_prompt_typed<A: serde::Serialize>(
+        &self,
+        name: impl Into<String>,
+        arguments: &A,
+    ) -> Result<GetPromptResult, ServiceError> {
+        let name = name.into();
+        let invalid = |reason: String| ServiceError::InvalidPromptArguments {
+            name: name.clone(),
+            reason,
+        };
+        let mut arguments = match serde_json::to_value(arguments) {
+            Ok(serde_json::Value::Object(arguments)) => arguments,
+            Ok(serde_json::Value::Null) => JsonObject::new(),
+            Ok(other) => {
+                return Err(invalid(format!("expected an object, got {other}")));
+            }
+            Err(e) => return Err(invalid(e.to_string())),
+        };
+        arguments.retain(|_, value| !value.is_null());
+        let prompts = self.cached_prompts().await?;
+        let prompt = prompts
+            .iter()
+            .find(|prompt| prompt.name == name)
+            .ok_or_else(|| invalid("the prompt isn't listed by the server".to_string()))?;
+        check_prompt_arguments(prompt, &arguments).map_err(invalid)?;
+        self.get_prompt(GetPromptRequestParam {
+            name: name.clone(),
+            arguments: (!arguments.is_empty()).then_some(arguments),
+        })
+        .await
+    }
+
+    /// All the prompts of the server, listed on the first call and cached until the server
+    /// notifies `notifications/prompts/list_changed`.
+    pub async fn cached_prompts(&self) -> Result<Arc<Vec<Prompt>>, ServiceError> {
+        let generation = {
+            let cache = self
+                .shared
+                .prompt_cache
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+            if let Some(prompts) = &cache.prompts {
+                return Ok(prompts.clone());
+            }
+            cache.generation
+        };
+        let prompts = Arc::new(self.list_all_prompts().await?);
+        let mut cache = self
+            .shared
+            .prompt_cache
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if cache.generation == generation {
+            cache.prompts = Some(prompts.clone());
+        }
+        Ok(prompts)
+    }
+
+    /// Forget the [`Peer::cached_prompts`], once the server notified they changed.
+    pub(crate) fn forget_cached_prompts(&self) {
+        let mut cache = self
+            .shared
+            .prompt_cache
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        cache.generation += 1;
+        cache.prompts = None;
+    }
+
+    /// Experimental: send a request of a method outside the MCP specification, like
+    /// `x-movie/refresh-cache`, see [`ServerHandler::on_custom_request`](crate::ServerHandler::on_custom_request).
+    ///
+    /// An empty result is returned as an empty object.
+    pub async fn send_custom_request(
+        &self,
+        method: impl Into<Cow<'static, str>>,
+        params: Option<JsonObject>,
+    ) -> Result<serde_json::Value, ServiceError> {
+        let result = self
+            .send_request(ClientRequest::CustomRequest(CustomRequest::new(
+                method, params,
+            )))
+            .await?;
+        match result {
+            ServerResult::CustomResult(result) => Ok(result.0),
+            ServerResult::EmptyResult(_) => Ok(serde_json::Value::Object(JsonObject::new())),
+            _ => Err(ServiceError::UnexpectedResponse),
+        }
+    }
+
+    /// Read a resource and deserialize its JSON text contents into `T`.
+    ///
+    /// When the resource has several contents, the first text with an `application/json` mime type
+    /// is used, or else the first text. Returns [`ServiceError::InvalidResourceContents`] if there's
+    /// no text contents, or if the text isn't a valid JSON for `T`.
+    pub async fn read_resource_typed<T: serde::de::DeserializeOwned>(
+        &self,
+        uri: impl Into<String>,
+    ) -> Result<T, ServiceError> {
+        let uri = uri.into();
+        let result = self
+            .read_resource(ReadResourceRequestParam { uri: uri.clone() })
+            .await?;
+        let invalid = |reason: String| ServiceError::InvalidResourceContents {
+            uri: uri.clone(),
+            reason,
+        };
+        let texts: Vec<_> = result
+            .contents
+            .iter()
+            .filter_map(|contents| match contents {
+                ResourceContents::TextResourceContents {
+                    mime_type, text, ..
+                } => Some((mime_type.as_deref(), text)),
+                ResourceContents::BlobResourceContents { .. } => None,
+            })
+            .collect();
+        let text = texts
+            .iter()
+            .find(|(mime_type, _)| *mime_type == Some("application/json"))
+            .or(texts.first());
+        match text {
+            Some((_, text)) => serde_json::from_str(text).map_err(|e| invalid(e.to_string())),
+            None if result.contents.is_empty() => Err(invalid("no contents".to_string())),
+            None => Err(invalid("expected text contents, got a blob".to_string())),
+        }
+    }
+
+    /// Read a blob resource and reassemble its chunks in memory, see [`Peer::read_resource_blob_to`]
+    /// to write them elsewhere as they arrive.
+    #[cfg(feature = "base64")]
+    pub async fn read_resource_blob(
+        &self,
+        uri: impl Into<String>,
+    ) -> Result<Vec<u8>, ServiceError> {
+        let mut data = Vec::new();
+        self.read_resource_blob_to(uri, &mut data).await?;
+        Ok(data)
+    }
+
+    /// Read a blob resource, writing its chunks to `writer` as they arrive, and return the number
+    /// of bytes written, see [`RequestContext::stream_blob`](crate::service::RequestContext::stream_blob).
+    ///
+    /// The request opts in to [`Meta::set_partial_results`], so a server streaming the blob sends
+    /// every chunk before the response, and the blob is never held in memory. The chunks returned
+    /// in the response, or a blob that wasn't chunked, are written once it's received.
+    ///
+    /// Returns [`ServiceError::InvalidResourceContents`] if there's no blob, if a chunk is missing,
+    /// or if a blob isn't valid base64, and [`ServiceError::WriteResourceContents`] if `writer`
+    /// fails, in which case the request is cancelled. `writer` isn't rolled back either way.
+    #[cfg(feature = "base64")]
+    pub async fn read_resource_blob_to<W>(
+        &self,
+        uri: impl Into<String>,
+        writer: &mut W,
+    ) -> Result<u64, ServiceError>
+    where
+        W: tokio::io::AsyncWrite + Unpin + Send + ?Sized,
+    {
+        use base64::engine::{Engine, general_purpose::STANDARD};
+        use tokio::io::AsyncWriteExt;
+
+        let uri = uri.into();
+        let invalid = |reason: String| ServiceError::InvalidResourceContents {
+            uri: uri.clone(),
+            reason,
+        };
+        let write_error = |source| ServiceError::WriteResourceContents {
+            uri: uri.clone(),
+            source,
+        };
+        let decode = |blob: &str, index: usize| {
+            STANDARD
+                .decode(blob)
+                .map_err(|e| invalid(format!("chunk {index}: {e}")))
+        };
+
+        // track the chunks before sending the request, so none is missed
+        let progress_token = self.shared.progress_token_provider.next_progress_token();
+        let mut chunks = self.shared.chunk_tracker.track(progress_token.clone());
+        let mut meta = Meta::new();
+        meta.set_progress_token(progress_token);
+        meta.set_partial_results(true);
+        let response = self
+            .send_request_with_option(
+                ClientRequest::ReadResourceRequest(ReadResourceRequest::new(
+                    ReadResourceRequestParam { uri: uri.clone() },
+                )),
+                PeerRequestOptions {
+                    timeout: None,
+                    meta: Some(meta),
+                },
+            )
+            .await?
+            .await_response();
+        let mut response = std::pin::pin!(response);
+        let mut streamed = 0;
+        let mut written = 0;
+        // the chunks are dispatched before the response, take them first
+        let response = loop {
+            let chunk = tokio::select! {
+                biased;
+                Some(chunk) = chunks.recv() => chunk,
+                response = &mut response => break response?,
+            };
+            if chunk.index != streamed {
+                return Err(invalid(format!(
+                    "expected chunk {streamed}, got chunk {}",
+                    chunk.index
+                )));
+            }
+            let data = decode(&chunk.blob, chunk.index)?;
+            writer.write_all(&data).await.map_err(write_error)?;
+            written += data.len() as u64;
+            streamed += 1;
+        };
+        drop(chunks);
+        let ServerResult::ReadResourceResult(result) = response else {
+            return Err(ServiceError::UnexpectedResponse);
+        };
+
+        if let Some(count) = result
+            .contents
+            .iter()
+            .find_map(ResourceContents::streamed_chunks)
+        {
+            if count != streamed {
+                return Err(invalid(format!("expected {count} chunks, got {streamed}")));
+            }
+        } else {
+            // the server returned the whole blob in the result
+            let mut blobs = Vec::new();
+            for contents in result.contents {
+                let (index, count) = contents.chunk().unwrap_or((0, 1));
+                if let ResourceContents::BlobResourceContents { blob, .. } = contents {
+                    blobs.push((index, count, blob));
+                }
+            }
+            if blobs.is_empty() {
+                return Err(invalid("expected blob contents".to_string()));
+            }
+            blobs.sort_by_key(|(index, ..)| *index);
+            for (expected, (index, count, blob)) in blobs.iter().enumerate() {
+                if *index != expected || *count != blobs.len() {
+                    return Err(invalid(format!(
+                        "expected chunk {expected} of {}, got chunk {index} of {count}",
+                        blobs.len()
+                    )));
+                }
+                let data = decode(blob, *index)?;
+                writer.write_all(&data).await.map_err(write_error)?;
+                written += data.len() as u64;
+            }
+        }
+        writer.flush().await.map_err(write_error)?;
+        Ok(written)
+    }
+
+    /// Read the `range` of a blob resource, see [`ResourceContents::blob_slice`].
+    ///
+    /// Returns the bytes along with the range they cover and the size of the whole blob. A server
+    /// ignoring the range returns the whole blob, as the range `0..total`. Returns
+    /// [`ServiceError::InvalidResourceContents`] if there's no blob, or if it isn't valid base64.
+    #[cfg(feature = "base64")]
+    pub async fn read_resource_range(
+        &self,
+        uri: impl Into<String>,
+        range: ByteRange,
+    ) -> Result<(Vec<u8>, ContentRange), ServiceError> {
+        use base64::engine::{Engine, general_purpose::STANDARD};
+
+        let uri = uri.into();
+        let mut meta = Meta::new();
+        meta.set_byte_range(range);
+        let response = self
+            .send_request_with_option(
+                ClientRequest::ReadResourceRequest(ReadResourceRequest::new(
+                    ReadResourceRequestParam { uri: uri.clone() },
+                )),
+                PeerRequestOptions {
+                    timeout: None,
+                    meta: Some(meta),
+                },
+            )
+            .await?
+            .await_response()
+            .await?;
+        let ServerResult::ReadResourceResult(result) = response else {
+            return Err(ServiceError::UnexpectedResponse);
+        };
+        let invalid = |reason: String| ServiceError::InvalidResourceContents {
+            uri: uri.clone(),
+            reason,
+        };
+        let (blob, content_range) = result
+            .contents
+            .iter()
+            .find_map(|contents| match contents {
+                ResourceContents::BlobResourceContents { blob, .. } => {
+                    Some((blob, contents.range()))
+                }
+                _ => None,
+            })
+            .ok_or_else(|| invalid("expected blob contents".to_string()))?;
+        let data = STANDARD
+            .decode(blob)
+            .map_err(|e| invalid(format!("blob: {e}")))?;
+        let content_range = content_range.unwrap_or(ContentRange {
+            start: 0,
+            end: data.len() as u64,
+            total: data.len() as u64,
+        });
+        Ok((data, content_range))
+    }
+
     /// Convenient method to get completion suggestions for a prompt argument
     ///
     /// # Arguments
@@ -0,0 +1,349 @@
+//! Connections to several MCP servers at once, see [`ClientManager`].
+use std::{
+    any::Any,
+    collections::BTreeMap,
+    future::Future,
+    sync::{Arc, PoisonError, RwLock},
+    time::Duration,
+};
+
+use futures::future::{BoxFuture, join_all};
+use thiserror::Error;
+
+use super::{
+    ClientInitializeError, Peer, PeerRequestOptions, RoleClient, RunningService, Service,
+    ServiceError,
+};
+use crate::model::{CallToolRequestParam, CallToolResult, ClientRequest, PingRequest, Tool};
+
+#[derive(Error, Debug)]
+#[allow(clippy::large_enum_variant)]
+pub enum ClientManagerError {
+    #[error("no server named {0:?}")]
+    UnknownServer(String),
+    #[error(
+        "invalid server name {0:?}, it must be non-empty, not contain {separator:?} and not start or end with '_'",
+        separator = ClientManager::TOOL_NAME_SEPARATOR
+    )]
+    InvalidServerName(String),
+    #[error("tool {0:?} isn't prefixed with a server name")]
+    InvalidToolName(String),
+    #[error("fail to connect to server {server}: {error}")]
+    Initialize {
+        server: String,
+        #[source]
+        error: ClientInitializeError,
+    },
+    #[error("server {server}: {error}")]
+    Service {
+        server: String,
+        #[source]
+        error: ServiceError,
+    },
+}
+
+/// A running client of any [`Service`], dropping it cancels the service.
+trait ManagedClient: Send + Sync {
+    fn peer(&self) -> &Peer<RoleClient>;
+    fn service(&self) -> &dyn Any;
+}
+
+impl<S: Service<RoleClient>> ManagedClient for RunningService<RoleClient, S> {
+    fn peer(&self) -> &Peer<RoleClient> {
+        RunningService::peer(self)
+    }
+
+    fn service(&self) -> &dyn Any {
+        RunningService::<RoleClient, S>::service(self)
+    }
+}
+
+type Connect = Arc<
+    dyn Fn() -> BoxFuture<'static, Result<Box<dyn ManagedClient>, ClientInitializeError>>
+        + Send
+        + Sync,
+>;
+
+struct Connection {
+    connect: Connect,
+    client: Box<dyn ManagedClient>,
+}
+
+/// Clients of several MCP servers, keyed by a name.
+///
+/// Each server is connected with a closure, which is called again by [`ClientManager::reconnect`].
+/// The tools of all the servers are listed together by [`ClientManager::list_all_tools`], their
+/// names prefixed with the name of their server, and [`ClientManager::call_tool`] routes a call
+/// back to its server:
+///
+/// ```rust,ignore
+/// let manager = ClientManager::new();
+/// manager
+///     .connect("movie", || async {
+///         ().serve(TokioChildProcess::new(Command::new("movie-server"))?).await
+///     })
+///     .await?;
+/// // `movie__get_cinema_list`, ...
+/// let tools = manager.list_all_tools().await?;
+/// ```
+///
+/// Removing a server, or dropping the manager, cancels the clients.
+pub struct ClientManager {
+    connections: RwLock<BTreeMap<String, Connection>>,
+    health_check_timeout: Duration,
+}
+
+impl Default for ClientManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for ClientManager {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ClientManager")
+            .field("servers", &self.servers())
+            .field("health_check_timeout", &self.health_check_timeout)
+            .finish()
+    }
+}
+
+impl ClientManager {
+    /// Separates the name of a server and the name of its tool, like `movie__get_cinema_list`
+    pub const TOOL_NAME_SEPARATOR: &str = "__";
+    pub const DEFAULT_HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+    pub fn new() -> Self {
+        Self {
+            connections: Default::default(),
+            health_check_timeout: Self::DEFAULT_HEALTH_CHECK_TIMEOUT,
+        }
+    }
+
+    /// How long [`ClientManager::check_health`] waits for a server to answer a ping
+    pub fn with_health_check_timeout(mut self, timeout: Duration) -> Self {
+        self.health_check_timeout = timeout;
+        self
+    }
+
+    /// Connect to a server, replacing the one already connected with the same name.
+    ///
+    /// The name prefixes the names of its tools, so it must be non-empty, not contain
+    /// [`ClientManager::TOOL_NAME_SEPARATOR`] and not start or end with `_`.
+    ///
+    /// `connect` is kept to reconnect, see [`ClientManager::reconnect`].
+    pub async fn connect<F, Fut, S>(
+        &self,
+        name: impl Into<String>,
+        connect: F,
+    ) -> Result<(), ClientManagerError>
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<RunningService<RoleClient, S>, ClientInitializeError>>
+            + Send
+            + 'static,
+        S: Service<RoleClient>,
+    {
+        let name = name.into();
+        // `movie_` would make `movie___get_cinema_list`, split as `movie` and `_get_cinema_list`
+        if name.is_empty()
+            || name.contains(Self::TOOL_NAME_SEPARATOR)
+            || name.starts_with('_')
+            || name.ends_with('_')
+        {
+            return Err(ClientManagerError::InvalidServerName(name));
+        }
+        let connect: Connect = Arc::new(move || {
+            let connecting = connect();
+            Box::pin(async move {
+                let client = connecting.await?;
+                Ok(Box::new(client) as Box<dyn ManagedClient>)
+            })
+        });
+        self.establish(name, connect).await
+    }
+
+    async fn establish(&self, name: String, connect: Connect) -> Result<(), ClientManagerError> {
+        let client = connect()
+            .await
+            .map_err(|error| ClientManagerError::Initialize {
+                server: name.clone(),
+                error,
+            })?;
+        let replaced = self
+            .connections
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(name, Connection { connect, client });
+        // cancelled out of the lock
+        drop(replaced);
+        Ok(())
+    }
+
+    /// Disconnect from a server, returns whether it was connected
+    pub fn remove(&self, name: &str) -> bool {
+        let removed = self
+            .connections
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(name);
+        removed.is_some()
+    }
+
+    /// Connect to a server again with its `connect` closure, see [`ClientManager::connect`].
+    ///
+    /// The previous client is kept if the connection fails.
+    pub async fn reconnect(&self, name: &str) -> Result<(), ClientManagerError> {
+        let connect = self
+            .connections
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(name)
+            .map(|connection| connection.connect.clone())
+            .ok_or_else(|| ClientManagerError::UnknownServer(name.to_string()))?;
+        self.establish(name.to_string(), connect).await
+    }
+
+    /// The names of the servers, sorted
+    pub fn servers(&self) -> Vec<String> {
+        self.connections
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .keys()
+            .cloned()
+            .collect()
+    }
+
+    /// The client of a server
+    pub fn peer(&self, name: &str) -> Option<Peer<RoleClient>> {
+        self.connections
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(name)
+            .map(|connection| connection.client.peer().clone())
+    }
+
+    /// The client handler of a server, if it's a `S`
+    pub fn service<S: Service<RoleClient> + Clone>(&self, name: &str) -> Option<S> {
+        self.connections
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(name)?
+            .client
+            .service()
+            .downcast_ref::<S>()
+            .cloned()
+    }
+
+    fn peers(&self) -> Vec<(String, Peer<RoleClient>)> {
+        self.connections
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .map(|(name, connection)| (name.clone(), connection.client.peer().clone()))
+            .collect()
+    }
+
+    /// Ping all the servers at once, a server is healthy if it answers within the
+    /// [health check timeout](ClientManager::with_health_check_timeout).
+    pub async fn check_health(&self) -> BTreeMap<String, Result<(), ServiceError>> {
+        let timeout = self.health_check_timeout;
+        let checks = self.peers().into_iter().map(|(name, peer)| async move {
+            let result = async {
+                if peer.is_transport_closed() {
+                    return Err(ServiceError::TransportClosed);
+                }
+                peer.send_request_with_option(
+                    ClientRequest::PingRequest(PingRequest::default()),
+                    PeerRequestOptions {
+                        timeout: Some(timeout),
+                        meta: None,
+                    },
+                )
+                .await?
+                .await_response()
+                .await?;
+                Ok(())
+            }
+            .await;
+            (name, result)
+        });
+        join_all(checks).await.into_iter().collect()
+    }
+
+    /// Reconnect the servers which fail the [health check](ClientManager::check_health), and
+    /// return the outcome of their reconnection.
+    pub async fn reconnect_unhealthy(&self) -> BTreeMap<String, Result<(), ClientManagerError>> {
+        let mut reconnected = BTreeMap::new();
+        for (name, health) in self.check_health().await {
+            if let Err(error) = health {
+                tracing::warn!(server = %name, %error, "unhealthy server, reconnecting");
+                let result = self.reconnect(&name).await;
+                reconnected.insert(name, result);
+            }
+        }
+        reconnected
+    }
+
+    /// List the tools of all the servers, their names prefixed with the name of their server
+    /// and [`ClientManager::TOOL_NAME_SEPARATOR`].
+    ///
+    /// Fails if any server fails to list its tools.
+    pub async fn list_all_tools(&self) -> Result<Vec<Tool>, ClientManagerError> {
+        let servers = self.servers();
+        let listings = servers.iter().map(|name| self.list_tools_of(name));
+        let mut all_tools = Vec::new();
+        for tools in join_all(listings).await {
+            all_tools.extend(tools?);
+        }
+        Ok(all_tools)
+    }
+
+    /// List the tools of a server, their names prefixed like [`ClientManager::list_all_tools`].
+    pub async fn list_tools_of(&self, name: &str) -> Result<Vec<Tool>, ClientManagerError> {
+        let peer = self
+            .peer(name)
+            .ok_or_else(|| ClientManagerError::UnknownServer(name.to_string()))?;
+        let tools = peer
+            .list_all_tools()
+            .await
+            .map_err(|error| ClientManagerError::Service {
+                server: name.to_string(),
+                error,
+            })?;
+        Ok(tools
+            .into_iter()
+            .map(|mut tool| {
+                tool.name = format!("{name}{}{}", Self::TOOL_NAME_SEPARATOR, tool.name).into();
+                tool
+            })
+            .collect())
+    }
+
+    /// Split a tool name listed by [`ClientManager::list_all_tools`] into the name of its server
+    /// and the name of the tool on that server.
+    pub fn split_tool_name(name: &str) -> Option<(&str, &str)> {
+        name.split_once(Self::TOOL_NAME_SEPARATOR)
+    }
+
+    /// Call a tool listed by [`ClientManager::list_all_tools`] on its server.
+    pub async fn call_tool(
+        &self,
+        request: CallToolRequestParam,
+    ) -> Result<CallToolResult, ClientManagerError> {
+        let (server, tool) = Self::split_tool_name(&request.name)
+            .ok_or_else(|| ClientManagerError::InvalidToolName(request.name.to_string()))?;
+        let peer = self
+            .peer(server)
+            .ok_or_else(|| ClientManagerError::UnknownServer(server.to_string()))?;
+        peer.call_tool(CallToolRequestParam {
+            name: tool.to_string().into(),
+            arguments: request.arguments,
+        })
+        .await
+        .map_err(|error| ClientManagerError::Service {
+            server: server.to_string(),
+            error,
+        })
+    }
+}
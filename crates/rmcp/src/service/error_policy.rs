@@ -0,0 +1,72 @@
+//! Which errors answered to the peer close the session, see [`Peer::set_close_on_error`](super::Peer::set_close_on_error).
+use crate::model::ErrorCode;
+
+/// What an error answered to the peer is about
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCategory {
+    /// The request breaks the protocol: a `PARSE_ERROR`, an `INVALID_REQUEST` or a
+    /// `METHOD_NOT_FOUND`
+    Protocol,
+    /// The request is valid but the handler failed, any other code, like `INVALID_PARAMS` or
+    /// `INTERNAL_ERROR`
+    Application,
+}
+
+impl ErrorCategory {
+    pub fn of(code: ErrorCode) -> Self {
+        match code {
+            ErrorCode::PARSE_ERROR | ErrorCode::INVALID_REQUEST | ErrorCode::METHOD_NOT_FOUND => {
+                Self::Protocol
+            }
+            _ => Self::Application,
+        }
+    }
+}
+
+impl std::fmt::Display for ErrorCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Protocol => write!(f, "protocol"),
+            Self::Application => write!(f, "application"),
+        }
+    }
+}
+
+/// What the session does once it answered an error
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ErrorAction {
+    /// Go on handling the next messages
+    #[default]
+    Continue,
+    /// Close the session, as [`Peer::close`](super::Peer::close) does
+    Close,
+}
+
+/// The [`ErrorAction`] of each [`ErrorCategory`], every error continues by default.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CloseOnError {
+    pub protocol: ErrorAction,
+    pub application: ErrorAction,
+}
+
+impl CloseOnError {
+    /// How long a closing session tries to send the error and the
+    /// `notifications/rmcp/session_closed` before it closes anyway, so a stalled transport
+    /// doesn't hold the session open.
+    pub const CLOSE_SEND_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+    /// Close the session on a protocol violation, go on after an application error
+    pub fn strict() -> Self {
+        Self {
+            protocol: ErrorAction::Close,
+            application: ErrorAction::Continue,
+        }
+    }
+
+    pub fn action(&self, category: ErrorCategory) -> ErrorAction {
+        match category {
+            ErrorCategory::Protocol => self.protocol,
+            ErrorCategory::Application => self.application,
+        }
+    }
+}
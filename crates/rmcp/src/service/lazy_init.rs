@@ -0,0 +1,186 @@
+//! A value initialized in the background, so expensive setup doesn't block the handshake.
+use std::{
+    future::Future,
+    pin::pin,
+    sync::{
+        Arc, Mutex, OnceLock, PoisonError, Weak,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+use thiserror::Error;
+use tokio::{sync::Notify, task::AbortHandle};
+
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("lazy initialization failed, panicked or was aborted")]
+pub struct LazyInitError;
+
+/// A readiness gate for a value computed by a background task.
+///
+/// Start the initialization with [`LazyInit::start`], for example in
+/// [`ServerHandler::initialize`](crate::ServerHandler::initialize), and let the handlers
+/// depending on the value wait for it with [`LazyInit::get`]:
+///
+/// ```rust,ignore
+/// async fn initialize(&self, request: InitializeRequestParam, context: RequestContext<RoleServer>)
+///     -> Result<InitializeResult, ErrorData> {
+///     // capture what the init needs, not `self`: the task would keep the value alive
+///     let client = self.client.clone();
+///     self.cities.try_start(async move { fetch_cities(&client).await });
+///     Ok(self.get_info())
+/// }
+///
+/// #[tool]
+/// async fn find_city(&self, Parameters(name): Parameters<String>) -> Result<String, ErrorData> {
+///     let cities = self.cities.get().await.map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+///     // ...
+/// }
+/// ```
+///
+/// Clones share the same value. The background task is aborted once all the clones are dropped.
+///
+/// Once an initialization failed, the waiters get a [`LazyInitError`], and the next call to
+/// [`LazyInit::start`] or [`LazyInit::try_start`] starts it again.
+pub struct LazyInit<T> {
+    inner: Arc<Inner<T>>,
+}
+
+struct Inner<T> {
+    value: OnceLock<T>,
+    failed: AtomicBool,
+    task: Mutex<Option<AbortHandle>>,
+    done: Notify,
+}
+
+impl<T> Clone for LazyInit<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T> Default for LazyInit<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> std::fmt::Debug for LazyInit<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LazyInit")
+            .field("ready", &self.is_ready())
+            .finish()
+    }
+}
+
+impl<T> Drop for Inner<T> {
+    fn drop(&mut self) {
+        let task = self.task.get_mut().unwrap_or_else(PoisonError::into_inner);
+        if let Some(task) = task.take() {
+            task.abort();
+        }
+    }
+}
+
+/// Wakes up the waiters when the init task finishes, fails, panics or is aborted
+struct Finish<T>(Weak<Inner<T>>);
+
+impl<T> Drop for Finish<T> {
+    fn drop(&mut self) {
+        if let Some(inner) = self.0.upgrade() {
+            if inner.value.get().is_none() {
+                inner.failed.store(true, Ordering::Release);
+            }
+            inner.done.notify_waiters();
+        }
+    }
+}
+
+impl<T> LazyInit<T> {
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                value: OnceLock::new(),
+                failed: AtomicBool::new(false),
+                task: Mutex::new(None),
+                done: Notify::new(),
+            }),
+        }
+    }
+
+    /// Whether the value is ready
+    pub fn is_ready(&self) -> bool {
+        self.inner.value.get().is_some()
+    }
+
+    /// Get the value if it's ready, without waiting
+    pub fn try_get(&self) -> Option<&T> {
+        self.inner.value.get()
+    }
+
+    /// Wait until the value is ready, or the initialization failed.
+    ///
+    /// If [`LazyInit::start`] wasn't called yet, this waits for it.
+    pub async fn get(&self) -> Result<&T, LazyInitError> {
+        loop {
+            // register before checking the state, so we won't miss a wake up
+            let mut done = pin!(self.inner.done.notified());
+            done.as_mut().enable();
+            if let Some(value) = self.inner.value.get() {
+                return Ok(value);
+            }
+            if self.inner.failed.load(Ordering::Acquire) {
+                return Err(LazyInitError);
+            }
+            done.await;
+        }
+    }
+}
+
+impl<T: Send + Sync + 'static> LazyInit<T> {
+    /// Spawn a task computing the value, must be called within a tokio runtime.
+    ///
+    /// Only the first call starts a task, or the first one after a failure. Return `false` if the
+    /// value is ready or being computed.
+    pub fn start<F>(&self, init: F) -> bool
+    where
+        F: Future<Output = T> + Send + 'static,
+    {
+        self.try_start(async move { Ok::<_, std::convert::Infallible>(init.await) })
+    }
+
+    /// Like [`LazyInit::start`], for an initialization which may fail.
+    ///
+    /// An error fails the waiters of [`LazyInit::get`], and lets the initialization be started
+    /// again, e.g. by the next handler needing the value.
+    pub fn try_start<F, E>(&self, init: F) -> bool
+    where
+        F: Future<Output = Result<T, E>> + Send + 'static,
+        E: std::fmt::Display,
+    {
+        let mut task = self
+            .inner
+            .task
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if self.is_ready() || (task.is_some() && !self.inner.failed.load(Ordering::Acquire)) {
+            return false;
+        }
+        self.inner.failed.store(false, Ordering::Release);
+        let finish = Finish(Arc::downgrade(&self.inner));
+        let handle = tokio::spawn(async move {
+            match init.await {
+                Ok(value) => {
+                    if let Some(inner) = finish.0.upgrade() {
+                        let _ = inner.value.set(value);
+                    }
+                }
+                Err(error) => tracing::warn!(%error, "lazy initialization failed"),
+            }
+            drop(finish);
+        });
+        *task = Some(handle.abort_handle());
+        true
+    }
+}
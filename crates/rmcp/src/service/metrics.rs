@@ -0,0 +1,65 @@
+//! Outcomes of the handled requests, reported to the recorder set by
+//! [`Peer::set_metrics_recorder`](super::Peer::set_metrics_recorder).
+use crate::model::ErrorCode;
+
+/// How the handling of a request received from the remote peer ended
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RequestOutcome {
+    /// Answered with a result
+    Completed,
+    /// Answered with an error, other than a timeout
+    Failed { code: ErrorCode },
+    /// Cancelled by the remote peer with a `notifications/cancelled`, before it was answered
+    Cancelled { reason: Option<String> },
+    /// Answered with a [`ErrorCode::REQUEST_TIMEOUT`] error, like when the handling exceeds
+    /// [`Peer::handler_timeout`](super::Peer::handler_timeout)
+    TimedOut,
+}
+
+impl RequestOutcome {
+    /// A label for the outcome, to tell the counters apart
+    pub fn label(&self) -> &'static str {
+        match self {
+            RequestOutcome::Completed => "completed",
+            RequestOutcome::Failed { .. } => "failed",
+            RequestOutcome::Cancelled { .. } => "cancelled",
+            RequestOutcome::TimedOut => "timed_out",
+        }
+    }
+}
+
+/// Receives the outcome of every request handled by the service, e.g. to count them.
+///
+/// It's implemented for the closures taking the method of the request and its outcome:
+///
+/// ```rust
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+///
+/// use rmcp::service::{MetricsRecorder, RequestOutcome};
+///
+/// static CANCELLED: AtomicUsize = AtomicUsize::new(0);
+/// let recorder = |method: &str, outcome: &RequestOutcome| {
+///     if method == "tools/call" && outcome.label() == "cancelled" {
+///         CANCELLED.fetch_add(1, Ordering::Relaxed);
+///     }
+/// };
+/// recorder.record_request(
+///     "tools/call",
+///     &RequestOutcome::Cancelled { reason: None },
+/// );
+/// assert_eq!(CANCELLED.load(Ordering::Relaxed), 1);
+/// ```
+pub trait MetricsRecorder: Send + Sync + 'static {
+    /// Called once per request, when it's answered or cancelled. It's called from the service
+    /// loop, so it shouldn't block.
+    fn record_request(&self, method: &str, outcome: &RequestOutcome);
+}
+
+impl<F> MetricsRecorder for F
+where
+    F: Fn(&str, &RequestOutcome) + Send + Sync + 'static,
+{
+    fn record_request(&self, method: &str, outcome: &RequestOutcome) {
+        self(method, outcome)
+    }
+}
@@ -0,0 +1,200 @@
+//! A bounded queue for outgoing notifications, see [`Peer::enable_notification_queue`](super::Peer::enable_notification_queue).
+use std::{
+    collections::VecDeque,
+    sync::{
+        Arc, Mutex, PoisonError,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+    },
+};
+
+use tokio::sync::{Notify, mpsc};
+
+use super::{PeerSinkMessage, ServiceError, ServiceRole};
+use crate::model::{
+    ClientNotification, ProgressNotificationParam, ProgressToken, ServerNotification,
+};
+
+/// What to do when the outgoing notification queue is full.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NotificationOverflowPolicy {
+    /// The sender waits until the queue has space again.
+    #[default]
+    Backpressure,
+    /// Progress notifications are coalescible: a progress notification replaces the queued
+    /// one with the same progress token, and when the queue is full the oldest queued progress
+    /// notification is dropped. Other notifications are never dropped, if there is no progress
+    /// notification to drop the sender waits like [`NotificationOverflowPolicy::Backpressure`].
+    DropOldestProgress,
+    /// The notification is dropped when the queue is full, the sender never waits. For the
+    /// high volume notifications which may be lost, like log messages, see
+    /// [`Peer::enable_log_queue`](super::Peer::enable_log_queue).
+    DropNewest,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct NotificationQueueConfig {
+    /// The max number of notifications waiting to be sent
+    pub capacity: usize,
+    pub overflow: NotificationOverflowPolicy,
+}
+
+impl NotificationQueueConfig {
+    pub const DEFAULT_CAPACITY: usize = 64;
+}
+
+impl Default for NotificationQueueConfig {
+    fn default() -> Self {
+        Self {
+            capacity: Self::DEFAULT_CAPACITY,
+            overflow: NotificationOverflowPolicy::default(),
+        }
+    }
+}
+
+/// Notifications which could be coalesced by their progress token
+pub(crate) trait CoalescibleNotification {
+    fn progress(&self) -> Option<&ProgressNotificationParam>;
+
+    fn progress_token(&self) -> Option<&ProgressToken> {
+        self.progress().map(|progress| &progress.progress_token)
+    }
+}
+
+impl CoalescibleNotification for ClientNotification {
+    fn progress(&self) -> Option<&ProgressNotificationParam> {
+        match self {
+            ClientNotification::ProgressNotification(n) => Some(&n.params),
+            _ => None,
+        }
+    }
+}
+
+impl CoalescibleNotification for ServerNotification {
+    fn progress(&self) -> Option<&ProgressNotificationParam> {
+        match self {
+            ServerNotification::ProgressNotification(n) => Some(&n.params),
+            _ => None,
+        }
+    }
+}
+
+pub(crate) struct NotificationQueue<R: ServiceRole> {
+    config: NotificationQueueConfig,
+    queue: Mutex<VecDeque<R::Not>>,
+    closed: AtomicBool,
+    dropped: AtomicU64,
+    item_ready: Notify,
+    space_ready: Notify,
+}
+
+impl<R: ServiceRole> NotificationQueue<R> {
+    /// Create the queue and spawn the task forwarding queued notifications to the serve loop.
+    pub(crate) fn spawn(
+        config: NotificationQueueConfig,
+        tx: mpsc::Sender<PeerSinkMessage<R>>,
+    ) -> Arc<Self> {
+        let queue = Arc::new(Self {
+            config,
+            queue: Mutex::new(VecDeque::with_capacity(config.capacity)),
+            closed: AtomicBool::new(false),
+            dropped: AtomicU64::new(0),
+            item_ready: Notify::new(),
+            space_ready: Notify::new(),
+        });
+        tokio::spawn(queue.clone().drain(tx));
+        queue
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.queue
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .len()
+    }
+
+    /// The number of notifications dropped by [`NotificationOverflowPolicy::DropNewest`]
+    pub(crate) fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    pub(crate) async fn push(&self, notification: R::Not) -> Result<(), ServiceError> {
+        let coalesce = self.config.overflow == NotificationOverflowPolicy::DropOldestProgress;
+        let progress_token = notification.progress_token().cloned();
+        let mut notification = Some(notification);
+        loop {
+            // register before checking the queue, so we won't miss a wake up
+            let space_ready = self.space_ready.notified();
+            if self.closed.load(Ordering::Acquire) {
+                return Err(ServiceError::TransportClosed);
+            }
+            {
+                let mut queue = self.queue.lock().unwrap_or_else(PoisonError::into_inner);
+                if coalesce {
+                    if let Some(token) = &progress_token {
+                        if let Some(queued) = queue
+                            .iter_mut()
+                            .find(|queued| queued.progress_token() == Some(token))
+                        {
+                            *queued = notification.take().expect("notification is not queued");
+                            return Ok(());
+                        }
+                    }
+                }
+                if queue.len() >= self.config.capacity.max(1) && coalesce {
+                    if let Some(index) = queue
+                        .iter()
+                        .position(|queued| queued.progress_token().is_some())
+                    {
+                        tracing::debug!("notification queue is full, drop the oldest progress");
+                        queue.remove(index);
+                    }
+                }
+                if queue.len() < self.config.capacity.max(1) {
+                    queue.push_back(notification.take().expect("notification is not queued"));
+                    self.item_ready.notify_one();
+                    return Ok(());
+                }
+                if self.config.overflow == NotificationOverflowPolicy::DropNewest {
+                    tracing::debug!("notification queue is full, drop the notification");
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                    return Ok(());
+                }
+            }
+            space_ready.await;
+        }
+    }
+
+    async fn drain(self: Arc<Self>, tx: mpsc::Sender<PeerSinkMessage<R>>) {
+        loop {
+            let next = self
+                .queue
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .pop_front();
+            let Some(notification) = next else {
+                tokio::select! {
+                    _ = self.item_ready.notified() => continue,
+                    _ = tx.closed() => break,
+                }
+            };
+            self.space_ready.notify_waiters();
+            let (responder, receiver) = tokio::sync::oneshot::channel();
+            if tx
+                .send(PeerSinkMessage::Notification {
+                    notification,
+                    responder,
+                })
+                .await
+                .is_err()
+            {
+                break;
+            }
+            // send one by one, so the queue fills up when the transport is slower than the sender
+            if let Ok(Err(error)) = receiver.await {
+                tracing::warn!(%error, "fail to send queued notification");
+            }
+        }
+        self.closed.store(true, Ordering::Release);
+        self.space_ready.notify_waiters();
+    }
+}
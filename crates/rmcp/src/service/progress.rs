@@ -0,0 +1,129 @@
+//! Typed progress of the requests sent to the peer, see [`Peer::track_progress`](super::Peer::track_progress).
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use futures::Stream;
+use tokio::sync::mpsc;
+
+use crate::model::{ProgressNotificationParam, ProgressToken};
+
+/// A progress update of a request, reported by the peer with a `notifications/progress`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Progress {
+    /// The progress thus far, it increases with every update even if the total is unknown
+    pub progress: f64,
+    pub total: Option<f64>,
+    pub message: Option<String>,
+}
+
+impl Progress {
+    /// The completed fraction of the total in `0.0..=1.0`, if the total is known
+    pub fn fraction(&self) -> Option<f64> {
+        self.total
+            .filter(|total| *total > 0.0)
+            .map(|total| (self.progress / total).clamp(0.0, 1.0))
+    }
+}
+
+impl From<ProgressNotificationParam> for Progress {
+    fn from(param: ProgressNotificationParam) -> Self {
+        Self {
+            progress: param.progress,
+            total: param.total,
+            message: param.message,
+        }
+    }
+}
+
+type Trackers = Arc<Mutex<HashMap<ProgressToken, mpsc::Sender<Progress>>>>;
+
+/// The progress tokens tracked on a peer, with the streams their updates are sent to
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ProgressTracker {
+    trackers: Trackers,
+}
+
+impl ProgressTracker {
+    const CHANNEL_SIZE: usize = 16;
+
+    /// Start tracking `progress_token`, replacing the stream already tracking it if any
+    pub(crate) fn track(&self, progress_token: ProgressToken) -> ProgressStream {
+        let (sender, receiver) = mpsc::channel(Self::CHANNEL_SIZE);
+        self.trackers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(progress_token.clone(), sender);
+        ProgressStream {
+            progress_token,
+            receiver,
+            trackers: self.trackers.clone(),
+        }
+    }
+
+    /// Send the update to the stream tracking its token.
+    ///
+    /// This never waits for the stream: an update it has no room for is dropped, the next one
+    /// carries the progress thus far anyway.
+    pub(crate) fn dispatch(&self, notification: &ProgressNotificationParam) {
+        let trackers = self.trackers.lock().unwrap_or_else(PoisonError::into_inner);
+        let Some(sender) = trackers.get(&notification.progress_token) else {
+            return;
+        };
+        if let Err(mpsc::error::TrySendError::Full(_)) =
+            sender.try_send(notification.clone().into())
+        {
+            tracing::debug!(progress_token = ?notification.progress_token, "progress stream lagging, update dropped");
+        }
+    }
+
+    /// Stop tracking `progress_token`, its stream ends after the updates already received
+    pub(crate) fn untrack(&self, progress_token: &ProgressToken) {
+        self.trackers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(progress_token);
+    }
+}
+
+/// The progress updates of a request, see [`Peer::track_progress`](super::Peer::track_progress).
+///
+/// It ends once the response of the request is received, drop it to stop tracking earlier.
+#[derive(Debug)]
+pub struct ProgressStream {
+    progress_token: ProgressToken,
+    receiver: mpsc::Receiver<Progress>,
+    trackers: Trackers,
+}
+
+impl ProgressStream {
+    pub fn progress_token(&self) -> &ProgressToken {
+        &self.progress_token
+    }
+}
+
+impl Stream for ProgressStream {
+    type Item = Progress;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        self.receiver.poll_recv(cx)
+    }
+}
+
+impl Drop for ProgressStream {
+    fn drop(&mut self) {
+        self.receiver.close();
+        let mut trackers = self.trackers.lock().unwrap_or_else(PoisonError::into_inner);
+        // spare the stream which replaced this one
+        if trackers
+            .get(&self.progress_token)
+            .is_some_and(mpsc::Sender::is_closed)
+        {
+            trackers.remove(&self.progress_token);
+        }
+    }
+}
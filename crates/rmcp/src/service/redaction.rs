@@ -0,0 +1,59 @@
+//! Redaction of the messages logged by [`Peer::set_message_logging`](super::Peer::set_message_logging).
+use serde_json::Value;
+
+/// What the redacted values are replaced with in the logs
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Picks the fields of the logged messages to mask, like tokens or the location of the user.
+///
+/// It's implemented for the closures taking the name of a field:
+///
+/// ```rust
+/// use rmcp::service::Redactor;
+///
+/// let redactor = |field: &str| matches!(field, "latitude" | "longitude");
+/// assert!(redactor.redact("latitude"));
+/// assert!(!redactor.redact("cityname"));
+/// ```
+pub trait Redactor: Send + Sync + 'static {
+    /// Whether the value of the field named `field` is masked, at any depth of the message
+    fn redact(&self, field: &str) -> bool;
+}
+
+impl<F> Redactor for F
+where
+    F: Fn(&str) -> bool + Send + Sync + 'static,
+{
+    fn redact(&self, field: &str) -> bool {
+        self(field)
+    }
+}
+
+/// Replace the values of the fields picked by the `redactor` with [`REDACTED`], including in the
+/// texts holding JSON
+pub fn redact(value: &mut Value, redactor: &dyn Redactor) {
+    match value {
+        Value::Object(object) => {
+            for (field, value) in object.iter_mut() {
+                if redactor.redact(field) {
+                    *value = Value::String(REDACTED.to_string());
+                } else {
+                    redact(value, redactor);
+                }
+            }
+        }
+        Value::Array(values) => {
+            for value in values {
+                redact(value, redactor);
+            }
+        }
+        // a text holding JSON, like the text content of a structured tool result
+        Value::String(text) if text.starts_with(['{', '[']) => {
+            if let Ok(mut json) = serde_json::from_str::<Value>(text) {
+                redact(&mut json, redactor);
+                *text = json.to_string();
+            }
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,99 @@
+//! The chunks of the blobs streamed by the server, see [`Peer::read_resource_blob_to`](super::Peer::read_resource_blob_to).
+#[cfg(feature = "client")]
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+#[cfg(feature = "client")]
+use tokio::sync::mpsc;
+
+#[cfg(feature = "client")]
+use crate::model::ProgressToken;
+use crate::model::{ClientNotification, ResourceChunkNotificationParam, ServerNotification};
+
+/// Notifications which could carry a chunk of a blob resource, only tracked by clients
+#[cfg_attr(not(feature = "client"), allow(dead_code))]
+pub(crate) trait ChunkNotification {
+    fn resource_chunk(&self) -> Option<&ResourceChunkNotificationParam>;
+}
+
+impl ChunkNotification for ClientNotification {
+    fn resource_chunk(&self) -> Option<&ResourceChunkNotificationParam> {
+        None
+    }
+}
+
+impl ChunkNotification for ServerNotification {
+    fn resource_chunk(&self) -> Option<&ResourceChunkNotificationParam> {
+        match self {
+            ServerNotification::ResourceChunkNotification(n) => Some(&n.params),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "client")]
+type Trackers =
+    Arc<Mutex<HashMap<ProgressToken, mpsc::UnboundedSender<ResourceChunkNotificationParam>>>>;
+
+/// The progress tokens of the blob reads in flight, with the streams their chunks are sent to
+#[cfg(feature = "client")]
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ChunkTracker {
+    trackers: Trackers,
+}
+
+#[cfg(feature = "client")]
+impl ChunkTracker {
+    /// Start tracking the chunks sent with `progress_token`
+    pub(crate) fn track(&self, progress_token: ProgressToken) -> ChunkStream {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        self.trackers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(progress_token.clone(), sender);
+        ChunkStream {
+            progress_token,
+            receiver,
+            trackers: self.trackers.clone(),
+        }
+    }
+
+    /// Send the chunk to the stream tracking its token.
+    ///
+    /// Unlike the progress updates, no chunk may be dropped: the channel is unbounded, it only
+    /// holds the chunks the reader hasn't written yet.
+    pub(crate) fn dispatch(&self, chunk: &ResourceChunkNotificationParam) {
+        let trackers = self.trackers.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(sender) = trackers.get(&chunk.progress_token) {
+            let _ = sender.send(chunk.clone());
+        }
+    }
+}
+
+/// The chunks of a blob read, in the order they are received
+#[cfg(feature = "client")]
+#[derive(Debug)]
+pub(crate) struct ChunkStream {
+    progress_token: ProgressToken,
+    receiver: mpsc::UnboundedReceiver<ResourceChunkNotificationParam>,
+    trackers: Trackers,
+}
+
+#[cfg(feature = "client")]
+impl ChunkStream {
+    pub(crate) async fn recv(&mut self) -> Option<ResourceChunkNotificationParam> {
+        self.receiver.recv().await
+    }
+}
+
+#[cfg(feature = "client")]
+impl Drop for ChunkStream {
+    fn drop(&mut self) {
+        self.trackers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&self.progress_token);
+    }
+}
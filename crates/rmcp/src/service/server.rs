@@ -1,4 +1,4 @@
-use std::borrow::Cow;
+use std::{borrow::Cow, collections::VecDeque};
 
 use thiserror::Error;
 
@@ -10,13 +10,16 @@ use crate::model::{
 use crate::{
     model::{
         CancelledNotification, CancelledNotificationParam, ClientInfo, ClientJsonRpcMessage,
-        ClientNotification, ClientRequest, ClientResult, CreateMessageRequest,
-        CreateMessageRequestParam, CreateMessageResult, ErrorData, ListRootsRequest,
-        ListRootsResult, LoggingMessageNotification, LoggingMessageNotificationParam,
-        ProgressNotification, ProgressNotificationParam, PromptListChangedNotification,
-        ProtocolVersion, ResourceListChangedNotification, ResourceUpdatedNotification,
-        ResourceUpdatedNotificationParam, ServerInfo, ServerNotification, ServerRequest,
-        ServerResult, ToolListChangedNotification,
+        ClientNotification, ClientRequest, ClientResult, ConfigChangedNotification,
+        ConfigChangedNotificationParam, CreateMessageRequest, CreateMessageRequestParam,
+        CreateMessageResult, ErrorData, ListRootsRequest, ListRootsResult, LoggingLevel,
+        LoggingMessageNotification, LoggingMessageNotificationParam, ProgressNotification,
+        ProgressNotificationParam, PromptListChangedNotification, ProtocolVersion,
+        ResourceChunkNotification, ResourceChunkNotificationParam, ResourceListChangedNotification,
+        ResourceUpdatedNotification, ResourceUpdatedNotificationParam, Root, ServerInfo,
+        ServerNotification, ServerRequest, ServerResult, SessionClosedNotification,
+        SessionClosedNotificationParam, ToolListChangedNotification, ToolPartialResultNotification,
+        ToolPartialResultNotificationParam,
     },
     transport::DynamicTransportError,
 };
@@ -61,6 +64,12 @@ pub enum ServerInitializeError {
     #[error("unsupported protocol version: {0}")]
     UnsupportedProtocolVersion(ProtocolVersion),
 
+    #[error("roots rejected: {0}")]
+    RootsRejected(String),
+
+    #[error("no roots/list response within {0:?}")]
+    RootsTimeout(Duration),
+
     #[error("Send message error {error}, when {context}")]
     TransportError {
         error: DynamicTransportError,
@@ -141,26 +150,200 @@ where
         )))
 }
 
-/// Helper function to expect a notification from the stream
-async fn expect_notification<T>(
+pub async fn serve_server_with_ct<S, T, E, A>(
+    service: S,
+    transport: T,
+    ct: CancellationToken,
+) -> Result<RunningService<RoleServer, S>, ServerInitializeError>
+where
+    S: Service<RoleServer>,
+    T: IntoTransport<RoleServer, E, A>,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    serve_server_with_config(service, transport, ct, HandshakeConfig::default()).await
+}
+
+/// How the server handles the messages received before the initialization, see [`serve_server_with_config`].
+#[derive(Debug, Clone, Default)]
+pub struct HandshakeConfig {
+    /// Answer the `ping` requests received before the `initialize` request, and keep waiting for it.
+    ///
+    /// Load balancers may open a connection and ping it as a health check, without a full handshake.
+    /// Any other request is still rejected. Disabled by default.
+    pub allow_ping_before_initialize: bool,
+
+    /// Only accept the clients requesting this protocol version, and advertise it instead of
+    /// negotiating the version. By default, the version requested by the client is accepted
+    /// unless it's newer than the one of the [`ServerInfo`], which is advertised then.
+    ///
+    /// The other clients are rejected with an `INVALID_PARAMS` error suggesting the pinned
+    /// version, as `{"supported": ["2025-03-26"], "requested": "2025-06-18"}` in its data, and
+    /// may retry the initialization with it.
+    pub pinned_protocol_version: Option<ProtocolVersion>,
+
+    /// Fetch the roots of the client once it's initialized, before the service handles the
+    /// `notifications/initialized`, and validate them. The accepted roots are available from
+    /// [`Peer::initialize_roots`], the rejected clients end the handshake with
+    /// [`ServerInitializeError::RootsRejected`]. Unset by default, the roots aren't fetched.
+    ///
+    /// The `roots/list` response is awaited for [`HandshakeConfig::request_timeout`], or
+    /// [`HandshakeConfig::DEFAULT_ROOTS_TIMEOUT`] when unset, a client not answering in time
+    /// ends the handshake with [`ServerInitializeError::RootsTimeout`].
+    pub roots_validator: Option<RootsValidator>,
+
+    /// The locale of the sessions whose client has no preference, as a BCP 47 language tag,
+    /// see [`RequestContext::locale`]. [`HandshakeConfig::DEFAULT_LOCALE`] when unset.
+    pub default_locale: Option<String>,
+
+    /// How long the requests sent to the client wait for a response, see
+    /// [`Peer::request_timeout`]. They wait forever when unset.
+    pub request_timeout: Option<Duration>,
+}
+
+impl HandshakeConfig {
+    pub const DEFAULT_LOCALE: &str = "en";
+    /// How long the `roots/list` response of the handshake is awaited without a request timeout
+    pub const DEFAULT_ROOTS_TIMEOUT: Duration = Duration::from_secs(30);
+}
+
+/// Validates the roots of the client during the handshake, see [`HandshakeConfig::roots_validator`].
+///
+/// It's given `None` when the client doesn't support roots, and the listed roots otherwise,
+/// which may be none. Returning an error rejects the client with this reason, a validator only
+/// warning about the missing roots logs them and accepts the client.
+#[derive(Clone)]
+pub struct RootsValidator(
+    #[allow(clippy::type_complexity)]
+    pub  Arc<dyn Fn(Option<&[Root]>) -> Result<(), String> + Send + Sync>,
+);
+
+impl RootsValidator {
+    pub fn new<F>(validate: F) -> Self
+    where
+        F: Fn(Option<&[Root]>) -> Result<(), String> + Send + Sync + 'static,
+    {
+        Self(Arc::new(validate))
+    }
+
+    /// Reject the clients without any root, e.g. for a server accessing their files
+    pub fn require_roots() -> Self {
+        Self::new(|roots| match roots {
+            None => Err("the client doesn't support roots, at least one is required".to_string()),
+            Some([]) => Err("the client listed no roots, at least one is required".to_string()),
+            Some(_) => Ok(()),
+        })
+    }
+}
+
+impl std::fmt::Debug for RootsValidator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("RootsValidator").finish_non_exhaustive()
+    }
+}
+
+/// Yields the messages received during the handshake before the next ones of the transport
+struct HandshakeTransport<T> {
+    pending: VecDeque<ClientJsonRpcMessage>,
+    transport: T,
+}
+
+impl<T: Transport<RoleServer>> Transport<RoleServer> for HandshakeTransport<T> {
+    type Error = T::Error;
+
+    fn name() -> Cow<'static, str> {
+        T::name()
+    }
+
+    fn send(
+        &mut self,
+        item: ServerJsonRpcMessage,
+    ) -> impl Future<Output = Result<(), Self::Error>> + Send + 'static {
+        self.transport.send(item)
+    }
+
+    async fn receive(&mut self) -> Option<ClientJsonRpcMessage> {
+        match self.pending.pop_front() {
+            Some(message) => Some(message),
+            None => self.transport.receive().await,
+        }
+    }
+
+    fn timed_out(&self) -> Option<crate::transport::TransportTimeout> {
+        self.transport.timed_out()
+    }
+
+    fn close(&mut self) -> impl Future<Output = Result<(), Self::Error>> + Send {
+        self.transport.close()
+    }
+}
+
+/// Request the roots of the client during the handshake, keeping the messages received before
+/// the response for the service. A failed request is logged and lists no roots.
+async fn list_roots_during_handshake<T>(
     transport: &mut T,
-    context: &str,
-) -> Result<ClientNotification, ServerInitializeError>
+    peer: &Peer<RoleServer>,
+    pending: &mut VecDeque<ClientJsonRpcMessage>,
+    timeout: Duration,
+) -> Result<Vec<Root>, ServerInitializeError>
 where
-    T: Transport<RoleServer>,
+    T: Transport<RoleServer> + 'static,
 {
-    let msg = expect_next_message(transport, context).await?;
-    let msg_clone = msg.clone();
-    msg.into_notification()
-        .ok_or(ServerInitializeError::ExpectedInitializedNotification(
-            Some(msg_clone),
-        ))
+    let request_id = peer.shared.request_id_provider.next_request_id();
+    let request = ServerRequest::ListRootsRequest(ListRootsRequest {
+        method: Default::default(),
+        extensions: Default::default(),
+    });
+    transport
+        .send(ServerJsonRpcMessage::request(request, request_id.clone()))
+        .await
+        .map_err(|error| {
+            ServerInitializeError::transport::<T>(error, "sending roots/list request")
+        })?;
+    let response = tokio::time::timeout(timeout, async {
+        loop {
+            let message = expect_next_message(transport, "roots/list response").await?;
+            match message.clone().into_result() {
+                Some((result, id)) if id == request_id => return Ok(result),
+                _ => pending.push_back(message),
+            }
+        }
+    });
+    let result = match response.await {
+        Ok(result) => result?,
+        Err(_) => {
+            tracing::warn!(?timeout, "no roots/list response");
+            let _ = transport.close().await;
+            return Err(ServerInitializeError::RootsTimeout(timeout));
+        }
+    };
+    Ok(match result {
+        Ok(ClientResult::ListRootsResult(result)) => result.roots,
+        Ok(result) => {
+            tracing::warn!(?result, "unexpected roots/list response");
+            Vec::new()
+        }
+        Err(error) => {
+            tracing::warn!(%error, "roots/list failed");
+            Vec::new()
+        }
+    })
 }
 
-pub async fn serve_server_with_ct<S, T, E, A>(
+/// Like [`serve_server_with_ct`], with a [`HandshakeConfig`].
+///
+/// The handshake goes through three states before the service handles the requests:
+/// 1. before the `initialize` request, any other request ends the handshake with an error,
+///    except the pings allowed by [`HandshakeConfig::allow_ping_before_initialize`];
+/// 2. once the `initialize` request is answered, the requests received before the
+///    `notifications/initialized` are rejected with `INVALID_REQUEST`, the pings are answered;
+/// 3. once the `notifications/initialized` is handled, e.g. by
+///    [`ServerHandler::on_initialized`](crate::ServerHandler::on_initialized), the session is
+///    operational.
+pub async fn serve_server_with_config<S, T, E, A>(
     service: S,
     transport: T,
     ct: CancellationToken,
+    config: HandshakeConfig,
 ) -> Result<RunningService<RoleServer, S>, ServerInitializeError>
 where
     S: Service<RoleServer>,
@@ -168,17 +351,29 @@ where
     E: std::error::Error + Send + Sync + 'static,
 {
     tokio::select! {
-        result = serve_server_with_ct_inner(service, transport.into_transport(), ct.clone()) => { result }
+        result = serve_server_with_ct_inner(service, transport.into_transport(), ct.clone(), config) => { result }
         _ = ct.cancelled() => {
             Err(ServerInitializeError::Cancelled)
         }
     }
 }
 
+/// The error answering the requests received after the `initialize` request and before the
+/// `notifications/initialized`, except the pings
+fn not_initialized_error(method: &str) -> ErrorData {
+    ErrorData::invalid_request(
+        format!(
+            "{method} received before notifications/initialized, the session isn't initialized yet"
+        ),
+        None,
+    )
+}
+
 async fn serve_server_with_ct_inner<S, T>(
     service: S,
     transport: T,
     ct: CancellationToken,
+    config: HandshakeConfig,
 ) -> Result<RunningService<RoleServer, S>, ServerInitializeError>
 where
     S: Service<RoleServer>,
@@ -187,8 +382,57 @@ where
     let mut transport = transport.into_transport();
     let id_provider = <Arc<AtomicU32RequestIdProvider>>::default();
 
-    // Get initialize request
-    let (request, id) = expect_request(&mut transport, "initialized request").await?;
+    // Get initialize request, answering the health checks before it
+    let mut rejected_version = None;
+    let (request, id) = loop {
+        let (request, id) = match expect_request(&mut transport, "initialized request").await {
+            Ok(request) => request,
+            // the client gave up once its protocol version was rejected
+            Err(ServerInitializeError::ConnectionClosed(context)) => {
+                return Err(match rejected_version {
+                    Some(version) => ServerInitializeError::UnsupportedProtocolVersion(version),
+                    None => ServerInitializeError::ConnectionClosed(context),
+                });
+            }
+            Err(error) => return Err(error),
+        };
+        match request {
+            ClientRequest::PingRequest(_) if config.allow_ping_before_initialize => {
+                transport
+                    .send(ServerJsonRpcMessage::response(ServerResult::empty(()), id))
+                    .await
+                    .map_err(|error| {
+                        ServerInitializeError::transport::<T>(error, "sending ping response")
+                    })?;
+            }
+            ClientRequest::InitializeRequest(ref initialize)
+                if config
+                    .pinned_protocol_version
+                    .as_ref()
+                    .is_some_and(|pinned| *pinned != initialize.params.protocol_version) =>
+            {
+                let requested = initialize.params.protocol_version.clone();
+                let pinned = config
+                    .pinned_protocol_version
+                    .as_ref()
+                    .expect("the protocol version is pinned");
+                let error = ErrorData::invalid_params(
+                    format!(
+                        "unsupported protocol version {requested}, this server only supports {pinned}"
+                    ),
+                    Some(serde_json::json!({ "supported": [pinned], "requested": requested })),
+                );
+                transport
+                    .send(ServerJsonRpcMessage::error(error, id))
+                    .await
+                    .map_err(|error| {
+                        ServerInitializeError::transport::<T>(error, "sending error response")
+                    })?;
+                rejected_version = Some(requested);
+            }
+            request => break (request, id),
+        }
+    };
 
     let ClientRequest::InitializeRequest(peer_info) = &request else {
         return Err(ServerInitializeError::ExpectedInitializeRequest(Some(
@@ -196,6 +440,11 @@ where
         )));
     };
     let (peer, peer_rx) = Peer::new(id_provider, Some(peer_info.params.clone()));
+    let _ = peer.shared.initialize_meta.set(request.get_meta().clone());
+    if let Some(locale) = &config.default_locale {
+        let _ = peer.shared.default_locale.set(locale.clone());
+    }
+    peer.set_request_timeout(config.request_timeout);
     let context = RequestContext {
         ct: ct.child_token(),
         id: id.clone(),
@@ -221,13 +470,16 @@ where
         }
     };
     let peer_protocol_version = peer_info.params.protocol_version.clone();
-    let protocol_version = match peer_protocol_version
-        .partial_cmp(&init_response.protocol_version)
-        .ok_or(ServerInitializeError::UnsupportedProtocolVersion(
-            peer_protocol_version,
-        ))? {
-        std::cmp::Ordering::Less => peer_info.params.protocol_version.clone(),
-        _ => init_response.protocol_version,
+    let protocol_version = match config.pinned_protocol_version {
+        Some(pinned) => pinned,
+        None => match peer_protocol_version
+            .partial_cmp(&init_response.protocol_version)
+            .ok_or(ServerInitializeError::UnsupportedProtocolVersion(
+                peer_protocol_version,
+            ))? {
+            std::cmp::Ordering::Less => peer_info.params.protocol_version.clone(),
+            _ => init_response.protocol_version,
+        },
     };
     init_response.protocol_version = protocol_version;
     transport
@@ -240,13 +492,61 @@ where
             ServerInitializeError::transport::<T>(error, "sending initialize response")
         })?;
 
-    // Wait for initialize notification
-    let notification = expect_notification(&mut transport, "initialize notification").await?;
+    // Wait for initialize notification, rejecting the requests sent before it
+    let notification = loop {
+        let message = expect_next_message(&mut transport, "initialize notification").await?;
+        let Some((request, id)) = message.clone().into_request() else {
+            break message.clone().into_notification().ok_or(
+                ServerInitializeError::ExpectedInitializedNotification(Some(message)),
+            )?;
+        };
+        let response = match request {
+            ClientRequest::PingRequest(_) => {
+                ServerJsonRpcMessage::response(ServerResult::empty(()), id)
+            }
+            request => ServerJsonRpcMessage::error(not_initialized_error(request.method()), id),
+        };
+        transport.send(response).await.map_err(|error| {
+            ServerInitializeError::transport::<T>(error, "sending response before initialized")
+        })?;
+    };
     let ClientNotification::InitializedNotification(_) = notification else {
         return Err(ServerInitializeError::ExpectedInitializedNotification(
             Some(ClientJsonRpcMessage::notification(notification)),
         ));
     };
+    // here rather than in the service, so that any service rejects a second initialize
+    peer.mark_initialized();
+    // Validate the roots of the client before it's served
+    let mut pending = VecDeque::new();
+    if let Some(validator) = &config.roots_validator {
+        let roots = match peer_info.params.capabilities.roots {
+            Some(_) => {
+                let timeout = config
+                    .request_timeout
+                    .unwrap_or(HandshakeConfig::DEFAULT_ROOTS_TIMEOUT);
+                Some(
+                    list_roots_during_handshake(&mut transport, &peer, &mut pending, timeout)
+                        .await?,
+                )
+            }
+            None => None,
+        };
+        if let Err(reason) = (validator.0)(roots.as_deref()) {
+            tracing::warn!(%reason, "rejecting the roots of the client");
+            let notification = SessionClosedNotification::new(SessionClosedNotificationParam {
+                reason: format!("roots rejected: {reason}"),
+            });
+            let _ = transport
+                .send(ServerJsonRpcMessage::notification(notification.into()))
+                .await;
+            let _ = transport.close().await;
+            return Err(ServerInitializeError::RootsRejected(reason));
+        }
+        if let Some(roots) = roots {
+            let _ = peer.shared.initialize_roots.set(roots);
+        }
+    }
     let context = NotificationContext {
         meta: notification.get_meta().clone(),
         extensions: notification.extensions().clone(),
@@ -254,6 +554,7 @@ where
     };
     let _ = service.handle_notification(notification, context).await;
     // Continue processing service
+    let transport = HandshakeTransport { pending, transport };
     Ok(serve_inner(service, transport, peer, peer_rx, ct))
 }
 
@@ -383,7 +684,198 @@ macro_rules! method {
     };
 }
 
+impl RequestContext<RoleServer> {
+    /// The locale to reply in, as a BCP 47 language tag.
+    ///
+    /// It's the first one of:
+    /// 1. `locale` in the request's `_meta`, see [`Meta::set_locale`](crate::model::Meta::set_locale)
+    /// 2. the locale declared by the client during initialization, see [`ClientCapabilities::set_locale`](crate::model::ClientCapabilities::set_locale)
+    /// 3. the [`HandshakeConfig::default_locale`] of the server
+    pub fn locale(&self) -> &str {
+        self.meta
+            .locale()
+            .or_else(|| self.peer.locale())
+            .unwrap_or_else(|| self.peer.default_locale())
+    }
+
+    /// The MIME types the client prefers for the result, most preferred first.
+    ///
+    /// It's the first one of:
+    /// 1. `accept` in the request's `_meta`, see [`Meta::set_accept`](crate::model::Meta::set_accept)
+    /// 2. the types declared by the client during initialization, see [`ClientCapabilities::set_accept`](crate::model::ClientCapabilities::set_accept)
+    /// 3. no preference at all
+    pub fn accept(&self) -> Vec<String> {
+        self.meta
+            .accept()
+            .or_else(|| self.peer.peer_info()?.capabilities.accept())
+            .unwrap_or_default()
+    }
+
+    /// Experimental: tell the client that a setting changed, see [`ConfigChangedNotification`].
+    pub async fn notify_config_changed(
+        &self,
+        config: impl Into<serde_json::Value>,
+    ) -> Result<(), ServiceError> {
+        self.peer
+            .notify_config_changed(ConfigChangedNotificationParam::new(config))
+            .await
+    }
+
+    /// Read a blob resource from `stream`, split in chunks of `chunk_size` bytes, e.g.
+    /// [`ResourceContents::DEFAULT_BLOB_CHUNK_SIZE`](crate::model::ResourceContents::DEFAULT_BLOB_CHUNK_SIZE).
+    ///
+    /// If the client opted in with [`Meta::set_partial_results`](crate::model::Meta::set_partial_results),
+    /// every chunk is sent with a [`ResourceChunkNotification`] as soon as it's read, and the
+    /// result only holds a [`ResourceContents::streamed_blob`](crate::model::ResourceContents::streamed_blob)
+    /// counting them: the blob is never held in memory. Otherwise, all the chunks are returned in
+    /// the result, see [`ResourceContents::blob_chunk`](crate::model::ResourceContents::blob_chunk).
+    /// The client reassembles them either way with [`Peer::read_resource_blob_to`].
+    ///
+    /// The stream is re-chunked as it's read, so its items don't have to match the chunk size.
+    #[cfg(feature = "base64")]
+    pub async fn stream_blob<S, B, E>(
+        &self,
+        uri: impl Into<String>,
+        mime_type: Option<String>,
+        chunk_size: usize,
+        stream: S,
+    ) -> Result<crate::model::ReadResourceResult, E>
+    where
+        S: futures::Stream<Item = Result<B, E>>,
+        B: AsRef<[u8]>,
+    {
+        use futures::StreamExt;
+
+        let chunk_size = chunk_size.max(1);
+        let progress_token = self
+            .meta
+            .partial_results()
+            .then(|| self.meta.get_progress_token())
+            .flatten();
+        let mut chunks = BlobChunks {
+            peer: &self.peer,
+            uri: uri.into(),
+            progress_token,
+            count: 0,
+            buffered: Vec::new(),
+        };
+        let mut pending = Vec::with_capacity(chunk_size);
+        let mut stream = std::pin::pin!(stream);
+        while let Some(bytes) = stream.next().await {
+            let item = bytes?;
+            let mut bytes = item.as_ref();
+            while !bytes.is_empty() {
+                let take = (chunk_size - pending.len()).min(bytes.len());
+                pending.extend_from_slice(&bytes[..take]);
+                bytes = &bytes[take..];
+                if pending.len() == chunk_size {
+                    chunks.push(&pending).await;
+                    pending.clear();
+                }
+            }
+        }
+        if !pending.is_empty() || chunks.count == 0 {
+            chunks.push(&pending).await;
+        }
+        Ok(chunks.finish(mime_type))
+    }
+}
+
+/// The chunks of a blob read by [`RequestContext::stream_blob`], sent to the client as they're
+/// read, or kept for the result if it didn't opt in
+#[cfg(feature = "base64")]
+struct BlobChunks<'a> {
+    peer: &'a Peer<RoleServer>,
+    uri: String,
+    progress_token: Option<crate::model::ProgressToken>,
+    count: usize,
+    buffered: Vec<String>,
+}
+
+#[cfg(feature = "base64")]
+impl BlobChunks<'_> {
+    async fn push(&mut self, chunk: &[u8]) {
+        use base64::engine::{Engine, general_purpose::STANDARD};
+
+        let blob = STANDARD.encode(chunk);
+        let index = self.count;
+        self.count += 1;
+        let Some(progress_token) = &self.progress_token else {
+            self.buffered.push(blob);
+            return;
+        };
+        let result = self
+            .peer
+            .notify_resource_chunk(ResourceChunkNotificationParam {
+                progress_token: progress_token.clone(),
+                uri: self.uri.clone(),
+                index,
+                blob,
+            })
+            .await;
+        if let Err(error) = result {
+            // the response won't make it either, the client fails on the missing chunk anyway
+            tracing::warn!(%error, uri = %self.uri, index, "fail to send resource chunk");
+        }
+    }
+
+    fn finish(self, mime_type: Option<String>) -> crate::model::ReadResourceResult {
+        use crate::model::ResourceContents;
+
+        let contents = if self.progress_token.is_some() {
+            vec![ResourceContents::streamed_blob(
+                self.uri, mime_type, self.count,
+            )]
+        } else {
+            let count = self.count;
+            self.buffered
+                .into_iter()
+                .enumerate()
+                .map(|(index, blob)| {
+                    ResourceContents::blob_chunk(&self.uri, mime_type.clone(), blob, index, count)
+                })
+                .collect()
+        };
+        crate::model::ReadResourceResult { contents }
+    }
+}
+
 impl Peer<RoleServer> {
+    /// The locale declared by the client during initialization, if any
+    pub fn locale(&self) -> Option<&str> {
+        self.peer_info()?.capabilities.locale()
+    }
+
+    /// The locale used when the client has no preference, see [`HandshakeConfig::default_locale`].
+    pub fn default_locale(&self) -> &str {
+        self.shared
+            .default_locale
+            .get()
+            .map_or(HandshakeConfig::DEFAULT_LOCALE, String::as_str)
+    }
+
+    /// The minimum level of the log messages the client asked for with `logging/setLevel`,
+    /// `None` until it does.
+    ///
+    /// [`Peer::notify_logging_message`] drops the messages below it.
+    pub fn min_log_level(&self) -> Option<LoggingLevel> {
+        *self
+            .shared
+            .min_log_level
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Change [`Peer::min_log_level`], it's set when a `logging/setLevel` request is received,
+    /// before [`ServerHandler::set_level`](crate::ServerHandler::set_level) is called.
+    pub fn set_min_log_level(&self, level: LoggingLevel) {
+        *self
+            .shared
+            .min_log_level
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner) = Some(level);
+    }
+
     pub async fn create_message(
         &self,
         params: CreateMessageRequestParam,
@@ -400,6 +892,33 @@ impl Peer<RoleServer> {
             _ => Err(ServiceError::UnexpectedResponse),
         }
     }
+    /// Like [`Peer::create_message`], waiting `timeout` for the response instead of the
+    /// [`Peer::request_timeout`], e.g. [`PeerRequestOptions::NO_TIMEOUT`](crate::service::PeerRequestOptions::NO_TIMEOUT)
+    /// while the user reviews the request.
+    pub async fn create_message_with_timeout(
+        &self,
+        params: CreateMessageRequestParam,
+        timeout: Option<std::time::Duration>,
+    ) -> Result<CreateMessageResult, ServiceError> {
+        let request = ServerRequest::CreateMessageRequest(CreateMessageRequest {
+            method: Default::default(),
+            params,
+            extensions: Default::default(),
+        });
+        let options = crate::service::PeerRequestOptions {
+            timeout,
+            meta: None,
+        };
+        let result = self
+            .send_request_with_option(request, options)
+            .await?
+            .await_response()
+            .await?;
+        match result {
+            ClientResult::CreateMessageResult(result) => Ok(*result),
+            _ => Err(ServiceError::UnexpectedResponse),
+        }
+    }
     method!(peer_req list_roots ListRootsRequest() => ListRootsResult);
     #[cfg(feature = "elicitation")]
     method!(peer_req create_elicitation CreateElicitationRequest(CreateElicitationRequestParam) => CreateElicitationResult);
@@ -408,11 +927,84 @@ impl Peer<RoleServer> {
 
     method!(peer_not notify_cancelled CancelledNotification(CancelledNotificationParam));
     method!(peer_not notify_progress ProgressNotification(ProgressNotificationParam));
-    method!(peer_not notify_logging_message LoggingMessageNotification(LoggingMessageNotificationParam));
+    /// Send a log message to the client, unless it's below the [`Peer::min_log_level`] the
+    /// client asked for, then it's dropped.
+    pub async fn notify_logging_message(
+        &self,
+        params: LoggingMessageNotificationParam,
+    ) -> Result<(), ServiceError> {
+        if self
+            .min_log_level()
+            .is_some_and(|level| params.level < level)
+        {
+            return Ok(());
+        }
+        let notification =
+            ServerNotification::LoggingMessageNotification(LoggingMessageNotification {
+                method: Default::default(),
+                params,
+                extensions: Default::default(),
+            });
+        if let Some(queue) = self.shared.log_queue.get() {
+            return queue.push(notification).await;
+        }
+        self.send_notification(notification).await
+    }
+
+    /// Send the log messages through a dedicated bounded queue of `capacity` messages, separate
+    /// from the other notifications, see [`Peer::enable_notification_queue`].
+    ///
+    /// Once enabled, [`Peer::notify_logging_message`] never waits for the transport: it returns
+    /// when the message is queued, and drops it if the queue is full, so a flood of log messages
+    /// doesn't slow down the tools. The log messages may then be sent after the notifications
+    /// sent later. Returns `false` if the queue has already been enabled.
+    pub fn enable_log_queue(&self, capacity: usize) -> bool {
+        let mut enabled = false;
+        self.shared.log_queue.get_or_init(|| {
+            enabled = true;
+            let config = NotificationQueueConfig {
+                capacity,
+                overflow: NotificationOverflowPolicy::DropNewest,
+            };
+            NotificationQueue::spawn(config, self.shared.tx.clone())
+        });
+        enabled
+    }
+
+    /// The number of log messages dropped because the log queue was full, see
+    /// [`Peer::enable_log_queue`]
+    pub fn dropped_log_messages(&self) -> u64 {
+        self.shared
+            .log_queue
+            .get()
+            .map(|queue| queue.dropped())
+            .unwrap_or_default()
+    }
+
+    /// The `_meta` of the `initialize` request, empty if the client sent none.
+    ///
+    /// Clients may pass setup hints there, like a tenant ID or feature flags, which the
+    /// handlers of the later requests can read here, see [`ServerHandler::initialize`](crate::ServerHandler::initialize).
+    pub fn initialize_meta(&self) -> &Meta {
+        self.shared
+            .initialize_meta
+            .get()
+            .unwrap_or(Meta::static_empty())
+    }
+
+    /// The roots listed by the client during the handshake, once accepted by the
+    /// [`HandshakeConfig::roots_validator`]. `None` without validator, or when the client doesn't
+    /// support roots.
+    pub fn initialize_roots(&self) -> Option<&[Root]> {
+        self.shared.initialize_roots.get().map(Vec::as_slice)
+    }
     method!(peer_not notify_resource_updated ResourceUpdatedNotification(ResourceUpdatedNotificationParam));
     method!(peer_not notify_resource_list_changed ResourceListChangedNotification);
     method!(peer_not notify_tool_list_changed ToolListChangedNotification);
     method!(peer_not notify_prompt_list_changed PromptListChangedNotification);
+    method!(peer_not notify_tool_partial_result ToolPartialResultNotification(ToolPartialResultNotificationParam));
+    method!(peer_not notify_resource_chunk ResourceChunkNotification(ResourceChunkNotificationParam));
+    method!(peer_not notify_config_changed ConfigChangedNotification(ConfigChangedNotificationParam));
 }
 
 // =============================================================================
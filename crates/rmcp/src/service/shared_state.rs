@@ -0,0 +1,111 @@
+//! State shared between the handlers, locked only while it's read or updated.
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// State shared between clones of a handler, like a cache of the city IDs looked up so far.
+///
+/// The state is locked only for the duration of the synchronous closure given to
+/// [`SharedState::with`], so the lock can't be held across an `.await`. Avoid this with a
+/// plain `Arc<Mutex<T>>`:
+///
+/// ```rust,ignore
+/// let mut cities = self.cities.lock().unwrap();
+/// // the lock is held while waiting, blocking the other requests, and a `std` guard held across
+/// // an `.await` may deadlock the runtime thread it's resumed on
+/// let city_id = self.fetch_city_id(&name).await?;
+/// cities.insert(name, city_id);
+/// ```
+///
+/// With a [`SharedState`], copy what the async part needs out of the state, and put the result
+/// back once it's ready:
+///
+/// ```rust
+/// # use std::collections::HashMap;
+/// # use rmcp::service::SharedState;
+/// # async fn fetch_city_id(name: &str) -> i32 { 1 }
+/// # async fn find_city(cities: SharedState<HashMap<String, i32>>) {
+/// let name = "北京".to_string();
+/// let city_id = match cities.with(|cities| cities.get(&name).copied()) {
+///     Some(city_id) => city_id,
+///     None => {
+///         // not locked while fetching
+///         let city_id = fetch_city_id(&name).await;
+///         cities.with(|cities| cities.insert(name, city_id));
+///         city_id
+///     }
+/// };
+/// # }
+/// ```
+///
+/// The closure may also return a future, which is awaited once the lock is released. It can't
+/// borrow the state, so move what it needs into an `async move` block:
+///
+/// ```rust
+/// # use rmcp::service::SharedState;
+/// # async fn fetch(url: String) {}
+/// # async fn refresh(state: SharedState<String>) {
+/// state.with(|url| {
+///     let url = url.clone();
+///     async move { fetch(url).await }
+/// })
+/// .await;
+/// # }
+/// ```
+///
+/// As nothing is locked while awaiting, dropping the future, like when racing it against the
+/// cancellation of the request with [`RequestContext::ct`](crate::service::RequestContext::ct),
+/// never leaves the state locked.
+///
+/// Clones share the same state.
+pub struct SharedState<T> {
+    inner: Arc<Mutex<T>>,
+}
+
+impl<T> Clone for SharedState<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T: Default> Default for SharedState<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for SharedState<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.with(|state| f.debug_tuple("SharedState").field(state).finish())
+    }
+}
+
+impl<T> SharedState<T> {
+    pub fn new(state: T) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(state)),
+        }
+    }
+
+    /// Read or update the state, locked until `f` returns.
+    ///
+    /// A panic in `f` doesn't poison the state for the other handlers, they see the state as
+    /// `f` left it.
+    pub fn with<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        let mut state = self.inner.lock().unwrap_or_else(PoisonError::into_inner);
+        f(&mut state)
+    }
+
+    /// A copy of the state
+    pub fn get(&self) -> T
+    where
+        T: Clone,
+    {
+        self.with(|state| state.clone())
+    }
+
+    /// Replace the state, returning the previous one
+    pub fn replace(&self, state: T) -> T {
+        self.with(|current| std::mem::replace(current, state))
+    }
+}
@@ -0,0 +1,123 @@
+//! A graceful shutdown signal shared by the servers, transports and tasks of an application.
+use std::future::Future;
+
+use tokio_util::sync::{CancellationToken, WaitForCancellationFutureOwned};
+
+/// A shutdown signal, triggered once by ctrl-c, SIGTERM, any future, or [`Shutdown::trigger`].
+///
+/// Everything waiting for [`Shutdown::on_shutdown`] is woken up once it's triggered, to clean up
+/// and stop:
+///
+/// ```rust,ignore
+/// let shutdown = Shutdown::new().with_ctrl_c().with_sigterm();
+/// let config = SseServerConfig {
+///     ct: shutdown.cancellation_token(),
+///     // ...
+/// };
+/// shutdown.on_shutdown().await;
+/// flush_logs().await;
+/// ```
+///
+/// It's a [`CancellationToken`] underneath, so it can be passed where a token is expected with
+/// [`Shutdown::cancellation_token`], and a token cancelled elsewhere triggers it when converted
+/// with [`From<CancellationToken>`]. Clones share the same signal.
+#[derive(Debug, Clone, Default)]
+pub struct Shutdown {
+    ct: CancellationToken,
+}
+
+impl Shutdown {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Trigger the shutdown, it does nothing if it's already triggered
+    pub fn trigger(&self) {
+        self.ct.cancel();
+    }
+
+    pub fn is_triggered(&self) -> bool {
+        self.ct.is_cancelled()
+    }
+
+    /// A future completing once the shutdown is triggered, which can be moved into a task
+    pub fn on_shutdown(&self) -> WaitForCancellationFutureOwned {
+        self.ct.clone().cancelled_owned()
+    }
+
+    /// The token cancelled by the shutdown, cancelling it triggers the shutdown
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.ct.clone()
+    }
+
+    /// A token cancelled by the shutdown, which can be cancelled without triggering it
+    pub fn child_token(&self) -> CancellationToken {
+        self.ct.child_token()
+    }
+
+    /// Trigger the shutdown once `signal` completes.
+    ///
+    /// `signal` is spawned on the current tokio runtime, and dropped if the shutdown is triggered
+    /// by something else first.
+    pub fn trigger_on<F>(self, signal: F) -> Self
+    where
+        F: Future + Send + 'static,
+    {
+        let ct = self.ct.clone();
+        tokio::spawn(async move {
+            tokio::select! {
+                _ = signal => ct.cancel(),
+                _ = ct.cancelled() => {}
+            }
+        });
+        self
+    }
+
+    /// Trigger the shutdown on ctrl-c, see [`Shutdown::trigger_on`]
+    #[cfg(feature = "shutdown-signal")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "shutdown-signal")))]
+    pub fn with_ctrl_c(self) -> Self {
+        self.trigger_on(async {
+            match tokio::signal::ctrl_c().await {
+                Ok(()) => tracing::info!("ctrl-c received, shutting down"),
+                // never trigger the shutdown if we can't listen to ctrl-c
+                Err(error) => {
+                    tracing::error!(%error, "fail to listen to ctrl-c");
+                    std::future::pending::<()>().await
+                }
+            }
+        })
+    }
+
+    /// Trigger the shutdown on SIGTERM, see [`Shutdown::trigger_on`]
+    #[cfg(all(unix, feature = "shutdown-signal"))]
+    #[cfg_attr(docsrs, doc(cfg(all(unix, feature = "shutdown-signal"))))]
+    pub fn with_sigterm(self) -> Self {
+        use tokio::signal::unix::{SignalKind, signal};
+        let sigterm = signal(SignalKind::terminate());
+        self.trigger_on(async move {
+            match sigterm {
+                Ok(mut sigterm) => {
+                    sigterm.recv().await;
+                    tracing::info!("SIGTERM received, shutting down");
+                }
+                Err(error) => {
+                    tracing::error!(%error, "fail to listen to SIGTERM");
+                    std::future::pending::<()>().await
+                }
+            }
+        })
+    }
+}
+
+impl From<CancellationToken> for Shutdown {
+    fn from(ct: CancellationToken) -> Self {
+        Self { ct }
+    }
+}
+
+impl From<Shutdown> for CancellationToken {
+    fn from(shutdown: Shutdown) -> Self {
+        shutdown.ct
+    }
+}
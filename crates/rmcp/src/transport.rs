@@ -102,13 +102,29 @@ pub mod sse_client;
 #[cfg_attr(docsrs, doc(cfg(feature = "transport-sse-client")))]
 pub use sse_client::SseClientTransport;
 
-#[cfg(feature = "transport-sse-server")]
-#[cfg_attr(docsrs, doc(cfg(feature = "transport-sse-server")))]
+#[cfg(any(
+    feature = "transport-sse-server",
+    feature = "transport-sse-server-hyper"
+))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(any(
+        feature = "transport-sse-server",
+        feature = "transport-sse-server-hyper"
+    )))
+)]
 pub mod sse_server;
+#[cfg(feature = "transport-sse-server-hyper")]
+#[cfg_attr(docsrs, doc(cfg(feature = "transport-sse-server-hyper")))]
+pub use sse_server::HyperSseServer;
 #[cfg(feature = "transport-sse-server")]
 #[cfg_attr(docsrs, doc(cfg(feature = "transport-sse-server")))]
 pub use sse_server::SseServer;
 
+#[cfg(feature = "transport-auto")]
+#[cfg_attr(docsrs, doc(cfg(feature = "transport-auto")))]
+pub mod auto;
+
 #[cfg(feature = "auth")]
 #[cfg_attr(docsrs, doc(cfg(feature = "auth")))]
 pub mod auth;
@@ -133,6 +149,9 @@ pub mod streamable_http_client;
 #[cfg_attr(docsrs, doc(cfg(feature = "transport-streamable-http-client")))]
 pub use streamable_http_client::StreamableHttpClientTransport;
 
+pub mod timeout;
+pub use timeout::{TimeoutTransport, TransportTimeout, TransportTimeouts};
+
 /// Common use codes
 pub mod common;
 
@@ -157,6 +176,12 @@ where
     /// Receive a message from the transport, this operation is sequential.
     fn receive(&mut self) -> impl Future<Output = Option<RxJsonRpcMessage<R>>> + Send;
 
+    /// The timeout which made [`Transport::receive`] return `None`, if it stalled rather than
+    /// closed, see [`TimeoutTransport`].
+    fn timed_out(&self) -> Option<TransportTimeout> {
+        None
+    }
+
     /// Close the transport
     fn close(&mut self) -> impl Future<Output = Result<(), Self::Error>> + Send;
 }
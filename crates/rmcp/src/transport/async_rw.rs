@@ -14,7 +14,10 @@ use tokio_util::{
 };
 
 use super::{IntoTransport, Transport};
-use crate::service::{RxJsonRpcMessage, ServiceRole, TxJsonRpcMessage};
+use crate::{
+    model::{JsonLimits, JsonRpcEnvelopeError, JsonRpcParseMode},
+    service::{RxJsonRpcMessage, ServiceRole, TxJsonRpcMessage},
+};
 
 pub enum TransportAdapterAsyncRW {}
 
@@ -65,6 +68,37 @@ where
         ))));
         Self { read, write }
     }
+
+    /// How strictly the envelope of incoming messages is checked, [`JsonRpcParseMode::Strict`] by default.
+    ///
+    /// Use [`JsonRpcParseMode::Lenient`] to talk to peers sending non-conformant messages.
+    pub fn with_parse_mode(mut self, parse_mode: JsonRpcParseMode) -> Self {
+        self.read.decoder_mut().parse_mode = parse_mode;
+        self
+    }
+
+    /// What to do with incoming lines which aren't JSON, [`StrayOutput::Warn`] by default.
+    pub fn with_stray_output(mut self, stray_output: StrayOutput) -> Self {
+        self.read.decoder_mut().stray_output = stray_output;
+        self
+    }
+
+    /// The limits on incoming messages, a message exceeding them closes the transport.
+    pub fn with_json_limits(mut self, json_limits: JsonLimits) -> Self {
+        self.read.decoder_mut().json_limits = json_limits;
+        self
+    }
+
+    /// How the messages are delimited in both directions, [`Framing::NewlineDelimited`] by default.
+    pub fn with_framing(mut self, framing: Framing) -> Self {
+        self.read.decoder_mut().framing = framing;
+        if let Some(write) =
+            Arc::get_mut(&mut self.write).and_then(|write| write.get_mut().as_mut())
+        {
+            write.encoder_mut().framing = framing;
+        }
+        self
+    }
 }
 
 #[cfg(feature = "client")]
@@ -135,12 +169,44 @@ where
     }
 }
 
+/// What to do with an incoming line which isn't a JSON object or array.
+///
+/// Over stdio, anything a peer process prints to its stdout besides the messages, a stray
+/// `println!` of its own or of a dependency, ends up in the stream.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StrayOutput {
+    /// Skip the line and log it as a warning, the session goes on.
+    #[default]
+    Warn,
+    /// Fail with a [`JsonRpcMessageCodecError::Serde`], which closes the transport.
+    Error,
+}
+
+/// How the messages are delimited in the stream.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Framing {
+    /// One message per line, as required by the MCP stdio transport.
+    #[default]
+    NewlineDelimited,
+    /// Each message preceded by a `Content-Length: <bytes>` header and an empty line, like the
+    /// Language Server Protocol, for the hosts which expect it. Other headers are ignored.
+    ContentLength,
+}
+
+const CONTENT_LENGTH_HEADER: &str = "content-length";
+/// The most reserved at once for the body of a `Content-Length` framed message
+const CONTENT_LENGTH_RESERVE_CHUNK: usize = 64 * 1024;
+
 #[derive(Debug, Clone)]
 pub struct JsonRpcMessageCodec<T> {
     _marker: PhantomData<fn() -> T>,
     next_index: usize,
     max_length: usize,
     is_discarding: bool,
+    parse_mode: JsonRpcParseMode,
+    stray_output: StrayOutput,
+    framing: Framing,
+    json_limits: JsonLimits,
 }
 
 impl<T> Default for JsonRpcMessageCodec<T> {
@@ -156,6 +222,10 @@ impl<T> JsonRpcMessageCodec<T> {
             next_index: 0,
             max_length: usize::MAX,
             is_discarding: false,
+            parse_mode: JsonRpcParseMode::default(),
+            stray_output: StrayOutput::default(),
+            framing: Framing::default(),
+            json_limits: JsonLimits::default(),
         }
     }
 
@@ -169,6 +239,138 @@ impl<T> JsonRpcMessageCodec<T> {
     pub fn max_length(&self) -> usize {
         self.max_length
     }
+
+    /// How strictly the envelope of decoded messages is checked, [`JsonRpcParseMode::Strict`] by default
+    pub fn with_parse_mode(mut self, parse_mode: JsonRpcParseMode) -> Self {
+        self.parse_mode = parse_mode;
+        self
+    }
+
+    pub fn parse_mode(&self) -> JsonRpcParseMode {
+        self.parse_mode
+    }
+
+    /// What to do with decoded lines which aren't JSON, [`StrayOutput::Warn`] by default
+    pub fn with_stray_output(mut self, stray_output: StrayOutput) -> Self {
+        self.stray_output = stray_output;
+        self
+    }
+
+    pub fn stray_output(&self) -> StrayOutput {
+        self.stray_output
+    }
+
+    /// How the messages are delimited, [`Framing::NewlineDelimited`] by default
+    pub fn with_framing(mut self, framing: Framing) -> Self {
+        self.framing = framing;
+        self
+    }
+
+    pub fn framing(&self) -> Framing {
+        self.framing
+    }
+
+    /// The limits on decoded messages, failing with a [`JsonRpcMessageCodecError::Envelope`] when
+    /// exceeded, only the default depth by default. A frame longer than the max size fails with
+    /// [`JsonRpcMessageCodecError::MaxLineLengthExceeded`] as soon as it's read that far.
+    pub fn with_json_limits(mut self, json_limits: JsonLimits) -> Self {
+        self.json_limits = json_limits;
+        self
+    }
+
+    pub fn json_limits(&self) -> JsonLimits {
+        self.json_limits
+    }
+
+    /// The max length of a frame, the [`JsonLimits::max_size`] when shorter than `max_length`,
+    /// so a frame over the limit is rejected while it's read rather than once in memory
+    fn max_frame_length(&self) -> usize {
+        self.json_limits
+            .max_size
+            .map_or(self.max_length, |max_size| max_size.min(self.max_length))
+    }
+
+    /// Decode a message framed by a `Content-Length` header, see [`Framing::ContentLength`]
+    fn decode_content_length(
+        &mut self,
+        buf: &mut BytesMut,
+    ) -> Result<Option<T>, JsonRpcMessageCodecError>
+    where
+        T: DeserializeOwned,
+    {
+        loop {
+            // skip the line breaks between messages
+            let leading = buf
+                .iter()
+                .take_while(|b| matches!(b, b'\r' | b'\n'))
+                .count();
+            buf.advance(leading);
+            let Some(header_end) = buf.windows(4).position(|window| window == b"\r\n\r\n") else {
+                if buf.len() > self.max_frame_length() {
+                    return Err(JsonRpcMessageCodecError::MaxLineLengthExceeded);
+                }
+                return Ok(None);
+            };
+            let headers = std::str::from_utf8(&buf[..header_end])
+                .map_err(|_| JsonRpcMessageCodecError::InvalidHeader("not utf-8".to_string()))?;
+            let length = headers
+                .split("\r\n")
+                .find_map(|header| {
+                    let (name, value) = header.split_once(':')?;
+                    name.trim()
+                        .eq_ignore_ascii_case(CONTENT_LENGTH_HEADER)
+                        .then(|| value.trim())
+                })
+                .ok_or_else(|| JsonRpcMessageCodecError::InvalidHeader(headers.to_string()))?
+                .parse::<usize>()
+                .map_err(|_| JsonRpcMessageCodecError::InvalidHeader(headers.to_string()))?;
+            // the length comes from the peer, don't trust it before any byte of the body arrives
+            if length > self.max_frame_length() {
+                return Err(JsonRpcMessageCodecError::MaxLineLengthExceeded);
+            }
+            let body_start = header_end + 4;
+            let body_end = body_start
+                .checked_add(length)
+                .ok_or_else(|| JsonRpcMessageCodecError::InvalidHeader(headers.to_string()))?;
+            if buf.len() < body_end {
+                buf.reserve((body_end - buf.len()).min(CONTENT_LENGTH_RESERVE_CHUNK));
+                return Ok(None);
+            }
+            buf.advance(body_start);
+            let body = buf.split_to(length);
+            if let Some(item) = self.parse_line(&body, "decode")? {
+                return Ok(Some(item));
+            }
+        }
+    }
+
+    /// Check the limits and the envelope according to `parse_mode`, then parse the message
+    fn parse_line(&self, line: &[u8], context: &str) -> Result<Option<T>, JsonRpcMessageCodecError>
+    where
+        T: DeserializeOwned,
+    {
+        self.json_limits.check(line)?;
+        // a conforming message is parsed right away, the others need their envelope checked
+        if self.parse_mode == JsonRpcParseMode::Strict {
+            if let Ok(item) = serde_json::from_slice::<T>(line) {
+                return Ok(Some(item));
+            }
+        }
+        let json_value = match serde_json::from_slice::<serde_json::Value>(line) {
+            Ok(json_value @ (serde_json::Value::Object(_) | serde_json::Value::Array(_))) => {
+                json_value
+            }
+            _ if self.stray_output == StrayOutput::Warn => {
+                let line = String::from_utf8_lossy(line);
+                if !line.trim().is_empty() {
+                    tracing::warn!(%line, "skipping a line which isn't a json-rpc message");
+                }
+                return Ok(None);
+            }
+            result => result?,
+        };
+        parse_value(json_value, line, context, self.parse_mode)
+    }
 }
 
 fn without_carriage_return(s: &[u8]) -> &[u8] {
@@ -242,48 +444,78 @@ fn should_ignore_notification(json_value: &serde_json::Value, method: &str) -> b
     )
 }
 
-/// Try to parse a message with compatibility handling for non-standard notifications
+/// Try to parse a message with compatibility handling for non-standard notifications, without checking the envelope
+#[cfg(test)]
 fn try_parse_with_compatibility<T: serde::de::DeserializeOwned>(
     line: &[u8],
     context: &str,
 ) -> Result<Option<T>, JsonRpcMessageCodecError> {
-    if let Ok(line_str) = std::str::from_utf8(line) {
-        match serde_json::from_slice(line) {
-            Ok(item) => Ok(Some(item)),
-            Err(e) => {
-                // Check if this is a notification that should be ignored for compatibility
-                if let Ok(json_value) = serde_json::from_str::<serde_json::Value>(line_str) {
-                    if let Some(method) =
-                        json_value.get("method").and_then(serde_json::Value::as_str)
-                    {
-                        if should_ignore_notification(&json_value, method) {
-                            return Ok(None);
-                        }
-                    }
-                }
+    let json_value = serde_json::from_slice::<serde_json::Value>(line)?;
+    parse_value_with_compatibility(json_value, line, context)
+}
 
-                tracing::debug!(
-                    "Failed to parse message {}: {} | Error: {}",
-                    context,
-                    line_str,
-                    e
-                );
-                Err(JsonRpcMessageCodecError::Serde(e))
+fn parse_value_with_compatibility<T: serde::de::DeserializeOwned>(
+    json_value: serde_json::Value,
+    line: &[u8],
+    context: &str,
+) -> Result<Option<T>, JsonRpcMessageCodecError> {
+    match T::deserialize(&json_value) {
+        Ok(item) => Ok(Some(item)),
+        Err(e) => {
+            // Check if this is a notification that should be ignored for compatibility
+            if is_ignored_notification(&json_value) {
+                return Ok(None);
             }
+
+            tracing::debug!(
+                "Failed to parse message {}: {} | Error: {}",
+                context,
+                String::from_utf8_lossy(line),
+                e
+            );
+            Err(JsonRpcMessageCodecError::Serde(e))
         }
-    } else {
-        serde_json::from_slice(line)
-            .map(Some)
-            .map_err(JsonRpcMessageCodecError::Serde)
     }
 }
 
+fn is_ignored_notification(json_value: &serde_json::Value) -> bool {
+    json_value
+        .get("method")
+        .and_then(serde_json::Value::as_str)
+        .is_some_and(|method| should_ignore_notification(json_value, method))
+}
+
+fn parse_value<T: serde::de::DeserializeOwned>(
+    mut json_value: serde_json::Value,
+    line: &[u8],
+    context: &str,
+    parse_mode: JsonRpcParseMode,
+) -> Result<Option<T>, JsonRpcMessageCodecError> {
+    if let Err(e) = parse_mode.check(&mut json_value) {
+        if is_ignored_notification(&json_value) {
+            return Ok(None);
+        }
+        tracing::debug!(
+            "Invalid envelope of message {}: {} | Error: {}",
+            context,
+            String::from_utf8_lossy(line),
+            e
+        );
+        return Err(JsonRpcMessageCodecError::Envelope(e));
+    }
+    parse_value_with_compatibility(json_value, line, context)
+}
+
 #[derive(Debug, Error)]
 pub enum JsonRpcMessageCodecError {
     #[error("max line length exceeded")]
     MaxLineLengthExceeded,
     #[error("serde error {0}")]
     Serde(#[from] serde_json::Error),
+    #[error("invalid json-rpc envelope: {0}")]
+    Envelope(#[from] JsonRpcEnvelopeError),
+    #[error("invalid Content-Length header: {0}")]
+    InvalidHeader(String),
     #[error("io error {0}")]
     Io(#[from] std::io::Error),
 }
@@ -295,6 +527,9 @@ impl From<JsonRpcMessageCodecError> for std::io::Error {
                 std::io::Error::new(std::io::ErrorKind::InvalidData, value)
             }
             JsonRpcMessageCodecError::Serde(e) => e.into(),
+            JsonRpcMessageCodecError::Envelope(_) | JsonRpcMessageCodecError::InvalidHeader(_) => {
+                std::io::Error::new(std::io::ErrorKind::InvalidData, value)
+            }
             JsonRpcMessageCodecError::Io(e) => e,
         }
     }
@@ -309,10 +544,14 @@ impl<T: DeserializeOwned> Decoder for JsonRpcMessageCodec<T> {
         &mut self,
         buf: &mut BytesMut,
     ) -> Result<Option<Self::Item>, JsonRpcMessageCodecError> {
+        if self.framing == Framing::ContentLength {
+            return self.decode_content_length(buf);
+        }
+        let max_length = self.max_frame_length();
         loop {
             // Determine how far into the buffer we'll search for a newline. If
             // there's no max_length set, we'll read to the end of the buffer.
-            let read_to = std::cmp::min(self.max_length.saturating_add(1), buf.len());
+            let read_to = std::cmp::min(max_length.saturating_add(1), buf.len());
 
             let newline_offset = buf[self.next_index..read_to]
                 .iter()
@@ -345,14 +584,13 @@ impl<T: DeserializeOwned> Decoder for JsonRpcMessageCodec<T> {
                     let line = &line[..line.len() - 1];
                     let line = without_carriage_return(line);
 
-                    // Use compatibility handling function
-                    let item = match try_parse_with_compatibility(line, "decode")? {
-                        Some(item) => item,
-                        None => return Ok(None), // Skip non-standard message
-                    };
-                    return Ok(Some(item));
+                    // Use compatibility handling function, and go on with the next line
+                    // when this one is skipped, it may already be in the buffer
+                    if let Some(item) = self.parse_line(line, "decode")? {
+                        return Ok(Some(item));
+                    }
                 }
-                (false, None) if buf.len() > self.max_length => {
+                (false, None) if buf.len() > max_length => {
                     // Reached the maximum length without finding a
                     // newline, return an error and start discarding on the
                     // next call.
@@ -370,6 +608,17 @@ impl<T: DeserializeOwned> Decoder for JsonRpcMessageCodec<T> {
     }
 
     fn decode_eof(&mut self, buf: &mut BytesMut) -> Result<Option<T>, JsonRpcMessageCodecError> {
+        if self.framing == Framing::ContentLength {
+            let item = self.decode_content_length(buf)?;
+            if item.is_none() && !buf.is_empty() {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::UnexpectedEof,
+                    "the stream ends in the middle of a message",
+                )
+                .into());
+            }
+            return Ok(item);
+        }
         Ok(match self.decode(buf)? {
             Some(frame) => Some(frame),
             None => {
@@ -382,7 +631,7 @@ impl<T: DeserializeOwned> Decoder for JsonRpcMessageCodec<T> {
                     let line = without_carriage_return(&line);
 
                     // Use compatibility handling function
-                    let item = match try_parse_with_compatibility(line, "decode_eof")? {
+                    let item = match self.parse_line(line, "decode_eof")? {
                         Some(item) => item,
                         None => return Ok(None), // Skip non-standard message
                     };
@@ -397,8 +646,17 @@ impl<T: Serialize> Encoder<T> for JsonRpcMessageCodec<T> {
     type Error = JsonRpcMessageCodecError;
 
     fn encode(&mut self, item: T, buf: &mut BytesMut) -> Result<(), JsonRpcMessageCodecError> {
-        serde_json::to_writer(buf.writer(), &item)?;
-        buf.put_u8(b'\n');
+        match self.framing {
+            Framing::NewlineDelimited => {
+                serde_json::to_writer(buf.writer(), &item)?;
+                buf.put_u8(b'\n');
+            }
+            Framing::ContentLength => {
+                let body = serde_json::to_vec(&item)?;
+                buf.put_slice(format!("Content-Length: {}\r\n\r\n", body.len()).as_bytes());
+                buf.put_slice(&body);
+            }
+        }
         Ok(())
     }
 }
@@ -554,4 +812,195 @@ mod test {
 
         println!("Standard notifications are preserved, non-standard are handled gracefully");
     }
+
+    fn decode_line<T: DeserializeOwned>(
+        parse_mode: JsonRpcParseMode,
+        line: &str,
+    ) -> Result<Option<T>, JsonRpcMessageCodecError> {
+        let mut codec = JsonRpcMessageCodec::<T>::new().with_parse_mode(parse_mode);
+        let mut buf = BytesMut::from(format!("{line}\n").as_str());
+        codec.decode(&mut buf)
+    }
+
+    #[test]
+    fn test_strict_mode_rejects_malformed_envelope() {
+        use crate::model::ClientJsonRpcMessage;
+
+        let missing_version = r#"{"id":1,"method":"ping"}"#;
+        let result = decode_line::<ClientJsonRpcMessage>(JsonRpcParseMode::Strict, missing_version);
+        assert!(matches!(
+            result,
+            Err(JsonRpcMessageCodecError::Envelope(
+                JsonRpcEnvelopeError::MissingVersion
+            ))
+        ));
+
+        let wrong_version = r#"{"jsonrpc":"1.0","id":1,"method":"ping"}"#;
+        let result = decode_line::<ClientJsonRpcMessage>(JsonRpcParseMode::Strict, wrong_version);
+        assert!(matches!(
+            result,
+            Err(JsonRpcMessageCodecError::Envelope(
+                JsonRpcEnvelopeError::UnsupportedVersion(_)
+            ))
+        ));
+
+        let float_id = r#"{"jsonrpc":"2.0","id":1.5,"method":"ping"}"#;
+        let result = decode_line::<ClientJsonRpcMessage>(JsonRpcParseMode::Strict, float_id);
+        assert!(matches!(
+            result,
+            Err(JsonRpcMessageCodecError::Envelope(
+                JsonRpcEnvelopeError::InvalidId(_)
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_lenient_mode_fixes_malformed_envelope() {
+        use crate::model::{ClientJsonRpcMessage, ClientRequest, JsonRpcMessage, NumberOrString};
+
+        let missing_version = r#"{"id":1,"method":"ping","params":null}"#;
+        let message =
+            decode_line::<ClientJsonRpcMessage>(JsonRpcParseMode::Lenient, missing_version)
+                .expect("lenient parse")
+                .expect("message");
+        let JsonRpcMessage::Request(request) = message else {
+            panic!("expected a request, got {message:?}");
+        };
+        assert!(matches!(request.request, ClientRequest::PingRequest(_)));
+
+        // a response to our request 7, with a string id
+        let string_id = r#"{"jsonrpc":"2.0","id":"7","result":{}}"#;
+        let message = decode_line::<ClientJsonRpcMessage>(JsonRpcParseMode::Lenient, string_id)
+            .expect("lenient parse")
+            .expect("message");
+        let JsonRpcMessage::Response(response) = message else {
+            panic!("expected a response, got {message:?}");
+        };
+        assert_eq!(response.id, NumberOrString::Number(7));
+
+        // still rejected, there's no way to fix it
+        let null_id = r#"{"jsonrpc":"2.0","id":null,"method":"ping"}"#;
+        let result = decode_line::<ClientJsonRpcMessage>(JsonRpcParseMode::Lenient, null_id);
+        assert!(matches!(
+            result,
+            Err(JsonRpcMessageCodecError::Envelope(
+                JsonRpcEnvelopeError::InvalidId(_)
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_decode_content_length_framing() {
+        use crate::model::ClientJsonRpcMessage;
+
+        let ping = r#"{"jsonrpc":"2.0","id":1,"method":"ping"}"#;
+        let list = r#"{"jsonrpc":"2.0","id":2,"method":"tools/list"}"#;
+        let data = format!(
+            "Content-Length: {}\r\n\r\n{ping}content-length:{}\r\nContent-Type: application/json\r\n\r\n{list}",
+            ping.len(),
+            list.len()
+        );
+        let mut codec =
+            JsonRpcMessageCodec::<ClientJsonRpcMessage>::new().with_framing(Framing::ContentLength);
+        // a message split across reads is decoded once complete
+        let (head, tail) = data.split_at(30);
+        let mut buf = BytesMut::from(head);
+        assert!(codec.decode(&mut buf).expect("decode").is_none());
+        buf.extend_from_slice(tail.as_bytes());
+        for id in 1..=2 {
+            let Some(ClientJsonRpcMessage::Request(request)) =
+                codec.decode(&mut buf).expect("decode")
+            else {
+                panic!("expect request {id}");
+            };
+            assert_eq!(request.id, crate::model::NumberOrString::Number(id));
+        }
+        assert!(buf.is_empty());
+
+        let mut buf =
+            BytesMut::from(format!("Content-Type: application/json\r\n\r\n{ping}").as_str());
+        assert!(matches!(
+            codec.decode(&mut buf),
+            Err(JsonRpcMessageCodecError::InvalidHeader(_))
+        ));
+    }
+
+    #[test]
+    fn test_decode_content_length_untrusted_length() {
+        use crate::model::{ClientJsonRpcMessage, JsonLimits};
+
+        let mut codec =
+            JsonRpcMessageCodec::<ClientJsonRpcMessage>::new().with_framing(Framing::ContentLength);
+        // a huge length neither overflows nor reserves it all upfront
+        let mut buf = BytesMut::from(format!("Content-Length: {}\r\n\r\n{{", usize::MAX).as_str());
+        assert!(matches!(
+            codec.decode(&mut buf),
+            Err(JsonRpcMessageCodecError::InvalidHeader(_))
+        ));
+        let mut buf =
+            BytesMut::from(format!("Content-Length: {}\r\n\r\n{{", usize::MAX / 2).as_str());
+        assert!(codec.decode(&mut buf).expect("decode").is_none());
+        assert!(buf.capacity() <= 2 * CONTENT_LENGTH_RESERVE_CHUNK);
+
+        // a length over the size limit is rejected before the body is read
+        let mut codec = codec.with_json_limits(JsonLimits {
+            max_size: Some(1024),
+            ..Default::default()
+        });
+        let mut buf = BytesMut::from("Content-Length: 1025\r\n\r\n{");
+        assert!(matches!(
+            codec.decode(&mut buf),
+            Err(JsonRpcMessageCodecError::MaxLineLengthExceeded)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_both_framings_round_trip() {
+        use crate::model::{ClientJsonRpcMessage, ClientRequest, PingRequest};
+
+        for framing in [Framing::NewlineDelimited, Framing::ContentLength] {
+            let message = ClientJsonRpcMessage::request(
+                ClientRequest::PingRequest(PingRequest::default()),
+                crate::model::NumberOrString::Number(1),
+            );
+            let mut buf = BytesMut::new();
+            let mut codec = JsonRpcMessageCodec::new().with_framing(framing);
+            codec.encode(message.clone(), &mut buf).expect("encode");
+            codec.encode(message, &mut buf).expect("encode");
+            let expected_header = framing == Framing::ContentLength;
+            assert_eq!(buf.starts_with(b"Content-Length: "), expected_header);
+
+            let mut stream = FramedRead::new(
+                &buf[..],
+                JsonRpcMessageCodec::<ClientJsonRpcMessage>::new().with_framing(framing),
+            );
+            for _ in 0..2 {
+                let message = stream.next().await.expect("message").expect("decode");
+                assert!(matches!(message, ClientJsonRpcMessage::Request(_)));
+            }
+            assert!(stream.next().await.is_none());
+        }
+    }
+
+    #[test]
+    fn test_stray_output_is_skipped() {
+        use crate::model::ClientJsonRpcMessage;
+
+        let data =
+            "Starting cinema server...\n\n42\n{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"ping\"}\n";
+        let mut codec = JsonRpcMessageCodec::<ClientJsonRpcMessage>::new();
+        let mut buf = BytesMut::from(data);
+        // the message behind the stray lines is decoded right away
+        let message = codec.decode(&mut buf).expect("decode").expect("message");
+        assert!(matches!(message, ClientJsonRpcMessage::Request(_)));
+        assert!(buf.is_empty());
+
+        let mut codec = JsonRpcMessageCodec::<ClientJsonRpcMessage>::new()
+            .with_stray_output(StrayOutput::Error);
+        let mut buf = BytesMut::from(data);
+        assert!(matches!(
+            codec.decode(&mut buf),
+            Err(JsonRpcMessageCodecError::Serde(_))
+        ));
+    }
 }
@@ -0,0 +1,181 @@
+//! Pick the transport of a server when it starts, so the same binary can be launched by a host
+//! over stdio or deployed behind HTTP.
+//!
+//! The transport is read from the `--transport` flag, or the `MCP_TRANSPORT` environment variable
+//! when the flag is absent, and defaults to stdio:
+//!
+//! | value                       | transport                                    |
+//! | :-------------------------- | :------------------------------------------- |
+//! | `stdio`                     | stdin and stdout                             |
+//! | `sse`                       | SSE on `/sse`, messages posted to `/message` |
+//! | `streamable-http` or `http` | streamable HTTP on `/mcp`                    |
+//!
+//! The HTTP transports listen on the `--bind` flag, or `MCP_BIND`, `127.0.0.1:8000` by default.
+//!
+//! ```rust,ignore
+//! #[tokio::main]
+//! async fn main() -> anyhow::Result<()> {
+//!     rmcp::serve_auto(Counter::new).await?;
+//!     Ok(())
+//! }
+//! ```
+//!
+//! ```sh
+//! MCP_TRANSPORT=sse MCP_BIND=0.0.0.0:9000 ./counter
+//! ./counter --transport streamable-http
+//! ```
+use std::net::SocketAddr;
+
+use thiserror::Error;
+use tokio_util::sync::CancellationToken;
+
+use crate::{
+    RoleServer, Service, ServiceExt,
+    service::{ServerInitializeError, Shutdown},
+    transport::{
+        SseServer, StreamableHttpService, stdio,
+        streamable_http_server::session::local::LocalSessionManager,
+    },
+};
+
+/// The transport a server is served over by [`serve_auto`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AutoTransport {
+    Stdio,
+    Sse { bind: SocketAddr },
+    StreamableHttp { bind: SocketAddr },
+}
+
+#[derive(Debug, Error)]
+pub enum AutoTransportError {
+    #[error("unknown transport {0:?}, expected stdio, sse or streamable-http")]
+    UnknownTransport(String),
+    #[error("invalid bind address {0:?}")]
+    InvalidBind(String, #[source] std::net::AddrParseError),
+    #[error("missing the value of {0}")]
+    MissingValue(&'static str),
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("initialize error: {0}")]
+    Initialize(#[from] Box<ServerInitializeError>),
+    #[error("join error: {0}")]
+    Join(#[from] tokio::task::JoinError),
+}
+
+impl AutoTransport {
+    /// The environment variable selecting the transport
+    pub const TRANSPORT_ENV: &str = "MCP_TRANSPORT";
+    /// The environment variable holding the address the HTTP transports listen on
+    pub const BIND_ENV: &str = "MCP_BIND";
+    pub const DEFAULT_BIND: &str = "127.0.0.1:8000";
+    /// The path of the streamable HTTP endpoint
+    pub const STREAMABLE_HTTP_PATH: &str = "/mcp";
+
+    /// Select the transport from the arguments and the environment of the process
+    pub fn from_env() -> Result<Self, AutoTransportError> {
+        Self::select(std::env::args().skip(1), |key| std::env::var(key).ok())
+    }
+
+    /// Select the transport from `args`, without the program name, falling back to `env`.
+    ///
+    /// The flags win over the environment variables, the other arguments are ignored.
+    pub fn select<I, E>(args: I, env: E) -> Result<Self, AutoTransportError>
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+        E: Fn(&str) -> Option<String>,
+    {
+        let mut transport = None;
+        let mut bind = None;
+        let mut args = args.into_iter().map(Into::into);
+        while let Some(arg) = args.next() {
+            for (flag, value) in [("--transport", &mut transport), ("--bind", &mut bind)] {
+                if arg == flag {
+                    *value = Some(args.next().ok_or(AutoTransportError::MissingValue(flag))?);
+                } else if let Some(inline) = arg
+                    .strip_prefix(flag)
+                    .and_then(|rest| rest.strip_prefix('='))
+                {
+                    *value = Some(inline.to_string());
+                }
+            }
+        }
+        let transport = transport.or_else(|| env(Self::TRANSPORT_ENV));
+        let bind_addr = || {
+            let bind = bind
+                .clone()
+                .or_else(|| env(Self::BIND_ENV))
+                .unwrap_or_else(|| Self::DEFAULT_BIND.to_string());
+            bind.parse()
+                .map_err(|error| AutoTransportError::InvalidBind(bind, error))
+        };
+        match transport.as_deref().map(str::trim) {
+            None | Some("") => Ok(Self::Stdio),
+            Some(kind) => match kind.to_ascii_lowercase().as_str() {
+                "stdio" => Ok(Self::Stdio),
+                "sse" => Ok(Self::Sse { bind: bind_addr()? }),
+                "streamable-http" | "http" => Ok(Self::StreamableHttp { bind: bind_addr()? }),
+                _ => Err(AutoTransportError::UnknownTransport(kind.to_string())),
+            },
+        }
+    }
+
+    /// Serve the services made by `service_provider` over this transport, until `ct` is
+    /// cancelled, or the client quits for stdio.
+    ///
+    /// Stdio serves a single service, the HTTP transports one per session.
+    pub async fn serve<S, F>(
+        self,
+        service_provider: F,
+        ct: CancellationToken,
+    ) -> Result<(), AutoTransportError>
+    where
+        S: Service<RoleServer> + Send + 'static,
+        F: Fn() -> S + Send + Sync + 'static,
+    {
+        match self {
+            Self::Stdio => {
+                let server = service_provider()
+                    .serve_with_ct(stdio(), ct)
+                    .await
+                    .map_err(Box::new)?;
+                server.waiting().await?;
+            }
+            Self::Sse { bind } => {
+                let sse_ct = SseServer::serve(bind).await?.with_service(service_provider);
+                tracing::info!(%bind, "serving over SSE");
+                ct.cancelled().await;
+                sse_ct.cancel();
+            }
+            Self::StreamableHttp { bind } => {
+                let service = StreamableHttpService::new(
+                    move || Ok(service_provider()),
+                    LocalSessionManager::default().into(),
+                    Default::default(),
+                );
+                let router = axum::Router::new().nest_service(Self::STREAMABLE_HTTP_PATH, service);
+                let listener = tokio::net::TcpListener::bind(bind).await?;
+                tracing::info!(%bind, "serving over streamable HTTP");
+                axum::serve(listener, router)
+                    .with_graceful_shutdown(ct.cancelled_owned())
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Serve the services made by `service_provider` over the transport selected by the
+/// `--transport` flag or the `MCP_TRANSPORT` environment variable, see [`AutoTransport`].
+///
+/// It returns when the client quits for stdio, on ctrl-c for the HTTP transports.
+pub async fn serve_auto<S, F>(service_provider: F) -> Result<(), AutoTransportError>
+where
+    S: Service<RoleServer> + Send + 'static,
+    F: Fn() -> S + Send + Sync + 'static,
+{
+    let shutdown = Shutdown::new().with_ctrl_c();
+    AutoTransport::from_env()?
+        .serve(service_provider, shutdown.cancellation_token())
+        .await
+}
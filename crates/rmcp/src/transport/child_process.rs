@@ -7,7 +7,10 @@ use tokio::{
     process::{ChildStderr, ChildStdin, ChildStdout},
 };
 
-use super::{RxJsonRpcMessage, Transport, TxJsonRpcMessage, async_rw::AsyncRwTransport};
+use super::{
+    RxJsonRpcMessage, Transport, TxJsonRpcMessage,
+    async_rw::{AsyncRwTransport, Framing},
+};
 use crate::RoleClient;
 
 const MAX_WAIT_ON_DROP_SECS: u64 = 3;
@@ -156,6 +159,7 @@ pub struct TokioChildProcessBuilder {
     stdin: Stdio,
     stdout: Stdio,
     stderr: Stdio,
+    framing: Framing,
 }
 
 impl TokioChildProcessBuilder {
@@ -165,6 +169,7 @@ impl TokioChildProcessBuilder {
             stdin: Stdio::piped(),
             stdout: Stdio::piped(),
             stderr: Stdio::inherit(),
+            framing: Framing::default(),
         }
     }
 
@@ -184,6 +189,12 @@ impl TokioChildProcessBuilder {
         self
     }
 
+    /// How the messages are delimited, for the servers expecting `Content-Length` headers.
+    pub fn framing(mut self, framing: Framing) -> Self {
+        self.framing = framing;
+        self
+    }
+
     /// Spawn the child process. Returns the transport plus an optional captured stderr handle.
     pub fn spawn(mut self) -> std::io::Result<(TokioChildProcess, Option<ChildStderr>)> {
         self.cmd
@@ -194,7 +205,7 @@ impl TokioChildProcessBuilder {
 
         let (child, stdout, stdin, stderr_opt) = child_process(self.cmd.spawn()?)?;
 
-        let transport = AsyncRwTransport::new(stdout, stdin);
+        let transport = AsyncRwTransport::new(stdout, stdin).with_framing(self.framing);
         let proc = TokioChildProcess {
             child: ChildWithCleanup { inner: Some(child) },
             transport,
@@ -1,6 +1,7 @@
 #[cfg(any(
     feature = "transport-streamable-http-server",
-    feature = "transport-sse-server"
+    feature = "transport-sse-server",
+    feature = "transport-sse-server-hyper"
 ))]
 pub mod server_side_http;
 
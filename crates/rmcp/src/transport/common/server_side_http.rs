@@ -1,14 +1,25 @@
 #![allow(dead_code)]
-use std::{convert::Infallible, fmt::Display, sync::Arc, time::Duration};
+use std::{
+    convert::Infallible,
+    fmt::Display,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
 
-use bytes::{Buf, Bytes};
+use bytes::{Buf, BufMut, Bytes};
 use http::Response;
 use http_body::Body;
 use http_body_util::{BodyExt, Empty, Full, combinators::BoxBody};
 use sse_stream::{KeepAlive, Sse, SseBody};
 
-use super::http_header::EVENT_STREAM_MIME_TYPE;
-use crate::model::{ClientJsonRpcMessage, ServerJsonRpcMessage};
+use super::http_header::{EVENT_STREAM_MIME_TYPE, HEADER_SESSION_ID, JSON_MIME_TYPE};
+use crate::model::{
+    ClientJsonRpcMessage, ErrorData, JsonLimits, JsonRpcEnvelopeError, JsonRpcParseMode,
+    ServerJsonRpcMessage,
+};
 
 pub type SessionId = Arc<str>;
 
@@ -20,6 +31,228 @@ pub const DEFAULT_AUTO_PING_INTERVAL: Duration = Duration::from_secs(15);
 
 pub(crate) type BoxResponse = Response<BoxBody<Bytes, Infallible>>;
 
+/// The health check route of an HTTP server, for load balancers.
+///
+/// A `GET` on `path` answers `200 OK` while the server accepts connections, and
+/// `503 Service Unavailable` once it is draining or shutting down. Draining doesn't close
+/// anything: the open sessions are served as usual, it only tells the load balancer to send the
+/// new clients elsewhere.
+///
+/// Clones share the drain state, so keep one to call [`HealthCheck::drain`] after handing the
+/// config to the server.
+#[derive(Debug, Clone)]
+pub struct HealthCheck {
+    /// The path of the route, it must not collide with the MCP endpoints
+    pub path: String,
+    draining: Arc<AtomicBool>,
+}
+
+impl HealthCheck {
+    pub const DEFAULT_PATH: &str = "/healthz";
+
+    pub fn new(path: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            draining: Default::default(),
+        }
+    }
+
+    /// Start answering `503 Service Unavailable`, this can't be undone.
+    pub fn drain(&self) {
+        self.draining.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_draining(&self) -> bool {
+        self.draining.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn response(&self, shutting_down: bool) -> BoxResponse {
+        let (status, body) = if shutting_down || self.is_draining() {
+            (http::StatusCode::SERVICE_UNAVAILABLE, "draining")
+        } else {
+            (http::StatusCode::OK, "ok")
+        };
+        Response::builder()
+            .status(status)
+            .header(http::header::CACHE_CONTROL, "no-cache")
+            .body(Full::new(Bytes::from(body)).boxed())
+            .expect("valid response")
+    }
+}
+
+impl Default for HealthCheck {
+    /// Serve on [`HealthCheck::DEFAULT_PATH`]
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_PATH)
+    }
+}
+
+/// The CORS policy of an HTTP server, for MCP clients running in a browser.
+///
+/// A preflight `OPTIONS` request from an allowed origin is answered `204 No Content` with the
+/// allowed methods and headers, and from any other origin `403 Forbidden`. The other responses
+/// carry `Access-Control-Allow-Origin` only for an allowed origin, so browsers refuse to hand them
+/// to the pages of any other origin.
+///
+/// The default allows no origin at all: list them with [`Cors::new`], or allow any of them with
+/// [`Cors::permissive`]. Credentials are never allowed unless `allow_credentials` is set, and
+/// only for the origins listed explicitly, see [`Cors::validate`].
+#[derive(Debug, Clone)]
+pub struct Cors {
+    /// The allowed origins, e.g. `https://movie.example.com`, [`Cors::ANY_ORIGIN`] allows any of them
+    pub allowed_origins: Vec<String>,
+    pub allowed_methods: Vec<http::Method>,
+    /// The request headers the browser may send, beside the ones always allowed by the spec
+    pub allowed_headers: Vec<http::HeaderName>,
+    /// Whether the browser may send cookies and the `Authorization` header of the page.
+    ///
+    /// Never for the origins only allowed by [`Cors::ANY_ORIGIN`], or any web page could call the
+    /// server on behalf of its visitors.
+    pub allow_credentials: bool,
+    /// How long the browser may cache the answer to a preflight request
+    pub max_age: Option<Duration>,
+}
+
+impl Cors {
+    pub const ANY_ORIGIN: &str = "*";
+
+    /// Allow the given origins, with the default methods and headers
+    pub fn new(allowed_origins: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self {
+            allowed_origins: allowed_origins.into_iter().map(Into::into).collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Allow any origin, without credentials
+    pub fn permissive() -> Self {
+        Self::new([Self::ANY_ORIGIN])
+    }
+
+    /// Reject the policy allowing credentials for [`Cors::ANY_ORIGIN`].
+    ///
+    /// The servers refuse to start with such a policy, and the ones built without starting them
+    /// don't send credentials to the origins matched by the wildcard.
+    pub fn validate(&self) -> Result<(), InvalidCors> {
+        if self.allow_credentials
+            && self
+                .allowed_origins
+                .iter()
+                .any(|allowed| allowed == Self::ANY_ORIGIN)
+        {
+            return Err(InvalidCors::CredentialsForAnyOrigin);
+        }
+        Ok(())
+    }
+
+    fn allows(&self, origin: &http::HeaderValue) -> bool {
+        self.allowed_origins
+            .iter()
+            .any(|allowed| allowed == Self::ANY_ORIGIN || origin == allowed.as_str())
+    }
+
+    fn allows_credentials(&self, origin: &http::HeaderValue) -> bool {
+        self.allow_credentials
+            && self
+                .allowed_origins
+                .iter()
+                .any(|allowed| origin == allowed.as_str())
+    }
+
+    /// Answer the request if it's a preflight request
+    pub(crate) fn preflight<B>(&self, request: &http::Request<B>) -> Option<BoxResponse> {
+        let headers = request.headers();
+        if request.method() != http::Method::OPTIONS
+            || !headers.contains_key(http::header::ACCESS_CONTROL_REQUEST_METHOD)
+        {
+            return None;
+        }
+        let origin = headers.get(http::header::ORIGIN)?;
+        if !self.allows(origin) {
+            return Some(
+                Response::builder()
+                    .status(http::StatusCode::FORBIDDEN)
+                    .body(Full::new(Bytes::from("CORS origin not allowed")).boxed())
+                    .expect("valid response"),
+            );
+        }
+        let join = |values: Vec<&str>| values.join(", ");
+        let mut response = Response::builder()
+            .status(http::StatusCode::NO_CONTENT)
+            .header(
+                http::header::ACCESS_CONTROL_ALLOW_METHODS,
+                join(
+                    self.allowed_methods
+                        .iter()
+                        .map(http::Method::as_str)
+                        .collect(),
+                ),
+            )
+            .header(
+                http::header::ACCESS_CONTROL_ALLOW_HEADERS,
+                join(
+                    self.allowed_headers
+                        .iter()
+                        .map(http::HeaderName::as_str)
+                        .collect(),
+                ),
+            );
+        if let Some(max_age) = self.max_age {
+            response = response.header(http::header::ACCESS_CONTROL_MAX_AGE, max_age.as_secs());
+        }
+        let mut response = response.body(Empty::new().boxed()).expect("valid response");
+        self.apply(Some(origin), &mut response);
+        Some(response)
+    }
+
+    /// Add the CORS headers to the response to a request from `origin`
+    pub(crate) fn apply<B>(&self, origin: Option<&http::HeaderValue>, response: &mut Response<B>) {
+        let headers = response.headers_mut();
+        headers.append(http::header::VARY, http::HeaderValue::from_static("origin"));
+        let Some(origin) = origin.filter(|origin| self.allows(origin)) else {
+            return;
+        };
+        headers.insert(http::header::ACCESS_CONTROL_ALLOW_ORIGIN, origin.clone());
+        headers.insert(
+            http::header::ACCESS_CONTROL_EXPOSE_HEADERS,
+            http::HeaderValue::from_static(HEADER_SESSION_ID),
+        );
+        if self.allows_credentials(origin) {
+            headers.insert(
+                http::header::ACCESS_CONTROL_ALLOW_CREDENTIALS,
+                http::HeaderValue::from_static("true"),
+            );
+        }
+    }
+}
+
+/// A [`Cors`] policy rejected by [`Cors::validate`]
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum InvalidCors {
+    #[error("CORS credentials can't be allowed for any origin, list the allowed origins instead")]
+    CredentialsForAnyOrigin,
+}
+
+impl Default for Cors {
+    /// No origin, the MCP methods and headers, and a preflight cached for 10 minutes
+    fn default() -> Self {
+        Self {
+            allowed_origins: Vec::new(),
+            allowed_methods: vec![http::Method::GET, http::Method::POST, http::Method::DELETE],
+            allowed_headers: vec![
+                http::header::CONTENT_TYPE,
+                http::header::AUTHORIZATION,
+                http::HeaderName::from_static("mcp-session-id"),
+                http::HeaderName::from_static("mcp-protocol-version"),
+                http::HeaderName::from_static("last-event-id"),
+            ],
+            allow_credentials: false,
+            max_age: Some(Duration::from_secs(600)),
+        }
+    }
+}
+
 pub(crate) fn accepted_response() -> Response<BoxBody<Bytes, Infallible>> {
     Response::builder()
         .status(http::StatusCode::ACCEPTED)
@@ -27,7 +260,7 @@ pub(crate) fn accepted_response() -> Response<BoxBody<Bytes, Infallible>> {
         .expect("valid response")
 }
 pin_project_lite::pin_project! {
-    struct TokioTimer {
+    pub(crate) struct TokioTimer {
         #[pin]
         sleep: tokio::time::Sleep,
     }
@@ -111,35 +344,79 @@ pub(crate) fn unexpected_message_response(expect: &str) -> Response<BoxBody<Byte
         .expect("valid response")
 }
 
+/// A JSON-RPC parse error, with a `null` id as the request can't be identified
+pub(crate) fn parse_error_response(error: JsonRpcEnvelopeError) -> BoxResponse {
+    let body = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": null,
+        "error": ErrorData::parse_error(error.to_string(), None),
+    });
+    Response::builder()
+        .status(http::StatusCode::BAD_REQUEST)
+        .header(http::header::CONTENT_TYPE, JSON_MIME_TYPE)
+        .body(Full::new(Bytes::from(body.to_string())).boxed())
+        .expect("valid response")
+}
+
+/// Parse a message, once it's checked against the `limits`, checking its envelope according
+/// to `parse_mode`
+#[allow(clippy::result_large_err)]
+pub(crate) fn parse_json(
+    bytes: &[u8],
+    limits: &JsonLimits,
+    parse_mode: JsonRpcParseMode,
+) -> Result<ClientJsonRpcMessage, BoxResponse> {
+    limits.check(bytes).map_err(parse_error_response)?;
+    let deserialize_error = |e: serde_json::Error| {
+        Response::builder()
+            .status(http::StatusCode::UNSUPPORTED_MEDIA_TYPE)
+            .body(Full::new(Bytes::from(format!("fail to deserialize request body {e}"))).boxed())
+            .expect("valid response")
+    };
+    // a conforming message is parsed right away, the others need their envelope checked
+    if parse_mode == JsonRpcParseMode::Strict {
+        if let Ok(message) = serde_json::from_slice::<ClientJsonRpcMessage>(bytes) {
+            return Ok(message);
+        }
+    }
+    let mut value =
+        serde_json::from_slice::<serde_json::Value>(bytes).map_err(deserialize_error)?;
+    parse_mode.check(&mut value).map_err(parse_error_response)?;
+    serde_json::from_value::<ClientJsonRpcMessage>(value).map_err(deserialize_error)
+}
+
+/// Collect the body, up to the max size of the `limits`, and parse it, see [`parse_json`]
 pub(crate) async fn expect_json<B>(
     body: B,
+    limits: &JsonLimits,
+    parse_mode: JsonRpcParseMode,
 ) -> Result<ClientJsonRpcMessage, Response<BoxBody<Bytes, Infallible>>>
 where
     B: Body + Send + 'static,
     B::Error: Display,
 {
-    match body.collect().await {
-        Ok(bytes) => {
-            match serde_json::from_reader::<_, ClientJsonRpcMessage>(bytes.aggregate().reader()) {
-                Ok(message) => Ok(message),
-                Err(e) => {
-                    let response = Response::builder()
-                        .status(http::StatusCode::UNSUPPORTED_MEDIA_TYPE)
-                        .body(
-                            Full::new(Bytes::from(format!("fail to deserialize request body {e}")))
-                                .boxed(),
-                        )
-                        .expect("valid response");
-                    Err(response)
-                }
-            }
-        }
-        Err(e) => {
-            let response = Response::builder()
+    let mut body = std::pin::pin!(body);
+    let mut bytes = Vec::new();
+    while let Some(frame) = body.frame().await {
+        let frame = frame.map_err(|e| {
+            Response::builder()
                 .status(http::StatusCode::INTERNAL_SERVER_ERROR)
                 .body(Full::new(Bytes::from(format!("Failed to read request body: {e}"))).boxed())
-                .expect("valid response");
-            Err(response)
+                .expect("valid response")
+        })?;
+        let Ok(data) = frame.into_data() else {
+            continue;
+        };
+        // stop reading as soon as the body is too large
+        if let Some(max_size) = limits
+            .max_size
+            .filter(|max_size| bytes.len() + data.remaining() > *max_size)
+        {
+            return Err(parse_error_response(JsonRpcEnvelopeError::TooLarge {
+                max_size,
+            }));
         }
+        bytes.put(data);
     }
+    parse_json(&bytes, limits, parse_mode)
 }
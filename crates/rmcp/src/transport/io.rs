@@ -1,6 +1,18 @@
 /// # StdIO Transport
 ///
 /// Create a pair of [`tokio::io::Stdin`] and [`tokio::io::Stdout`].
+///
+/// Stdout carries the messages, so logs should go to stderr. A peer skips the lines which aren't
+/// JSON with a warning, see [`StrayOutput`](crate::transport::async_rw::StrayOutput).
+///
+/// The messages are newline-delimited as the MCP spec requires. For the hosts expecting
+/// LSP-style `Content-Length` headers instead, select the framing on the transport:
+///
+/// ```rust,ignore
+/// let (stdin, stdout) = stdio();
+/// let transport = AsyncRwTransport::new_server(stdin, stdout).with_framing(Framing::ContentLength);
+/// let server = service.serve(transport).await?;
+/// ```
 pub fn stdio() -> (tokio::io::Stdin, tokio::io::Stdout) {
     (tokio::io::stdin(), tokio::io::stdout())
 }
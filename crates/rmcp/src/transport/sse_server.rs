@@ -1,42 +1,255 @@
-use std::{collections::HashMap, io, net::SocketAddr, sync::Arc, time::Duration};
+use std::{
+    collections::{HashMap, VecDeque},
+    io,
+    net::SocketAddr,
+    sync::{Arc, PoisonError},
+    time::{Duration, SystemTime},
+};
 
+#[cfg(feature = "transport-sse-server")]
 use axum::{
-    Extension, Json, Router,
-    extract::{NestedPath, Query, State},
-    http::{StatusCode, request::Parts},
+    Extension, Router,
+    body::Bytes,
+    extract::{NestedPath, Query, Request, State},
+    middleware::Next,
     response::{
-        Response,
+        IntoResponse, Response,
         sse::{Event, KeepAlive, Sse},
     },
-    routing::{get, post},
+    routing::{Route, get, post},
 };
-use futures::{Sink, SinkExt, Stream};
+use futures::{Sink, SinkExt, Stream, StreamExt};
+use http::{StatusCode, request::Parts};
+#[cfg(feature = "transport-sse-server")]
+use std::convert::Infallible;
+use tokio::sync::OwnedMutexGuard;
 use tokio_stream::wrappers::ReceiverStream;
 use tokio_util::sync::{CancellationToken, PollSender};
+
+#[cfg(feature = "transport-sse-server")]
+use tower_layer::Layer;
+#[cfg(feature = "transport-sse-server")]
 use tracing::Instrument;
 
+#[cfg(feature = "transport-sse-server")]
+use crate::transport::common::{
+    http_header::HEADER_LAST_EVENT_ID,
+    server_side_http::{DEFAULT_AUTO_PING_INTERVAL, parse_json},
+};
 use crate::{
     RoleServer, Service,
-    model::ClientJsonRpcMessage,
+    model::{
+        ClientJsonRpcMessage, ClientNotification, JsonLimits, JsonRpcError, JsonRpcMessage,
+        JsonRpcNotification, JsonRpcParseMode, JsonRpcResponse,
+    },
     service::{RxJsonRpcMessage, TxJsonRpcMessage, serve_directly_with_ct},
-    transport::common::server_side_http::{DEFAULT_AUTO_PING_INTERVAL, SessionId, session_id},
+    transport::{
+        IntoTransport, TimeoutTransport, TransportTimeouts,
+        common::server_side_http::{Cors, HealthCheck, SessionId, session_id},
+        sink_stream,
+    },
 };
 
-type TxStore =
-    Arc<tokio::sync::RwLock<HashMap<SessionId, tokio::sync::mpsc::Sender<ClientJsonRpcMessage>>>>;
+#[cfg(feature = "transport-sse-server-hyper")]
+#[cfg_attr(docsrs, doc(cfg(feature = "transport-sse-server-hyper")))]
+pub mod hyper_server;
+#[cfg(feature = "transport-sse-server-hyper")]
+#[cfg_attr(docsrs, doc(cfg(feature = "transport-sse-server-hyper")))]
+pub use hyper_server::{HyperSseServer, SseService};
+
+type ToClient = ReceiverStream<TxJsonRpcMessage<RoleServer>>;
+/// `Sync` too, as hyper's boxed bodies require it
+type SessionStream = std::pin::Pin<Box<dyn Stream<Item = SessionEvent> + Send + Sync>>;
+
+#[derive(Debug)]
+struct SessionEntry {
+    tx: tokio::sync::mpsc::Sender<ClientJsonRpcMessage>,
+    connected_at: SystemTime,
+    initialized: bool,
+    /// Locked by the SSE stream currently connected to the session
+    to_client: Arc<tokio::sync::Mutex<ToClient>>,
+    replay: Arc<std::sync::Mutex<ReplayBuffer>>,
+}
+
+type TxStore = Arc<tokio::sync::RwLock<HashMap<SessionId, SessionEntry>>>;
 pub type TransportReceiver = ReceiverStream<RxJsonRpcMessage<RoleServer>>;
 
+/// An event of the SSE stream of a session, before it is converted by the axum or hyper front end
+#[derive(Debug, Clone)]
+struct SessionEvent {
+    id: Option<String>,
+    event: String,
+    data: String,
+}
+
+/// The most recent events sent to a session, retained for clients resuming with `Last-Event-ID`.
+///
+/// Event ids are `<session id>/<index>`, the `endpoint` event has index `0`.
+#[derive(Debug)]
+struct ReplayBuffer {
+    capacity: usize,
+    next_index: u64,
+    events: VecDeque<(u64, SessionEvent)>,
+    /// Bumped on every connection, so the cleanup of a dropped one spares a resumed session
+    connections: u64,
+}
+
+impl ReplayBuffer {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            next_index: 1,
+            events: VecDeque::with_capacity(capacity),
+            connections: 0,
+        }
+    }
+
+    fn event_id(&self, session: &SessionId, index: u64) -> Option<String> {
+        (self.capacity > 0).then(|| format!("{session}/{index}"))
+    }
+
+    /// Give the event its id and retain it, evicting the oldest one when full
+    fn record(&mut self, session: &SessionId, event: String, data: String) -> SessionEvent {
+        let index = self.next_index;
+        self.next_index += 1;
+        let event = SessionEvent {
+            id: self.event_id(session, index),
+            event,
+            data,
+        };
+        if self.capacity > 0 {
+            if self.events.len() == self.capacity {
+                self.events.pop_front();
+            }
+            self.events.push_back((index, event.clone()));
+        }
+        event
+    }
+
+    /// The retained events after `index`, all of them must still be in the buffer
+    fn replay_after(&self, index: u64) -> Result<Vec<SessionEvent>, SseStreamError> {
+        if index >= self.next_index {
+            return Err(SseStreamError::InvalidLastEventId);
+        }
+        let oldest = self
+            .events
+            .front()
+            .map_or(self.next_index, |(index, _)| *index);
+        if index + 1 < oldest {
+            return Err(SseStreamError::ReplayWindowExceeded);
+        }
+        Ok(self
+            .events
+            .iter()
+            .filter(|(i, _)| *i > index)
+            .map(|(_, event)| event.clone())
+            .collect())
+    }
+}
+
+/// Why a `GET` on the `sse_path` couldn't open a stream
+#[derive(Debug, thiserror::Error)]
+enum SseStreamError {
+    #[error("fail to send out transport, it seems server is closed")]
+    ServerClosed,
+    #[error("invalid Last-Event-ID")]
+    InvalidLastEventId,
+    #[error("session not found")]
+    SessionNotFound,
+    #[error("the session already has a connected sse stream")]
+    AlreadyConnected,
+    /// Some of the events after `Last-Event-ID` were already evicted, the client should initialize a new session
+    #[error("replay window exceeded")]
+    ReplayWindowExceeded,
+}
+
+impl SseStreamError {
+    fn status(&self) -> StatusCode {
+        match self {
+            SseStreamError::ServerClosed => StatusCode::INTERNAL_SERVER_ERROR,
+            SseStreamError::InvalidLastEventId => StatusCode::BAD_REQUEST,
+            SseStreamError::SessionNotFound => StatusCode::NOT_FOUND,
+            SseStreamError::AlreadyConnected => StatusCode::CONFLICT,
+            SseStreamError::ReplayWindowExceeded => StatusCode::GONE,
+        }
+    }
+}
+
+/// Removes the session once its SSE stream is dropped, unless it gets resumed within `grace`
+struct Detach {
+    txs: TxStore,
+    session: SessionId,
+    replay: Arc<std::sync::Mutex<ReplayBuffer>>,
+    connection: u64,
+    grace: Duration,
+}
+
+impl Detach {
+    /// Remove the session, unless it was resumed by another connection
+    fn remove(
+        txs: &mut HashMap<SessionId, SessionEntry>,
+        session: &SessionId,
+        replay: &Arc<std::sync::Mutex<ReplayBuffer>>,
+        connection: u64,
+    ) {
+        if replay
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .connections
+            != connection
+        {
+            return;
+        }
+        if txs
+            .get(session)
+            .is_some_and(|entry| Arc::ptr_eq(&entry.replay, replay))
+        {
+            txs.remove(session);
+            tracing::debug!(session_id = %session, "Closed session and cleaned up resources");
+        }
+    }
+}
+
+impl Drop for Detach {
+    fn drop(&mut self) {
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            // no runtime to wait for a resumption, don't leave the session behind
+            Self::remove(
+                &mut self.txs.blocking_write(),
+                &self.session,
+                &self.replay,
+                self.connection,
+            );
+            return;
+        };
+        let txs = self.txs.clone();
+        let session = self.session.clone();
+        let replay = self.replay.clone();
+        let connection = self.connection;
+        let grace = self.grace;
+        runtime.spawn(async move {
+            tokio::time::sleep(grace).await;
+            Self::remove(&mut *txs.write().await, &session, &replay, connection);
+        });
+    }
+}
+
 #[derive(Clone)]
 struct App {
     txs: TxStore,
     transport_tx: tokio::sync::mpsc::UnboundedSender<SseServerTransport>,
     post_path: Arc<str>,
     sse_ping_interval: Duration,
+    event_names: Arc<SseEventNames>,
+    replay_buffer_size: usize,
+    json_limits: JsonLimits,
+    parse_mode: JsonRpcParseMode,
 }
 
 impl App {
     pub fn new(
-        post_path: String,
+        txs: TxStore,
+        config: &SseServerConfig,
         sse_ping_interval: Duration,
     ) -> (
         Self,
@@ -45,14 +258,228 @@ impl App {
         let (transport_tx, transport_rx) = tokio::sync::mpsc::unbounded_channel();
         (
             Self {
-                txs: Default::default(),
+                txs,
                 transport_tx,
-                post_path: post_path.into(),
+                post_path: config.post_path.as_str().into(),
                 sse_ping_interval,
+                event_names: Arc::new(config.event_names.clone()),
+                replay_buffer_size: config.replay_buffer_size,
+                json_limits: config.json_limits,
+                parse_mode: config.parse_mode,
             },
             transport_rx,
         )
     }
+
+    /// Open the SSE stream of a `GET` on the `sse_path`.
+    ///
+    /// Without a `Last-Event-ID` (or with replay disabled) a new session is opened, otherwise the
+    /// session it names is resumed, replaying the events the client missed.
+    async fn open_stream(
+        &self,
+        nested_path: &str,
+        last_event_id: Option<&str>,
+    ) -> Result<(SessionId, SessionStream), SseStreamError> {
+        match last_event_id {
+            Some(last_event_id) if self.replay_buffer_size > 0 => {
+                self.resume_session(last_event_id).await
+            }
+            _ => self.open_session(nested_path).await,
+        }
+    }
+
+    /// Register a new session and send its transport out.
+    ///
+    /// The stream starts with the `endpoint` event, the session is removed once the stream is
+    /// dropped, or one keep alive interval later when replay is enabled and it isn't resumed.
+    async fn open_session(
+        &self,
+        nested_path: &str,
+    ) -> Result<(SessionId, SessionStream), SseStreamError> {
+        let session = session_id();
+        let (from_client_tx, from_client_rx) = tokio::sync::mpsc::channel(64);
+        let (to_client_tx, to_client_rx) = tokio::sync::mpsc::channel(64);
+        let to_client = Arc::new(tokio::sync::Mutex::new(ReceiverStream::new(to_client_rx)));
+        let replay = Arc::new(std::sync::Mutex::new(ReplayBuffer::new(
+            self.replay_buffer_size,
+        )));
+        let connected = to_client.clone().lock_owned().await;
+
+        self.txs.write().await.insert(
+            session.clone(),
+            SessionEntry {
+                tx: from_client_tx,
+                connected_at: SystemTime::now(),
+                initialized: false,
+                to_client,
+                replay: replay.clone(),
+            },
+        );
+        let transport = SseServerTransport {
+            stream: ReceiverStream::new(from_client_rx),
+            sink: PollSender::new(to_client_tx),
+            session_id: session.clone(),
+            tx_store: self.txs.clone(),
+        };
+        if self.transport_tx.send(transport).is_err() {
+            tracing::warn!("send transport out error");
+            self.txs.write().await.remove(&session);
+            return Err(SseStreamError::ServerClosed);
+        }
+
+        let endpoint = SessionEvent {
+            id: replay
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .event_id(&session, 0),
+            event: "endpoint".to_string(),
+            data: self.endpoint(nested_path, &session),
+        };
+        let stream = futures::stream::once(futures::future::ready(endpoint))
+            .chain(self.session_stream(session.clone(), connected, replay));
+        Ok((session, Box::pin(stream)))
+    }
+
+    /// Reconnect to the session named by `last_event_id`, starting with the events after it
+    async fn resume_session(
+        &self,
+        last_event_id: &str,
+    ) -> Result<(SessionId, SessionStream), SseStreamError> {
+        let (session, index) = last_event_id
+            .rsplit_once('/')
+            .and_then(|(session, index)| Some((session, index.parse::<u64>().ok()?)))
+            .ok_or(SseStreamError::InvalidLastEventId)?;
+        let (session, to_client, replay) = {
+            let txs = self.txs.read().await;
+            let (session, entry) = txs
+                .get_key_value(session)
+                .ok_or(SseStreamError::SessionNotFound)?;
+            (
+                session.clone(),
+                entry.to_client.clone(),
+                entry.replay.clone(),
+            )
+        };
+        let connected = to_client
+            .try_lock_owned()
+            .map_err(|_| SseStreamError::AlreadyConnected)?;
+        let missed = replay
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .replay_after(index)?;
+        tracing::debug!(session_id = %session, replayed = missed.len(), "resume sse session");
+        let stream = futures::stream::iter(missed).chain(self.session_stream(
+            session.clone(),
+            connected,
+            replay,
+        ));
+        Ok((session, Box::pin(stream)))
+    }
+
+    /// The messages sent to the session while this stream is connected, recorded for replay
+    fn session_stream(
+        &self,
+        session: SessionId,
+        to_client: OwnedMutexGuard<ToClient>,
+        replay: Arc<std::sync::Mutex<ReplayBuffer>>,
+    ) -> impl Stream<Item = SessionEvent> + Send + Sync + 'static {
+        let connection = {
+            let mut replay = replay.lock().unwrap_or_else(PoisonError::into_inner);
+            replay.connections += 1;
+            replay.connections
+        };
+        let detach = Detach {
+            txs: self.txs.clone(),
+            session,
+            replay,
+            connection,
+            grace: if self.replay_buffer_size > 0 {
+                self.sse_ping_interval
+            } else {
+                Duration::ZERO
+            },
+        };
+        let event_names = self.event_names.clone();
+        futures::stream::unfold((to_client, detach), move |(mut to_client, detach)| {
+            let event_names = event_names.clone();
+            async move {
+                loop {
+                    let message = to_client.next().await?;
+                    match serde_json::to_string(&message) {
+                        Ok(data) => {
+                            let event = detach
+                                .replay
+                                .lock()
+                                .unwrap_or_else(PoisonError::into_inner)
+                                .record(&detach.session, event_names.of(&message).to_owned(), data);
+                            return Some((event, (to_client, detach)));
+                        }
+                        Err(e) => {
+                            tracing::error!(error = %e, "fail to serialize message");
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    /// The data of the `endpoint` event telling the client where to post its messages
+    fn endpoint(&self, nested_path: &str, session: &SessionId) -> String {
+        format!("{nested_path}{}?sessionId={session}", self.post_path)
+    }
+
+    /// Forward a message posted by the client to its session.
+    async fn post_message(
+        &self,
+        session_id: &str,
+        parts: Parts,
+        mut message: ClientJsonRpcMessage,
+    ) -> Result<StatusCode, StatusCode> {
+        // the message itself is only logged by the session, through its redactor
+        match &message {
+            JsonRpcMessage::Request(request) => {
+                tracing::debug!(
+                    session_id,
+                    id = %request.id,
+                    method = request.request.method(),
+                    "new client message"
+                )
+            }
+            JsonRpcMessage::Notification(notification) => {
+                tracing::debug!(
+                    session_id,
+                    method = notification.notification.method(),
+                    "new client message"
+                )
+            }
+            JsonRpcMessage::Response(JsonRpcResponse { id, .. })
+            | JsonRpcMessage::Error(JsonRpcError { id, .. }) => {
+                tracing::debug!(session_id, %id, "new client message")
+            }
+        }
+        let is_initialized_notification = matches!(
+            message,
+            JsonRpcMessage::Notification(JsonRpcNotification {
+                notification: ClientNotification::InitializedNotification(_),
+                ..
+            })
+        );
+        let tx = if is_initialized_notification {
+            let mut wg = self.txs.write().await;
+            let entry = wg.get_mut(session_id).ok_or(StatusCode::NOT_FOUND)?;
+            entry.initialized = true;
+            entry.tx.clone()
+        } else {
+            let rg = self.txs.read().await;
+            rg.get(session_id).ok_or(StatusCode::NOT_FOUND)?.tx.clone()
+        };
+        message.insert_extension(parts);
+        if tx.send(message).await.is_err() {
+            tracing::error!("send message error");
+            return Err(StatusCode::GONE);
+        }
+        Ok(StatusCode::ACCEPTED)
+    }
 }
 
 #[derive(Debug, serde::Deserialize)]
@@ -61,89 +488,51 @@ pub struct PostEventQuery {
     pub session_id: String,
 }
 
+#[cfg(feature = "transport-sse-server")]
 async fn post_event_handler(
     State(app): State<App>,
     Query(PostEventQuery { session_id }): Query<PostEventQuery>,
     parts: Parts,
-    Json(mut message): Json<ClientJsonRpcMessage>,
-) -> Result<StatusCode, StatusCode> {
-    tracing::debug!(session_id, ?parts, ?message, "new client message");
-    let tx = {
-        let rg = app.txs.read().await;
-        rg.get(session_id.as_str())
-            .ok_or(StatusCode::NOT_FOUND)?
-            .clone()
-    };
-    message.insert_extension(parts);
-    if tx.send(message).await.is_err() {
-        tracing::error!("send message error");
-        return Err(StatusCode::GONE);
-    }
-    Ok(StatusCode::ACCEPTED)
+    body: Bytes,
+) -> Result<StatusCode, Response> {
+    use axum::response::IntoResponse;
+
+    let message =
+        parse_json(&body, &app.json_limits, app.parse_mode).map_err(IntoResponse::into_response)?;
+    app.post_message(&session_id, parts, message)
+        .await
+        .map_err(IntoResponse::into_response)
 }
 
+#[cfg(feature = "transport-sse-server")]
 async fn sse_handler(
     State(app): State<App>,
     nested_path: Option<Extension<NestedPath>>,
     parts: Parts,
 ) -> Result<Sse<impl Stream<Item = Result<Event, io::Error>>>, Response<String>> {
-    let session = session_id();
-    tracing::info!(%session, ?parts, "sse connection");
-    use tokio_stream::{StreamExt, wrappers::ReceiverStream};
-    use tokio_util::sync::PollSender;
-    let (from_client_tx, from_client_rx) = tokio::sync::mpsc::channel(64);
-    let (to_client_tx, to_client_rx) = tokio::sync::mpsc::channel(64);
-    let to_client_tx_clone = to_client_tx.clone();
-
-    app.txs
-        .write()
-        .await
-        .insert(session.clone(), from_client_tx);
-    let session = session.clone();
-    let stream = ReceiverStream::new(from_client_rx);
-    let sink = PollSender::new(to_client_tx);
-    let transport = SseServerTransport {
-        stream,
-        sink,
-        session_id: session.clone(),
-        tx_store: app.txs.clone(),
-    };
-    let transport_send_result = app.transport_tx.send(transport);
-    if transport_send_result.is_err() {
-        tracing::warn!("send transport out error");
-        let mut response =
-            Response::new("fail to send out transport, it seems server is closed".to_string());
-        *response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
-        return Err(response);
-    }
     let nested_path = nested_path.as_deref().map(NestedPath::as_str).unwrap_or("");
-    let post_path = app.post_path.as_ref();
-    let ping_interval = app.sse_ping_interval;
-    let stream = futures::stream::once(futures::future::ok(
-        Event::default()
-            .event("endpoint")
-            .data(format!("{nested_path}{post_path}?sessionId={session}")),
-    ))
-    .chain(ReceiverStream::new(to_client_rx).map(|message| {
-        match serde_json::to_string(&message) {
-            Ok(bytes) => Ok(Event::default().event("message").data(&bytes)),
-            Err(e) => Err(io::Error::new(io::ErrorKind::InvalidData, e)),
+    let last_event_id = parts
+        .headers
+        .get(HEADER_LAST_EVENT_ID)
+        .and_then(|value| value.to_str().ok());
+    let (session, stream) = app
+        .open_stream(nested_path, last_event_id)
+        .await
+        .map_err(|e| {
+            let mut response = Response::new(e.to_string());
+            *response.status_mut() = e.status();
+            response
+        })?;
+    tracing::info!(%session, ?parts, "sse connection");
+    let stream = stream.map(|event| {
+        let mut sse = Event::default().event(event.event).data(event.data);
+        if let Some(id) = event.id {
+            sse = sse.id(id);
         }
-    }));
-
-    tokio::spawn(async move {
-        // Wait for connection closure
-        to_client_tx_clone.closed().await;
-
-        // Clean up session
-        let session_id = session.clone();
-        let tx_store = app.txs.clone();
-        let mut txs = tx_store.write().await;
-        txs.remove(&session_id);
-        tracing::debug!(%session_id, "Closed session and cleaned up resources");
+        Ok(sse)
     });
 
-    Ok(Sse::new(stream).keep_alive(KeepAlive::new().interval(ping_interval)))
+    Ok(Sse::new(stream).keep_alive(KeepAlive::new().interval(app.sse_ping_interval)))
 }
 
 pub struct SseServerTransport {
@@ -221,30 +610,174 @@ pub struct SseServerConfig {
     pub post_path: String,
     pub ct: CancellationToken,
     pub sse_keep_alive: Option<Duration>,
+    pub event_names: SseEventNames,
+    /// How many of the most recent events are retained per session for clients resuming their
+    /// stream with `Last-Event-ID`, the oldest ones are evicted first.
+    ///
+    /// `0` disables resumption: events carry no id and a session ends with its stream. Otherwise a
+    /// session outlives a dropped stream by one keep alive interval, and resuming from an event
+    /// that was already evicted is answered with `410 Gone` and `replay window exceeded`.
+    pub replay_buffer_size: usize,
+    /// Serve a health check for load balancers besides the MCP endpoints, see [`HealthCheck`].
+    ///
+    /// It answers `503 Service Unavailable` after [`SseServer::drain`], or once `ct` is cancelled.
+    pub health_check: Option<HealthCheck>,
+    /// The limits on the messages posted by clients, a message exceeding them is answered with
+    /// `400 Bad Request` and a JSON-RPC parse error.
+    pub json_limits: JsonLimits,
+    /// How strictly the envelope of the messages posted by clients is checked,
+    /// [`JsonRpcParseMode::Strict`] by default. A message rejected by it is answered with
+    /// `400 Bad Request` and a JSON-RPC parse error.
+    pub parse_mode: JsonRpcParseMode,
+    /// The CORS policy for browser clients, applied to the SSE and POST endpoints and the health
+    /// check, see [`Cors`].
+    pub cors: Option<Cors>,
+    /// The read and write timeouts of the sessions served by [`SseServer::with_service`] and
+    /// its variants, closing the stalled ones, see [`TimeoutTransport`].
+    pub timeouts: TransportTimeouts,
+}
+
+impl SseServerConfig {
+    fn new(bind: SocketAddr) -> Self {
+        Self {
+            bind,
+            ..Default::default()
+        }
+    }
 }
 
+impl Default for SseServerConfig {
+    /// Serve on `/sse` and `/message` of an ephemeral localhost port, with the default keep
+    /// alive and event names, and without replay, health check, CORS or timeouts
+    fn default() -> Self {
+        Self {
+            bind: SocketAddr::from(([127, 0, 0, 1], 0)),
+            sse_path: "/sse".to_string(),
+            post_path: "/message".to_string(),
+            ct: CancellationToken::new(),
+            sse_keep_alive: None,
+            event_names: SseEventNames::default(),
+            replay_buffer_size: 0,
+            health_check: None,
+            json_limits: JsonLimits::default(),
+            parse_mode: JsonRpcParseMode::default(),
+            cors: None,
+            timeouts: TransportTimeouts::default(),
+        }
+    }
+}
+
+/// The `event:` names of the SSE events carrying JSON-RPC messages to the client.
+///
+/// By default every message is sent as a `message` event, as the MCP HTTP+SSE transport
+/// requires. Clients routing on the event name, like a plain `EventSource`, may prefer
+/// distinct names per message type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SseEventNames {
+    /// Used for responses and errors
+    pub response: String,
+    pub notification: String,
+    pub request: String,
+}
+
+impl SseEventNames {
+    pub const DEFAULT_EVENT_NAME: &str = "message";
+
+    fn of(&self, message: &TxJsonRpcMessage<RoleServer>) -> &str {
+        match message {
+            JsonRpcMessage::Response(_) | JsonRpcMessage::Error(_) => &self.response,
+            JsonRpcMessage::Notification(_) => &self.notification,
+            JsonRpcMessage::Request(_) => &self.request,
+        }
+    }
+}
+
+impl Default for SseEventNames {
+    fn default() -> Self {
+        Self {
+            response: Self::DEFAULT_EVENT_NAME.to_string(),
+            notification: Self::DEFAULT_EVENT_NAME.to_string(),
+            request: Self::DEFAULT_EVENT_NAME.to_string(),
+        }
+    }
+}
+
+/// A snapshot of an active SSE session, see [`SseServer::sessions`].
+#[derive(Debug, Clone)]
+pub struct SseSessionInfo {
+    pub id: SessionId,
+    pub connected_at: SystemTime,
+    /// Whether the client has sent the `notifications/initialized` notification.
+    pub initialized: bool,
+}
+
+/// A server of the legacy HTTP+SSE transport, built on axum.
+///
+/// The `HyperSseServer` behind the `transport-sse-server-hyper` feature serves the same
+/// transport directly on hyper, for those who don't want to depend on axum.
+///
+/// # Mounting multiple servers
+///
+/// [`SseServer::new`] returns the [`Router`] of this server alone, several of them can be mounted
+/// on the same axum app, either with distinct `sse_path`/`post_path`, or with [`Router::nest`]:
+///
+/// ```rust,ignore
+/// let (movie, movie_router) = SseServer::new(SseServerConfig { sse_path: "/sse".into(), post_path: "/message".into(), ..config.clone() });
+/// let (weather, weather_router) = SseServer::new(SseServerConfig { sse_path: "/sse".into(), post_path: "/message".into(), ..config });
+/// let app = Router::new()
+///     .nest("/movie", movie_router)
+///     .nest("/weather", weather_router);
+/// movie.with_service(Movie::new);
+/// weather.with_service(Weather::new);
+/// ```
+///
+/// When nested, the `endpoint` event sent to the client includes the prefix, e.g. `/movie/message?sessionId=...`.
+///
+/// Session ids are namespaced per mount: each server only knows the sessions opened on its own
+/// `sse_path`, so posting a message to another mount with the same session id is rejected with
+/// `404 Not Found`. The ids themselves are random UUIDs, so they don't collide across mounts either.
+#[cfg(feature = "transport-sse-server")]
+#[cfg_attr(docsrs, doc(cfg(feature = "transport-sse-server")))]
 #[derive(Debug)]
 pub struct SseServer {
     transport_rx: tokio::sync::mpsc::UnboundedReceiver<SseServerTransport>,
+    txs: TxStore,
     pub config: SseServerConfig,
 }
 
+#[cfg(feature = "transport-sse-server")]
 impl SseServer {
     pub async fn serve(bind: SocketAddr) -> io::Result<Self> {
-        Self::serve_with_config(SseServerConfig {
-            bind,
-            sse_path: "/sse".to_string(),
-            post_path: "/message".to_string(),
-            ct: CancellationToken::new(),
-            sse_keep_alive: None,
-        })
-        .await
+        Self::serve_with_config(SseServerConfig::new(bind)).await
     }
     pub async fn serve_with_config(config: SseServerConfig) -> io::Result<Self> {
-        let (sse_server, service) = Self::new(config);
-        let listener = tokio::net::TcpListener::bind(sse_server.config.bind).await?;
-        let ct = sse_server.config.ct.child_token();
-        let server = axum::serve(listener, service).with_graceful_shutdown(async move {
+        let (sse_server, router) = Self::new(config);
+        sse_server.serve_router(router).await
+    }
+
+    /// Like [`SseServer::serve_with_config`], with `layers` wrapping every route of the server,
+    /// e.g. a `tower_http` CORS, tracing or compression layer, or a `tower::ServiceBuilder`
+    /// stacking several of them.
+    pub async fn serve_with_layers<L>(config: SseServerConfig, layers: L) -> io::Result<Self>
+    where
+        L: Layer<Route> + Clone + Send + Sync + 'static,
+        L::Service: tower_service::Service<Request> + Clone + Send + Sync + 'static,
+        <L::Service as tower_service::Service<Request>>::Response: IntoResponse + 'static,
+        <L::Service as tower_service::Service<Request>>::Error: Into<Infallible> + 'static,
+        <L::Service as tower_service::Service<Request>>::Future: Send + 'static,
+    {
+        let (sse_server, router) = Self::router_with_layers(config, layers);
+        sse_server.serve_router(router).await
+    }
+
+    async fn serve_router(self, router: Router) -> io::Result<Self> {
+        if let Some(cors) = &self.config.cors {
+            cors.validate()
+                .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))?;
+        }
+        let listener = tokio::net::TcpListener::bind(self.config.bind).await?;
+        let ct = self.config.ct.child_token();
+        let server = axum::serve(listener, router).with_graceful_shutdown(async move {
             ct.cancelled().await;
             tracing::info!("sse server cancelled");
         });
@@ -254,72 +787,131 @@ impl SseServer {
                     tracing::error!(error = %e, "sse server shutdown with error");
                 }
             }
-            .instrument(tracing::info_span!("sse-server", bind_address = %sse_server.config.bind)),
+            .instrument(tracing::info_span!("sse-server", bind_address = %self.config.bind)),
         );
-        Ok(sse_server)
+        Ok(self)
     }
 
     pub fn new(config: SseServerConfig) -> (SseServer, Router) {
+        let txs = TxStore::default();
         let (app, transport_rx) = App::new(
-            config.post_path.clone(),
+            txs.clone(),
+            &config,
             config.sse_keep_alive.unwrap_or(DEFAULT_AUTO_PING_INTERVAL),
         );
-        let router = Router::new()
+        let mut router = Router::new()
             .route(&config.sse_path, get(sse_handler))
             .route(&config.post_path, post(post_event_handler))
             .with_state(app);
+        if let Some(health_check) = config.health_check.clone() {
+            let ct = config.ct.clone();
+            router = router.route(
+                &health_check.path.clone(),
+                get(move || async move { health_check.response(ct.is_cancelled()) }),
+            );
+        }
+        if let Some(cors) = config.cors.clone() {
+            router = router.layer(axum::middleware::from_fn(
+                move |request: Request, next: Next| {
+                    let cors = cors.clone();
+                    async move {
+                        if let Some(preflight) = cors.preflight(&request) {
+                            return preflight.into_response();
+                        }
+                        let origin = request.headers().get(http::header::ORIGIN).cloned();
+                        let mut response = next.run(request).await;
+                        cors.apply(origin.as_ref(), &mut response);
+                        response
+                    }
+                },
+            ));
+        }
 
         let server = SseServer {
             transport_rx,
+            txs,
             config,
         };
 
         (server, router)
     }
 
-    pub fn with_service<S, F>(mut self, service_provider: F) -> CancellationToken
+    /// Like [`SseServer::new`], with `layers` wrapping every route of the server, the MCP
+    /// endpoints and the health check alike.
+    ///
+    /// ```rust,ignore
+    /// let (sse_server, router) = SseServer::router_with_layers(config, CorsLayer::permissive());
+    /// ```
+    ///
+    /// The returned [`Router`] can also be extended with routes of your own, or layered again.
+    pub fn router_with_layers<L>(config: SseServerConfig, layers: L) -> (SseServer, Router)
+    where
+        L: Layer<Route> + Clone + Send + Sync + 'static,
+        L::Service: tower_service::Service<Request> + Clone + Send + Sync + 'static,
+        <L::Service as tower_service::Service<Request>>::Response: IntoResponse + 'static,
+        <L::Service as tower_service::Service<Request>>::Error: Into<Infallible> + 'static,
+        <L::Service as tower_service::Service<Request>>::Future: Send + 'static,
+    {
+        let (server, router) = Self::new(config);
+        (server, router.layer(layers))
+    }
+
+    pub fn with_service<S, F>(self, service_provider: F) -> CancellationToken
     where
         S: Service<RoleServer>,
         F: Fn() -> S + Send + 'static,
     {
-        use crate::service::ServiceExt;
         let ct = self.config.ct.clone();
-        tokio::spawn(async move {
-            while let Some(transport) = self.next_transport().await {
-                let service = service_provider();
-                let ct = self.config.ct.child_token();
-                tokio::spawn(async move {
-                    let server = service
-                        .serve_with_ct(transport, ct)
-                        .await
-                        .map_err(std::io::Error::other)?;
-                    server.waiting().await?;
-                    tokio::io::Result::Ok(())
-                });
-            }
-        });
-        ct
+        let timeouts = self.config.timeouts;
+        serve_transports(self, ct, service_provider, timeouts, false)
+    }
+
+    /// Like [`SseServer::with_service`], also returning the token the services are cancelled by, a child of the
+    /// returned cancel handle.
+    ///
+    /// It fires when the server is cancelled, so background tasks can observe the same shutdown
+    /// as the services. Cancelling it stops every session, while the server keeps running.
+    pub fn with_service_and_ct<S, F>(
+        self,
+        service_provider: F,
+    ) -> (CancellationToken, CancellationToken)
+    where
+        S: Service<RoleServer>,
+        F: Fn() -> S + Send + 'static,
+    {
+        let ct = self.config.ct.clone();
+        let service_ct = ct.child_token();
+        let timeouts = self.config.timeouts;
+        serve_transports(self, service_ct.clone(), service_provider, timeouts, false);
+        (ct, service_ct)
     }
 
     /// This allows you to skip the initialization steps for incoming request.
-    pub fn with_service_directly<S, F>(mut self, service_provider: F) -> CancellationToken
+    pub fn with_service_directly<S, F>(self, service_provider: F) -> CancellationToken
     where
         S: Service<RoleServer>,
         F: Fn() -> S + Send + 'static,
     {
         let ct = self.config.ct.clone();
-        tokio::spawn(async move {
-            while let Some(transport) = self.next_transport().await {
-                let service = service_provider();
-                let ct = self.config.ct.child_token();
-                tokio::spawn(async move {
-                    let server = serve_directly_with_ct(service, transport, None, ct);
-                    server.waiting().await?;
-                    tokio::io::Result::Ok(())
-                });
-            }
-        });
-        ct
+        let timeouts = self.config.timeouts;
+        serve_transports(self, ct, service_provider, timeouts, true)
+    }
+
+    /// Take a snapshot of the currently connected sessions.
+    ///
+    /// Only a read lock is held while the metadata is copied out, so this is
+    /// cheap enough to call from a status or health route.
+    pub async fn sessions(&self) -> Vec<SseSessionInfo> {
+        session_infos(&self.txs).await
+    }
+
+    /// Make the health check answer `503 Service Unavailable`, while still serving every session.
+    ///
+    /// Does nothing without a `health_check` in the config.
+    pub fn drain(&self) {
+        if let Some(health_check) = &self.config.health_check {
+            health_check.drain();
+        }
     }
 
     pub fn cancel(&self) {
@@ -331,6 +923,7 @@ impl SseServer {
     }
 }
 
+#[cfg(feature = "transport-sse-server")]
 impl Stream for SseServer {
     type Item = SseServerTransport;
 
@@ -341,3 +934,61 @@ impl Stream for SseServer {
         self.transport_rx.poll_recv(cx)
     }
 }
+
+/// Serve every incoming transport with a new service, until the transports run out.
+fn serve_transports<T, S, F>(
+    mut transports: T,
+    ct: CancellationToken,
+    service_provider: F,
+    timeouts: TransportTimeouts,
+    directly: bool,
+) -> CancellationToken
+where
+    T: Stream<Item = SseServerTransport> + Unpin + Send + 'static,
+    S: Service<RoleServer>,
+    F: Fn() -> S + Send + 'static,
+{
+    use futures::StreamExt;
+
+    use crate::service::ServiceExt;
+    tokio::spawn({
+        let ct = ct.clone();
+        async move {
+            while let Some(transport) = transports.next().await {
+                let service = service_provider();
+                let ct = ct.child_token();
+                let transport = IntoTransport::<
+                    RoleServer,
+                    io::Error,
+                    sink_stream::TransportAdapterAsyncCombinedRW,
+                >::into_transport(transport);
+                let transport = TimeoutTransport::new(transport, timeouts);
+                tokio::spawn(async move {
+                    let server = if directly {
+                        serve_directly_with_ct(service, transport, None, ct)
+                    } else {
+                        service
+                            .serve_with_ct(transport, ct)
+                            .await
+                            .map_err(std::io::Error::other)?
+                    };
+                    server.waiting().await?;
+                    tokio::io::Result::Ok(())
+                });
+            }
+        }
+    });
+    ct
+}
+
+async fn session_infos(txs: &TxStore) -> Vec<SseSessionInfo> {
+    txs.read()
+        .await
+        .iter()
+        .map(|(id, entry)| SseSessionInfo {
+            id: id.clone(),
+            connected_at: entry.connected_at,
+            initialized: entry.initialized,
+        })
+        .collect()
+}
@@ -0,0 +1,363 @@
+//! The HTTP+SSE server transport served directly on hyper, without pulling in axum.
+use std::{convert::Infallible, fmt::Display, io, net::SocketAddr, sync::Arc};
+
+use bytes::Bytes;
+use futures::{Stream, StreamExt, future::BoxFuture};
+use http::{Method, Request, Response, StatusCode, header::ALLOW};
+use http_body::Body;
+use http_body_util::{BodyExt, Empty, Full};
+use hyper_util::rt::TokioIo;
+use sse_stream::{KeepAlive, Sse, SseBody};
+use tokio_util::sync::CancellationToken;
+use tracing::Instrument;
+
+use super::{
+    App, SseServerConfig, SseServerTransport, SseSessionInfo, TxStore, serve_transports,
+    session_infos,
+};
+use crate::{
+    RoleServer, Service,
+    transport::common::{
+        http_header::{EVENT_STREAM_MIME_TYPE, HEADER_LAST_EVENT_ID},
+        server_side_http::{
+            BoxResponse, Cors, DEFAULT_AUTO_PING_INTERVAL, HealthCheck, TokioTimer, expect_json,
+        },
+    },
+};
+
+/// The tower service of a [`HyperSseServer`], answering `GET` on the `sse_path` and `POST` on the `post_path`,
+/// and `GET` on the path of the health check if any.
+///
+/// It can be served by hyper with `hyper_util::service::TowerToHyperService`, or mounted in any
+/// tower based router.
+#[derive(Clone)]
+pub struct SseService {
+    app: App,
+    sse_path: Arc<str>,
+    health_check: Option<HealthCheck>,
+    cors: Option<Cors>,
+    ct: CancellationToken,
+}
+
+impl<RequestBody> tower_service::Service<Request<RequestBody>> for SseService
+where
+    RequestBody: Body + Send + 'static,
+    RequestBody::Error: Display,
+    RequestBody::Data: Send + 'static,
+{
+    type Response = BoxResponse;
+    type Error = Infallible;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+    fn call(&mut self, req: Request<RequestBody>) -> Self::Future {
+        let service = self.clone();
+        Box::pin(async move {
+            let response = service.handle(req).await;
+            Ok(response)
+        })
+    }
+    fn poll_ready(
+        &mut self,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+}
+
+impl SseService {
+    pub async fn handle<B>(&self, request: Request<B>) -> BoxResponse
+    where
+        B: Body + Send + 'static,
+        B::Error: Display,
+    {
+        let Some(cors) = &self.cors else {
+            return self.route(request).await;
+        };
+        if let Some(preflight) = cors.preflight(&request) {
+            return preflight;
+        }
+        let origin = request.headers().get(http::header::ORIGIN).cloned();
+        let mut response = self.route(request).await;
+        cors.apply(origin.as_ref(), &mut response);
+        response
+    }
+
+    async fn route<B>(&self, request: Request<B>) -> BoxResponse
+    where
+        B: Body + Send + 'static,
+        B::Error: Display,
+    {
+        let path = request.uri().path();
+        let health_check = self
+            .health_check
+            .as_ref()
+            .filter(|health_check| health_check.path == path);
+        let allowed_method = if path == &*self.sse_path || health_check.is_some() {
+            Method::GET
+        } else if path == &*self.app.post_path {
+            Method::POST
+        } else {
+            return status_response(StatusCode::NOT_FOUND);
+        };
+        match (request.method(), health_check) {
+            (&Method::GET, Some(health_check)) => health_check.response(self.ct.is_cancelled()),
+            (method, _) if method == allowed_method && method == Method::GET => {
+                self.handle_sse(request).await
+            }
+            (method, _) if method == allowed_method => self.handle_post(request).await,
+            _ => Response::builder()
+                .status(StatusCode::METHOD_NOT_ALLOWED)
+                .header(ALLOW, allowed_method.as_str())
+                .body(Full::new(Bytes::from("Method Not Allowed")).boxed())
+                .expect("valid response"),
+        }
+    }
+
+    async fn handle_sse<B>(&self, request: Request<B>) -> BoxResponse {
+        let (parts, _body) = request.into_parts();
+        let last_event_id = parts
+            .headers
+            .get(HEADER_LAST_EVENT_ID)
+            .and_then(|value| value.to_str().ok());
+        let (session, stream) = match self.app.open_stream("", last_event_id).await {
+            Ok(stream) => stream,
+            Err(e) => {
+                return Response::builder()
+                    .status(e.status())
+                    .body(Full::new(Bytes::from(e.to_string())).boxed())
+                    .expect("valid response");
+            }
+        };
+        tracing::info!(%session, ?parts, "sse connection");
+        let stream = stream.map(|event| {
+            let mut sse = Sse::default().event(event.event).data(event.data);
+            if let Some(id) = event.id {
+                sse = sse.id(id);
+            }
+            Result::<Sse, Infallible>::Ok(sse)
+        });
+        let body = SseBody::new(stream)
+            .with_keep_alive::<TokioTimer>(KeepAlive::new().interval(self.app.sse_ping_interval));
+        Response::builder()
+            .status(StatusCode::OK)
+            .header(http::header::CONTENT_TYPE, EVENT_STREAM_MIME_TYPE)
+            .header(http::header::CACHE_CONTROL, "no-cache")
+            .body(body.boxed())
+            .expect("valid response")
+    }
+
+    async fn handle_post<B>(&self, request: Request<B>) -> BoxResponse
+    where
+        B: Body + Send + 'static,
+        B::Error: Display,
+    {
+        let (parts, body) = request.into_parts();
+        let Some(session_id) = parts.uri.query().and_then(query_session_id) else {
+            return Response::builder()
+                .status(StatusCode::BAD_REQUEST)
+                .body(Full::new(Bytes::from("Bad Request: sessionId is required")).boxed())
+                .expect("valid response");
+        };
+        let message = match expect_json(body, &self.app.json_limits, self.app.parse_mode).await {
+            Ok(message) => message,
+            Err(response) => return response,
+        };
+        match self.app.post_message(&session_id, parts, message).await {
+            Ok(status) | Err(status) => status_response(status),
+        }
+    }
+}
+
+/// The `sessionId` of the query, percent-decoded like the axum server does
+fn query_session_id(query: &str) -> Option<String> {
+    form_urlencoded::parse(query.as_bytes())
+        .find_map(|(key, value)| (key == "sessionId").then(|| value.into_owned()))
+}
+
+fn status_response(status: StatusCode) -> BoxResponse {
+    Response::builder()
+        .status(status)
+        .body(Empty::new().boxed())
+        .expect("valid response")
+}
+
+/// A server of the legacy HTTP+SSE transport built directly on hyper, see [`SseServer`](super::SseServer) for the axum one.
+///
+/// It has the same shape as [`SseServer`](super::SseServer), but [`HyperSseServer::new`] returns
+/// a tower [`SseService`] instead of an axum router.
+///
+/// ```rust,ignore
+/// let ct = HyperSseServer::serve("127.0.0.1:8000".parse()?)
+///     .await?
+///     .with_service(Counter::new);
+/// ```
+///
+/// The `endpoint` event always points to the `post_path` as is, so when mounting the
+/// [`SseService`] under a prefix, include the prefix in the `post_path`.
+#[derive(Debug)]
+pub struct HyperSseServer {
+    transport_rx: tokio::sync::mpsc::UnboundedReceiver<SseServerTransport>,
+    txs: TxStore,
+    pub config: SseServerConfig,
+}
+
+impl HyperSseServer {
+    pub async fn serve(bind: SocketAddr) -> io::Result<Self> {
+        Self::serve_with_config(SseServerConfig::new(bind)).await
+    }
+
+    pub async fn serve_with_config(config: SseServerConfig) -> io::Result<Self> {
+        if let Some(cors) = &config.cors {
+            cors.validate()
+                .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))?;
+        }
+        let (sse_server, service) = Self::new(config);
+        let listener = tokio::net::TcpListener::bind(sse_server.config.bind).await?;
+        let ct = sse_server.config.ct.child_token();
+        tokio::spawn(
+            serve_connections(listener, service, ct).instrument(tracing::info_span!(
+                "sse-server",
+                bind_address = %sse_server.config.bind
+            )),
+        );
+        Ok(sse_server)
+    }
+
+    pub fn new(config: SseServerConfig) -> (HyperSseServer, SseService) {
+        let txs = TxStore::default();
+        let (app, transport_rx) = App::new(
+            txs.clone(),
+            &config,
+            config.sse_keep_alive.unwrap_or(DEFAULT_AUTO_PING_INTERVAL),
+        );
+        let service = SseService {
+            app,
+            sse_path: config.sse_path.as_str().into(),
+            health_check: config.health_check.clone(),
+            cors: config.cors.clone(),
+            ct: config.ct.clone(),
+        };
+        let server = HyperSseServer {
+            transport_rx,
+            txs,
+            config,
+        };
+        (server, service)
+    }
+
+    pub fn with_service<S, F>(self, service_provider: F) -> CancellationToken
+    where
+        S: Service<RoleServer>,
+        F: Fn() -> S + Send + 'static,
+    {
+        let ct = self.config.ct.clone();
+        let timeouts = self.config.timeouts;
+        serve_transports(self, ct, service_provider, timeouts, false)
+    }
+
+    /// Like [`HyperSseServer::with_service`], also returning the token the services are cancelled by, a child of the
+    /// returned cancel handle.
+    ///
+    /// It fires when the server is cancelled, so background tasks can observe the same shutdown
+    /// as the services. Cancelling it stops every session, while the server keeps running.
+    pub fn with_service_and_ct<S, F>(
+        self,
+        service_provider: F,
+    ) -> (CancellationToken, CancellationToken)
+    where
+        S: Service<RoleServer>,
+        F: Fn() -> S + Send + 'static,
+    {
+        let ct = self.config.ct.clone();
+        let service_ct = ct.child_token();
+        let timeouts = self.config.timeouts;
+        serve_transports(self, service_ct.clone(), service_provider, timeouts, false);
+        (ct, service_ct)
+    }
+
+    /// This allows you to skip the initialization steps for incoming request.
+    pub fn with_service_directly<S, F>(self, service_provider: F) -> CancellationToken
+    where
+        S: Service<RoleServer>,
+        F: Fn() -> S + Send + 'static,
+    {
+        let ct = self.config.ct.clone();
+        let timeouts = self.config.timeouts;
+        serve_transports(self, ct, service_provider, timeouts, true)
+    }
+
+    /// Take a snapshot of the currently connected sessions, see [`SseServer::sessions`](super::SseServer::sessions).
+    pub async fn sessions(&self) -> Vec<SseSessionInfo> {
+        session_infos(&self.txs).await
+    }
+
+    /// Make the health check answer `503 Service Unavailable`, see [`SseServer::drain`](super::SseServer::drain).
+    pub fn drain(&self) {
+        if let Some(health_check) = &self.config.health_check {
+            health_check.drain();
+        }
+    }
+
+    pub fn cancel(&self) {
+        self.config.ct.cancel();
+    }
+
+    pub async fn next_transport(&mut self) -> Option<SseServerTransport> {
+        self.transport_rx.recv().await
+    }
+}
+
+impl Stream for HyperSseServer {
+    type Item = SseServerTransport;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        self.transport_rx.poll_recv(cx)
+    }
+}
+
+/// Accept http/1 connections until cancelled, then shut the open ones down gracefully
+async fn serve_connections(
+    listener: tokio::net::TcpListener,
+    service: SseService,
+    ct: CancellationToken,
+) {
+    loop {
+        let stream = tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => stream,
+                Err(e) => {
+                    tracing::error!(error = %e, "fail to accept connection");
+                    continue;
+                }
+            },
+            _ = ct.cancelled() => {
+                tracing::info!("sse server cancelled");
+                return;
+            }
+        };
+        let service = service.clone();
+        let ct = ct.clone();
+        tokio::spawn(async move {
+            let connection = hyper::server::conn::http1::Builder::new().serve_connection(
+                TokioIo::new(stream),
+                hyper::service::service_fn(move |request| {
+                    let service = service.clone();
+                    async move { Ok::<_, Infallible>(service.handle(request).await) }
+                }),
+            );
+            let mut connection = std::pin::pin!(connection);
+            let result = tokio::select! {
+                result = connection.as_mut() => result,
+                _ = ct.cancelled() => {
+                    connection.as_mut().graceful_shutdown();
+                    connection.await
+                }
+            };
+            if let Err(e) = result {
+                tracing::debug!(error = %e, "sse connection closed with error");
+            }
+        });
+    }
+}
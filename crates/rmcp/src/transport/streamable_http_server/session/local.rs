@@ -19,7 +19,8 @@ use crate::{
     model::{
         CancelledNotificationParam, ClientJsonRpcMessage, ClientNotification, ClientRequest,
         JsonRpcNotification, JsonRpcRequest, Notification, ProgressNotificationParam,
-        ProgressToken, RequestId, ServerJsonRpcMessage, ServerNotification,
+        ProgressToken, RequestId, ResourceChunkNotificationParam, ServerJsonRpcMessage,
+        ServerNotification, ToolPartialResultNotificationParam,
     },
     transport::{
         WorkerTransport,
@@ -231,7 +232,8 @@ impl CachedTx {
         });
     }
 
-    async fn sync(&mut self, index: usize) -> Result<(), SessionError> {
+    /// Resend the cached messages after `last_index`, or all of them if it's `None`.
+    async fn sync(&mut self, last_index: Option<usize>) -> Result<(), SessionError> {
         let Some(front) = self.cache.front() else {
             return Ok(());
         };
@@ -240,7 +242,8 @@ impl CachedTx {
             .as_deref()
             .unwrap_or_default()
             .parse::<EventId>()?;
-        let sync_index = index.saturating_sub(front_event_id.index);
+        let sync_index =
+            last_index.map_or(0, |index| (index + 1).saturating_sub(front_event_id.index));
         if sync_index > self.cache.len() {
             // invalid index
             return Err(SessionError::InvalidEventId);
@@ -400,11 +403,21 @@ impl LocalSessionWorker {
     fn resolve_outbound_channel(&self, message: &ServerJsonRpcMessage) -> OutboundChannel {
         match &message {
             ServerJsonRpcMessage::Request(_) => OutboundChannel::Common,
+            // the partial results of a streaming tool and the chunks of a blob go along their
+            // response, as they're produced
             ServerJsonRpcMessage::Notification(JsonRpcNotification {
                 notification:
                     ServerNotification::ProgressNotification(Notification {
                         params: ProgressNotificationParam { progress_token, .. },
                         ..
+                    })
+                    | ServerNotification::ToolPartialResultNotification(Notification {
+                        params: ToolPartialResultNotificationParam { progress_token, .. },
+                        ..
+                    })
+                    | ServerNotification::ResourceChunkNotification(Notification {
+                        params: ResourceChunkNotificationParam { progress_token, .. },
+                        ..
                     }),
                 ..
             }) => {
@@ -490,6 +503,32 @@ impl LocalSessionWorker {
         }
         Ok(())
     }
+    /// Attach a new standalone stream, taking over the current one if it's still open.
+    ///
+    /// A client can reconnect before its old connection is torn down, the new stream wins and the
+    /// old one is closed by dropping its sender. Pending requests and cached messages are kept,
+    /// but on a take over nothing is replayed since the old stream already got it, the client can
+    /// resume with a `Last-Event-Id` to catch up instead.
+    async fn establish_common_channel(
+        &mut self,
+    ) -> Result<StreamableHttpMessageReceiver, SessionError> {
+        let (tx, rx) = tokio::sync::mpsc::channel(self.session_config.channel_capacity);
+        let old_tx = std::mem::replace(&mut self.common.tx, tx);
+        if old_tx.is_closed() {
+            self.common.sync(None).await?;
+        } else {
+            tracing::debug!(session_id = ?self.id, "standalone stream taken over by a new connection");
+        }
+        Ok(StreamableHttpMessageReceiver {
+            http_request_id: None,
+            inner: rx,
+        })
+    }
+    /// Resume a stream after its last received event.
+    ///
+    /// For a request wise stream only the sender is swapped: the request and its progress token
+    /// stay bound to the same channel, so the progress of a request still running keeps flowing
+    /// to the new connection once the missed messages are replayed.
     async fn resume(
         &mut self,
         last_event_id: EventId,
@@ -505,7 +544,7 @@ impl LocalSessionWorker {
                 request_wise.tx.tx = tx;
                 let index = last_event_id.index;
                 // sync messages after index
-                request_wise.tx.sync(index).await?;
+                request_wise.tx.sync(Some(index)).await?;
                 Ok(StreamableHttpMessageReceiver {
                     http_request_id: Some(http_request_id),
                     inner: rx,
@@ -517,7 +556,7 @@ impl LocalSessionWorker {
                 self.common.tx = tx;
                 let index = last_event_id.index;
                 // sync messages after index
-                self.common.sync(index).await?;
+                self.common.sync(Some(index)).await?;
                 Ok(StreamableHttpMessageReceiver {
                     http_request_id: None,
                     inner: rx,
@@ -539,6 +578,9 @@ pub enum SessionEvent {
         id: HttpRequestId,
         responder: oneshot::Sender<Result<(), SessionError>>,
     },
+    EstablishCommonChannel {
+        responder: oneshot::Sender<Result<StreamableHttpMessageReceiver, SessionError>>,
+    },
     Resume {
         last_event_id: EventId,
         responder: oneshot::Sender<Result<StreamableHttpMessageReceiver, SessionError>>,
@@ -630,18 +672,15 @@ impl LocalSessionHandle {
     }
 
     /// Establish a common channel for general purpose messages.
+    ///
+    /// If the session already has one, e.g. the client reconnected before the old connection was
+    /// torn down, the old channel is closed and replaced by the new one.
     pub async fn establish_common_channel(
         &self,
     ) -> Result<StreamableHttpMessageReceiver, SessionError> {
         let (tx, rx) = tokio::sync::oneshot::channel();
         self.event_tx
-            .send(SessionEvent::Resume {
-                last_event_id: EventId {
-                    http_request_id: None,
-                    index: 0,
-                },
-                responder: tx,
-            })
+            .send(SessionEvent::EstablishCommonChannel { responder: tx })
             .await
             .map_err(|_| SessionError::SessionServiceTerminated)?;
         rx.await
@@ -835,9 +874,18 @@ impl Worker for LocalSessionWorker {
                     id,
                     responder,
                 }) => {
-                    let _handle_result = self.tx_router.remove(&id);
+                    // drop the bindings too, later progress goes to the common channel
+                    if let Some(channel) = self.tx_router.remove(&id) {
+                        for resource in channel.resources {
+                            self.resource_router.remove(&resource);
+                        }
+                    }
                     let _ = responder.send(Ok(()));
                 }
+                InnerEvent::FromHttpService(SessionEvent::EstablishCommonChannel { responder }) => {
+                    let handle_result = self.establish_common_channel().await;
+                    let _ = responder.send(handle_result);
+                }
                 InnerEvent::FromHttpService(SessionEvent::Resume {
                     last_event_id,
                     responder,
@@ -10,17 +10,17 @@ use tokio_stream::wrappers::ReceiverStream;
 use super::session::SessionManager;
 use crate::{
     RoleServer,
-    model::{ClientJsonRpcMessage, ClientRequest, GetExtensions},
+    model::{ClientJsonRpcMessage, ClientRequest, GetExtensions, JsonLimits, JsonRpcParseMode},
     serve_server,
     service::serve_directly,
     transport::{
-        OneshotTransport, TransportAdapterIdentity,
+        OneshotTransport, TimeoutTransport, TransportAdapterIdentity, TransportTimeouts,
         common::{
             http_header::{
                 EVENT_STREAM_MIME_TYPE, HEADER_LAST_EVENT_ID, HEADER_SESSION_ID, JSON_MIME_TYPE,
             },
             server_side_http::{
-                BoxResponse, ServerSseMessage, accepted_response, expect_json,
+                BoxResponse, Cors, HealthCheck, ServerSseMessage, accepted_response, expect_json,
                 internal_error_response, sse_stream_response, unexpected_message_response,
             },
         },
@@ -33,19 +33,49 @@ pub struct StreamableHttpServerConfig {
     pub sse_keep_alive: Option<Duration>,
     /// If true, the server will create a session for each request and keep it alive.
     pub stateful_mode: bool,
+    /// Answer `GET` on the path of the health check for load balancers, see [`HealthCheck`].
+    ///
+    /// The path is relative to where the service is mounted, drain it through a clone kept
+    /// before building the config.
+    pub health_check: Option<HealthCheck>,
+    /// The limits on the messages posted by clients, a message exceeding them is answered with
+    /// `400 Bad Request` and a JSON-RPC parse error.
+    pub json_limits: JsonLimits,
+    /// How strictly the envelope of the messages posted by clients is checked,
+    /// [`JsonRpcParseMode::Strict`] by default. A message rejected by it is answered with
+    /// `400 Bad Request` and a JSON-RPC parse error.
+    pub parse_mode: JsonRpcParseMode,
+    /// The CORS policy for browser clients, see [`Cors`], none by default.
+    pub cors: Option<Cors>,
+    /// The read and write timeouts of the stateful sessions, closing the stalled ones, see
+    /// [`TimeoutTransport`]. A stateless request ends with its response anyway.
+    pub timeouts: TransportTimeouts,
 }
 
 impl Default for StreamableHttpServerConfig {
+    /// Stateful, with a keep alive every 15 seconds, and without health check, CORS or timeouts
     fn default() -> Self {
         Self {
             sse_keep_alive: Some(Duration::from_secs(15)),
             stateful_mode: true,
+            health_check: None,
+            json_limits: JsonLimits::default(),
+            parse_mode: JsonRpcParseMode::default(),
+            cors: None,
+            timeouts: TransportTimeouts::default(),
         }
     }
 }
 
 /// # Streamable Http Server
 ///
+/// ## Streamed responses
+///
+/// The response to a request is an event stream, sent with chunked transfer encoding as the
+/// messages are produced: the progress and the partial results of a `#[tool(streaming)]` tool
+/// reach the client before the tool is done. Compressing it would buffer the events, the default
+/// predicate of `tower_http`'s `CompressionLayer` already leaves `text/event-stream` alone.
+///
 /// ## Extract information from raw http request
 ///
 /// The http service will consume the request body, however the rest part will be remain and injected into [`crate::model::Extensions`],
@@ -119,11 +149,32 @@ where
         (self.service_factory)()
     }
     pub async fn handle<B>(&self, request: Request<B>) -> Response<BoxBody<Bytes, Infallible>>
+    where
+        B: Body + Send + 'static,
+        B::Error: Display,
+    {
+        let Some(cors) = &self.config.cors else {
+            return self.route(request).await;
+        };
+        if let Some(preflight) = cors.preflight(&request) {
+            return preflight;
+        }
+        let origin = request.headers().get(http::header::ORIGIN).cloned();
+        let mut response = self.route(request).await;
+        cors.apply(origin.as_ref(), &mut response);
+        response
+    }
+    async fn route<B>(&self, request: Request<B>) -> BoxResponse
     where
         B: Body + Send + 'static,
         B::Error: Display,
     {
         let method = request.method().clone();
+        if let Some(health_check) = &self.config.health_check {
+            if method == Method::GET && request.uri().path() == health_check.path {
+                return health_check.response(false);
+            }
+        }
         let allowed_methods = match self.config.stateful_mode {
             true => "GET, POST, DELETE",
             false => "POST",
@@ -261,10 +312,11 @@ where
 
         // json deserialize request body
         let (part, body) = request.into_parts();
-        let mut message = match expect_json(body).await {
-            Ok(message) => message,
-            Err(response) => return Ok(response),
-        };
+        let mut message =
+            match expect_json(body, &self.config.json_limits, self.config.parse_mode).await {
+                Ok(message) => message,
+                Err(response) => return Ok(response),
+            };
 
         if self.config.stateful_mode {
             // do we have a session id?
@@ -338,14 +390,18 @@ where
                 let service = self
                     .get_service()
                     .map_err(internal_error_response("get service"))?;
+                let transport = TimeoutTransport::new(transport, self.config.timeouts);
                 // spawn a task to serve the session
                 tokio::spawn({
                     let session_manager = self.session_manager.clone();
                     let session_id = session_id.clone();
                     async move {
-                        let service = serve_server::<S, M::Transport, _, TransportAdapterIdentity>(
-                            service, transport,
-                        )
+                        let service = serve_server::<
+                            S,
+                            TimeoutTransport<M::Transport>,
+                            _,
+                            TransportAdapterIdentity,
+                        >(service, transport)
                         .await;
                         match service {
                             Ok(service) => {
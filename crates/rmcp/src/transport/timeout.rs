@@ -0,0 +1,187 @@
+//! Close a connection which stalls, like a half-open TCP connection, see [`TimeoutTransport`].
+use std::time::Duration;
+
+use thiserror::Error;
+use tokio_util::sync::CancellationToken;
+
+use super::Transport;
+use crate::{
+    model::{SessionClosedNotification, SessionClosedNotificationParam},
+    service::{RxJsonRpcMessage, ServiceRole, TxJsonRpcMessage},
+};
+
+/// The read and write timeouts of a [`TimeoutTransport`], both disabled by default
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TransportTimeouts {
+    /// The longest time without receiving any message
+    pub read: Option<Duration>,
+    /// The longest time sending a message may take
+    pub write: Option<Duration>,
+}
+
+impl TransportTimeouts {
+    pub fn with_read(mut self, read: Duration) -> Self {
+        self.read = Some(read);
+        self
+    }
+
+    pub fn with_write(mut self, write: Duration) -> Self {
+        self.write = Some(write);
+        self
+    }
+}
+
+/// Which timeout of a [`TimeoutTransport`] expired
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+pub enum TransportTimeout {
+    #[error("no message received for {0:?}")]
+    Read(Duration),
+    #[error("no message could be sent for {0:?}")]
+    Write(Duration),
+}
+
+#[derive(Debug, Error)]
+pub enum TimeoutTransportError<E> {
+    #[error("{0}")]
+    Transport(#[source] E),
+    #[error(transparent)]
+    Timeout(TransportTimeout),
+}
+
+/// Wraps a transport to close the session once it stalls.
+///
+/// When no message is received for the read timeout, or sending a message takes longer than
+/// the write timeout, the session quits with [`QuitReason::TransportTimeout`](crate::service::QuitReason::TransportTimeout).
+/// The requests still waiting for a response fail with [`ServiceError::TransportTimeout`](crate::service::ServiceError::TransportTimeout).
+/// On a read timeout, the peer is also sent a `notifications/rmcp/session_closed` in case it's
+/// still there.
+///
+/// ```rust,ignore
+/// let timeouts = TransportTimeouts::default()
+///     .with_read(Duration::from_secs(300))
+///     .with_write(Duration::from_secs(10));
+/// let server = Movie::new()
+///     .serve(TimeoutTransport::new(stdio(), timeouts))
+///     .await?;
+/// ```
+///
+/// The SSE server wraps the transport of each session with its
+/// [`SseServerConfig::timeouts`](crate::transport::sse_server::SseServerConfig::timeouts), and
+/// the streamable HTTP server the transport of each stateful session with its
+/// [`StreamableHttpServerConfig::timeouts`](crate::transport::streamable_http_server::StreamableHttpServerConfig::timeouts).
+pub struct TimeoutTransport<T> {
+    transport: T,
+    timeouts: TransportTimeouts,
+    /// Cancelled once a message couldn't be sent in time
+    write_timed_out: CancellationToken,
+    timed_out: Option<TransportTimeout>,
+}
+
+impl<T> TimeoutTransport<T> {
+    pub fn new(transport: T, timeouts: TransportTimeouts) -> Self {
+        Self {
+            transport,
+            timeouts,
+            write_timed_out: CancellationToken::new(),
+            timed_out: None,
+        }
+    }
+
+    pub fn timeouts(&self) -> TransportTimeouts {
+        self.timeouts
+    }
+
+    pub fn into_inner(self) -> T {
+        self.transport
+    }
+}
+
+impl<R, T> Transport<R> for TimeoutTransport<T>
+where
+    R: ServiceRole,
+    T: Transport<R>,
+{
+    type Error = TimeoutTransportError<T::Error>;
+
+    fn name() -> std::borrow::Cow<'static, str> {
+        T::name()
+    }
+
+    fn send(
+        &mut self,
+        item: TxJsonRpcMessage<R>,
+    ) -> impl Future<Output = Result<(), Self::Error>> + Send + 'static {
+        let send = self.transport.send(item);
+        let write = self.timeouts.write;
+        let write_timed_out = self.write_timed_out.clone();
+        async move {
+            let Some(write) = write else {
+                return send.await.map_err(TimeoutTransportError::Transport);
+            };
+            match tokio::time::timeout(write, send).await {
+                Ok(result) => result.map_err(TimeoutTransportError::Transport),
+                Err(_) => {
+                    write_timed_out.cancel();
+                    Err(TimeoutTransportError::Timeout(TransportTimeout::Write(
+                        write,
+                    )))
+                }
+            }
+        }
+    }
+
+    async fn receive(&mut self) -> Option<RxJsonRpcMessage<R>> {
+        if self.timed_out.is_some() {
+            return None;
+        }
+        let read_timeout = self.timeouts.read;
+        let transport = &mut self.transport;
+        let read = async move {
+            match read_timeout {
+                Some(read) => tokio::time::timeout(read, transport.receive())
+                    .await
+                    .map_err(|_| read),
+                None => Ok(transport.receive().await),
+            }
+        };
+        let timeout = tokio::select! {
+            received = read => match received {
+                Ok(message) => return message,
+                Err(read) => TransportTimeout::Read(read),
+            },
+            _ = self.write_timed_out.cancelled() => {
+                TransportTimeout::Write(self.timeouts.write.unwrap_or_default())
+            }
+        };
+        tracing::warn!(%timeout, "closing the stalled session");
+        self.timed_out = Some(timeout);
+        // the peer may still be reading, if it doesn't either, don't wait for it longer
+        if let TransportTimeout::Read(read) = timeout {
+            let notification = SessionClosedNotification::new(SessionClosedNotificationParam {
+                reason: timeout.to_string(),
+            });
+            let send = self
+                .transport
+                .send(TxJsonRpcMessage::<R>::notification(notification.into()));
+            match tokio::time::timeout(self.timeouts.write.unwrap_or(read), send).await {
+                Ok(Ok(())) => {}
+                Ok(Err(error)) => {
+                    tracing::debug!(%error, "fail to send session closed notification")
+                }
+                Err(_) => tracing::debug!("timeout sending session closed notification"),
+            }
+        }
+        None
+    }
+
+    fn timed_out(&self) -> Option<TransportTimeout> {
+        self.timed_out
+    }
+
+    async fn close(&mut self) -> Result<(), Self::Error> {
+        self.transport
+            .close()
+            .await
+            .map_err(TimeoutTransportError::Transport)
+    }
+}
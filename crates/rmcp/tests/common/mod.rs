@@ -1,2 +1,3 @@
 pub mod calculator;
 pub mod handlers;
+pub mod movie;
@@ -0,0 +1,23 @@
+#![allow(dead_code)]
+use rmcp::{ClientHandler, RoleClient, ServerHandler, ServiceExt, service::RunningService};
+
+/// A server with the default handlers, for the tests which only need a peer to talk to
+#[derive(Debug, Clone, Default)]
+pub struct MovieServer;
+
+impl ServerHandler for MovieServer {}
+
+/// Serve `server` on one end of an in-memory duplex stream and `client` on the other
+pub async fn connect<S, C>(server: S, client: C) -> anyhow::Result<RunningService<RoleClient, C>>
+where
+    S: ServerHandler,
+    C: ClientHandler,
+{
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    tokio::spawn(async move {
+        let server = server.serve(server_transport).await?;
+        server.waiting().await?;
+        anyhow::Ok(())
+    });
+    Ok(client.serve(client_transport).await?)
+}
@@ -0,0 +1,89 @@
+use futures::StreamExt;
+use rmcp::{
+    RoleServer, ServerHandler,
+    handler::server::tool::ToolRouter,
+    model::{CallToolRequestParam, ProgressNotificationParam},
+    service::{Progress, RequestContext},
+    tool, tool_handler, tool_router,
+};
+
+mod common;
+use common::movie::connect;
+
+const STEPS: [&str; 3] = ["find the cinema", "pick the seats", "print the ticket"];
+
+#[derive(Clone)]
+struct MovieServer {
+    tool_router: ToolRouter<Self>,
+}
+
+#[tool_router]
+impl MovieServer {
+    fn new() -> Self {
+        Self {
+            tool_router: Self::tool_router(),
+        }
+    }
+
+    #[tool(description = "Book a movie ticket")]
+    async fn book_ticket(&self, context: RequestContext<RoleServer>) -> String {
+        let progress_token = context.meta.get_progress_token().expect("a progress token");
+        for (step, message) in STEPS.into_iter().enumerate() {
+            let _ = context
+                .peer
+                .notify_progress(ProgressNotificationParam {
+                    progress_token: progress_token.clone(),
+                    progress: (step + 1) as f64,
+                    total: Some(STEPS.len() as f64),
+                    message: Some(message.to_string()),
+                })
+                .await;
+        }
+        "万达影城 7排8座".to_string()
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for MovieServer {}
+
+fn book_ticket() -> CallToolRequestParam {
+    CallToolRequestParam {
+        name: "book_ticket".into(),
+        arguments: None,
+    }
+}
+
+#[tokio::test]
+async fn test_call_tool_with_progress() -> anyhow::Result<()> {
+    let client = connect(MovieServer::new(), ()).await?;
+
+    let (progress, result) = client.call_tool_with_progress(book_ticket());
+    // a concurrent call is tracked with its own token
+    let (other_progress, other_result) = client.call_tool_with_progress(book_ticket());
+    assert_ne!(progress.progress_token(), other_progress.progress_token());
+
+    let (updates, result, other_updates, other_result) = tokio::join!(
+        progress.collect::<Vec<Progress>>(),
+        result,
+        other_progress.collect::<Vec<Progress>>(),
+        other_result
+    );
+    assert_eq!(
+        result?.content[0].as_text().unwrap().text,
+        "万达影城 7排8座"
+    );
+    other_result?;
+    for updates in [updates, other_updates] {
+        assert_eq!(
+            updates
+                .iter()
+                .map(|update| update.message.as_deref())
+                .collect::<Vec<_>>(),
+            STEPS.map(Some)
+        );
+        assert_eq!(updates[2].fraction(), Some(1.0));
+    }
+
+    client.cancel().await?;
+    Ok(())
+}
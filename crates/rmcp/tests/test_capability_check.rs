@@ -0,0 +1,117 @@
+use rmcp::{
+    ErrorData, RoleServer, ServerHandler,
+    handler::server::{
+        consistency::{CapabilityMismatch, check_capabilities},
+        tool::ToolRouter,
+    },
+    model::{ListToolsResult, PaginatedRequestParam, ServerCapabilities, ServerInfo},
+    service::RequestContext,
+    tool, tool_handler, tool_router,
+};
+
+#[derive(Clone)]
+struct Movie {
+    tool_router: ToolRouter<Self>,
+    capabilities: ServerCapabilities,
+}
+
+#[tool_router]
+impl Movie {
+    fn new(capabilities: ServerCapabilities) -> Self {
+        Self {
+            tool_router: Self::tool_router(),
+            capabilities,
+        }
+    }
+
+    #[tool(description = "Get the current hot movies")]
+    fn get_hot_movies(&self) -> String {
+        "[]".to_string()
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for Movie {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo {
+            capabilities: self.capabilities.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+#[test]
+fn test_capabilities_match_the_routers() {
+    let movie = Movie::new(ServerCapabilities::builder().enable_tools().build());
+    assert!(check_capabilities(&movie, &movie.get_info().capabilities).is_empty());
+}
+
+#[test]
+fn test_mismatched_capabilities() {
+    // prompts advertised, tools forgotten
+    let movie = Movie::new(ServerCapabilities::builder().enable_prompts().build());
+    let mismatches = check_capabilities(&movie, &movie.get_info().capabilities);
+    assert_eq!(mismatches, vec![CapabilityMismatch::ToolsNotAdvertised]);
+    assert_eq!(
+        mismatches[0].to_string(),
+        "a tool router is registered but the tools capability isn't advertised"
+    );
+}
+
+/// Handles the tools by hand, without a router
+#[derive(Clone)]
+struct HandWrittenMovie;
+
+impl ServerHandler for HandWrittenMovie {
+    async fn list_tools(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListToolsResult, ErrorData> {
+        Ok(ListToolsResult::default())
+    }
+
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo {
+            capabilities: ServerCapabilities::builder().enable_tools().build(),
+            ..Default::default()
+        }
+    }
+}
+
+#[test]
+fn test_hand_written_handler_matches_its_capabilities() {
+    let movie = HandWrittenMovie;
+    assert!(check_capabilities(&movie, &movie.get_info().capabilities).is_empty());
+}
+
+/// Answers `handles_tools` itself along with `#[tool_handler]`
+#[derive(Clone)]
+struct ClosedMovie {
+    tool_router: ToolRouter<Self>,
+}
+
+#[tool_handler]
+impl ServerHandler for ClosedMovie {
+    fn handles_tools(&self) -> bool {
+        false
+    }
+
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo {
+            capabilities: ServerCapabilities::builder().enable_tools().build(),
+            ..Default::default()
+        }
+    }
+}
+
+#[test]
+fn test_handles_tools_defined_along_the_tool_handler() {
+    let movie = ClosedMovie {
+        tool_router: ToolRouter::new(),
+    };
+    assert_eq!(
+        check_capabilities(&movie, &movie.get_info().capabilities),
+        vec![CapabilityMismatch::ToolsNotHandled]
+    );
+}
@@ -0,0 +1,56 @@
+use rmcp::{
+    ClientHandler, ServiceExt,
+    model::{ClientCapabilities, ClientInfo},
+};
+use serde_json::json;
+
+mod common;
+use common::movie::MovieServer;
+
+#[derive(Clone)]
+struct MovieClient;
+
+impl ClientHandler for MovieClient {
+    fn get_info(&self) -> ClientInfo {
+        ClientInfo::default().with_capabilities(
+            ClientCapabilities::builder()
+                .enable_roots()
+                .enable_roots_list_changed()
+                .enable_sampling()
+                .enable_elicitation(),
+        )
+    }
+}
+
+/// The capabilities the server received in the initialize request of `client`
+async fn advertised(client: impl ClientHandler) -> anyhow::Result<serde_json::Value> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    let (server, client) = tokio::try_join!(
+        async { anyhow::Ok(MovieServer.serve(server_transport).await?) },
+        async { anyhow::Ok(client.serve(client_transport).await?) },
+    )?;
+    let capabilities = &server.peer_info().expect("initialized").capabilities;
+    let capabilities = serde_json::to_value(capabilities)?;
+    client.cancel().await?;
+    server.cancel().await?;
+    Ok(capabilities)
+}
+
+#[tokio::test]
+async fn test_advertised_capabilities_in_initialize_params() -> anyhow::Result<()> {
+    assert_eq!(
+        advertised(MovieClient).await?,
+        json!({
+            "roots": { "listChanged": true },
+            "sampling": {},
+            "elicitation": {}
+        })
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_nothing_advertised_by_default() -> anyhow::Result<()> {
+    assert_eq!(advertised(()).await?, json!({}));
+    Ok(())
+}
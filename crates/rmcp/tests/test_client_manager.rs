@@ -0,0 +1,188 @@
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+
+use rmcp::{
+    RoleClient, ServerHandler, ServiceExt,
+    handler::server::{tool::ToolRouter, wrapper::Parameters},
+    model::CallToolRequestParam,
+    service::{ClientInitializeError, ClientManager, ClientManagerError, RunningService},
+    tool, tool_handler, tool_router,
+};
+use serde_json::json;
+use tokio_util::sync::CancellationToken;
+
+#[derive(Clone)]
+struct MovieServer {
+    tool_router: ToolRouter<Self>,
+}
+
+#[tool_router]
+impl MovieServer {
+    fn new() -> Self {
+        Self {
+            tool_router: Self::tool_router(),
+        }
+    }
+
+    #[tool(description = "Get the cinemas nearby")]
+    fn get_cinema_list(&self) -> String {
+        "万达影城".to_string()
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for MovieServer {}
+
+#[derive(serde::Deserialize, schemars::JsonSchema)]
+struct ForecastRequest {
+    city: String,
+}
+
+#[derive(Clone)]
+struct WeatherServer {
+    tool_router: ToolRouter<Self>,
+}
+
+#[tool_router]
+impl WeatherServer {
+    fn new() -> Self {
+        Self {
+            tool_router: Self::tool_router(),
+        }
+    }
+
+    #[tool(description = "Get the weather forecast of a city")]
+    fn get_forecast(
+        &self,
+        Parameters(ForecastRequest { city }): Parameters<ForecastRequest>,
+    ) -> String {
+        format!("{city}: sunny")
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for WeatherServer {}
+
+/// Serve `server` in memory until `stop` is cancelled
+async fn serve_in_memory<S: ServerHandler>(
+    server: S,
+    stop: CancellationToken,
+) -> Result<RunningService<RoleClient, ()>, ClientInitializeError> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    tokio::spawn(async move {
+        let server = server.serve(server_transport).await?;
+        stop.cancelled().await;
+        server.cancel().await?;
+        anyhow::Ok(())
+    });
+    ().serve(client_transport).await
+}
+
+#[tokio::test]
+async fn test_client_manager_lists_and_calls_all_tools() -> anyhow::Result<()> {
+    let manager = ClientManager::new();
+    manager
+        .connect("weather", || {
+            serve_in_memory(WeatherServer::new(), CancellationToken::new())
+        })
+        .await?;
+    manager
+        .connect("movie", || {
+            serve_in_memory(MovieServer::new(), CancellationToken::new())
+        })
+        .await?;
+    assert_eq!(manager.servers(), ["movie", "weather"]);
+
+    let mut names: Vec<_> = manager
+        .list_all_tools()
+        .await?
+        .into_iter()
+        .map(|tool| tool.name.to_string())
+        .collect();
+    names.sort();
+    assert_eq!(names, ["movie__get_cinema_list", "weather__get_forecast"]);
+
+    let result = manager
+        .call_tool(CallToolRequestParam {
+            name: "weather__get_forecast".into(),
+            arguments: json!({ "city": "北京" }).as_object().cloned(),
+        })
+        .await?;
+    assert_eq!(result.content[0].as_text().unwrap().text, "北京: sunny");
+    let result = manager
+        .call_tool(CallToolRequestParam {
+            name: "movie__get_cinema_list".into(),
+            arguments: None,
+        })
+        .await?;
+    assert_eq!(result.content[0].as_text().unwrap().text, "万达影城");
+
+    let error = manager
+        .call_tool(CallToolRequestParam {
+            name: "traffic__get_jams".into(),
+            arguments: None,
+        })
+        .await
+        .unwrap_err();
+    assert!(matches!(error, ClientManagerError::UnknownServer(name) if name == "traffic"));
+    for name in ["a__b", "movie_", "_movie", ""] {
+        assert!(matches!(
+            manager
+                .connect(name, || serve_in_memory(
+                    MovieServer::new(),
+                    CancellationToken::new()
+                ))
+                .await,
+            Err(ClientManagerError::InvalidServerName(_))
+        ));
+    }
+
+    assert!(manager.remove("movie"));
+    assert_eq!(manager.servers(), ["weather"]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_client_manager_reconnects_unhealthy_servers() -> anyhow::Result<()> {
+    let manager = ClientManager::new();
+    let connections = Arc::new(AtomicUsize::new(0));
+    let stop = CancellationToken::new();
+    manager
+        .connect("movie", {
+            let connections = connections.clone();
+            let stop = stop.clone();
+            move || {
+                // only the first connection is stopped
+                let stop = match connections.fetch_add(1, Ordering::SeqCst) {
+                    0 => stop.clone(),
+                    _ => CancellationToken::new(),
+                };
+                serve_in_memory(MovieServer::new(), stop)
+            }
+        })
+        .await?;
+    assert!(manager.check_health().await["movie"].is_ok());
+    assert!(manager.reconnect_unhealthy().await.is_empty());
+
+    stop.cancel();
+    let peer = manager.peer("movie").expect("connected");
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while !peer.is_transport_closed() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await?;
+    assert!(manager.check_health().await["movie"].is_err());
+    let reconnected = manager.reconnect_unhealthy().await;
+    assert!(reconnected["movie"].is_ok());
+    assert_eq!(connections.load(Ordering::SeqCst), 2);
+
+    assert!(manager.check_health().await["movie"].is_ok());
+    assert_eq!(manager.list_all_tools().await?.len(), 1);
+    Ok(())
+}
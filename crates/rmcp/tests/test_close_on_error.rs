@@ -0,0 +1,198 @@
+use std::time::Duration;
+
+use rmcp::{
+    ErrorData, ServerHandler, ServiceExt,
+    handler::server::{router::tool::ToolRouter, wrapper::Parameters},
+    model::{
+        CallToolRequestParam, ClientJsonRpcMessage, ClientRequest, CustomRequest, ErrorCode,
+        InitializeRequestParam, InitializeResult, RequestId, ServerJsonRpcMessage,
+    },
+    service::{
+        CloseOnError, ErrorAction, ErrorCategory, QuitReason, RequestContext, RoleServer,
+        ServiceError, serve_directly,
+    },
+    tool, tool_handler, tool_router,
+    transport::Transport,
+};
+use serde_json::json;
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct MovieDetailRequest {
+    pub movie_id: i32,
+}
+
+#[derive(Clone)]
+struct MovieServer {
+    tool_router: ToolRouter<Self>,
+}
+
+#[tool_router]
+impl MovieServer {
+    fn new() -> Self {
+        Self {
+            tool_router: Self::tool_router(),
+        }
+    }
+
+    #[tool(description = "Get movie details based on the movie ID")]
+    fn get_movie_detail_info(
+        &self,
+        Parameters(request): Parameters<MovieDetailRequest>,
+    ) -> Result<String, ErrorData> {
+        Err(ErrorData::internal_error(
+            format!("the details of movie {} are unavailable", request.movie_id),
+            None,
+        ))
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for MovieServer {
+    async fn initialize(
+        &self,
+        _request: InitializeRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<InitializeResult, ErrorData> {
+        context.peer.set_close_on_error(CloseOnError::strict());
+        Ok(self.get_info())
+    }
+}
+
+#[test]
+fn test_error_categories() {
+    assert_eq!(
+        ErrorCategory::of(ErrorCode::METHOD_NOT_FOUND),
+        ErrorCategory::Protocol
+    );
+    assert_eq!(
+        ErrorCategory::of(ErrorCode::INVALID_PARAMS),
+        ErrorCategory::Application
+    );
+    assert_eq!(
+        CloseOnError::default().action(ErrorCategory::Protocol),
+        ErrorAction::Continue
+    );
+    assert_eq!(
+        CloseOnError::strict().action(ErrorCategory::Protocol),
+        ErrorAction::Close
+    );
+}
+
+#[tokio::test]
+async fn test_close_on_protocol_violation_only() -> anyhow::Result<()> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    let server = tokio::spawn(async move {
+        let server = MovieServer::new().serve(server_transport).await?;
+        anyhow::Ok(server.waiting().await?)
+    });
+    let client = ().serve(client_transport).await?;
+
+    // an application error, the session goes on
+    let error = client
+        .call_tool(CallToolRequestParam {
+            name: "get_movie_detail_info".into(),
+            arguments: json!({ "movie_id": 1297 }).as_object().cloned(),
+        })
+        .await
+        .expect_err("application error");
+    assert!(
+        matches!(error, ServiceError::McpError(ref error) if error.code == ErrorCode::INTERNAL_ERROR)
+    );
+    assert_eq!(client.list_all_tools().await?.len(), 1);
+
+    // a method which doesn't exist, the session is closed after the error
+    let error = client
+        .send_request(ClientRequest::CustomRequest(CustomRequest::new(
+            "movies/unknown",
+            None,
+        )))
+        .await
+        .expect_err("protocol violation");
+    assert!(
+        matches!(error, ServiceError::McpError(ref error) if error.code == ErrorCode::METHOD_NOT_FOUND),
+        "{error:?}"
+    );
+
+    let quit_reason = server.await??;
+    assert!(
+        matches!(&quit_reason, QuitReason::SessionClosed { reason, by_peer: false } if reason.contains("protocol")),
+        "{quit_reason:?}"
+    );
+    let quit_reason = client.waiting().await?;
+    assert!(
+        matches!(quit_reason, QuitReason::SessionClosed { by_peer: true, .. }),
+        "{quit_reason:?}"
+    );
+    Ok(())
+}
+
+/// A transport whose peer never reads the errors and the notifications sent to it
+struct StalledTransport {
+    rx: tokio::sync::mpsc::Receiver<ClientJsonRpcMessage>,
+}
+
+impl Transport<RoleServer> for StalledTransport {
+    type Error = std::io::Error;
+
+    fn send(
+        &mut self,
+        item: ServerJsonRpcMessage,
+    ) -> impl Future<Output = Result<(), Self::Error>> + Send + 'static {
+        let stalled = matches!(
+            item,
+            ServerJsonRpcMessage::Error(_) | ServerJsonRpcMessage::Notification(_)
+        );
+        async move {
+            if stalled {
+                std::future::pending::<()>().await;
+            }
+            Ok(())
+        }
+    }
+
+    fn receive(&mut self) -> impl Future<Output = Option<ClientJsonRpcMessage>> + Send {
+        self.rx.recv()
+    }
+
+    async fn close(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_stalled_close_can_be_cancelled() -> anyhow::Result<()> {
+    let (tx, rx) = tokio::sync::mpsc::channel(1);
+    let server = serve_directly(MovieServer::new(), StalledTransport { rx }, None);
+    server.peer().set_close_on_error(CloseOnError::strict());
+
+    let request = ClientRequest::CustomRequest(CustomRequest::new("movies/unknown", None));
+    tx.send(ClientJsonRpcMessage::request(request, RequestId::Number(1)))
+        .await?;
+    // let the server answer the error, and get stuck sending it
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let quit_reason = tokio::time::timeout(Duration::from_secs(1), server.cancel()).await??;
+    assert!(
+        matches!(quit_reason, QuitReason::Cancelled),
+        "{quit_reason:?}"
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_stalled_peer_close_can_be_cancelled() -> anyhow::Result<()> {
+    let (_tx, rx) = tokio::sync::mpsc::channel(1);
+    let server = serve_directly(MovieServer::new(), StalledTransport { rx }, None);
+    let peer = server.peer().clone();
+    let close = tokio::spawn(async move { peer.close("maintenance").await });
+    // let the server get stuck sending the session closed notification
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let quit_reason = tokio::time::timeout(Duration::from_secs(1), server.cancel()).await??;
+    assert!(
+        matches!(quit_reason, QuitReason::Cancelled),
+        "{quit_reason:?}"
+    );
+    tokio::time::timeout(Duration::from_secs(1), close).await???;
+    Ok(())
+}
@@ -0,0 +1,113 @@
+use rmcp::{
+    ClientHandler, ErrorData, RoleClient, RoleServer, ServerHandler, ServiceExt,
+    model::{
+        CallToolRequestParam, CallToolResult, ConfigChangedNotificationParam, Content,
+        ServerCapabilities, ServerInfo, ServerJsonRpcMessage, ServerNotification,
+    },
+    service::{NotificationContext, RequestContext},
+};
+use serde::Deserialize;
+use serde_json::json;
+use tokio::sync::mpsc;
+
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct MovieConfig {
+    default_city: String,
+}
+
+struct MovieServer;
+
+impl ServerHandler for MovieServer {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo {
+            capabilities: ServerCapabilities::builder().enable_tools().build(),
+            ..Default::default()
+        }
+    }
+
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let city = request
+            .arguments
+            .as_ref()
+            .and_then(|arguments| arguments.get("city")?.as_str())
+            .map(str::to_owned)
+            .unwrap_or_default();
+        context
+            .notify_config_changed(json!({ "defaultCity": city }))
+            .await
+            .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "default city set to {city}"
+        ))]))
+    }
+}
+
+struct MovieClient {
+    configs: mpsc::UnboundedSender<MovieConfig>,
+}
+
+impl ClientHandler for MovieClient {
+    async fn on_config_changed(
+        &self,
+        params: ConfigChangedNotificationParam,
+        _context: NotificationContext<RoleClient>,
+    ) {
+        let config = params.config_as().expect("a movie config");
+        let _ = self.configs.send(config);
+    }
+}
+
+#[tokio::test]
+async fn test_config_changed_round_trip() -> anyhow::Result<()> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    tokio::spawn(async move {
+        MovieServer.serve(server_transport).await?.waiting().await?;
+        anyhow::Ok(())
+    });
+    let (configs, mut received) = mpsc::unbounded_channel();
+    let client = MovieClient { configs }.serve(client_transport).await?;
+
+    client
+        .call_tool(CallToolRequestParam {
+            name: "set_default_city".into(),
+            arguments: json!({ "city": "上海" }).as_object().cloned(),
+        })
+        .await?;
+    assert_eq!(
+        received.recv().await,
+        Some(MovieConfig {
+            default_city: "上海".into()
+        })
+    );
+
+    client.cancel().await?;
+    Ok(())
+}
+
+#[test]
+fn test_config_changed_method() {
+    let message: ServerJsonRpcMessage = serde_json::from_value(json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/rmcp/config_changed",
+        "params": { "config": { "defaultCity": "北京" } }
+    }))
+    .expect("a config changed notification");
+    let ServerJsonRpcMessage::Notification(notification) = message else {
+        panic!("expected a notification");
+    };
+    let ServerNotification::ConfigChangedNotification(notification) = notification.notification
+    else {
+        panic!("expected a config changed notification");
+    };
+    assert_eq!(
+        notification.params.config_as::<MovieConfig>().unwrap(),
+        MovieConfig {
+            default_city: "北京".into()
+        }
+    );
+}
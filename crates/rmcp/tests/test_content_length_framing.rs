@@ -0,0 +1,47 @@
+use rmcp::{
+    ServiceExt,
+    transport::async_rw::{AsyncRwTransport, Framing},
+};
+use tokio::io::AsyncReadExt;
+mod common;
+use common::calculator::Calculator;
+
+#[tokio::test]
+async fn test_content_length_framing() -> anyhow::Result<()> {
+    let (server_stdin, client_stdin) = tokio::io::simplex(4096);
+    let (mut server_output, server_stdout) = tokio::io::simplex(4096);
+    let (client_stdout, mut stdout) = tokio::io::simplex(4096);
+    tokio::spawn(async move {
+        let transport = AsyncRwTransport::new_server(server_stdin, server_stdout)
+            .with_framing(Framing::ContentLength);
+        let server = Calculator::new().serve(transport).await?;
+        server.waiting().await?;
+        anyhow::Ok(())
+    });
+    // check what the server writes, then pass it on to the client
+    let (first_bytes_tx, first_bytes) = tokio::sync::oneshot::channel();
+    tokio::spawn(async move {
+        let mut first_bytes_tx = Some(first_bytes_tx);
+        let mut chunk = vec![0; 4096];
+        loop {
+            let n = server_output.read(&mut chunk).await?;
+            if n == 0 {
+                break;
+            }
+            if let Some(tx) = first_bytes_tx.take() {
+                let _ = tx.send(chunk[..n].to_vec());
+            }
+            tokio::io::AsyncWriteExt::write_all(&mut stdout, &chunk[..n]).await?;
+        }
+        anyhow::Ok(())
+    });
+
+    let transport = AsyncRwTransport::new_client(client_stdout, client_stdin)
+        .with_framing(Framing::ContentLength);
+    let client = ().serve(transport).await?;
+    assert!(first_bytes.await?.starts_with(b"Content-Length: "));
+    assert_eq!(client.server_instructions(), Some("A simple calculator"));
+    client.list_all_tools().await?;
+    client.cancel().await?;
+    Ok(())
+}
@@ -0,0 +1,134 @@
+use rmcp::{
+    ServerHandler,
+    handler::server::tool::{Accept, ToolRouter},
+    model::{
+        CallToolRequestParam, CallToolResult, ClientInfo, ClientRequest, Content, Meta,
+        Representations, Request, ServerResult,
+    },
+    service::PeerRequestOptions,
+    tool, tool_handler, tool_router,
+};
+use serde_json::json;
+
+mod common;
+use common::movie::connect;
+
+const MARKDOWN: &str = "text/markdown";
+const JSON: &str = "application/json";
+
+#[derive(Clone)]
+struct MovieServer {
+    tool_router: ToolRouter<Self>,
+}
+
+#[tool_router]
+impl MovieServer {
+    fn new() -> Self {
+        Self {
+            tool_router: Self::tool_router(),
+        }
+    }
+
+    #[tool(description = "Get the details of a movie, as markdown or JSON")]
+    fn get_movie_detail_info(&self, accept: Accept) -> Result<CallToolResult, rmcp::ErrorData> {
+        let movie = json!({ "id": 1297, "name": "哪吒之魔童闹海", "score": 9.7 });
+        Ok(accept.negotiate(
+            Representations::new()
+                .with(
+                    MARKDOWN,
+                    Content::text("# 哪吒之魔童闹海\n\nScore: **9.7**"),
+                )
+                .with(JSON, Content::json(movie)?),
+        ))
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for MovieServer {}
+
+/// Call the tool, returning the content type picked and the text of the result
+async fn get_movie_detail_info(
+    client_accept: Option<&[&str]>,
+    request_accept: Option<&[&str]>,
+) -> anyhow::Result<(String, String)> {
+    let mut client_info = ClientInfo::default();
+    if let Some(accept) = client_accept {
+        client_info.capabilities.set_accept(accept.iter().copied());
+    }
+    let client = connect(MovieServer::new(), client_info).await?;
+
+    let mut meta = Meta::new();
+    if let Some(accept) = request_accept {
+        meta.set_accept(accept.iter().copied());
+    }
+    let response = client
+        .send_request_with_option(
+            ClientRequest::CallToolRequest(Request::new(CallToolRequestParam {
+                name: "get_movie_detail_info".into(),
+                arguments: None,
+            })),
+            PeerRequestOptions {
+                timeout: None,
+                meta: Some(meta),
+            },
+        )
+        .await?
+        .await_response()
+        .await?;
+    let ServerResult::CallToolResult(result) = response else {
+        panic!("expected call tool result, got {response:?}");
+    };
+    client.cancel().await?;
+    let content_type = result
+        .meta
+        .as_ref()
+        .and_then(Meta::content_type)
+        .expect("content type")
+        .to_owned();
+    assert_eq!(result.content.len(), 1);
+    let text = result.content[0]
+        .as_text()
+        .expect("text content")
+        .text
+        .clone();
+    Ok((content_type, text))
+}
+
+#[tokio::test]
+async fn test_negotiate_markdown_or_json() -> anyhow::Result<()> {
+    // the first representation offered without a preference
+    let (content_type, text) = get_movie_detail_info(None, None).await?;
+    assert_eq!(content_type, MARKDOWN);
+    assert!(text.starts_with("# 哪吒之魔童闹海"));
+
+    let (content_type, text) = get_movie_detail_info(None, Some(&[JSON])).await?;
+    assert_eq!(content_type, JSON);
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&text)?["score"],
+        9.7
+    );
+
+    // declared during initialization, and overridden by the request
+    let (content_type, _) = get_movie_detail_info(Some(&[JSON]), None).await?;
+    assert_eq!(content_type, JSON);
+    let (content_type, _) = get_movie_detail_info(Some(&[JSON]), Some(&[MARKDOWN])).await?;
+    assert_eq!(content_type, MARKDOWN);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_negotiate_in_order_of_preference() -> anyhow::Result<()> {
+    // the types not offered are skipped
+    let (content_type, _) =
+        get_movie_detail_info(None, Some(&["text/html", JSON, MARKDOWN])).await?;
+    assert_eq!(content_type, JSON);
+    let (content_type, _) = get_movie_detail_info(None, Some(&["application/*"])).await?;
+    assert_eq!(content_type, JSON);
+    let (content_type, _) =
+        get_movie_detail_info(None, Some(&["text/markdown; charset=utf-8"])).await?;
+    assert_eq!(content_type, MARKDOWN);
+    // nothing acceptable, fall back to the first representation offered
+    let (content_type, _) = get_movie_detail_info(None, Some(&["image/png"])).await?;
+    assert_eq!(content_type, MARKDOWN);
+    Ok(())
+}
@@ -0,0 +1,246 @@
+use std::time::Duration;
+
+use rmcp::transport::{
+    HyperSseServer, SseServer, StreamableHttpServerConfig, StreamableHttpService,
+    common::server_side_http::{Cors, HealthCheck, InvalidCors},
+    sse_server::SseServerConfig,
+    streamable_http_server::session::local::LocalSessionManager,
+};
+use serde_json::json;
+use tokio_util::sync::CancellationToken;
+mod common;
+use common::calculator::Calculator;
+
+const MOVIE_ORIGIN: &str = "https://movie.example.com";
+
+fn cors() -> Cors {
+    Cors {
+        allow_credentials: true,
+        max_age: Some(Duration::from_secs(60)),
+        ..Cors::new([MOVIE_ORIGIN])
+    }
+}
+
+fn sse_config(bind_address: &str, ct: CancellationToken) -> anyhow::Result<SseServerConfig> {
+    Ok(SseServerConfig {
+        bind: bind_address.parse()?,
+        ct,
+        health_check: Some(HealthCheck::default()),
+        cors: Some(cors()),
+        ..Default::default()
+    })
+}
+
+async fn preflight(url: &str, origin: &str) -> reqwest::Result<reqwest::Response> {
+    reqwest::Client::new()
+        .request(reqwest::Method::OPTIONS, url)
+        .header("Origin", origin)
+        .header("Access-Control-Request-Method", "POST")
+        .header(
+            "Access-Control-Request-Headers",
+            "content-type, mcp-session-id",
+        )
+        .send()
+        .await
+}
+
+/// Check the preflight of an allowed origin is answered with the configured headers, and the
+/// other origins are refused
+async fn check_preflight(url: &str) -> anyhow::Result<()> {
+    let response = preflight(url, MOVIE_ORIGIN).await?;
+    assert_eq!(response.status(), reqwest::StatusCode::NO_CONTENT);
+    let headers = response.headers();
+    assert_eq!(headers["access-control-allow-origin"], MOVIE_ORIGIN);
+    assert_eq!(headers["access-control-allow-methods"], "GET, POST, DELETE");
+    assert!(
+        headers["access-control-allow-headers"]
+            .to_str()?
+            .contains("mcp-session-id")
+    );
+    assert_eq!(headers["access-control-allow-credentials"], "true");
+    assert_eq!(headers["access-control-max-age"], "60");
+
+    let response = preflight(url, "https://evil.example.com").await?;
+    assert_eq!(response.status(), reqwest::StatusCode::FORBIDDEN);
+    assert!(
+        !response
+            .headers()
+            .contains_key("access-control-allow-origin")
+    );
+    Ok(())
+}
+
+/// Check a plain request carries the CORS headers for an allowed origin only
+async fn check_response(url: &str) -> anyhow::Result<()> {
+    let http = reqwest::Client::new();
+    let response = http.get(url).header("Origin", MOVIE_ORIGIN).send().await?;
+    assert!(response.status().is_success());
+    assert_eq!(
+        response.headers()["access-control-allow-origin"],
+        MOVIE_ORIGIN
+    );
+
+    let response = http
+        .get(url)
+        .header("Origin", "https://evil.example.com")
+        .send()
+        .await?;
+    assert!(response.status().is_success());
+    assert!(
+        !response
+            .headers()
+            .contains_key("access-control-allow-origin")
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sse_server_cors() -> anyhow::Result<()> {
+    const BIND_ADDRESS: &str = "127.0.0.1:8175";
+    let ct = CancellationToken::new();
+    let sse_server = SseServer::serve_with_config(sse_config(BIND_ADDRESS, ct.clone())?).await?;
+    sse_server.with_service(Calculator::default);
+
+    check_preflight(&format!("http://{BIND_ADDRESS}/message")).await?;
+    check_preflight(&format!("http://{BIND_ADDRESS}/sse")).await?;
+    check_response(&format!("http://{BIND_ADDRESS}/healthz")).await?;
+
+    ct.cancel();
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_hyper_sse_server_cors() -> anyhow::Result<()> {
+    const BIND_ADDRESS: &str = "127.0.0.1:8176";
+    let ct = CancellationToken::new();
+    let sse_server =
+        HyperSseServer::serve_with_config(sse_config(BIND_ADDRESS, ct.clone())?).await?;
+    sse_server.with_service(Calculator::default);
+
+    check_preflight(&format!("http://{BIND_ADDRESS}/message")).await?;
+    check_response(&format!("http://{BIND_ADDRESS}/healthz")).await?;
+
+    ct.cancel();
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_streamable_http_cors() -> anyhow::Result<()> {
+    const BIND_ADDRESS: &str = "127.0.0.1:8177";
+    let service: StreamableHttpService<Calculator, LocalSessionManager> =
+        StreamableHttpService::new(
+            || Ok(Calculator::new()),
+            Default::default(),
+            StreamableHttpServerConfig {
+                health_check: Some(HealthCheck::default()),
+                cors: Some(cors()),
+                ..Default::default()
+            },
+        );
+    let router = axum::Router::new().nest_service("/mcp", service);
+    let tcp_listener = tokio::net::TcpListener::bind(BIND_ADDRESS).await?;
+    let ct = CancellationToken::new();
+    let handle = tokio::spawn({
+        let ct = ct.clone();
+        async move {
+            let _ = axum::serve(tcp_listener, router)
+                .with_graceful_shutdown(async move { ct.cancelled_owned().await })
+                .await;
+        }
+    });
+
+    check_preflight(&format!("http://{BIND_ADDRESS}/mcp")).await?;
+    check_response(&format!("http://{BIND_ADDRESS}/mcp/healthz")).await?;
+
+    // the page can read the session id to send it back
+    let initialize = reqwest::Client::new()
+        .post(format!("http://{BIND_ADDRESS}/mcp"))
+        .header("Origin", MOVIE_ORIGIN)
+        .header("Accept", "application/json, text/event-stream")
+        .json(&json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "initialize",
+            "params": {
+                "protocolVersion": "2025-03-26",
+                "capabilities": {},
+                "clientInfo": { "name": "browser", "version": "0.0.1" }
+            }
+        }))
+        .send()
+        .await?;
+    let headers = initialize.headers();
+    assert!(headers.contains_key("mcp-session-id"));
+    assert_eq!(headers["access-control-allow-origin"], MOVIE_ORIGIN);
+    assert_eq!(headers["access-control-expose-headers"], "Mcp-Session-Id");
+
+    ct.cancel();
+    handle.await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_credentials_for_any_origin_rejected() -> anyhow::Result<()> {
+    const BIND_ADDRESS: &str = "127.0.0.1:8178";
+    let any_origin = Cors {
+        allow_credentials: true,
+        ..Cors::permissive()
+    };
+    assert!(matches!(
+        any_origin.validate(),
+        Err(InvalidCors::CredentialsForAnyOrigin)
+    ));
+    assert!(Cors::permissive().validate().is_ok());
+    assert!(cors().validate().is_ok());
+
+    let ct = CancellationToken::new();
+    let config = SseServerConfig {
+        cors: Some(any_origin.clone()),
+        ..sse_config(BIND_ADDRESS, ct.clone())?
+    };
+    let Err(error) = SseServer::serve_with_config(config.clone()).await else {
+        panic!("the policy is rejected");
+    };
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+    let Err(error) = HyperSseServer::serve_with_config(config).await else {
+        panic!("the policy is rejected");
+    };
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+
+    // a service built anyway never sends credentials to the origins matched by the wildcard
+    let service: StreamableHttpService<Calculator, LocalSessionManager> =
+        StreamableHttpService::new(
+            || Ok(Calculator::new()),
+            Default::default(),
+            StreamableHttpServerConfig {
+                cors: Some(any_origin),
+                ..Default::default()
+            },
+        );
+    let router = axum::Router::new().nest_service("/mcp", service);
+    let tcp_listener = tokio::net::TcpListener::bind(BIND_ADDRESS).await?;
+    let handle = tokio::spawn({
+        let ct = ct.clone();
+        async move {
+            let _ = axum::serve(tcp_listener, router)
+                .with_graceful_shutdown(async move { ct.cancelled_owned().await })
+                .await;
+        }
+    });
+    let response = preflight(
+        &format!("http://{BIND_ADDRESS}/mcp"),
+        "https://evil.example.com",
+    )
+    .await?;
+    assert_eq!(response.status(), reqwest::StatusCode::NO_CONTENT);
+    let headers = response.headers();
+    assert_eq!(
+        headers["access-control-allow-origin"],
+        "https://evil.example.com"
+    );
+    assert!(!headers.contains_key("access-control-allow-credentials"));
+
+    ct.cancel();
+    handle.await?;
+    Ok(())
+}
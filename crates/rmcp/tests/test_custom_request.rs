@@ -0,0 +1,154 @@
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
+
+use rmcp::{
+    ErrorData, RoleServer, ServerHandler, ServiceError, ServiceExt,
+    model::{CallToolRequestParam, ClientRequest, CustomRequest, CustomResult, ErrorCode},
+    service::RequestContext,
+};
+use serde_json::{Value, json};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, DuplexStream};
+
+#[derive(Clone, Default)]
+struct MovieServer {
+    refreshed: Arc<AtomicUsize>,
+}
+
+impl ServerHandler for MovieServer {
+    async fn on_custom_request(
+        &self,
+        request: CustomRequest,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<CustomResult, ErrorData> {
+        match request.method.as_str() {
+            "x-movie/refresh-cache" => {
+                let city = request
+                    .params
+                    .as_ref()
+                    .and_then(|params| params.get("city"))
+                    .cloned();
+                let count = self.refreshed.fetch_add(1, Ordering::SeqCst) + 1;
+                Ok(CustomResult::new(json!({ "city": city, "count": count })))
+            }
+            "x-movie/reset" => Ok(CustomResult::new(json!({}))),
+            method => Err(ErrorData::new(
+                ErrorCode::METHOD_NOT_FOUND,
+                method.to_string(),
+                None,
+            )),
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_custom_request() -> anyhow::Result<()> {
+    let server = MovieServer::default();
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    tokio::spawn({
+        let server = server.clone();
+        async move {
+            let server = server.serve(server_transport).await?;
+            server.waiting().await?;
+            anyhow::Ok(())
+        }
+    });
+    let client = ().serve(client_transport).await?;
+
+    let result = client
+        .send_custom_request(
+            "x-movie/refresh-cache",
+            json!({ "city": "北京" }).as_object().cloned(),
+        )
+        .await?;
+    assert_eq!(result, json!({ "city": "北京", "count": 1 }));
+    assert_eq!(server.refreshed.load(Ordering::SeqCst), 1);
+
+    let result = client.send_custom_request("x-movie/reset", None).await?;
+    assert_eq!(result, json!({}));
+
+    let Err(ServiceError::McpError(error)) =
+        client.send_custom_request("x-movie/unknown", None).await
+    else {
+        panic!("expect an unknown custom method to fail");
+    };
+    assert_eq!(error.code, ErrorCode::METHOD_NOT_FOUND);
+    assert_eq!(error.message, "x-movie/unknown");
+
+    // the core methods still work along with the custom ones
+    client.list_all_tools().await?;
+    client.cancel().await?;
+    Ok(())
+}
+
+#[test]
+fn test_core_methods_are_never_custom() {
+    let request: ClientRequest =
+        serde_json::from_value(json!({ "method": "x-movie/refresh-cache" })).unwrap();
+    assert!(matches!(request, ClientRequest::CustomRequest(_)));
+    assert_eq!(request.method(), "x-movie/refresh-cache");
+
+    let request: ClientRequest = serde_json::from_value(json!({ "method": "tools/list" })).unwrap();
+    assert!(matches!(request, ClientRequest::ListToolsRequest(_)));
+
+    // a malformed core request isn't taken for a custom one
+    let malformed = serde_json::from_value::<ClientRequest>(
+        json!({ "method": "tools/call", "params": { "arguments": 42 } }),
+    );
+    assert!(malformed.is_err());
+}
+
+/// A server answering `tools/call` with a malformed result, and `x-movie/refresh-cache` with
+/// the same one
+fn malformed_server(stream: DuplexStream) {
+    tokio::spawn(async move {
+        let (read, mut write) = tokio::io::split(stream);
+        let mut lines = BufReader::new(read).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            let message: Value = serde_json::from_str(&line).expect("json message");
+            let result = match message["method"].as_str() {
+                Some("initialize") => json!({
+                    "protocolVersion": "2025-06-18",
+                    "capabilities": { "tools": {} },
+                    "serverInfo": { "name": "movie server", "version": "0.1.0" }
+                }),
+                Some("tools/call" | "x-movie/refresh-cache") => json!({ "content": 42 }),
+                _ => continue,
+            };
+            let response = json!({ "jsonrpc": "2.0", "id": message["id"], "result": result });
+            let mut response = serde_json::to_vec(&response).expect("serializable");
+            response.push(b'\n');
+            if write.write_all(&response).await.is_err() {
+                break;
+            }
+        }
+    });
+}
+
+#[tokio::test]
+async fn test_malformed_result_is_a_decode_error() -> anyhow::Result<()> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    malformed_server(server_transport);
+    let client = ().serve(client_transport).await?;
+
+    let result = client
+        .call_tool(CallToolRequestParam {
+            name: "get_hot_movies".into(),
+            arguments: None,
+        })
+        .await;
+    let Err(ServiceError::InvalidResponse(error)) = result else {
+        panic!("expect a decode error, got {result:?}");
+    };
+    assert!(error.to_string().contains("invalid type"), "{error}");
+
+    // the same result is fine for a custom request
+    let result = client
+        .send_custom_request("x-movie/refresh-cache", None)
+        .await?;
+    assert_eq!(result, json!({ "content": 42 }));
+
+    client.cancel().await?;
+    Ok(())
+}
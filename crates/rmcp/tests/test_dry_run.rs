@@ -0,0 +1,100 @@
+use std::sync::{Arc, Mutex};
+
+use rmcp::{
+    RoleServer, ServerHandler,
+    handler::server::{tool::ToolRouter, wrapper::Parameters},
+    model::{CallToolRequestParam, ClientRequest, Meta, Request, ServerResult},
+    service::{PeerRequestOptions, RequestContext},
+    tool, tool_handler, tool_router,
+};
+
+mod common;
+use common::movie::connect;
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct DeleteRequest {
+    pub path: String,
+}
+
+#[derive(Clone)]
+pub struct FileServer {
+    deleted: Arc<Mutex<Vec<String>>>,
+    tool_router: ToolRouter<Self>,
+}
+
+#[tool_router]
+impl FileServer {
+    pub fn new(deleted: Arc<Mutex<Vec<String>>>) -> Self {
+        Self {
+            deleted,
+            tool_router: Self::tool_router(),
+        }
+    }
+
+    #[tool(description = "Delete a file")]
+    fn delete(
+        &self,
+        Parameters(DeleteRequest { path }): Parameters<DeleteRequest>,
+        ctx: RequestContext<RoleServer>,
+    ) -> String {
+        if ctx.is_dry_run() {
+            return format!("would delete {path}");
+        }
+        let message = format!("deleted {path}");
+        self.deleted.lock().unwrap().push(path);
+        message
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for FileServer {}
+
+async fn call_delete(deleted: Arc<Mutex<Vec<String>>>, dry_run: bool) -> anyhow::Result<String> {
+    let client = connect(FileServer::new(deleted), ()).await?;
+
+    let mut meta = Meta::new();
+    meta.set_dry_run(dry_run);
+    let response = client
+        .send_request_with_option(
+            ClientRequest::CallToolRequest(Request::new(CallToolRequestParam {
+                name: "delete".into(),
+                arguments: serde_json::json!({ "path": "notes.txt" })
+                    .as_object()
+                    .cloned(),
+            })),
+            PeerRequestOptions {
+                timeout: None,
+                meta: Some(meta),
+            },
+        )
+        .await?
+        .await_response()
+        .await?;
+    let ServerResult::CallToolResult(result) = response else {
+        panic!("expected call tool result, got {response:?}");
+    };
+    client.cancel().await?;
+    Ok(result.content[0]
+        .as_text()
+        .expect("text content")
+        .text
+        .clone())
+}
+
+#[tokio::test]
+async fn test_dry_run_returns_preview() -> anyhow::Result<()> {
+    let deleted = Arc::new(Mutex::new(Vec::new()));
+    let text = call_delete(deleted.clone(), true).await?;
+    assert_eq!(text, "would delete notes.txt");
+    assert!(deleted.lock().unwrap().is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_call_without_dry_run_executes() -> anyhow::Result<()> {
+    let deleted = Arc::new(Mutex::new(Vec::new()));
+    let text = call_delete(deleted.clone(), false).await?;
+    assert_eq!(text, "deleted notes.txt");
+    assert_eq!(*deleted.lock().unwrap(), ["notes.txt"]);
+    Ok(())
+}
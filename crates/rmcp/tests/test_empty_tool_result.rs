@@ -0,0 +1,80 @@
+use rmcp::{
+    ServerHandler,
+    handler::server::tool::ToolRouter,
+    model::{CallToolRequestParam, CallToolResult, Content},
+    tool, tool_handler, tool_router,
+};
+use serde_json::json;
+
+mod common;
+use common::movie::connect;
+
+#[derive(Clone)]
+struct MovieServer {
+    tool_router: ToolRouter<Self>,
+}
+
+#[tool_router]
+impl MovieServer {
+    fn new() -> Self {
+        Self {
+            tool_router: Self::tool_router(),
+        }
+    }
+
+    #[tool(description = "Refresh the movie schedules in the background")]
+    fn refresh_schedules(&self) {}
+
+    #[tool(description = "Clear the cached cinemas")]
+    fn clear_cache(&self) -> Result<CallToolResult, rmcp::ErrorData> {
+        Ok(CallToolResult::empty())
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for MovieServer {}
+
+#[test]
+fn test_empty_result_serialization() -> anyhow::Result<()> {
+    let result = CallToolResult::empty();
+    assert!(result.is_empty());
+    let value = serde_json::to_value(&result)?;
+    assert_eq!(value, json!({ "content": [], "isError": false }));
+
+    let result: CallToolResult = serde_json::from_value(value)?;
+    assert_eq!(result, CallToolResult::empty());
+    assert!(result.is_empty());
+
+    // without `isError`, it's a success as well
+    let result: CallToolResult = serde_json::from_value(json!({ "content": [] }))?;
+    assert!(result.is_empty());
+
+    // content is still required
+    assert!(serde_json::from_value::<CallToolResult>(json!({})).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_empty_is_not_an_error() {
+    assert!(!CallToolResult::error(vec![]).is_empty());
+    assert!(!CallToolResult::success(vec![Content::text("done")]).is_empty());
+    assert!(!CallToolResult::structured(json!({})).is_empty());
+}
+
+#[tokio::test]
+async fn test_client_receives_empty_result() -> anyhow::Result<()> {
+    let client = connect(MovieServer::new(), ()).await?;
+
+    for name in ["refresh_schedules", "clear_cache"] {
+        let result = client
+            .call_tool(CallToolRequestParam {
+                name: name.into(),
+                arguments: None,
+            })
+            .await?;
+        assert!(result.is_empty(), "{name}: {result:?}");
+    }
+
+    client.cancel().await?;
+    Ok(())
+}
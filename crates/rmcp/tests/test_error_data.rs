@@ -0,0 +1,38 @@
+use rmcp::{ErrorData, model::ErrorCode};
+
+#[derive(Debug, thiserror::Error)]
+#[error("failed to fetch movie detail")]
+struct FetchError {
+    #[source]
+    source: std::io::Error,
+}
+
+fn fetch_error() -> FetchError {
+    FetchError {
+        source: std::io::Error::other("connection reset by peer"),
+    }
+}
+
+#[test]
+fn test_from_error_with_chain_captures_source_chain() {
+    let error = ErrorData::from_error_with_chain(ErrorCode::INTERNAL_ERROR, &fetch_error());
+    assert_eq!(error.code, ErrorCode::INTERNAL_ERROR);
+    assert_eq!(error.message, "failed to fetch movie detail");
+    assert_eq!(
+        error.data,
+        Some(serde_json::json!({ "sourceChain": ["connection reset by peer"] }))
+    );
+
+    // without sources there is nothing to record
+    let error = ErrorData::from_error_with_chain(ErrorCode::INTERNAL_ERROR, &fetch_error().source);
+    assert_eq!(error.data, None);
+}
+
+#[test]
+fn test_from_error_captures_source_chain_in_debug_builds() {
+    let error = ErrorData::from_error(ErrorCode::INTERNAL_ERROR, &fetch_error());
+    assert_eq!(error.code, ErrorCode::INTERNAL_ERROR);
+    assert_eq!(error.message, "failed to fetch movie detail");
+    let chain = serde_json::json!({ "sourceChain": ["connection reset by peer"] });
+    assert_eq!(error.data, cfg!(debug_assertions).then_some(chain));
+}
@@ -0,0 +1,61 @@
+use rmcp::{
+    handler::server::fn_handler::ServerHandlerBuilder,
+    model::{CallToolRequestParam, CallToolResult, Content, ErrorCode, GetPromptRequestParam},
+};
+use serde_json::json;
+
+mod common;
+use common::movie::connect;
+
+#[tokio::test]
+async fn test_handler_from_call_tool_closure() -> anyhow::Result<()> {
+    let handler = ServerHandlerBuilder::new()
+        .call_tool(|request: CallToolRequestParam, _context| async move {
+            let name = request
+                .arguments
+                .as_ref()
+                .and_then(|arguments| arguments.get("name"))
+                .and_then(|name| name.as_str())
+                .unwrap_or("world")
+                .to_owned();
+            Ok(CallToolResult::success(vec![Content::text(format!(
+                "{} says hello, {name}",
+                request.name
+            ))]))
+        })
+        .build();
+
+    let client = connect(handler, ()).await?;
+
+    let server_info = client.peer_info().expect("initialized");
+    assert!(server_info.capabilities.tools.is_some());
+    assert!(server_info.capabilities.prompts.is_none());
+
+    let result = client
+        .call_tool(CallToolRequestParam {
+            name: "greet".into(),
+            arguments: json!({ "name": "rmcp" }).as_object().cloned(),
+        })
+        .await?;
+    assert_eq!(
+        result.content[0].as_text().unwrap().text,
+        "greet says hello, rmcp"
+    );
+
+    // the methods without a closure keep their defaults
+    assert!(client.list_all_prompts().await?.is_empty());
+    let error = client
+        .get_prompt(GetPromptRequestParam {
+            name: "missing".into(),
+            arguments: None,
+        })
+        .await
+        .unwrap_err();
+    match error {
+        rmcp::ServiceError::McpError(error) => assert_eq!(error.code, ErrorCode::METHOD_NOT_FOUND),
+        other => panic!("expected method not found, got {other:?}"),
+    }
+
+    client.cancel().await?;
+    Ok(())
+}
@@ -0,0 +1,197 @@
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
+
+use rmcp::{
+    ClientHandler, RoleServer, ServerHandler, ServiceError, ServiceExt,
+    handler::server::{router::prompt::PromptRouter, wrapper::Parameters},
+    model::{
+        GetPromptRequestParam, GetPromptResult, ListPromptsResult, PaginatedRequestParam,
+        PromptMessage, PromptMessageContent, PromptMessageRole, ServerCapabilities, ServerInfo,
+    },
+    prompt, prompt_router,
+    service::{NotificationContext, RequestContext},
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+struct MovieArgs {
+    /// The city to look for cinemas in
+    city: String,
+    /// Only the cinemas showing this movie
+    movie: Option<String>,
+}
+
+#[derive(Clone)]
+struct MovieServer {
+    prompt_router: PromptRouter<Self>,
+    /// How many times the prompts were listed
+    listed: Arc<AtomicUsize>,
+}
+
+#[prompt_router]
+impl MovieServer {
+    fn new() -> Self {
+        Self {
+            prompt_router: Self::prompt_router(),
+            listed: Default::default(),
+        }
+    }
+
+    #[prompt(name = "find-cinema")]
+    async fn find_cinema(
+        &self,
+        Parameters(MovieArgs { city, movie }): Parameters<MovieArgs>,
+    ) -> Vec<PromptMessage> {
+        let movie = movie.unwrap_or_else(|| "any movie".to_string());
+        vec![PromptMessage::new_text(
+            PromptMessageRole::User,
+            format!("Find a cinema in {city} showing {movie}"),
+        )]
+    }
+}
+
+impl ServerHandler for MovieServer {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo {
+            capabilities: ServerCapabilities::builder()
+                .enable_prompts()
+                .enable_prompts_list_changed()
+                .build(),
+            ..Default::default()
+        }
+    }
+
+    async fn list_prompts(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListPromptsResult, rmcp::ErrorData> {
+        self.listed.fetch_add(1, Ordering::SeqCst);
+        Ok(ListPromptsResult::with_all_items(
+            self.prompt_router.list_all(),
+        ))
+    }
+
+    async fn get_prompt(
+        &self,
+        request: GetPromptRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<GetPromptResult, rmcp::ErrorData> {
+        let context = rmcp::handler::server::prompt::PromptContext::new(
+            self,
+            request.name,
+            request.arguments,
+            context,
+        );
+        self.prompt_router.get_prompt(context).await
+    }
+}
+
+/// A client told when the prompts changed
+#[derive(Clone, Default)]
+struct Client {
+    prompts_changed: Arc<tokio::sync::Notify>,
+}
+
+impl ClientHandler for Client {
+    async fn on_prompt_list_changed(&self, _context: NotificationContext<rmcp::RoleClient>) {
+        self.prompts_changed.notify_one();
+    }
+}
+
+/// A typo of `city`
+#[derive(Serialize)]
+struct TypoArgs {
+    cty: String,
+}
+
+#[tokio::test]
+async fn test_get_prompt_typed() -> anyhow::Result<()> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    tokio::spawn(async move {
+        MovieServer::new()
+            .serve(server_transport)
+            .await?
+            .waiting()
+            .await?;
+        anyhow::Ok(())
+    });
+    let client = ().serve(client_transport).await?;
+
+    let args = MovieArgs {
+        city: "北京".into(),
+        movie: None,
+    };
+    let result = client.get_prompt_typed("find-cinema", &args).await?;
+    let PromptMessageContent::Text { text } = &result.messages[0].content else {
+        panic!("expected a text message");
+    };
+    assert_eq!(text, "Find a cinema in 北京 showing any movie");
+
+    let error = client
+        .get_prompt_typed(
+            "find-cinema",
+            &TypoArgs {
+                cty: "北京".into()
+            },
+        )
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(&error, ServiceError::InvalidPromptArguments { name, reason }
+            if name == "find-cinema" && reason.contains("unknown argument `cty`")),
+        "{error}"
+    );
+
+    let error = client
+        .get_prompt_typed("find-cinema", &())
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(&error, ServiceError::InvalidPromptArguments { reason, .. }
+            if reason.contains("missing required argument `city`")),
+        "{error}"
+    );
+
+    let error = client.get_prompt_typed("missing", &args).await.unwrap_err();
+    assert!(matches!(error, ServiceError::InvalidPromptArguments { .. }));
+
+    client.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_prompts_cached_until_list_changed() -> anyhow::Result<()> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    let server = MovieServer::new();
+    let listed = server.listed.clone();
+    let client = Client::default();
+    let prompts_changed = client.prompts_changed.clone();
+    let (server, client) = tokio::join!(
+        server.serve(server_transport),
+        client.serve(client_transport)
+    );
+    let (server, client) = (server?, client?);
+
+    let args = MovieArgs {
+        city: "上海".into(),
+        movie: Some("流浪地球".into()),
+    };
+    client.get_prompt_typed("find-cinema", &args).await?;
+    client.get_prompt_typed("find-cinema", &args).await?;
+    assert_eq!(listed.load(Ordering::SeqCst), 1);
+
+    server.notify_prompt_list_changed().await?;
+    prompts_changed.notified().await;
+    client.get_prompt_typed("find-cinema", &args).await?;
+    assert_eq!(listed.load(Ordering::SeqCst), 2);
+    assert_eq!(client.cached_prompts().await?.len(), 1);
+    assert_eq!(listed.load(Ordering::SeqCst), 2);
+
+    client.cancel().await?;
+    server.cancel().await?;
+    Ok(())
+}
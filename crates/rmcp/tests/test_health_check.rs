@@ -0,0 +1,100 @@
+use rmcp::transport::{
+    SseServer, StreamableHttpServerConfig,
+    common::server_side_http::HealthCheck,
+    sse_server::SseServerConfig,
+    streamable_http_server::{session::local::LocalSessionManager, tower::StreamableHttpService},
+};
+use tokio_util::sync::CancellationToken;
+mod common;
+use common::calculator::Calculator;
+
+const SSE_BIND_ADDRESS: &str = "127.0.0.1:8157";
+const STREAMABLE_BIND_ADDRESS: &str = "127.0.0.1:8158";
+
+async fn health(url: &str) -> reqwest::Result<reqwest::StatusCode> {
+    Ok(reqwest::get(url).await?.status())
+}
+
+#[tokio::test]
+async fn test_sse_server_health_check() -> anyhow::Result<()> {
+    let ct = CancellationToken::new();
+    let sse_server = SseServer::serve_with_config(SseServerConfig {
+        bind: SSE_BIND_ADDRESS.parse()?,
+        ct: ct.clone(),
+        health_check: Some(HealthCheck::default()),
+        ..Default::default()
+    })
+    .await?;
+    let url = format!("http://{SSE_BIND_ADDRESS}/healthz");
+    assert_eq!(health(&url).await?, reqwest::StatusCode::OK);
+
+    // the mcp endpoints are still served while draining
+    sse_server.drain();
+    assert_eq!(
+        health(&url).await?,
+        reqwest::StatusCode::SERVICE_UNAVAILABLE
+    );
+    let sse = reqwest::get(format!("http://{SSE_BIND_ADDRESS}/sse")).await?;
+    assert_eq!(sse.status(), reqwest::StatusCode::OK);
+    drop(sse);
+
+    let ct = sse_server.with_service(Calculator::default);
+    ct.cancel();
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sse_server_without_health_check() -> anyhow::Result<()> {
+    let (_, router) = SseServer::new(SseServerConfig {
+        bind: "127.0.0.1:0".parse()?,
+        ..Default::default()
+    });
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+    let server = tokio::spawn(async move { axum::serve(listener, router).await });
+    assert_eq!(
+        health(&format!("http://{addr}/healthz")).await?,
+        reqwest::StatusCode::NOT_FOUND
+    );
+    server.abort();
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_streamable_http_health_check() -> anyhow::Result<()> {
+    let health_check = HealthCheck::new("/ready");
+    let service: StreamableHttpService<Calculator, LocalSessionManager> =
+        StreamableHttpService::new(
+            || Ok(Calculator::new()),
+            Default::default(),
+            StreamableHttpServerConfig {
+                sse_keep_alive: None,
+                health_check: Some(health_check.clone()),
+                ..Default::default()
+            },
+        );
+    let router = axum::Router::new().nest_service("/mcp", service);
+    let tcp_listener = tokio::net::TcpListener::bind(STREAMABLE_BIND_ADDRESS).await?;
+    let ct = CancellationToken::new();
+    let handle = tokio::spawn({
+        let ct = ct.clone();
+        async move {
+            let _ = axum::serve(tcp_listener, router)
+                .with_graceful_shutdown(async move { ct.cancelled_owned().await })
+                .await;
+        }
+    });
+
+    let url = format!("http://{STREAMABLE_BIND_ADDRESS}/mcp/ready");
+    assert_eq!(health(&url).await?, reqwest::StatusCode::OK);
+    health_check.drain();
+    assert!(health_check.is_draining());
+    assert_eq!(
+        health(&url).await?,
+        reqwest::StatusCode::SERVICE_UNAVAILABLE
+    );
+
+    ct.cancel();
+    handle.await?;
+    Ok(())
+}
@@ -0,0 +1,30 @@
+use rmcp::{ServerHandler, include_instructions, model::ServerInfo};
+
+mod common;
+use common::movie::connect;
+
+#[derive(Clone)]
+struct MovieServer;
+
+impl ServerHandler for MovieServer {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo {
+            instructions: Some(include_instructions!(
+                "test_include_instructions/instructions.md"
+            )),
+            ..Default::default()
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_instructions_from_a_file() -> anyhow::Result<()> {
+    let client = connect(MovieServer, ()).await?;
+
+    let expected = std::fs::read_to_string("tests/test_include_instructions/instructions.md")?;
+    assert_eq!(client.server_instructions(), Some(expected.trim()));
+    assert!(expected.ends_with('\n'), "the trailing newline is trimmed");
+
+    client.cancel().await?;
+    Ok(())
+}
@@ -0,0 +1,119 @@
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
+
+use rmcp::{
+    RoleServer, ServerHandler, Service, ServiceError, ServiceExt,
+    model::{
+        ClientNotification, ClientRequest, ErrorCode, InitializeRequest, InitializeRequestParam,
+        InitializeResult, ServerInfo, ServerResult,
+    },
+    service::{NotificationContext, RequestContext},
+};
+
+mod common;
+use common::movie::connect;
+
+#[derive(Clone, Default)]
+pub struct CountingServer {
+    initialize_count: Arc<AtomicUsize>,
+}
+
+impl ServerHandler for CountingServer {
+    async fn initialize(
+        &self,
+        _request: InitializeRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<InitializeResult, rmcp::ErrorData> {
+        self.initialize_count.fetch_add(1, Ordering::SeqCst);
+        Ok(ServerInfo::default())
+    }
+}
+
+#[tokio::test]
+async fn test_second_initialize_is_rejected() -> anyhow::Result<()> {
+    let server = CountingServer::default();
+    let initialize_count = server.initialize_count.clone();
+    let client = connect(server, ()).await?;
+    assert_eq!(initialize_count.load(Ordering::SeqCst), 1);
+
+    let result = client
+        .send_request(ClientRequest::InitializeRequest(InitializeRequest::new(
+            InitializeRequestParam::default(),
+        )))
+        .await;
+    match result {
+        Err(ServiceError::McpError(error)) => {
+            assert_eq!(error.code, ErrorCode::INVALID_REQUEST);
+        }
+        other => panic!("expected invalid request error, got {other:?}"),
+    }
+    assert_eq!(initialize_count.load(Ordering::SeqCst), 1);
+
+    // the session is still usable after the rejected initialize
+    client
+        .send_request(ClientRequest::PingRequest(Default::default()))
+        .await?;
+
+    client.cancel().await?;
+    Ok(())
+}
+
+/// A service handling the messages itself, without [`ServerHandler`]
+#[derive(Clone, Default)]
+pub struct RawCountingService {
+    initialize_count: Arc<AtomicUsize>,
+}
+
+impl Service<RoleServer> for RawCountingService {
+    async fn handle_request(
+        &self,
+        request: ClientRequest,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ServerResult, rmcp::ErrorData> {
+        if let ClientRequest::InitializeRequest(_) = request {
+            self.initialize_count.fetch_add(1, Ordering::SeqCst);
+        }
+        Ok(ServerResult::InitializeResult(ServerInfo::default()))
+    }
+
+    async fn handle_notification(
+        &self,
+        _notification: ClientNotification,
+        _context: NotificationContext<RoleServer>,
+    ) -> Result<(), rmcp::ErrorData> {
+        Ok(())
+    }
+
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo::default()
+    }
+}
+
+#[tokio::test]
+async fn test_second_initialize_is_rejected_without_server_handler() -> anyhow::Result<()> {
+    let server = RawCountingService::default();
+    let initialize_count = server.initialize_count.clone();
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    tokio::spawn(async move {
+        server.serve(server_transport).await?.waiting().await?;
+        anyhow::Ok(())
+    });
+    let client = ().serve(client_transport).await?;
+    assert_eq!(initialize_count.load(Ordering::SeqCst), 1);
+
+    let result = client
+        .send_request(ClientRequest::InitializeRequest(InitializeRequest::new(
+            InitializeRequestParam::default(),
+        )))
+        .await;
+    let Err(ServiceError::McpError(error)) = result else {
+        panic!("expected invalid request error, got {result:?}");
+    };
+    assert_eq!(error.code, ErrorCode::INVALID_REQUEST);
+    assert_eq!(initialize_count.load(Ordering::SeqCst), 1);
+
+    client.cancel().await?;
+    Ok(())
+}
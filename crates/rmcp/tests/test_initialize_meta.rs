@@ -0,0 +1,98 @@
+use rmcp::{
+    ErrorData, ServerHandler, ServiceExt,
+    model::{InitializeRequestParam, InitializeResult},
+    service::{RequestContext, RoleServer, SharedState},
+};
+use serde_json::{Value, json};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+#[derive(Clone, Default)]
+struct MovieServer {
+    region: SharedState<Option<String>>,
+}
+
+impl ServerHandler for MovieServer {
+    async fn initialize(
+        &self,
+        _request: InitializeRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<InitializeResult, ErrorData> {
+        let region = context
+            .meta
+            .get("region")
+            .and_then(Value::as_str)
+            .map(str::to_owned);
+        self.region.replace(region);
+        Ok(self.get_info())
+    }
+}
+
+#[tokio::test]
+async fn test_read_the_initialize_meta() -> anyhow::Result<()> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    let movie_server = MovieServer::default();
+    let server = tokio::spawn(movie_server.clone().serve(server_transport));
+
+    let (read, mut write) = tokio::io::split(client_transport);
+    let mut read = BufReader::new(read);
+    let initialize = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "initialize",
+        "params": {
+            "_meta": { "region": "华东", "tenantId": "cinema-42" },
+            "protocolVersion": "2025-06-18",
+            "capabilities": {},
+            "clientInfo": { "name": "movie client", "version": "0.1.0" }
+        }
+    });
+    write
+        .write_all(format!("{initialize}\n").as_bytes())
+        .await?;
+    let mut line = String::new();
+    read.read_line(&mut line).await?;
+    let response: Value = serde_json::from_str(&line)?;
+    assert!(response["result"].is_object(), "{response}");
+    let initialized = json!({ "jsonrpc": "2.0", "method": "notifications/initialized" });
+    write
+        .write_all(format!("{initialized}\n").as_bytes())
+        .await?;
+
+    let server = server.await??;
+    assert_eq!(movie_server.region.get().as_deref(), Some("华东"));
+    // still there for the later requests
+    assert_eq!(server.peer().initialize_meta()["tenantId"], "cinema-42");
+
+    server.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_empty_without_initialize_meta() -> anyhow::Result<()> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    let server = tokio::spawn(MovieServer::default().serve(server_transport));
+    let (read, mut write) = tokio::io::split(client_transport);
+    let initialize = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": "2025-06-18",
+            "capabilities": {},
+            "clientInfo": { "name": "movie client", "version": "0.1.0" }
+        }
+    });
+    write
+        .write_all(format!("{initialize}\n").as_bytes())
+        .await?;
+    BufReader::new(read).read_line(&mut String::new()).await?;
+    let initialized = json!({ "jsonrpc": "2.0", "method": "notifications/initialized" });
+    write
+        .write_all(format!("{initialized}\n").as_bytes())
+        .await?;
+
+    let server = server.await??;
+    assert!(server.peer().initialize_meta().is_empty());
+    server.cancel().await?;
+    Ok(())
+}
@@ -0,0 +1,194 @@
+use std::time::Duration;
+
+use rmcp::{
+    ClientHandler, RoleClient, ServiceExt,
+    model::{ClientCapabilities, ClientInfo, ListRootsResult, Root},
+    service::{
+        HandshakeConfig, QuitReason, RequestContext, RootsValidator, RunningService,
+        ServerInitializeError, serve_server_with_config,
+    },
+};
+use tokio_util::sync::CancellationToken;
+
+mod common;
+use common::movie::MovieServer;
+
+#[derive(Clone)]
+struct MovieClient {
+    /// The roots listed by the client, `None` if it doesn't support roots
+    roots: Option<Vec<Root>>,
+}
+
+impl ClientHandler for MovieClient {
+    fn get_info(&self) -> ClientInfo {
+        let capabilities = match self.roots {
+            Some(_) => ClientCapabilities::builder().enable_roots().build(),
+            None => ClientCapabilities::default(),
+        };
+        ClientInfo::default().with_capabilities(capabilities)
+    }
+
+    async fn list_roots(
+        &self,
+        _context: RequestContext<RoleClient>,
+    ) -> Result<ListRootsResult, rmcp::ErrorData> {
+        // let the requests of the client reach the server before the roots
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        Ok(ListRootsResult {
+            roots: self.roots.clone().unwrap_or_default(),
+        })
+    }
+}
+
+fn posters() -> Root {
+    Root {
+        uri: "file:///movies/posters".to_string(),
+        name: Some("posters".to_string()),
+    }
+}
+
+async fn serve(
+    client: MovieClient,
+    validator: RootsValidator,
+) -> anyhow::Result<(
+    Result<RunningService<rmcp::RoleServer, MovieServer>, ServerInitializeError>,
+    RunningService<RoleClient, MovieClient>,
+)> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    let server = tokio::spawn(serve_server_with_config(
+        MovieServer,
+        server_transport,
+        CancellationToken::new(),
+        HandshakeConfig {
+            roots_validator: Some(validator),
+            ..Default::default()
+        },
+    ));
+    let client = client.serve(client_transport).await?;
+    Ok((server.await?, client))
+}
+
+#[tokio::test]
+async fn test_accept_the_required_roots() -> anyhow::Result<()> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    let server = tokio::spawn(serve_server_with_config(
+        MovieServer,
+        server_transport,
+        CancellationToken::new(),
+        HandshakeConfig {
+            roots_validator: Some(RootsValidator::require_roots()),
+            ..Default::default()
+        },
+    ));
+    let client = MovieClient {
+        roots: Some(vec![posters()]),
+    }
+    .serve(client_transport)
+    .await?;
+    // sent while the server waits for the roots, and served once they're accepted
+    client.list_tools(None).await?;
+
+    let server = server.await??;
+    assert_eq!(server.peer().initialize_roots(), Some(&[posters()][..]));
+    client.cancel().await?;
+    server.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_reject_missing_required_roots() -> anyhow::Result<()> {
+    for roots in [None, Some(vec![])] {
+        let (server, client) =
+            serve(MovieClient { roots }, RootsValidator::require_roots()).await?;
+        let Err(ServerInitializeError::RootsRejected(reason)) = server else {
+            panic!("the roots should be rejected");
+        };
+        assert!(reason.contains("at least one is required"), "{reason}");
+
+        let QuitReason::SessionClosed { reason, by_peer } = client.waiting().await? else {
+            panic!("the session should be closed by the server");
+        };
+        assert!(by_peer);
+        assert!(reason.starts_with("roots rejected: "), "{reason}");
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_warn_about_missing_roots() -> anyhow::Result<()> {
+    let warn = RootsValidator::new(|roots| {
+        if roots.is_none_or(<[Root]>::is_empty) {
+            tracing::warn!("the posters can't be saved without roots");
+        }
+        Ok(())
+    });
+    for roots in [None, Some(vec![])] {
+        let expected = roots.clone();
+        let (server, client) = serve(MovieClient { roots }, warn.clone()).await?;
+        let server = server?;
+        assert_eq!(server.peer().initialize_roots(), expected.as_deref());
+        client.cancel().await?;
+        server.cancel().await?;
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_roots_not_fetched_without_validator() -> anyhow::Result<()> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    let (server, client) = tokio::try_join!(
+        async { anyhow::Ok(MovieServer.serve(server_transport).await?) },
+        async {
+            let client = MovieClient {
+                roots: Some(vec![posters()]),
+            };
+            anyhow::Ok(client.serve(client_transport).await?)
+        },
+    )?;
+    assert_eq!(server.peer().initialize_roots(), None);
+    client.cancel().await?;
+    server.cancel().await?;
+    Ok(())
+}
+
+#[derive(Clone)]
+struct SilentClient;
+
+impl ClientHandler for SilentClient {
+    fn get_info(&self) -> ClientInfo {
+        ClientInfo::default()
+            .with_capabilities(ClientCapabilities::builder().enable_roots().build())
+    }
+
+    async fn list_roots(
+        &self,
+        _context: RequestContext<RoleClient>,
+    ) -> Result<ListRootsResult, rmcp::ErrorData> {
+        std::future::pending().await
+    }
+}
+
+#[tokio::test]
+async fn test_fail_when_the_roots_never_come() -> anyhow::Result<()> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    let timeout = Duration::from_millis(200);
+    let server = tokio::spawn(serve_server_with_config(
+        MovieServer,
+        server_transport,
+        CancellationToken::new(),
+        HandshakeConfig {
+            roots_validator: Some(RootsValidator::require_roots()),
+            request_timeout: Some(timeout),
+            ..Default::default()
+        },
+    ));
+    let client = SilentClient.serve(client_transport).await?;
+
+    let server = tokio::time::timeout(Duration::from_secs(5), server).await??;
+    let Err(ServerInitializeError::RootsTimeout(waited)) = server else {
+        panic!("the handshake should time out");
+    };
+    assert_eq!(waited, timeout);
+    client.cancel().await?;
+    Ok(())
+}
@@ -0,0 +1,109 @@
+use rmcp::{
+    ServiceExt,
+    model::{ErrorCode, ProtocolVersion},
+    service::ClientInitializeError,
+};
+use serde_json::{Value, json};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, DuplexStream};
+
+/// An older server only speaking `2024-11-05`, rejecting any other version with the one
+/// `suggest`ed in the error.
+///
+/// Returns how many initialize requests it received.
+fn older_server(
+    stream: DuplexStream,
+    suggest: fn(&str) -> Option<&'static str>,
+) -> tokio::task::JoinHandle<usize> {
+    tokio::spawn(async move {
+        let (read, mut write) = tokio::io::split(stream);
+        let mut lines = BufReader::new(read).lines();
+        let mut initialize_requests = 0;
+        while let Ok(Some(line)) = lines.next_line().await {
+            let message: Value = serde_json::from_str(&line).expect("json message");
+            if message["method"] != "initialize" {
+                continue;
+            }
+            initialize_requests += 1;
+            let id = message["id"].clone();
+            let requested = message["params"]["protocolVersion"]
+                .as_str()
+                .expect("protocol version");
+            let response = if requested == "2024-11-05" {
+                json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "result": {
+                        "protocolVersion": "2024-11-05",
+                        "capabilities": {},
+                        "serverInfo": { "name": "older movie server", "version": "0.1.0" }
+                    }
+                })
+            } else {
+                let data = suggest(requested)
+                    .map(|supported| json!({ "supported": [supported], "requested": requested }));
+                json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "error": {
+                        "code": ErrorCode::INVALID_PARAMS.0,
+                        "message": "Unsupported protocol version",
+                        "data": data
+                    }
+                })
+            };
+            let mut response = serde_json::to_vec(&response).expect("serializable");
+            response.push(b'\n');
+            if write.write_all(&response).await.is_err() {
+                break;
+            }
+        }
+        initialize_requests
+    })
+}
+
+#[tokio::test]
+async fn test_retry_with_the_version_suggested_by_the_server() -> anyhow::Result<()> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    let server = older_server(server_transport, |_| Some("2024-11-05"));
+
+    let client = ().serve(client_transport).await?;
+    let server_info = client.peer_info().expect("initialized");
+    assert_eq!(server_info.protocol_version, ProtocolVersion::V_2024_11_05);
+    assert_eq!(server_info.server_info.name, "older movie server");
+
+    client.cancel().await?;
+    assert_eq!(server.await?, 2);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_fail_without_a_suggested_version() -> anyhow::Result<()> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    let server = older_server(server_transport, |_| None);
+
+    let error = ().serve(client_transport).await.expect_err("rejected");
+    let ClientInitializeError::InitializeFailed(error) = error else {
+        panic!("expected the error of the server, got {error:?}");
+    };
+    assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+    assert_eq!(server.await?, 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_retry_only_once() -> anyhow::Result<()> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    // the server suggests another version each time, but never accepts them
+    let server = older_server(server_transport, |requested| match requested {
+        "2025-06-18" => Some("2025-03-26"),
+        _ => Some("2025-06-18"),
+    });
+
+    let error = ().serve(client_transport).await.expect_err("rejected");
+    assert!(
+        matches!(error, ClientInitializeError::InitializeFailed(_)),
+        "{error:?}"
+    );
+    assert_eq!(server.await?, 2);
+    Ok(())
+}
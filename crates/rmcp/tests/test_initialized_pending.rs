@@ -0,0 +1,112 @@
+use rmcp::{
+    ServerHandler, ServiceExt,
+    handler::server::tool::ToolRouter,
+    model::ErrorCode,
+    service::{NotificationContext, RoleServer, SharedState},
+    tool, tool_handler, tool_router,
+};
+use serde_json::{Value, json};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, DuplexStream, ReadHalf, WriteHalf};
+
+#[derive(Clone)]
+struct MovieServer {
+    cities: SharedState<Vec<String>>,
+    tool_router: ToolRouter<Self>,
+}
+
+#[tool_router]
+impl MovieServer {
+    fn new() -> Self {
+        Self {
+            cities: SharedState::default(),
+            tool_router: Self::tool_router(),
+        }
+    }
+
+    #[tool(description = "List the cities with cinemas")]
+    fn list_cities(&self) -> String {
+        self.cities.get().join(",")
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for MovieServer {
+    async fn on_initialized(&self, _context: NotificationContext<RoleServer>) {
+        self.cities
+            .replace(vec!["北京".to_string(), "上海".to_string()]);
+    }
+}
+
+struct RawClient {
+    read: BufReader<ReadHalf<DuplexStream>>,
+    write: WriteHalf<DuplexStream>,
+}
+
+impl RawClient {
+    async fn send(&mut self, message: Value) -> anyhow::Result<()> {
+        self.write
+            .write_all(format!("{message}\n").as_bytes())
+            .await?;
+        Ok(())
+    }
+
+    async fn request(&mut self, id: u32, method: &str, params: Value) -> anyhow::Result<Value> {
+        self.send(json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }))
+            .await?;
+        let mut line = String::new();
+        self.read.read_line(&mut line).await?;
+        Ok(serde_json::from_str(&line)?)
+    }
+}
+
+#[tokio::test]
+async fn test_reject_tool_call_before_initialized() -> anyhow::Result<()> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    let server = tokio::spawn(MovieServer::new().serve(server_transport));
+    let (read, write) = tokio::io::split(client_transport);
+    let mut client = RawClient {
+        read: BufReader::new(read),
+        write,
+    };
+
+    let response = client
+        .request(
+            1,
+            "initialize",
+            json!({
+                "protocolVersion": "2025-06-18",
+                "capabilities": {},
+                "clientInfo": { "name": "movie client", "version": "0.1.0" }
+            }),
+        )
+        .await?;
+    assert!(response["result"].is_object(), "{response}");
+
+    // too early, the cities aren't loaded yet
+    let response = client
+        .request(2, "tools/call", json!({ "name": "list_cities" }))
+        .await?;
+    assert_eq!(response["id"], 2);
+    assert_eq!(response["error"]["code"], ErrorCode::INVALID_REQUEST.0);
+    let message = response["error"]["message"].as_str().unwrap();
+    assert!(message.contains("tools/call"), "{message}");
+    assert!(message.contains("notifications/initialized"), "{message}");
+
+    // pings are still answered
+    let response = client.request(3, "ping", json!({})).await?;
+    assert_eq!(response["result"], json!({}));
+
+    client
+        .send(json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }))
+        .await?;
+    let server = server.await??;
+    assert!(server.peer().is_initialized());
+
+    let response = client
+        .request(4, "tools/call", json!({ "name": "list_cities" }))
+        .await?;
+    assert_eq!(response["result"]["content"][0]["text"], "北京,上海");
+
+    server.cancel().await?;
+    Ok(())
+}
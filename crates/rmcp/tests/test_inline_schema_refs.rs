@@ -0,0 +1,91 @@
+use rmcp::{
+    ServerHandler,
+    handler::server::{common::inline_schema_refs, router::tool::ToolRouter, wrapper::Parameters},
+    tool, tool_handler, tool_router,
+};
+use serde_json::json;
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct Location {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct CinemaListRequest {
+    /// Where the user is
+    pub location: Location,
+    /// Where the user is heading to
+    pub destination: Option<Location>,
+}
+
+#[derive(Clone)]
+struct MovieServer {
+    tool_router: ToolRouter<Self>,
+}
+
+#[tool_router]
+impl MovieServer {
+    #[tool(description = "Get the cinemas nearby")]
+    fn get_cinema_list(&self, Parameters(request): Parameters<CinemaListRequest>) -> String {
+        format!("{:?}", request.location)
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for MovieServer {}
+
+fn input_schema(router: &ToolRouter<MovieServer>) -> serde_json::Value {
+    serde_json::Value::Object(router.list_all()[0].input_schema.as_ref().clone())
+}
+
+#[test]
+fn test_no_ref_remains_when_inlined() {
+    let schema = input_schema(&MovieServer::tool_router());
+    assert!(schema.to_string().contains("$ref"), "{schema}");
+
+    let schema = input_schema(&MovieServer::tool_router().with_inlined_schemas());
+    assert!(!schema.to_string().contains("$ref"), "{schema}");
+    assert!(schema.get("definitions").is_none() && schema.get("$defs").is_none());
+    let location = &schema["properties"]["location"];
+    assert_eq!(location["description"], "Where the user is");
+    assert_eq!(location["properties"]["latitude"]["type"], "number");
+    assert_eq!(location["required"], json!(["latitude", "longitude"]));
+}
+
+#[test]
+fn test_inline_the_routes_added_later() {
+    let mut router = ToolRouter::<MovieServer>::new().with_inlined_schemas();
+    router.merge(MovieServer::tool_router());
+    assert!(!input_schema(&router).to_string().contains("$ref"));
+}
+
+#[test]
+fn test_keep_recursive_refs() {
+    let schema = json!({
+        "type": "object",
+        "properties": {
+            "movie": { "$ref": "#/definitions/Movie" },
+            "cinema": { "$ref": "#/definitions/Cinema" }
+        },
+        "definitions": {
+            "Cinema": { "type": "object", "properties": { "name": { "type": "string" } } },
+            "Movie": {
+                "type": "object",
+                "properties": { "sequel": { "$ref": "#/definitions/Movie" } }
+            }
+        }
+    });
+    let inlined = inline_schema_refs(schema.as_object().unwrap());
+    let inlined = serde_json::Value::Object(inlined);
+    assert_eq!(
+        inlined["properties"]["cinema"]["properties"]["name"]["type"],
+        "string"
+    );
+    assert_eq!(
+        inlined["properties"]["movie"]["properties"]["sequel"],
+        json!({ "$ref": "#/definitions/Movie" })
+    );
+    // still resolvable
+    assert_eq!(inlined["definitions"], schema["definitions"]);
+}
@@ -0,0 +1,122 @@
+use bytes::BytesMut;
+use rmcp::{
+    model::{ClientJsonRpcMessage, ErrorCode, JsonLimits, JsonRpcEnvelopeError},
+    transport::{
+        SseServer,
+        async_rw::{JsonRpcMessageCodec, JsonRpcMessageCodecError},
+        sse_server::SseServerConfig,
+    },
+};
+use serde_json::json;
+use tokio_util::{codec::Decoder, sync::CancellationToken};
+
+const BIND_ADDRESS: &str = "127.0.0.1:8159";
+
+const LIMITS: JsonLimits = JsonLimits {
+    max_depth: 16,
+    max_size: Some(1024),
+};
+
+/// A ping request whose params are nested `depth` levels deep
+fn nested_ping(depth: usize) -> String {
+    format!(
+        r#"{{"jsonrpc":"2.0","id":1,"method":"ping","params":{{"_meta":{{"deep":{}{}}}}}}}"#,
+        "[".repeat(depth),
+        "]".repeat(depth)
+    )
+}
+
+#[test]
+fn test_json_limits_check() {
+    assert_eq!(LIMITS.check(nested_ping(13).as_bytes()), Ok(()));
+    assert_eq!(
+        LIMITS.check(nested_ping(14).as_bytes()),
+        Err(JsonRpcEnvelopeError::TooDeep { max_depth: 16 })
+    );
+    // brackets in strings don't count
+    let brackets = json!({ "jsonrpc": "2.0", "method": "ping", "id": 1, "params": { "_meta": { "title": "[[[[[[[[[[[[[[[[[[{{{{{{{{{{{{{{{{{{" } } });
+    assert_eq!(LIMITS.check(brackets.to_string().as_bytes()), Ok(()));
+    let large = json!({ "jsonrpc": "2.0", "method": "ping", "id": 1, "params": { "_meta": { "title": "x".repeat(1024) } } });
+    assert_eq!(
+        LIMITS.check(large.to_string().as_bytes()),
+        Err(JsonRpcEnvelopeError::TooLarge { max_size: 1024 })
+    );
+    // the defaults only bound the depth
+    assert_eq!(
+        JsonLimits::default().check(large.to_string().as_bytes()),
+        Ok(())
+    );
+    assert!(
+        JsonLimits::default()
+            .check(nested_ping(10_000).as_bytes())
+            .is_err()
+    );
+}
+
+#[test]
+fn test_codec_rejects_deeply_nested_messages() {
+    let mut codec =
+        JsonRpcMessageCodec::<ClientJsonRpcMessage>::new().with_json_limits(JsonLimits {
+            max_size: None,
+            ..LIMITS
+        });
+    let mut buf =
+        BytesMut::from(format!("{}\n{}\n", nested_ping(1), nested_ping(100_000)).as_str());
+    assert!(codec.decode(&mut buf).unwrap().is_some());
+    assert!(matches!(
+        codec.decode(&mut buf),
+        Err(JsonRpcMessageCodecError::Envelope(
+            JsonRpcEnvelopeError::TooDeep { max_depth: 16 }
+        ))
+    ));
+}
+
+#[test]
+fn test_codec_rejects_oversized_frames_while_reading() {
+    let mut codec = JsonRpcMessageCodec::<ClientJsonRpcMessage>::new().with_json_limits(LIMITS);
+    // no newline yet, the frame is already over the limit
+    let mut buf = BytesMut::from(format!("{:<2048}", nested_ping(1)).as_str());
+    assert!(matches!(
+        codec.decode(&mut buf),
+        Err(JsonRpcMessageCodecError::MaxLineLengthExceeded)
+    ));
+    // a frame within the limit waits for the rest
+    let mut codec = JsonRpcMessageCodec::<ClientJsonRpcMessage>::new().with_json_limits(LIMITS);
+    let mut buf = BytesMut::from(format!("{:<1000}", nested_ping(1)).as_str());
+    assert!(codec.decode(&mut buf).unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_sse_server_answers_a_parse_error() -> anyhow::Result<()> {
+    let ct = CancellationToken::new();
+    let sse_server = SseServer::serve_with_config(SseServerConfig {
+        bind: BIND_ADDRESS.parse()?,
+        ct: ct.clone(),
+        json_limits: LIMITS,
+        ..Default::default()
+    })
+    .await?;
+    let http = reqwest::Client::new();
+    let post = |body: String| {
+        http.post(format!("http://{BIND_ADDRESS}/message?sessionId=unknown"))
+            .header("Content-Type", "application/json")
+            .body(body)
+            .send()
+    };
+
+    let response = post(nested_ping(100_000)).await?;
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+    let error: serde_json::Value = response.json().await?;
+    assert_eq!(error["id"], serde_json::Value::Null);
+    assert_eq!(error["error"]["code"], ErrorCode::PARSE_ERROR.0);
+
+    let response = post(format!("{:<2048}", nested_ping(1))).await?;
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+
+    // within the limits, the message goes on to its session
+    let response = post(nested_ping(1)).await?;
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+
+    sse_server.cancel();
+    Ok(())
+}
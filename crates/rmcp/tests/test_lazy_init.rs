@@ -0,0 +1,130 @@
+use std::{sync::Arc, time::Duration};
+
+use rmcp::{
+    ErrorData, RoleServer, ServerHandler,
+    handler::server::tool::ToolRouter,
+    model::{CallToolRequestParam, InitializeRequestParam, InitializeResult},
+    service::{LazyInit, LazyInitError, RequestContext},
+    tool, tool_handler, tool_router,
+};
+use tokio::sync::{Mutex, oneshot};
+
+mod common;
+use common::movie::connect;
+
+/// The expensive init waits until the test releases it
+#[derive(Clone)]
+pub struct PreloadingServer {
+    release: Arc<Mutex<Option<oneshot::Receiver<()>>>>,
+    cities: LazyInit<Vec<String>>,
+    tool_router: ToolRouter<Self>,
+}
+
+#[tool_router]
+impl PreloadingServer {
+    pub fn new(release: oneshot::Receiver<()>) -> Self {
+        Self {
+            release: Arc::new(Mutex::new(Some(release))),
+            cities: LazyInit::new(),
+            tool_router: Self::tool_router(),
+        }
+    }
+
+    #[tool(description = "List the preloaded cities")]
+    async fn cities(&self) -> Result<String, ErrorData> {
+        let cities = self
+            .cities
+            .get()
+            .await
+            .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+        Ok(cities.join(","))
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for PreloadingServer {
+    async fn initialize(
+        &self,
+        _request: InitializeRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<InitializeResult, ErrorData> {
+        let release = self.release.lock().await.take();
+        self.cities.start(async move {
+            if let Some(release) = release {
+                let _ = release.await;
+            }
+            vec!["Beijing".to_string(), "Shanghai".to_string()]
+        });
+        Ok(self.get_info())
+    }
+}
+
+#[tokio::test]
+async fn test_tool_awaits_lazy_init() -> anyhow::Result<()> {
+    let (release_tx, release_rx) = oneshot::channel();
+    let server = PreloadingServer::new(release_rx);
+    let cities = server.cities.clone();
+
+    // initialize returns while the init is still pending
+    let client = tokio::time::timeout(Duration::from_secs(5), connect(server, ())).await??;
+    assert!(!cities.is_ready());
+
+    let peer = client.peer().clone();
+    let call = tokio::spawn(async move {
+        peer.call_tool(CallToolRequestParam {
+            name: "cities".into(),
+            arguments: None,
+        })
+        .await
+    });
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(!call.is_finished());
+
+    release_tx.send(()).expect("init is waiting");
+    let result = call.await??;
+    let text = result.content[0].as_text().expect("text content");
+    assert_eq!(text.text, "Beijing,Shanghai");
+    assert!(cities.is_ready());
+    client.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_lazy_init_start_once() {
+    let lazy = LazyInit::new();
+    assert!(lazy.try_get().is_none());
+    assert!(lazy.start(async { 1 }));
+    assert!(!lazy.start(async { 2 }));
+    assert_eq!(lazy.get().await, Ok(&1));
+    assert_eq!(lazy.try_get(), Some(&1));
+}
+
+#[tokio::test]
+async fn test_lazy_init_panicked() {
+    let lazy = LazyInit::<u32>::new();
+    lazy.start(async { panic!("init failed") });
+    assert_eq!(lazy.get().await, Err(LazyInitError));
+}
+
+#[tokio::test]
+async fn test_lazy_init_restart_after_error() {
+    let lazy = LazyInit::<u32>::new();
+    assert!(lazy.try_start(async { Err("upstream unavailable") }));
+    assert_eq!(lazy.get().await, Err(LazyInitError));
+    assert!(!lazy.is_ready());
+
+    // the failed init can be started again
+    assert!(lazy.try_start(async { Ok::<_, &str>(1) }));
+    assert_eq!(lazy.get().await, Ok(&1));
+    assert!(!lazy.try_start(async { Ok::<_, &str>(2) }));
+    assert_eq!(lazy.try_get(), Some(&1));
+}
+
+#[tokio::test]
+async fn test_lazy_init_restart_after_panic() {
+    let lazy = LazyInit::<u32>::new();
+    lazy.start(async { panic!("init failed") });
+    assert_eq!(lazy.get().await, Err(LazyInitError));
+    assert!(lazy.start(async { 1 }));
+    assert_eq!(lazy.get().await, Ok(&1));
+}
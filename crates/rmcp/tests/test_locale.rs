@@ -0,0 +1,114 @@
+use rmcp::{
+    RoleServer, ServerHandler, ServiceExt,
+    handler::server::tool::ToolRouter,
+    model::{CallToolRequestParam, ClientInfo, ClientRequest, Meta, Request, ServerResult},
+    service::{HandshakeConfig, PeerRequestOptions, RequestContext, serve_server_with_config},
+    tool, tool_handler, tool_router,
+};
+
+#[derive(Clone)]
+pub struct GreetingServer {
+    tool_router: ToolRouter<Self>,
+}
+
+#[tool_router]
+impl GreetingServer {
+    pub fn new() -> Self {
+        Self {
+            tool_router: Self::tool_router(),
+        }
+    }
+
+    #[tool(description = "Say hello in the language of the client")]
+    fn greet(&self, ctx: RequestContext<RoleServer>) -> String {
+        match ctx.locale() {
+            "fr-FR" => "bonjour".to_string(),
+            "zh-CN" => "你好".to_string(),
+            locale => format!("hello ({locale})"),
+        }
+    }
+}
+
+impl Default for GreetingServer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for GreetingServer {}
+
+async fn greet(
+    config: HandshakeConfig,
+    client_locale: Option<&str>,
+    request_locale: Option<&str>,
+) -> anyhow::Result<String> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    tokio::spawn(async move {
+        let server = serve_server_with_config(
+            GreetingServer::new(),
+            server_transport,
+            Default::default(),
+            config,
+        )
+        .await?;
+        server.waiting().await?;
+        anyhow::Ok(())
+    });
+    let mut client_info = ClientInfo::default();
+    if let Some(locale) = client_locale {
+        client_info.capabilities.set_locale(locale);
+    }
+    let client = client_info.serve(client_transport).await?;
+
+    let mut meta = Meta::new();
+    if let Some(locale) = request_locale {
+        meta.set_locale(locale);
+    }
+    let response = client
+        .send_request_with_option(
+            ClientRequest::CallToolRequest(Request::new(CallToolRequestParam {
+                name: "greet".into(),
+                arguments: None,
+            })),
+            PeerRequestOptions {
+                timeout: None,
+                meta: Some(meta),
+            },
+        )
+        .await?
+        .await_response()
+        .await?;
+    let ServerResult::CallToolResult(result) = response else {
+        panic!("expected call tool result, got {response:?}");
+    };
+    client.cancel().await?;
+    Ok(result.content[0]
+        .as_text()
+        .expect("text content")
+        .text
+        .clone())
+}
+
+#[tokio::test]
+async fn test_locale_negotiation() -> anyhow::Result<()> {
+    let config = HandshakeConfig::default();
+    // declared during initialization
+    assert_eq!(greet(config.clone(), Some("fr-FR"), None).await?, "bonjour");
+    // the request overrides the session preference
+    assert_eq!(
+        greet(config.clone(), Some("fr-FR"), Some("zh-CN")).await?,
+        "你好"
+    );
+    assert_eq!(greet(config.clone(), None, Some("fr-FR")).await?, "bonjour");
+    // fall back to the server default
+    assert_eq!(greet(config, None, None).await?, "hello (en)");
+
+    let config = HandshakeConfig {
+        default_locale: Some("de-DE".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(greet(config.clone(), None, None).await?, "hello (de-DE)");
+    assert_eq!(greet(config, Some("fr-FR"), None).await?, "bonjour");
+    Ok(())
+}
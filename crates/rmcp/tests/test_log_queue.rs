@@ -0,0 +1,129 @@
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+
+use rmcp::{
+    ClientHandler, RoleServer, ServerHandler,
+    handler::server::tool::ToolRouter,
+    model::{
+        CallToolRequestParam, LoggingLevel, LoggingMessageNotificationParam, ServerCapabilities,
+        ServerInfo,
+    },
+    service::{NotificationContext, Peer, RequestContext, RoleClient},
+    tool, tool_handler, tool_router,
+};
+use tokio::sync::Notify;
+
+mod common;
+use common::movie::connect;
+
+const LOG_QUEUE_CAPACITY: usize = 16;
+const LOG_MESSAGES: usize = 1000;
+
+#[derive(Clone)]
+struct MovieServer {
+    peer: Arc<std::sync::OnceLock<Peer<RoleServer>>>,
+    tool_router: ToolRouter<Self>,
+}
+
+#[tool_router]
+impl MovieServer {
+    fn new() -> Self {
+        Self {
+            peer: Default::default(),
+            tool_router: Self::tool_router(),
+        }
+    }
+
+    #[tool(description = "Refresh the movie schedules, logging each cinema")]
+    async fn refresh_schedules(&self, context: RequestContext<RoleServer>) -> String {
+        self.peer.get_or_init(|| context.peer.clone());
+        for cinema in 0..LOG_MESSAGES {
+            context
+                .peer
+                .notify_logging_message(LoggingMessageNotificationParam {
+                    level: LoggingLevel::Info,
+                    logger: Some("schedules".into()),
+                    data: format!("refreshed cinema {cinema}").into(),
+                })
+                .await
+                .expect("log message queued");
+        }
+        "refreshed".to_string()
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for MovieServer {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo {
+            capabilities: ServerCapabilities::builder()
+                .enable_logging()
+                .enable_tools()
+                .build(),
+            ..Default::default()
+        }
+    }
+
+    async fn on_initialized(&self, context: NotificationContext<RoleServer>) {
+        context.peer.enable_log_queue(LOG_QUEUE_CAPACITY);
+    }
+}
+
+#[derive(Clone, Default)]
+struct LogCounter {
+    received: Arc<AtomicUsize>,
+    notify: Arc<Notify>,
+}
+
+impl ClientHandler for LogCounter {
+    async fn on_logging_message(
+        &self,
+        _params: LoggingMessageNotificationParam,
+        _context: NotificationContext<RoleClient>,
+    ) {
+        self.received.fetch_add(1, Ordering::SeqCst);
+        self.notify.notify_one();
+    }
+}
+
+#[tokio::test]
+async fn test_log_flood_doesnt_block_tools() -> anyhow::Result<()> {
+    let server = MovieServer::new();
+    let server_peer = server.peer.clone();
+    let client_handler = LogCounter::default();
+    let client = connect(server, client_handler.clone()).await?;
+
+    let result = tokio::time::timeout(
+        Duration::from_secs(5),
+        client.call_tool(CallToolRequestParam {
+            name: "refresh_schedules".into(),
+            arguments: None,
+        }),
+    )
+    .await??;
+    assert_eq!(result.content[0].as_text().unwrap().text, "refreshed");
+
+    // the messages are queued without waiting for the transport, so the queue overflows
+    let peer = server_peer.get().expect("tool called");
+    let dropped = peer.dropped_log_messages();
+    assert!(dropped > 0);
+    assert!(dropped <= (LOG_MESSAGES - LOG_QUEUE_CAPACITY) as u64);
+
+    // the others are delivered
+    let delivered = LOG_MESSAGES - dropped as usize;
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while client_handler.received.load(Ordering::SeqCst) < delivered {
+            client_handler.notify.notified().await;
+        }
+    })
+    .await?;
+    assert_eq!(client_handler.received.load(Ordering::SeqCst), delivered);
+
+    client.cancel().await?;
+    Ok(())
+}
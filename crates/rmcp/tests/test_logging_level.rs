@@ -0,0 +1,143 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use rmcp::{
+    ClientHandler, ErrorData, RoleClient, RoleServer, ServerHandler, ServiceExt,
+    model::{
+        LoggingLevel, LoggingMessageNotificationParam, ServerCapabilities, ServerInfo,
+        SetLevelRequestParam,
+    },
+    service::{NotificationContext, RequestContext},
+};
+use serde_json::json;
+
+#[derive(Clone)]
+struct MovieServer;
+
+impl ServerHandler for MovieServer {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo {
+            capabilities: ServerCapabilities::builder().enable_logging().build(),
+            ..Default::default()
+        }
+    }
+
+    async fn set_level(
+        &self,
+        _request: SetLevelRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<(), ErrorData> {
+        Ok(())
+    }
+}
+
+#[derive(Clone, Default)]
+struct LogCollector {
+    received: Arc<Mutex<Vec<LoggingLevel>>>,
+}
+
+impl LogCollector {
+    /// Wait until `count` log messages were received, and take them
+    async fn take(&self, count: usize) -> anyhow::Result<Vec<LoggingLevel>> {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                {
+                    let mut received = self.received.lock().unwrap();
+                    if received.len() >= count {
+                        let mut levels = std::mem::take(&mut *received);
+                        levels.sort();
+                        return levels;
+                    }
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .map_err(Into::into)
+    }
+}
+
+impl ClientHandler for LogCollector {
+    async fn on_logging_message(
+        &self,
+        params: LoggingMessageNotificationParam,
+        _context: NotificationContext<RoleClient>,
+    ) {
+        self.received.lock().unwrap().push(params.level);
+    }
+}
+
+fn log(level: LoggingLevel) -> LoggingMessageNotificationParam {
+    LoggingMessageNotificationParam {
+        level,
+        logger: Some("movie".to_string()),
+        data: json!({ "message": "looking for cinemas nearby" }),
+    }
+}
+
+#[tokio::test]
+async fn test_server_logs_respect_the_client_level() -> anyhow::Result<()> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    let (server, client) = tokio::join!(
+        MovieServer.serve(server_transport),
+        LogCollector::default().serve(client_transport)
+    );
+    let (server, client) = (server?, client?);
+    let collector = client.service().clone();
+
+    // every message is sent until the client sets a level
+    assert_eq!(server.min_log_level(), None);
+    server
+        .notify_logging_message(log(LoggingLevel::Debug))
+        .await?;
+    assert_eq!(collector.take(1).await?, [LoggingLevel::Debug]);
+
+    client.set_logging_level(LoggingLevel::Warning).await?;
+    assert_eq!(server.min_log_level(), Some(LoggingLevel::Warning));
+    for level in [
+        LoggingLevel::Debug,
+        LoggingLevel::Info,
+        LoggingLevel::Notice,
+        LoggingLevel::Warning,
+        LoggingLevel::Error,
+        LoggingLevel::Emergency,
+    ] {
+        server.notify_logging_message(log(level)).await?;
+    }
+    assert_eq!(
+        collector.take(3).await?,
+        [
+            LoggingLevel::Warning,
+            LoggingLevel::Error,
+            LoggingLevel::Emergency
+        ]
+    );
+
+    client.set_logging_level(LoggingLevel::Debug).await?;
+    server
+        .notify_logging_message(log(LoggingLevel::Info))
+        .await?;
+    assert_eq!(collector.take(1).await?, [LoggingLevel::Info]);
+
+    client.cancel().await?;
+    server.cancel().await?;
+    Ok(())
+}
+
+#[test]
+fn test_logging_levels_are_ordered_by_severity() {
+    assert!(LoggingLevel::Debug < LoggingLevel::Info);
+    assert!(LoggingLevel::Warning < LoggingLevel::Error);
+    assert_eq!(
+        [
+            LoggingLevel::Info,
+            LoggingLevel::Emergency,
+            LoggingLevel::Debug
+        ]
+        .into_iter()
+        .max(),
+        Some(LoggingLevel::Emergency)
+    );
+}
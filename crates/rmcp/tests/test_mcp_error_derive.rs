@@ -0,0 +1,78 @@
+use std::collections::HashMap;
+
+use rmcp::{
+    ErrorData, McpError, handler::server::wrapper::Parameters, model::ErrorCode, tool, tool_router,
+};
+
+#[derive(Debug, McpError)]
+enum MovieError {
+    #[mcp(code = ErrorCode::RESOURCE_NOT_FOUND, message = "movie {movie_id} not found")]
+    NotFound { movie_id: i32 },
+    #[mcp(code = -32602, message = "unknown city {_0}")]
+    UnknownCity(String),
+    /// Displayed, as an internal error
+    Upstream,
+}
+
+impl std::fmt::Display for MovieError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("the upstream is down")
+    }
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct GetMovieDetailInfoRequest {
+    movie_id: i32,
+}
+
+struct MovieServer {
+    movies: HashMap<i32, String>,
+}
+
+#[tool_router]
+impl MovieServer {
+    #[tool(description = "Get movie details based on the movie ID")]
+    async fn get_movie_detail_info(
+        &self,
+        Parameters(req): Parameters<GetMovieDetailInfoRequest>,
+    ) -> Result<String, ErrorData> {
+        let movie = self.movies.get(&req.movie_id).ok_or(MovieError::NotFound {
+            movie_id: req.movie_id,
+        })?;
+        Ok(movie.clone())
+    }
+}
+
+#[test]
+fn test_variants_map_to_their_code_and_message() {
+    let not_found = ErrorData::from(MovieError::NotFound { movie_id: 1297 });
+    assert_eq!(not_found.code, ErrorCode::RESOURCE_NOT_FOUND);
+    assert_eq!(not_found.message, "movie 1297 not found");
+
+    let unknown_city = ErrorData::from(MovieError::UnknownCity("Atlantis".to_string()));
+    assert_eq!(unknown_city.code, ErrorCode::INVALID_PARAMS);
+    assert_eq!(unknown_city.message, "unknown city Atlantis");
+
+    let upstream = ErrorData::from(MovieError::Upstream);
+    assert_eq!(upstream.code, ErrorCode::INTERNAL_ERROR);
+    assert_eq!(upstream.message, "the upstream is down");
+}
+
+#[tokio::test]
+async fn test_tool_returns_domain_error_with_question_mark() {
+    let server = MovieServer {
+        movies: HashMap::from([(1297, "Your Name".to_string())]),
+    };
+    assert_eq!(MovieServer::tool_router().list_all().len(), 1);
+    let found = server
+        .get_movie_detail_info(Parameters(GetMovieDetailInfoRequest { movie_id: 1297 }))
+        .await;
+    assert_eq!(found.unwrap(), "Your Name");
+
+    let missing = server
+        .get_movie_detail_info(Parameters(GetMovieDetailInfoRequest { movie_id: 42 }))
+        .await
+        .unwrap_err();
+    assert_eq!(missing.code, ErrorCode::RESOURCE_NOT_FOUND);
+    assert_eq!(missing.message, "movie 42 not found");
+}
@@ -0,0 +1,142 @@
+use std::{
+    io::Write,
+    sync::{Arc, Mutex},
+};
+
+use rmcp::{
+    ServerHandler, ServiceExt,
+    handler::server::{tool::ToolRouter, wrapper::Parameters},
+    model::{CallToolRequestParam, NumberOrString, ProgressNotificationParam, ProgressToken},
+    service::{REDACTED, Redactor},
+    tool, tool_handler, tool_router,
+};
+use serde_json::json;
+use tracing_subscriber::util::SubscriberInitExt;
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct GetCinemaListRequest {
+    latitude: f64,
+    longitude: f64,
+}
+
+#[derive(Clone)]
+struct MovieServer {
+    tool_router: ToolRouter<Self>,
+}
+
+#[tool_router]
+impl MovieServer {
+    fn new() -> Self {
+        Self {
+            tool_router: Self::tool_router(),
+        }
+    }
+
+    #[tool(description = "Get the cinemas near the user")]
+    async fn get_cinema_list(
+        &self,
+        Parameters(req): Parameters<GetCinemaListRequest>,
+    ) -> rmcp::Json<serde_json::Value> {
+        rmcp::Json(json!({
+            "cinemas": [{ "name": "万达影城", "latitude": req.latitude + 0.001, "longitude": req.longitude }]
+        }))
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for MovieServer {}
+
+/// The logs written by the subscriber
+#[derive(Clone, Default)]
+struct Logs(Arc<Mutex<Vec<u8>>>);
+
+impl Logs {
+    fn text(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).into_owned()
+    }
+}
+
+impl Write for Logs {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+fn location(field: &str) -> bool {
+    matches!(field, "latitude" | "longitude")
+}
+
+#[tokio::test]
+async fn test_logged_messages_are_redacted() -> anyhow::Result<()> {
+    let logs = Logs::default();
+    let writer = logs.clone();
+    // the test runtime is single threaded, the default subscriber sees both peers
+    let _guard = tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::DEBUG)
+        .with_ansi(false)
+        .with_writer(move || writer.clone())
+        .finish()
+        .set_default();
+
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    let server = MovieServer::new().serve(server_transport);
+    let client = ().serve(client_transport);
+    let (server, client) = tokio::join!(server, client);
+    let (server, client) = (server?, client?);
+    server.set_message_logging(Some(Arc::new(location) as Arc<dyn Redactor>));
+
+    // only the method of a notification is logged
+    client
+        .notify_progress(ProgressNotificationParam {
+            progress_token: ProgressToken(NumberOrString::Number(1)),
+            progress: 1.0,
+            total: None,
+            message: Some("near 39.9087,116.3975".into()),
+        })
+        .await?;
+    let result = client
+        .call_tool(CallToolRequestParam {
+            name: "get_cinema_list".into(),
+            arguments: json!({ "latitude": 39.9087, "longitude": 116.3975 })
+                .as_object()
+                .cloned(),
+        })
+        .await?;
+    assert!(result.structured_content.is_some());
+
+    let logs = logs.text();
+    assert!(logs.contains("tools/call"), "the request is logged: {logs}");
+    assert!(
+        logs.contains("notifications/progress"),
+        "the notification is logged: {logs}"
+    );
+    assert!(logs.contains("万达影城"), "the response is logged: {logs}");
+    assert!(logs.contains(REDACTED));
+    for location in ["39.90", "116.39"] {
+        assert!(!logs.contains(location), "{location} is leaked: {logs}");
+    }
+
+    client.cancel().await?;
+    server.cancel().await?;
+    Ok(())
+}
+
+#[test]
+fn test_closure_redactor() {
+    let mut message = json!({
+        "params": { "arguments": { "latitude": 39.9087, "cityname": "北京" } },
+        "result": { "cinemas": [{ "longitude": 116.3975 }], "text": r#"{"latitude":39.9087}"# }
+    });
+    rmcp::service::redact(&mut message, &location);
+    assert_eq!(
+        message,
+        json!({
+            "params": { "arguments": { "latitude": REDACTED, "cityname": "北京" } },
+            "result": { "cinemas": [{ "longitude": REDACTED }], "text": r#"{"latitude":"[REDACTED]"}"# }
+        })
+    );
+}
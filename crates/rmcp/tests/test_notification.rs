@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use rmcp::{
-    ClientHandler, ServerHandler, ServiceExt,
+    ClientHandler, ServerHandler,
     model::{
         ResourceUpdatedNotificationParam, ServerCapabilities, ServerInfo, SubscribeRequestParam,
     },
@@ -9,6 +9,9 @@ use rmcp::{
 use tokio::sync::Notify;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
+mod common;
+use common::movie::connect;
+
 pub struct Server {}
 
 impl ServerHandler for Server {
@@ -72,17 +75,13 @@ async fn test_server_notification() -> anyhow::Result<()> {
         )
         .with(tracing_subscriber::fmt::layer())
         .try_init();
-    let (server_transport, client_transport) = tokio::io::duplex(4096);
-    tokio::spawn(async move {
-        let server = Server {}.serve(server_transport).await?;
-        server.waiting().await?;
-        anyhow::Ok(())
-    });
     let receive_signal = Arc::new(Notify::new());
-    let client = Client {
-        receive_signal: receive_signal.clone(),
-    }
-    .serve(client_transport)
+    let client = connect(
+        Server {},
+        Client {
+            receive_signal: receive_signal.clone(),
+        },
+    )
     .await?;
     client
         .subscribe(SubscribeRequestParam {
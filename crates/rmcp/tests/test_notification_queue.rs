@@ -0,0 +1,114 @@
+use std::sync::{Arc, Mutex};
+
+use rmcp::{
+    ClientHandler, RoleClient, ServerHandler, ServiceExt,
+    model::{NumberOrString, ProgressNotificationParam, ProgressToken},
+    service::{NotificationContext, NotificationOverflowPolicy, NotificationQueueConfig},
+};
+use tokio::sync::Notify;
+
+pub struct Server;
+
+impl ServerHandler for Server {}
+
+#[derive(Clone, Default)]
+pub struct ProgressRecorder {
+    received: Arc<Mutex<Vec<ProgressNotificationParam>>>,
+    signal: Arc<Notify>,
+}
+
+impl ClientHandler for ProgressRecorder {
+    async fn on_progress(
+        &self,
+        params: ProgressNotificationParam,
+        _context: NotificationContext<RoleClient>,
+    ) {
+        self.received.lock().unwrap().push(params);
+        self.signal.notify_one();
+    }
+}
+
+const FLOOD: usize = 10_000;
+
+async fn flood_progress(
+    config: NotificationQueueConfig,
+) -> anyhow::Result<Vec<ProgressNotificationParam>> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    let recorder = ProgressRecorder::default();
+    let client_handle = tokio::spawn({
+        let recorder = recorder.clone();
+        async move {
+            let client = recorder.serve(client_transport).await?;
+            client.waiting().await?;
+            anyhow::Ok(())
+        }
+    });
+    let server = Server.serve(server_transport).await?;
+    let peer = server.peer().clone();
+    assert!(peer.enable_notification_queue(config));
+    assert!(!peer.enable_notification_queue(config));
+
+    let progress_token = ProgressToken(NumberOrString::Number(1));
+    for progress in 0..FLOOD {
+        peer.notify_progress(ProgressNotificationParam {
+            progress_token: progress_token.clone(),
+            progress: progress as f64,
+            total: Some((FLOOD - 1) as f64),
+            message: None,
+        })
+        .await?;
+        assert!(peer.queued_notifications() <= config.capacity);
+    }
+
+    // wait for the final progress to arrive
+    let last = (FLOOD - 1) as f64;
+    tokio::time::timeout(tokio::time::Duration::from_secs(10), async {
+        loop {
+            let notified = recorder.signal.notified();
+            if recorder
+                .received
+                .lock()
+                .unwrap()
+                .last()
+                .is_some_and(|p| p.progress == last)
+            {
+                break;
+            }
+            notified.await;
+        }
+    })
+    .await?;
+
+    server.cancel().await?;
+    client_handle.await??;
+    let received = recorder.received.lock().unwrap().clone();
+    Ok(received)
+}
+
+#[tokio::test]
+async fn test_progress_flood_is_coalesced() -> anyhow::Result<()> {
+    let received = flood_progress(NotificationQueueConfig {
+        capacity: 4,
+        overflow: NotificationOverflowPolicy::DropOldestProgress,
+    })
+    .await?;
+    assert!(
+        received.len() < FLOOD,
+        "progress should be coalesced, received {}",
+        received.len()
+    );
+    // coalescing keeps the order of progress
+    assert!(received.windows(2).all(|w| w[0].progress < w[1].progress));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_progress_flood_with_backpressure() -> anyhow::Result<()> {
+    let received = flood_progress(NotificationQueueConfig {
+        capacity: 4,
+        overflow: NotificationOverflowPolicy::Backpressure,
+    })
+    .await?;
+    assert_eq!(received.len(), FLOOD);
+    Ok(())
+}
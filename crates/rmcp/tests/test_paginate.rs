@@ -0,0 +1,98 @@
+use rmcp::{
+    ErrorData, ServerHandler,
+    handler::server::pagination::{Page, paginate},
+    model::{
+        AnnotateAble, ErrorCode, ListResourcesResult, PaginatedRequestParam, RawResource, Resource,
+        ServerCapabilities, ServerInfo,
+    },
+    service::{RequestContext, RoleServer},
+};
+
+mod common;
+use common::movie::connect;
+
+#[test]
+fn test_paginate_in_pages_of_4() {
+    let cinemas = || (1..=10).map(|id| format!("cinema {id}"));
+
+    let page = paginate(cinemas(), None, 4).unwrap();
+    assert_eq!(page.items, ["cinema 1", "cinema 2", "cinema 3", "cinema 4"]);
+    let page = paginate(cinemas(), page.next_cursor.as_deref(), 4).unwrap();
+    assert_eq!(page.items, ["cinema 5", "cinema 6", "cinema 7", "cinema 8"]);
+    let page = paginate(cinemas(), page.next_cursor.as_deref(), 4).unwrap();
+    assert_eq!(
+        page,
+        Page {
+            items: vec!["cinema 9".to_string(), "cinema 10".to_string()],
+            next_cursor: None,
+        }
+    );
+
+    // no cursor when the last page is full
+    let page = paginate(cinemas(), Some("6"), 4).unwrap();
+    assert_eq!(page.items.len(), 4);
+    assert_eq!(page.next_cursor, None);
+}
+
+#[test]
+fn test_invalid_cursor() {
+    let error = paginate(1..=10, Some("cinema"), 4).unwrap_err();
+    assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+}
+
+#[derive(Clone)]
+struct MovieServer;
+
+impl MovieServer {
+    fn cinemas() -> impl Iterator<Item = Resource> {
+        (1..=10).map(|id| {
+            RawResource::new(format!("cinema://{id}"), format!("cinema {id}")).no_annotation()
+        })
+    }
+}
+
+impl ServerHandler for MovieServer {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo {
+            capabilities: ServerCapabilities::builder().enable_resources().build(),
+            ..Default::default()
+        }
+    }
+
+    async fn list_resources(
+        &self,
+        request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourcesResult, ErrorData> {
+        let cursor = request.and_then(|request| request.cursor);
+        let page = paginate(Self::cinemas(), cursor.as_deref(), 4)?;
+        Ok(ListResourcesResult {
+            resources: page.items,
+            next_cursor: page.next_cursor,
+            meta: None,
+        })
+    }
+}
+
+#[tokio::test]
+async fn test_list_paginated_resources() -> anyhow::Result<()> {
+    let client = connect(MovieServer, ()).await?;
+
+    let first = client.list_resources(None).await?;
+    assert_eq!(first.resources.len(), 4);
+    assert!(first.next_cursor.is_some());
+
+    let resources = client.list_all_resources().await?;
+    assert_eq!(
+        resources
+            .iter()
+            .map(|resource| resource.name.as_str())
+            .collect::<Vec<_>>(),
+        MovieServer::cinemas()
+            .map(|resource| resource.raw.name)
+            .collect::<Vec<_>>()
+    );
+
+    client.cancel().await?;
+    Ok(())
+}
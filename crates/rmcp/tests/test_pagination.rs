@@ -0,0 +1,84 @@
+use std::sync::Arc;
+
+use rmcp::{
+    RoleServer, ServerHandler, ServiceError,
+    model::{ListToolsResult, PaginatedRequestParam, ServerCapabilities, ServerInfo, Tool},
+    service::RequestContext,
+};
+
+mod common;
+use common::movie::connect;
+
+fn tool(name: &str) -> Tool {
+    Tool::new(name.to_string(), "test tool", Arc::new(Default::default()))
+}
+
+/// Serves three pages of tools, or never advances the cursor if `stuck` is set
+pub struct PaginatingServer {
+    stuck: bool,
+}
+
+impl ServerHandler for PaginatingServer {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo {
+            capabilities: ServerCapabilities::builder().enable_tools().build(),
+            ..Default::default()
+        }
+    }
+
+    async fn list_tools(
+        &self,
+        request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListToolsResult, rmcp::ErrorData> {
+        if self.stuck {
+            return Ok(ListToolsResult {
+                next_cursor: Some("same".to_string()),
+                meta: None,
+                tools: vec![tool("stuck")],
+            });
+        }
+        let cursor = request.and_then(|request| request.cursor);
+        let (tools, next_cursor) = match cursor.as_deref() {
+            None => (vec![tool("a"), tool("b")], Some("page-2")),
+            Some("page-2") => (vec![tool("c"), tool("d")], Some("page-3")),
+            Some("page-3") => (vec![tool("e")], None),
+            Some(cursor) => {
+                return Err(rmcp::ErrorData::invalid_params(
+                    format!("unknown cursor {cursor}"),
+                    None,
+                ));
+            }
+        };
+        Ok(ListToolsResult {
+            next_cursor: next_cursor.map(str::to_string),
+            meta: None,
+            tools,
+        })
+    }
+}
+
+async fn list_all_tools(stuck: bool) -> anyhow::Result<Result<Vec<Tool>, ServiceError>> {
+    let client = connect(PaginatingServer { stuck }, ()).await?;
+    let result = client.list_all_tools().await;
+    client.cancel().await?;
+    Ok(result)
+}
+
+#[tokio::test]
+async fn test_list_all_tools_follows_cursor() -> anyhow::Result<()> {
+    let tools = list_all_tools(false).await??;
+    let names: Vec<_> = tools.iter().map(|tool| tool.name.as_ref()).collect();
+    assert_eq!(names, ["a", "b", "c", "d", "e"]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_list_all_tools_stops_on_stalled_cursor() -> anyhow::Result<()> {
+    let result = list_all_tools(true).await?;
+    assert!(
+        matches!(result, Err(ServiceError::PaginationStalled { ref cursor }) if cursor == "same"),
+        "unexpected result {result:?}"
+    );
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use rmcp::{
+    model::{ErrorCode, JsonRpcParseMode},
+    transport::{SseServer, sse_server::SseServerConfig},
+};
+use tokio_util::sync::CancellationToken;
+
+const STRICT_BIND_ADDRESS: &str = "127.0.0.1:8160";
+const LENIENT_BIND_ADDRESS: &str = "127.0.0.1:8161";
+
+/// A ping request without the `jsonrpc` version
+const MISSING_VERSION: &str = r#"{"id":1,"method":"ping","params":null}"#;
+
+async fn post(bind_address: &str, body: &str) -> reqwest::Result<reqwest::Response> {
+    reqwest::Client::new()
+        .post(format!("http://{bind_address}/message?sessionId=unknown"))
+        .header("Content-Type", "application/json")
+        .body(body.to_string())
+        .send()
+        .await
+}
+
+#[tokio::test]
+async fn test_sse_server_applies_the_parse_mode() -> anyhow::Result<()> {
+    let ct = CancellationToken::new();
+    let strict = SseServer::serve_with_config(SseServerConfig {
+        bind: STRICT_BIND_ADDRESS.parse()?,
+        ct: ct.child_token(),
+        ..Default::default()
+    })
+    .await?;
+    let lenient = SseServer::serve_with_config(SseServerConfig {
+        bind: LENIENT_BIND_ADDRESS.parse()?,
+        ct: ct.child_token(),
+        parse_mode: JsonRpcParseMode::Lenient,
+        ..Default::default()
+    })
+    .await?;
+
+    let response = post(STRICT_BIND_ADDRESS, MISSING_VERSION).await?;
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+    let error: serde_json::Value = response.json().await?;
+    assert_eq!(error["error"]["code"], ErrorCode::PARSE_ERROR.0);
+    assert_eq!(
+        error["error"]["message"],
+        "missing `jsonrpc` field, expected \"2.0\""
+    );
+
+    // fixed, the message goes on to its session
+    let response = post(LENIENT_BIND_ADDRESS, MISSING_VERSION).await?;
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+
+    strict.cancel();
+    lenient.cancel();
+    ct.cancel();
+    Ok(())
+}
@@ -0,0 +1,95 @@
+use rmcp::{
+    ServerHandler,
+    handler::server::{tool::ToolRouter, wrapper::Parameters},
+    model::{CallToolRequestParam, PartialResult},
+    tool, tool_handler, tool_router,
+};
+
+mod common;
+use common::movie::connect;
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct CinemaRequest {
+    pub cinema_id: i32,
+}
+
+#[derive(Clone)]
+pub struct CinemaServer {
+    tool_router: ToolRouter<Self>,
+}
+
+#[tool_router]
+impl CinemaServer {
+    pub fn new() -> Self {
+        Self {
+            tool_router: Self::tool_router(),
+        }
+    }
+
+    async fn fetch_cinema(&self, cinema_id: i32) -> Result<String, String> {
+        Ok(format!("cinema {cinema_id}"))
+    }
+
+    async fn fetch_movies(&self, _cinema_id: i32) -> Result<String, String> {
+        Err("upstream timeout".to_string())
+    }
+
+    #[tool(description = "Get the cinema and its movies")]
+    async fn get_cinema_information(
+        &self,
+        Parameters(CinemaRequest { cinema_id }): Parameters<CinemaRequest>,
+    ) -> PartialResult {
+        PartialResult::new()
+            .text_part("cinema", self.fetch_cinema(cinema_id).await)
+            .text_part("movies", self.fetch_movies(cinema_id).await)
+    }
+}
+
+impl Default for CinemaServer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for CinemaServer {}
+
+#[tokio::test]
+async fn test_partial_result_keeps_successful_parts() -> anyhow::Result<()> {
+    let client = connect(CinemaServer::new(), ()).await?;
+
+    let result = client
+        .call_tool(CallToolRequestParam {
+            name: "get_cinema_information".into(),
+            arguments: serde_json::json!({ "cinema_id": 42 }).as_object().cloned(),
+        })
+        .await?;
+    assert_eq!(result.is_error, Some(false));
+    let texts: Vec<_> = result
+        .content
+        .iter()
+        .map(|content| content.as_text().expect("text").text.as_str())
+        .collect();
+    assert_eq!(texts, ["cinema 42", "movies failed: upstream timeout"]);
+
+    let parts = PartialResult::parts_of(&result).expect("partial result");
+    assert_eq!(parts.len(), 2);
+    assert!(parts[0].ok);
+    assert_eq!(parts[1].name, "movies");
+    assert!(!parts[1].ok);
+    assert_eq!(parts[1].error.as_deref(), Some("upstream timeout"));
+
+    client.cancel().await?;
+    Ok(())
+}
+
+#[test]
+fn test_partial_result_all_failed_is_error() {
+    let result = PartialResult::new()
+        .text_part("cinema", Err::<String, _>("not found"))
+        .into_call_tool_result();
+    assert_eq!(result.is_error, Some(true));
+    // a result without parts isn't a partial result
+    let result = rmcp::model::CallToolResult::success(vec![]);
+    assert!(PartialResult::parts_of(&result).is_none());
+}
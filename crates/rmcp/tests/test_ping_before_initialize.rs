@@ -0,0 +1,119 @@
+use rmcp::service::{HandshakeConfig, ServerInitializeError, serve_server_with_config};
+use serde_json::{Value, json};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, DuplexStream};
+use tokio_util::sync::CancellationToken;
+
+mod common;
+use common::movie::MovieServer;
+
+/// A client sending raw messages, as a load balancer would
+struct RawClient {
+    reader: BufReader<tokio::io::ReadHalf<DuplexStream>>,
+    writer: tokio::io::WriteHalf<DuplexStream>,
+}
+
+impl RawClient {
+    async fn send(&mut self, message: Value) -> anyhow::Result<()> {
+        self.writer
+            .write_all(format!("{message}\n").as_bytes())
+            .await?;
+        Ok(())
+    }
+
+    async fn receive(&mut self) -> anyhow::Result<Value> {
+        let mut line = String::new();
+        self.reader.read_line(&mut line).await?;
+        Ok(serde_json::from_str(&line)?)
+    }
+}
+
+fn serve(
+    allow_ping_before_initialize: bool,
+) -> (
+    RawClient,
+    tokio::task::JoinHandle<Result<(), ServerInitializeError>>,
+) {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    let server = tokio::spawn(async move {
+        let server = serve_server_with_config(
+            MovieServer,
+            server_transport,
+            CancellationToken::new(),
+            HandshakeConfig {
+                allow_ping_before_initialize,
+                ..Default::default()
+            },
+        )
+        .await?;
+        server.cancel().await.expect("cancel the server");
+        Ok(())
+    });
+    let (reader, writer) = tokio::io::split(client_transport);
+    let client = RawClient {
+        reader: BufReader::new(reader),
+        writer,
+    };
+    (client, server)
+}
+
+fn ping(id: u32) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "method": "ping" })
+}
+
+#[tokio::test]
+async fn test_ping_fresh_session_before_initialize() -> anyhow::Result<()> {
+    let (mut client, server) = serve(true);
+    client.send(ping(1)).await?;
+    assert_eq!(
+        client.receive().await?,
+        json!({ "jsonrpc": "2.0", "id": 1, "result": {} })
+    );
+    client.send(ping(2)).await?;
+    assert_eq!(client.receive().await?["id"], 2);
+
+    // the handshake still happens afterwards
+    client
+        .send(json!({
+            "jsonrpc": "2.0",
+            "id": 3,
+            "method": "initialize",
+            "params": {
+                "protocolVersion": "2025-03-26",
+                "capabilities": {},
+                "clientInfo": { "name": "test", "version": "0.0.1" }
+            }
+        }))
+        .await?;
+    let initialized = client.receive().await?;
+    assert_eq!(initialized["id"], 3);
+    assert!(initialized["result"]["serverInfo"].is_object());
+    client
+        .send(json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }))
+        .await?;
+    server.await??;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_only_ping_is_allowed_before_initialize() -> anyhow::Result<()> {
+    let (mut client, server) = serve(true);
+    client
+        .send(json!({ "jsonrpc": "2.0", "id": 1, "method": "tools/list" }))
+        .await?;
+    assert!(matches!(
+        server.await?,
+        Err(ServerInitializeError::ExpectedInitializeRequest(_))
+    ));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_ping_before_initialize_is_rejected_by_default() -> anyhow::Result<()> {
+    let (mut client, server) = serve(false);
+    client.send(ping(1)).await?;
+    assert!(matches!(
+        server.await?,
+        Err(ServerInitializeError::ExpectedInitializeRequest(_))
+    ));
+    Ok(())
+}
@@ -0,0 +1,112 @@
+use rmcp::{
+    ServiceExt,
+    model::{ClientInfo, ErrorCode, ProtocolVersion},
+    service::{HandshakeConfig, ServerInitializeError, serve_server_with_config},
+};
+use serde_json::{Value, json};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, DuplexStream};
+use tokio_util::sync::CancellationToken;
+
+mod common;
+use common::movie::MovieServer;
+
+fn serve_pinned(
+    transport: DuplexStream,
+    version: ProtocolVersion,
+) -> tokio::task::JoinHandle<Result<(), ServerInitializeError>> {
+    tokio::spawn(async move {
+        let server = serve_server_with_config(
+            MovieServer,
+            transport,
+            CancellationToken::new(),
+            HandshakeConfig {
+                pinned_protocol_version: Some(version),
+                ..Default::default()
+            },
+        )
+        .await?;
+        server.waiting().await.expect("server quits");
+        Ok(())
+    })
+}
+
+/// Send an initialize request for `version`, returning the response
+async fn initialize(transport: DuplexStream, version: &str) -> anyhow::Result<Value> {
+    let (read, mut write) = tokio::io::split(transport);
+    let request = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": version,
+            "capabilities": {},
+            "clientInfo": { "name": "newer movie client", "version": "0.1.0" }
+        }
+    });
+    write.write_all(format!("{request}\n").as_bytes()).await?;
+    let mut line = String::new();
+    BufReader::new(read).read_line(&mut line).await?;
+    Ok(serde_json::from_str(&line)?)
+}
+
+#[tokio::test]
+async fn test_reject_a_newer_client() -> anyhow::Result<()> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    let server = serve_pinned(server_transport, ProtocolVersion::V_2024_11_05);
+
+    let response = initialize(client_transport, "2025-06-18").await?;
+    let error = &response["error"];
+    assert_eq!(error["code"], ErrorCode::INVALID_PARAMS.0);
+    assert!(
+        error["message"]
+            .as_str()
+            .unwrap()
+            .contains("only supports 2024-11-05")
+    );
+    assert_eq!(
+        error["data"],
+        json!({ "supported": ["2024-11-05"], "requested": "2025-06-18" })
+    );
+
+    // the client disconnected
+    let error = server.await?.expect_err("rejected");
+    assert!(
+        matches!(
+            &error,
+            ServerInitializeError::UnsupportedProtocolVersion(version)
+                if *version == ProtocolVersion::V_2025_06_18
+        ),
+        "{error:?}"
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_advertise_the_pinned_version() -> anyhow::Result<()> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    let _server = serve_pinned(server_transport, ProtocolVersion::V_2025_06_18);
+
+    // newer than the version the server negotiates by default
+    let response = initialize(client_transport, "2025-06-18").await?;
+    assert_eq!(response["result"]["protocolVersion"], "2025-06-18");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_client_retries_with_the_pinned_version() -> anyhow::Result<()> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    let server = serve_pinned(server_transport, ProtocolVersion::V_2024_11_05);
+
+    let client = ClientInfo {
+        protocol_version: ProtocolVersion::V_2025_06_18,
+        ..Default::default()
+    }
+    .serve(client_transport)
+    .await?;
+    let server_info = client.peer_info().expect("initialized");
+    assert_eq!(server_info.protocol_version, ProtocolVersion::V_2024_11_05);
+
+    client.cancel().await?;
+    server.await??;
+    Ok(())
+}
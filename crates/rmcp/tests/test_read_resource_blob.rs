@@ -0,0 +1,141 @@
+use std::{sync::Arc, time::Duration};
+
+use base64::engine::{Engine, general_purpose::STANDARD};
+use futures::StreamExt;
+use rmcp::{
+    ErrorData, RoleServer, ServerHandler, ServiceError, ServiceExt,
+    model::{ReadResourceRequestParam, ReadResourceResult, ResourceContents},
+    service::RequestContext,
+};
+use tokio::{
+    io::AsyncReadExt,
+    sync::{Mutex, oneshot},
+};
+
+const CHUNK_SIZE: usize = 4096;
+
+fn poster() -> Vec<u8> {
+    (0..10_000u32).map(|i| (i % 251) as u8).collect()
+}
+
+/// The last read of the poster waits until the test releases it
+#[derive(Clone, Default)]
+struct PosterServer {
+    release: Arc<Mutex<Option<oneshot::Receiver<()>>>>,
+}
+
+impl ServerHandler for PosterServer {
+    async fn read_resource(
+        &self,
+        ReadResourceRequestParam { uri }: ReadResourceRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<ReadResourceResult, ErrorData> {
+        match uri.as_str() {
+            "movie://posters/1" => {
+                // uneven reads, as from a file or an http body
+                let release = self.release.lock().await.take();
+                let poster = poster();
+                let head = [poster[..1000].to_vec(), poster[1000..8192].to_vec()];
+                let tail = poster[8192..].to_vec();
+                let reads = futures::stream::iter(head).chain(futures::stream::once(async move {
+                    if let Some(release) = release {
+                        let _ = release.await;
+                    }
+                    tail
+                }));
+                context
+                    .stream_blob(
+                        uri,
+                        Some("image/png".into()),
+                        CHUNK_SIZE,
+                        reads.map(Ok::<_, std::io::Error>),
+                    )
+                    .await
+                    .map_err(|e| ErrorData::internal_error(e.to_string(), None))
+            }
+            "movie://posters/broken" => {
+                let poster = poster();
+                let contents = [0, 2]
+                    .into_iter()
+                    .map(|index| {
+                        let chunk = poster.chunks(CHUNK_SIZE).nth(index).expect("3 chunks");
+                        ResourceContents::blob_chunk(&uri, None, STANDARD.encode(chunk), index, 3)
+                    })
+                    .collect();
+                Ok(ReadResourceResult { contents })
+            }
+            _ => Err(ErrorData::resource_not_found(uri, None)),
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_read_chunked_blob() -> anyhow::Result<()> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    tokio::spawn(async move {
+        PosterServer::default()
+            .serve(server_transport)
+            .await?
+            .waiting()
+            .await?;
+        anyhow::Ok(())
+    });
+    let client = ().serve(client_transport).await?;
+
+    // without opting in, the chunks are returned in the result
+    let result = client
+        .read_resource(ReadResourceRequestParam {
+            uri: "movie://posters/1".into(),
+        })
+        .await?;
+    let chunks: Vec<_> = result.contents.iter().map(|c| c.chunk()).collect();
+    assert_eq!(chunks, [Some((0, 3)), Some((1, 3)), Some((2, 3))]);
+
+    let poster = client.read_resource_blob("movie://posters/1").await?;
+    assert_eq!(poster, self::poster());
+
+    let missing_chunk = client.read_resource_blob("movie://posters/broken").await;
+    assert!(matches!(
+        missing_chunk,
+        Err(ServiceError::InvalidResourceContents { .. })
+    ));
+
+    client.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_blob_chunks_written_as_they_are_read() -> anyhow::Result<()> {
+    let (release_tx, release_rx) = oneshot::channel();
+    let server = PosterServer {
+        release: Arc::new(Mutex::new(Some(release_rx))),
+    };
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    tokio::spawn(async move {
+        server.serve(server_transport).await?.waiting().await?;
+        anyhow::Ok(())
+    });
+    let client = ().serve(client_transport).await?;
+
+    let (mut writer, mut written) = tokio::io::duplex(64 * 1024);
+    let peer = client.peer().clone();
+    let read = tokio::spawn(async move {
+        peer.read_resource_blob_to("movie://posters/1", &mut writer)
+            .await
+    });
+
+    // the first two chunks are written while the handler still waits for the rest of the poster
+    let mut head = vec![0; 2 * CHUNK_SIZE];
+    tokio::time::timeout(Duration::from_secs(5), written.read_exact(&mut head)).await??;
+    assert_eq!(head, poster()[..2 * CHUNK_SIZE]);
+    assert!(!read.is_finished());
+
+    release_tx.send(()).expect("the handler is waiting");
+    assert_eq!(read.await??, poster().len() as u64);
+    let mut tail = Vec::new();
+    written.read_to_end(&mut tail).await?;
+    assert_eq!(tail, poster()[2 * CHUNK_SIZE..]);
+
+    client.cancel().await?;
+    Ok(())
+}
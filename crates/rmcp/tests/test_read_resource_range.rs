@@ -0,0 +1,93 @@
+use rmcp::{
+    ErrorData, RoleServer, ServerHandler, ServiceExt,
+    model::{
+        ByteRange, ContentRange, ReadResourceRequestParam, ReadResourceResult, ResourceContents,
+    },
+    service::RequestContext,
+};
+
+/// A large dataset, read page by page
+fn showtimes() -> Vec<u8> {
+    (0..10_000u32).map(|i| (i % 251) as u8).collect()
+}
+
+#[derive(Clone)]
+struct ShowtimesServer;
+
+impl ServerHandler for ShowtimesServer {
+    async fn read_resource(
+        &self,
+        ReadResourceRequestParam { uri }: ReadResourceRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<ReadResourceResult, ErrorData> {
+        match uri.as_str() {
+            "movie://showtimes" => Ok(ReadResourceResult {
+                contents: vec![ResourceContents::blob_slice(
+                    uri,
+                    Some("application/octet-stream".into()),
+                    &showtimes(),
+                    context.meta.byte_range(),
+                )],
+            }),
+            _ => Err(ErrorData::resource_not_found(uri, None)),
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_read_byte_range() -> anyhow::Result<()> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    tokio::spawn(async move {
+        ShowtimesServer
+            .serve(server_transport)
+            .await?
+            .waiting()
+            .await?;
+        anyhow::Ok(())
+    });
+    let client = ().serve(client_transport).await?;
+    let showtimes = showtimes();
+
+    let (data, range) = client
+        .read_resource_range("movie://showtimes", ByteRange::new(1000, 1500))
+        .await?;
+    assert_eq!(data, &showtimes[1000..1500]);
+    assert_eq!(
+        range,
+        ContentRange {
+            start: 1000,
+            end: 1500,
+            total: 10_000
+        }
+    );
+
+    // page through the dataset until its end
+    let mut pages = Vec::new();
+    let mut start = 0;
+    loop {
+        let (data, range) = client
+            .read_resource_range("movie://showtimes", ByteRange::new(start, start + 4096))
+            .await?;
+        pages.extend(data);
+        if range.end == range.total {
+            break;
+        }
+        start = range.end;
+    }
+    assert_eq!(pages, showtimes);
+
+    // clamped to the blob
+    let (data, range) = client
+        .read_resource_range("movie://showtimes", ByteRange::starting_at(9_990))
+        .await?;
+    assert_eq!(data, &showtimes[9_990..]);
+    assert_eq!((range.start, range.end), (9_990, 10_000));
+    let (data, range) = client
+        .read_resource_range("movie://showtimes", ByteRange::new(20_000, 30_000))
+        .await?;
+    assert!(data.is_empty());
+    assert_eq!((range.start, range.end), (10_000, 10_000));
+
+    client.cancel().await?;
+    Ok(())
+}
@@ -0,0 +1,141 @@
+use rmcp::{
+    ErrorData, RoleServer, ServerHandler, ServiceError, ServiceExt,
+    model::{ReadResourceRequestParam, ReadResourceResult, ResourceContents},
+    service::RequestContext,
+};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct City {
+    id: i32,
+    nm: String,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Cities {
+    cts: Vec<City>,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Cinema {
+    id: i32,
+    nm: String,
+}
+
+#[derive(Clone)]
+struct CityServer;
+
+impl ServerHandler for CityServer {
+    async fn read_resource(
+        &self,
+        ReadResourceRequestParam { uri }: ReadResourceRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ReadResourceResult, ErrorData> {
+        if uri == "movie://cinema/1" {
+            // the schedule comes first, the typed read still picks the json metadata
+            return Ok(ReadResourceResult {
+                contents: vec![
+                    ResourceContents::TextResourceContents {
+                        uri: format!("{uri}/schedule"),
+                        mime_type: Some("text/plain".into()),
+                        text: "19:30 流浪地球".into(),
+                        meta: None,
+                    },
+                    ResourceContents::TextResourceContents {
+                        uri,
+                        mime_type: Some("application/json".into()),
+                        text: r#"{"id":1,"nm":"万达影城"}"#.into(),
+                        meta: None,
+                    },
+                ],
+            });
+        }
+        let contents = match uri.as_str() {
+            "movie://cities" => ResourceContents::text(
+                r#"{"cts":[{"id":1,"nm":"北京"},{"id":10,"nm":"上海"}]}"#,
+                uri,
+            ),
+            "movie://broken" => ResourceContents::text("{\"cts\":", uri),
+            _ => ResourceContents::BlobResourceContents {
+                uri,
+                mime_type: Some("image/png".into()),
+                blob: "iVBORw0KGgo=".into(),
+                meta: None,
+            },
+        };
+        Ok(ReadResourceResult {
+            contents: vec![contents],
+        })
+    }
+}
+
+#[tokio::test]
+async fn test_read_resource_typed() -> anyhow::Result<()> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    tokio::spawn(async move {
+        CityServer.serve(server_transport).await?.waiting().await?;
+        anyhow::Ok(())
+    });
+    let client = ().serve(client_transport).await?;
+
+    let cities: Cities = client.read_resource_typed("movie://cities").await?;
+    assert_eq!(
+        cities.cts,
+        [
+            City {
+                id: 1,
+                nm: "北京".into()
+            },
+            City {
+                id: 10,
+                nm: "上海".into()
+            }
+        ]
+    );
+
+    let broken = client.read_resource_typed::<Cities>("movie://broken").await;
+    assert!(
+        matches!(&broken, Err(ServiceError::InvalidResourceContents { uri, .. }) if uri == "movie://broken"),
+        "{broken:?}"
+    );
+    let blob = client.read_resource_typed::<Cities>("movie://poster").await;
+    assert!(
+        matches!(&blob, Err(ServiceError::InvalidResourceContents { reason, .. }) if reason.contains("blob")),
+        "{blob:?}"
+    );
+
+    client.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_read_resource_with_multiple_contents() -> anyhow::Result<()> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    tokio::spawn(async move {
+        CityServer.serve(server_transport).await?.waiting().await?;
+        anyhow::Ok(())
+    });
+    let client = ().serve(client_transport).await?;
+
+    let result = client
+        .read_resource(ReadResourceRequestParam {
+            uri: "movie://cinema/1".into(),
+        })
+        .await?;
+    assert_eq!(result.contents.len(), 2);
+    let schedule: Vec<_> = result.contents_with_mime_type("text/plain").collect();
+    assert_eq!(schedule.len(), 1);
+    assert_eq!(schedule[0].uri(), "movie://cinema/1/schedule");
+
+    let cinema: Cinema = client.read_resource_typed("movie://cinema/1").await?;
+    assert_eq!(
+        cinema,
+        Cinema {
+            id: 1,
+            nm: "万达影城".into()
+        }
+    );
+
+    client.cancel().await?;
+    Ok(())
+}
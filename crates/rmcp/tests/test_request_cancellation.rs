@@ -0,0 +1,111 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use rmcp::{
+    RoleServer, ServerHandler, ServiceExt,
+    handler::server::{tool::ToolRouter, wrapper::Parameters},
+    model::{CallToolRequestParam, ClientRequest, Request, ServerResult},
+    service::{PeerRequestOptions, RequestContext},
+    tool, tool_handler, tool_router,
+};
+use serde_json::json;
+use tokio::sync::{Notify, Semaphore};
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct GetCinemaListRequest {
+    city: String,
+}
+
+/// The calls share the same state, they run until released or cancelled
+#[derive(Clone)]
+struct MovieServer {
+    started: Arc<Semaphore>,
+    release: Arc<Notify>,
+    cancelled: Arc<Mutex<Vec<String>>>,
+    tool_router: ToolRouter<Self>,
+}
+
+#[tool_router]
+impl MovieServer {
+    fn new() -> Self {
+        Self {
+            started: Arc::new(Semaphore::new(0)),
+            release: Default::default(),
+            cancelled: Default::default(),
+            tool_router: Self::tool_router(),
+        }
+    }
+
+    #[tool(description = "Get a list of nearby movie theaters")]
+    async fn get_cinema_list(
+        &self,
+        Parameters(GetCinemaListRequest { city }): Parameters<GetCinemaListRequest>,
+        context: RequestContext<RoleServer>,
+    ) -> String {
+        self.started.add_permits(1);
+        tokio::select! {
+            _ = self.release.notified() => format!("{city}: 万达影城"),
+            _ = context.ct.cancelled() => {
+                self.cancelled.lock().unwrap().push(city);
+                String::new()
+            }
+        }
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for MovieServer {}
+
+fn get_cinema_list(city: &str) -> ClientRequest {
+    ClientRequest::CallToolRequest(Request::new(CallToolRequestParam {
+        name: "get_cinema_list".into(),
+        arguments: json!({ "city": city }).as_object().cloned(),
+    }))
+}
+
+#[tokio::test]
+async fn test_cancelling_one_call_lets_the_other_complete() -> anyhow::Result<()> {
+    let server = MovieServer::new();
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    tokio::spawn({
+        let server = server.clone();
+        async move {
+            let server = server.serve(server_transport).await?;
+            server.waiting().await?;
+            anyhow::Ok(())
+        }
+    });
+    let client = ().serve(client_transport).await?;
+
+    let beijing = client
+        .send_cancellable_request(get_cinema_list("北京"), PeerRequestOptions::no_options())
+        .await?;
+    let shanghai = client
+        .send_cancellable_request(get_cinema_list("上海"), PeerRequestOptions::no_options())
+        .await?;
+    // wait for both calls to run on the server
+    server.started.acquire_many(2).await?.forget();
+    beijing.cancel(Some("the user left".to_string())).await?;
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while server.cancelled.lock().unwrap().is_empty() {
+            tokio::task::yield_now().await;
+        }
+    })
+    .await?;
+
+    server.release.notify_one();
+    let ServerResult::CallToolResult(result) =
+        tokio::time::timeout(Duration::from_secs(5), shanghai.await_response()).await??
+    else {
+        panic!("expect a tool result");
+    };
+    assert_eq!(result.content[0].as_text().unwrap().text, "上海: 万达影城");
+    assert_eq!(*server.cancelled.lock().unwrap(), ["北京"]);
+
+    // the session goes on
+    client.list_all_tools().await?;
+    client.cancel().await?;
+    Ok(())
+}
@@ -0,0 +1,141 @@
+use rmcp::{
+    ErrorData, RoleClient, RoleServer, ServerHandler, ServiceExt,
+    handler::server::{
+        common::{FromRequestContext, Locale},
+        router::tool::ToolRouter,
+        wrapper::Parameters,
+    },
+    model::{CallToolRequestParam, ClientRequest, Meta, Request, ServerResult},
+    service::{Peer, PeerRequestOptions, RequestContext, ServiceError},
+    tool, tool_handler, tool_router,
+};
+use serde_json::json;
+
+/// The user authenticated by the `user` field of the request's `_meta`
+struct AuthUser(String);
+
+impl FromRequestContext for AuthUser {
+    fn from_request_context(context: &RequestContext<RoleServer>) -> Result<Self, ErrorData> {
+        context
+            .meta
+            .get("user")
+            .and_then(|user| user.as_str())
+            .map(|user| AuthUser(user.to_owned()))
+            .ok_or_else(|| ErrorData::invalid_request("unauthenticated", None))
+    }
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct BookTicketRequest {
+    cinema_id: String,
+    seats: u32,
+}
+
+#[derive(Clone)]
+struct MovieServer {
+    tool_router: ToolRouter<Self>,
+}
+
+#[tool_router]
+impl MovieServer {
+    fn new() -> Self {
+        Self {
+            tool_router: Self::tool_router(),
+        }
+    }
+
+    #[tool(description = "Book tickets in a cinema")]
+    async fn book_ticket(
+        &self,
+        Parameters(req): Parameters<BookTicketRequest>,
+        AuthUser(user): AuthUser,
+    ) -> String {
+        format!("{} seat(s) at {} for {user}", req.seats, req.cinema_id)
+    }
+
+    #[tool(description = "Greet the user")]
+    fn greet(&self, AuthUser(user): AuthUser, Locale(locale): Locale) -> String {
+        match locale.as_str() {
+            "fr" => format!("Bonjour {user}"),
+            _ => format!("Hello {user}"),
+        }
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for MovieServer {}
+
+async fn call_tool(
+    client: &Peer<RoleClient>,
+    name: &'static str,
+    arguments: serde_json::Value,
+    meta: serde_json::Value,
+) -> Result<String, ServiceError> {
+    let response = client
+        .send_request_with_option(
+            ClientRequest::CallToolRequest(Request::new(CallToolRequestParam {
+                name: name.into(),
+                arguments: arguments.as_object().cloned(),
+            })),
+            PeerRequestOptions {
+                timeout: None,
+                meta: Some(Meta(meta.as_object().cloned().expect("meta object"))),
+            },
+        )
+        .await?
+        .await_response()
+        .await?;
+    let ServerResult::CallToolResult(result) = response else {
+        panic!("expected call tool result, got {response:?}");
+    };
+    Ok(result.content[0]
+        .as_text()
+        .expect("text content")
+        .text
+        .clone())
+}
+
+#[tokio::test]
+async fn test_tool_with_two_extractor_args() -> anyhow::Result<()> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    let (server, client) = tokio::join!(
+        MovieServer::new().serve(server_transport),
+        ().serve(client_transport)
+    );
+    let (server, client) = (server?, client?);
+
+    let booked = call_tool(
+        client.peer(),
+        "book_ticket",
+        json!({ "cinema_id": "wanda", "seats": 2 }),
+        json!({ "user": "alice" }),
+    )
+    .await?;
+    assert_eq!(booked, "2 seat(s) at wanda for alice");
+
+    let greeting = call_tool(
+        client.peer(),
+        "greet",
+        json!({}),
+        json!({ "user": "alice", "rmcp/locale": "fr" }),
+    )
+    .await?;
+    assert_eq!(greeting, "Bonjour alice");
+
+    // the extraction fails without the user, the tool doesn't run
+    let unauthenticated = call_tool(
+        client.peer(),
+        "book_ticket",
+        json!({ "cinema_id": "wanda", "seats": 2 }),
+        json!({}),
+    )
+    .await;
+    let Err(ServiceError::McpError(error)) = unauthenticated else {
+        panic!("expected an error: {unauthenticated:?}");
+    };
+    assert_eq!(error.message, "unauthenticated");
+
+    client.cancel().await?;
+    server.waiting().await?;
+    Ok(())
+}
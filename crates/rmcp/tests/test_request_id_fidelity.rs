@@ -0,0 +1,95 @@
+use rmcp::{ServiceExt, model::JsonRpcParseMode, transport::async_rw::AsyncRwTransport};
+use serde_json::{Value, json};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, DuplexStream};
+
+mod common;
+use common::movie::MovieServer;
+
+/// A strict client sending raw messages, matching the responses by their exact id
+struct RawClient {
+    reader: BufReader<tokio::io::ReadHalf<DuplexStream>>,
+    writer: tokio::io::WriteHalf<DuplexStream>,
+}
+
+impl RawClient {
+    async fn send(&mut self, message: Value) -> anyhow::Result<()> {
+        self.writer
+            .write_all(format!("{message}\n").as_bytes())
+            .await?;
+        Ok(())
+    }
+
+    async fn receive(&mut self) -> anyhow::Result<Value> {
+        let mut line = String::new();
+        self.reader.read_line(&mut line).await?;
+        Ok(serde_json::from_str(&line)?)
+    }
+
+    async fn request(&mut self, id: Value, method: &str) -> anyhow::Result<Value> {
+        self.send(json!({ "jsonrpc": "2.0", "id": id, "method": method }))
+            .await?;
+        self.receive().await
+    }
+}
+
+async fn serve(parse_mode: JsonRpcParseMode) -> anyhow::Result<RawClient> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    let (read, write) = tokio::io::split(server_transport);
+    let transport = AsyncRwTransport::new_server(read, write).with_parse_mode(parse_mode);
+    tokio::spawn(async move {
+        let server = MovieServer.serve(transport).await?;
+        server.waiting().await?;
+        anyhow::Ok(())
+    });
+    let (reader, writer) = tokio::io::split(client_transport);
+    let mut client = RawClient {
+        reader: BufReader::new(reader),
+        writer,
+    };
+    client
+        .send(json!({
+            "jsonrpc": "2.0",
+            "id": "initialize-movies",
+            "method": "initialize",
+            "params": {
+                "protocolVersion": "2025-03-26",
+                "capabilities": {},
+                "clientInfo": { "name": "strict movie client", "version": "0.1.0" }
+            }
+        }))
+        .await?;
+    let initialized = client.receive().await?;
+    assert_eq!(initialized["id"], json!("initialize-movies"));
+    client
+        .send(json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }))
+        .await?;
+    Ok(client)
+}
+
+#[tokio::test]
+async fn test_response_echoes_the_request_id() -> anyhow::Result<()> {
+    for parse_mode in [JsonRpcParseMode::Strict, JsonRpcParseMode::Lenient] {
+        let mut client = serve(parse_mode).await?;
+        for id in [json!("abc"), json!("42"), json!(""), json!(42), json!(0)] {
+            let response = client.request(id.clone(), "ping").await?;
+            assert_eq!(
+                response,
+                json!({ "jsonrpc": "2.0", "id": id, "result": {} }),
+                "{parse_mode:?}"
+            );
+        }
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_error_echoes_the_request_id() -> anyhow::Result<()> {
+    let mut client = serve(JsonRpcParseMode::Strict).await?;
+    // the movie server doesn't know this method
+    let response = client
+        .request(json!("007"), "x-movie/refresh-cache")
+        .await?;
+    assert_eq!(response["id"], json!("007"));
+    assert!(response["error"].is_object(), "{response}");
+    Ok(())
+}
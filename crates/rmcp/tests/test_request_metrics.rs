@@ -0,0 +1,116 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use rmcp::{
+    RoleServer, ServerHandler, ServiceExt,
+    handler::server::router::tool::ToolRouter,
+    model::{CallToolRequestParam, ClientRequest, Request},
+    service::{PeerRequestOptions, RequestContext, RequestOutcome},
+    tool, tool_handler, tool_router,
+};
+
+#[derive(Clone)]
+struct MovieServer {
+    tool_router: ToolRouter<Self>,
+}
+
+#[tool_router]
+impl MovieServer {
+    fn new() -> Self {
+        Self {
+            tool_router: Self::tool_router(),
+        }
+    }
+
+    #[tool(description = "Gets the current system time")]
+    async fn get_current_time(&self) -> String {
+        "2025-06-01 20:00:00".to_string()
+    }
+
+    #[tool(description = "Get the showtimes of a cinema, from a slow upstream")]
+    async fn get_showtimes(&self, context: RequestContext<RoleServer>) -> String {
+        context.ct.cancelled().await;
+        "cancelled".to_string()
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for MovieServer {}
+
+fn call(name: &'static str) -> ClientRequest {
+    ClientRequest::CallToolRequest(Request::new(CallToolRequestParam {
+        name: name.into(),
+        arguments: None,
+    }))
+}
+
+#[tokio::test]
+async fn test_request_outcomes_are_recorded() -> anyhow::Result<()> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    let (server, client) = tokio::join!(
+        MovieServer::new().serve(server_transport),
+        ().serve(client_transport)
+    );
+    let (server, client) = (server?, client?);
+    let recorded = Arc::new(Mutex::new(Vec::<(String, RequestOutcome)>::new()));
+    server.set_metrics_recorder(Some(Arc::new({
+        let recorded = recorded.clone();
+        move |method: &str, outcome: &RequestOutcome| {
+            recorded
+                .lock()
+                .unwrap()
+                .push((method.to_string(), outcome.clone()));
+        }
+    })));
+    let wait_for = |count: usize| {
+        let recorded = recorded.clone();
+        tokio::time::timeout(Duration::from_secs(5), async move {
+            while recorded.lock().unwrap().len() < count {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+    };
+
+    client.send_request(call("get_current_time")).await?;
+    wait_for(1).await?;
+
+    // the user left before the showtimes were found
+    let handle = client
+        .send_request_with_option(call("get_showtimes"), PeerRequestOptions::no_options())
+        .await?;
+    handle.cancel(Some("user left".to_string())).await?;
+    wait_for(2).await?;
+
+    server.set_handler_timeout(Some(Duration::from_millis(50)));
+    client
+        .send_request(call("get_showtimes"))
+        .await
+        .unwrap_err();
+    wait_for(3).await?;
+
+    let recorded = recorded.lock().unwrap().clone();
+    assert_eq!(
+        recorded,
+        vec![
+            ("tools/call".to_string(), RequestOutcome::Completed),
+            (
+                "tools/call".to_string(),
+                RequestOutcome::Cancelled {
+                    reason: Some("user left".to_string())
+                }
+            ),
+            ("tools/call".to_string(), RequestOutcome::TimedOut),
+        ]
+    );
+    let cancelled = recorded
+        .iter()
+        .filter(|(_, outcome)| outcome.label() == "cancelled")
+        .count();
+    assert_eq!(cancelled, 1);
+
+    client.cancel().await?;
+    server.waiting().await?;
+    Ok(())
+}
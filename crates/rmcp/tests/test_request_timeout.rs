@@ -0,0 +1,293 @@
+use std::{sync::Arc, time::Duration};
+
+use rmcp::{
+    ClientHandler, ErrorData, RoleClient, RoleServer, ServerHandler, ServiceError, ServiceExt,
+    model::{
+        CallToolRequestParam, CallToolResult, ClientInfo, ClientRequest, Content,
+        CreateMessageRequestParam, CreateMessageResult, ErrorCode, Meta, Request, Role,
+        SamplingMessage, ServerResult,
+    },
+    service::{
+        HandshakeConfig, Peer, PeerRequestOptions, RequestContext, serve_server_with_config,
+    },
+};
+use tokio::sync::Notify;
+use tokio_util::sync::CancellationToken;
+
+#[derive(Clone, Default)]
+struct SilentClient {
+    cancelled: Arc<Notify>,
+}
+
+impl ClientHandler for SilentClient {
+    async fn create_message(
+        &self,
+        _params: CreateMessageRequestParam,
+        context: RequestContext<RoleClient>,
+    ) -> Result<CreateMessageResult, ErrorData> {
+        // never responds, until the server gives up
+        context.ct.cancelled().await;
+        self.cancelled.notify_one();
+        std::future::pending().await
+    }
+
+    fn get_info(&self) -> ClientInfo {
+        ClientInfo::default()
+    }
+}
+
+#[derive(Clone)]
+struct Server;
+
+impl ServerHandler for Server {}
+
+fn sampling_request() -> CreateMessageRequestParam {
+    CreateMessageRequestParam {
+        messages: vec![SamplingMessage {
+            role: Role::User,
+            content: Content::text("hello"),
+        }],
+        model_preferences: None,
+        system_prompt: None,
+        include_context: None,
+        temperature: None,
+        max_tokens: 16,
+        stop_sequences: None,
+        metadata: None,
+    }
+}
+
+#[tokio::test]
+async fn test_unanswered_request_is_evicted() -> anyhow::Result<()> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    let client = SilentClient::default();
+    let cancelled = client.cancelled.clone();
+    let client = tokio::spawn(async move { client.serve(client_transport).await });
+    let server = Server.serve(server_transport).await?;
+    let _client = client.await??;
+
+    let peer: &Peer<_> = server.peer();
+    // waits forever unless configured
+    assert_eq!(peer.request_timeout(), None);
+    peer.set_request_timeout(Some(Duration::from_millis(100)));
+
+    let result = peer.create_message(sampling_request()).await;
+    assert!(
+        matches!(result, Err(ServiceError::Timeout { timeout }) if timeout == Duration::from_millis(100)),
+        "{result:?}"
+    );
+    // the client is told to stop handling the evicted request
+    tokio::time::timeout(Duration::from_secs(5), cancelled.notified()).await?;
+
+    server.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_request_timeout_from_config() -> anyhow::Result<()> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    let client = tokio::spawn(async move { SilentClient::default().serve(client_transport).await });
+    let config = HandshakeConfig {
+        request_timeout: Some(Duration::from_millis(100)),
+        ..Default::default()
+    };
+    let server =
+        serve_server_with_config(Server, server_transport, CancellationToken::new(), config)
+            .await?;
+    let _client = client.await??;
+    let peer = server.peer();
+    assert_eq!(peer.request_timeout(), Some(Duration::from_millis(100)));
+
+    let result = peer.create_message(sampling_request()).await;
+    assert!(
+        matches!(result, Err(ServiceError::Timeout { .. })),
+        "{result:?}"
+    );
+
+    // a sampling request waiting on the user opts out
+    let waiting = tokio::time::timeout(
+        Duration::from_millis(300),
+        peer.create_message_with_timeout(sampling_request(), Some(PeerRequestOptions::NO_TIMEOUT)),
+    )
+    .await;
+    assert!(waiting.is_err(), "{waiting:?}");
+
+    server.cancel().await?;
+    Ok(())
+}
+
+#[derive(Clone, Default)]
+struct SlowServer {
+    handling: Arc<std::sync::Mutex<Option<CancellationToken>>>,
+}
+
+impl ServerHandler for SlowServer {
+    async fn call_tool(
+        &self,
+        _request: CallToolRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, ErrorData> {
+        // like an upstream call that hangs
+        *self.handling.lock().unwrap() = Some(context.ct);
+        std::future::pending().await
+    }
+}
+
+async fn call_slow_tool(
+    client: &Peer<RoleClient>,
+    timeout: Duration,
+) -> Result<ServerResult, ServiceError> {
+    let mut meta = Meta::new();
+    meta.set_timeout(timeout);
+    client
+        .send_request_with_option(
+            ClientRequest::CallToolRequest(Request::new(CallToolRequestParam {
+                name: "slow".into(),
+                arguments: None,
+            })),
+            PeerRequestOptions {
+                timeout: None,
+                meta: Some(meta),
+            },
+        )
+        .await?
+        .await_response()
+        .await
+}
+
+#[tokio::test]
+async fn test_client_supplied_timeout_fires() -> anyhow::Result<()> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    let server = SlowServer::default();
+    let handling = server.handling.clone();
+    let server = tokio::spawn(async move { server.serve(server_transport).await });
+    let client = ().serve(client_transport).await?;
+    let server = server.await??;
+
+    let result = call_slow_tool(client.peer(), Duration::from_millis(100)).await;
+    match result {
+        Err(ServiceError::McpError(error)) => {
+            assert_eq!(error.code, ErrorCode::REQUEST_TIMEOUT);
+            assert_eq!(error.data, Some(serde_json::json!({ "timeoutMs": 100 })));
+        }
+        other => panic!("expected a timeout error, got {other:?}"),
+    }
+    // the handler is cancelled
+    let ct = handling.lock().unwrap().take().expect("handled");
+    assert!(ct.is_cancelled());
+
+    // the server's own limit caps a longer hint
+    server
+        .peer()
+        .set_handler_timeout(Some(Duration::from_millis(50)));
+    let result = tokio::time::timeout(
+        Duration::from_secs(5),
+        call_slow_tool(client.peer(), Duration::from_secs(60)),
+    )
+    .await?;
+    match result {
+        Err(ServiceError::McpError(error)) => {
+            assert_eq!(error.data, Some(serde_json::json!({ "timeoutMs": 50 })));
+        }
+        other => panic!("expected a timeout error, got {other:?}"),
+    }
+
+    client.cancel().await?;
+    Ok(())
+}
+
+#[derive(Clone, Default)]
+struct TimedServer {
+    remaining: Arc<std::sync::Mutex<Vec<Option<Duration>>>>,
+}
+
+impl ServerHandler for TimedServer {
+    async fn call_tool(
+        &self,
+        _request: CallToolRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let before = context.time_remaining();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let after = context.time_remaining();
+        self.remaining.lock().unwrap().extend([before, after]);
+        Ok(CallToolResult::success(vec![Content::text("showtimes")]))
+    }
+}
+
+#[tokio::test]
+async fn test_handler_reads_time_remaining() -> anyhow::Result<()> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    let server = TimedServer::default();
+    let remaining = server.remaining.clone();
+    let server = tokio::spawn(async move { server.serve(server_transport).await });
+    let client = ().serve(client_transport).await?;
+    let _server = server.await??;
+
+    call_slow_tool(client.peer(), Duration::from_secs(10)).await?;
+    let [before, after] = remaining.lock().unwrap().drain(..).collect::<Vec<_>>()[..] else {
+        panic!("expected two readings");
+    };
+    let (before, after) = (before.expect("a deadline"), after.expect("a deadline"));
+    assert!(before <= Duration::from_secs(10));
+    assert!(
+        after + Duration::from_millis(20) <= before,
+        "{after:?} {before:?}"
+    );
+
+    // no deadline without a timeout
+    client
+        .call_tool(CallToolRequestParam {
+            name: "showtimes".into(),
+            arguments: None,
+        })
+        .await?;
+    assert_eq!(*remaining.lock().unwrap(), [None, None]);
+
+    client.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_huge_client_supplied_timeout_is_clamped() -> anyhow::Result<()> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    let server = TimedServer::default();
+    let remaining = server.remaining.clone();
+    let server = tokio::spawn(async move { server.serve(server_transport).await });
+    let client = ().serve(client_transport).await?;
+    let _server = server.await??;
+
+    let mut meta = Meta::new();
+    meta.0.insert(
+        "rmcp/timeoutMs".to_string(),
+        serde_json::json!(18446744073709551615u64),
+    );
+    let response = client
+        .send_request_with_option(
+            ClientRequest::CallToolRequest(Request::new(CallToolRequestParam {
+                name: "showtimes".into(),
+                arguments: None,
+            })),
+            PeerRequestOptions {
+                timeout: None,
+                meta: Some(meta),
+            },
+        )
+        .await?
+        .await_response()
+        .await?;
+    assert!(matches!(response, ServerResult::CallToolResult(_)));
+    let before = remaining.lock().unwrap()[0].expect("a deadline");
+    assert!(before <= Meta::MAX_TIMEOUT, "{before:?}");
+
+    // the serve loop is still alive
+    client
+        .call_tool(CallToolRequestParam {
+            name: "showtimes".into(),
+            arguments: None,
+        })
+        .await?;
+
+    client.cancel().await?;
+    Ok(())
+}
@@ -0,0 +1,108 @@
+use rmcp::{
+    ErrorData, RoleServer, ServerHandler, ServiceExt,
+    model::{
+        AnnotateAble, ListResourceTemplatesResult, PaginatedRequestParam, RawResourceTemplate,
+        ReadResourceRequestParam, ReadResourceResult, ResourceContents, ServerCapabilities,
+        ServerInfo,
+    },
+    service::RequestContext,
+};
+
+#[derive(serde::Deserialize, schemars::JsonSchema)]
+pub struct CinemaShowsParameters {
+    /// Cinema ID, as returned by `get_cinema_list`
+    pub cinema_id: i32,
+}
+
+fn cinema_shows() -> RawResourceTemplate {
+    RawResourceTemplate::new("cinema://{cinema_id}/shows", "cinema_shows")
+        .with_description("The movie schedule of a cinema")
+        .with_mime_type("application/json")
+        .with_parameters::<CinemaShowsParameters>()
+}
+
+#[derive(Clone)]
+struct CinemaServer;
+
+impl ServerHandler for CinemaServer {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo {
+            capabilities: ServerCapabilities::builder().enable_resources().build(),
+            ..Default::default()
+        }
+    }
+
+    async fn list_resource_templates(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourceTemplatesResult, ErrorData> {
+        Ok(ListResourceTemplatesResult {
+            next_cursor: None,
+            meta: None,
+            resource_templates: vec![cinema_shows().no_annotation()],
+        })
+    }
+
+    async fn read_resource(
+        &self,
+        ReadResourceRequestParam { uri }: ReadResourceRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ReadResourceResult, ErrorData> {
+        let variables = cinema_shows()
+            .match_uri(&uri)
+            .ok_or_else(|| ErrorData::resource_not_found("resource_not_found", None))?;
+        let shows = format!("shows of cinema {}", variables["cinema_id"]);
+        Ok(ReadResourceResult {
+            contents: vec![ResourceContents::text(shows, uri)],
+        })
+    }
+}
+
+#[tokio::test]
+async fn test_resource_template_with_parameters() -> anyhow::Result<()> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    tokio::spawn(async move {
+        CinemaServer
+            .serve(server_transport)
+            .await?
+            .waiting()
+            .await?;
+        anyhow::Ok(())
+    });
+    let client = ().serve(client_transport).await?;
+
+    let templates = client.list_all_resource_templates().await?;
+    assert_eq!(templates.len(), 1);
+    let template = &templates[0].raw;
+    assert_eq!(template.uri_template, "cinema://{cinema_id}/shows");
+    assert_eq!(template.variables(), ["cinema_id"]);
+    let parameters = template.parameters().expect("documented parameters");
+    let cinema_id = &parameters["properties"]["cinema_id"];
+    assert_eq!(cinema_id["type"], "integer");
+    assert_eq!(
+        cinema_id["description"],
+        "Cinema ID, as returned by `get_cinema_list`"
+    );
+
+    let result = client
+        .read_resource(ReadResourceRequestParam {
+            uri: "cinema://42/shows".into(),
+        })
+        .await?;
+    let ResourceContents::TextResourceContents { text, .. } = &result.contents[0] else {
+        panic!("expected text contents");
+    };
+    assert_eq!(text, "shows of cinema 42");
+    assert!(
+        client
+            .read_resource(ReadResourceRequestParam {
+                uri: "cinema://42/tickets".into(),
+            })
+            .await
+            .is_err()
+    );
+
+    client.cancel().await?;
+    Ok(())
+}
@@ -0,0 +1,140 @@
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+
+use rmcp::{
+    ErrorData, RoleServer, ServerHandler, ServiceError,
+    model::{
+        CallToolRequestParam, CallToolResult, Content, ErrorCode, ServerCapabilities, ServerInfo,
+    },
+    service::{RequestContext, RetryPolicy},
+};
+use serde_json::json;
+
+mod common;
+use common::movie::connect;
+
+/// The movie server, whose upstream is down for the first `outages` calls
+#[derive(Clone, Default)]
+struct MovieServer {
+    outages: usize,
+    calls: Arc<AtomicUsize>,
+}
+
+impl ServerHandler for MovieServer {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo {
+            capabilities: ServerCapabilities::builder().enable_tools().build(),
+            ..Default::default()
+        }
+    }
+
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let calls = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+        match request.name.as_ref() {
+            "book_ticket" => Err(ErrorData::invalid_request("the show is sold out", None)),
+            _ if calls <= self.outages => {
+                Err(ErrorData::unavailable("the cinema service is down", None))
+            }
+            _ => Ok(CallToolResult::success(vec![Content::text("万达影城")])),
+        }
+    }
+}
+
+fn call(name: &'static str) -> CallToolRequestParam {
+    CallToolRequestParam {
+        name: name.into(),
+        arguments: None,
+    }
+}
+
+const RETRY: RetryPolicy = RetryPolicy {
+    max_retries: 3,
+    initial_delay: Duration::from_millis(1),
+    max_delay: Duration::from_millis(10),
+};
+
+#[test]
+fn test_retryable_flag_round_trips() -> anyhow::Result<()> {
+    let error = ErrorData::unavailable("the cinema service is down", None);
+    assert_eq!(error.code, ErrorCode::UNAVAILABLE);
+    let raw = serde_json::to_value(&error)?;
+    assert_eq!(raw["data"], json!({ "retryable": true }));
+    let error: ErrorData = serde_json::from_value(raw)?;
+    assert!(error.is_retryable());
+
+    // the flag overrides the code, and keeps the rest of the data
+    let error =
+        ErrorData::invalid_request("Failed to get movie info", Some(json!({ "movieId": 1 })))
+            .with_retryable(true);
+    let error: ErrorData = serde_json::from_value(serde_json::to_value(&error)?)?;
+    assert!(error.is_retryable());
+    assert_eq!(error.data, Some(json!({ "movieId": 1, "retryable": true })));
+    assert!(
+        !ErrorData::unavailable("maintenance", None)
+            .with_retryable(false)
+            .is_retryable()
+    );
+
+    // without the flag, it depends on the code
+    assert!(ErrorData::request_timeout("too slow", None).is_retryable());
+    assert!(!ErrorData::invalid_params("missing movie id", None).is_retryable());
+    assert!(!ErrorData::internal_error("oops", None).is_retryable());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_client_retries_retryable_errors() -> anyhow::Result<()> {
+    let server = MovieServer {
+        outages: 2,
+        ..Default::default()
+    };
+    let client = connect(server.clone(), ()).await?;
+    client.set_retry_policy(Some(RETRY));
+    let result = client.call_tool(call("get_cinema_list")).await?;
+    assert_eq!(result.content[0].as_text().unwrap().text, "万达影城");
+    assert_eq!(server.calls.load(Ordering::SeqCst), 3);
+
+    // errors which aren't retryable fail right away
+    server.calls.store(0, Ordering::SeqCst);
+    let Err(ServiceError::McpError(error)) = client.call_tool(call("book_ticket")).await else {
+        panic!("expect booking to fail");
+    };
+    assert_eq!(error.code, ErrorCode::INVALID_REQUEST);
+    assert_eq!(server.calls.load(Ordering::SeqCst), 1);
+    client.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_client_gives_up_after_max_retries() -> anyhow::Result<()> {
+    let server = MovieServer {
+        outages: usize::MAX,
+        ..Default::default()
+    };
+    let client = connect(server.clone(), ()).await?;
+    // no retry by default
+    let Err(ServiceError::McpError(error)) = client.call_tool(call("get_cinema_list")).await else {
+        panic!("expect the call to fail");
+    };
+    assert!(error.is_retryable());
+    assert_eq!(server.calls.load(Ordering::SeqCst), 1);
+
+    server.calls.store(0, Ordering::SeqCst);
+    client.set_retry_policy(Some(RETRY));
+    let Err(ServiceError::McpError(error)) = client.call_tool(call("get_cinema_list")).await else {
+        panic!("expect the call to fail");
+    };
+    assert_eq!(error.code, ErrorCode::UNAVAILABLE);
+    assert_eq!(server.calls.load(Ordering::SeqCst), 1 + RETRY.max_retries);
+    client.cancel().await?;
+    Ok(())
+}
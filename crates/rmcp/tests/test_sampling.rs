@@ -5,12 +5,37 @@ mod common;
 use anyhow::Result;
 use common::handlers::{TestClientHandler, TestServer};
 use rmcp::{
-    ServiceExt,
+    ClientHandler, RoleClient, ServiceExt,
     model::*,
-    service::{RequestContext, Service},
+    service::{NotificationContext, RequestContext, Service},
 };
 use tokio_util::sync::CancellationToken;
 
+/// A client which never answers sampling requests, and reports cancellations it receives.
+#[derive(Clone)]
+struct PendingSamplingClient {
+    cancelled_tx: tokio::sync::mpsc::UnboundedSender<CancelledNotificationParam>,
+}
+
+impl ClientHandler for PendingSamplingClient {
+    async fn create_message(
+        &self,
+        _params: CreateMessageRequestParam,
+        context: RequestContext<RoleClient>,
+    ) -> Result<CreateMessageResult, ErrorData> {
+        context.ct.cancelled().await;
+        Err(ErrorData::internal_error("cancelled", None))
+    }
+
+    async fn on_cancelled(
+        &self,
+        params: CancelledNotificationParam,
+        _context: NotificationContext<RoleClient>,
+    ) {
+        let _ = self.cancelled_tx.send(params);
+    }
+}
+
 #[tokio::test]
 async fn test_basic_sampling_message_creation() -> Result<()> {
     // Test basic sampling message structure
@@ -28,6 +53,65 @@ async fn test_basic_sampling_message_creation() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_sampling_request_params_builder() -> Result<()> {
+    let params = CreateMessageRequestParam::new(
+        vec![
+            SamplingMessage::user_text("Summarize 流浪地球"),
+            SamplingMessage::assistant_text("A rogue planet..."),
+        ],
+        1000,
+    )
+    .with_model_preferences(
+        ModelPreferences::new()
+            .with_hint("claude-3-haiku")
+            .with_hint("claude")
+            .with_cost_priority(0.5)
+            .with_speed_priority(0.75)
+            .with_intelligence_priority(0.25),
+    )
+    .with_system_prompt("Answer in at most three sentences.")
+    .with_include_context(ContextInclusion::ThisServer)
+    .with_temperature(0.5)
+    .with_max_tokens(120)
+    .with_stop_sequences(["\n\n"])
+    .with_stop_sequences(vec!["END".to_string()])
+    .with_metadata(serde_json::json!({ "movieId": 1255 }));
+
+    assert_eq!(
+        serde_json::to_value(&params)?,
+        serde_json::json!({
+            "messages": [
+                { "role": "user", "content": { "type": "text", "text": "Summarize 流浪地球" } },
+                { "role": "assistant", "content": { "type": "text", "text": "A rogue planet..." } }
+            ],
+            "modelPreferences": {
+                "hints": [{ "name": "claude-3-haiku" }, { "name": "claude" }],
+                "costPriority": 0.5,
+                "speedPriority": 0.75,
+                "intelligencePriority": 0.25
+            },
+            "systemPrompt": "Answer in at most three sentences.",
+            "includeContext": "thisServer",
+            "temperature": 0.5,
+            "maxTokens": 120,
+            "stopSequences": ["\n\n", "END"],
+            "metadata": { "movieId": 1255 }
+        })
+    );
+
+    // only the required fields by default
+    let params = CreateMessageRequestParam::new(vec![SamplingMessage::user_text("Hi")], 10);
+    assert_eq!(
+        serde_json::to_value(&params)?,
+        serde_json::json!({
+            "messages": [{ "role": "user", "content": { "type": "text", "text": "Hi" } }],
+            "maxTokens": 10
+        })
+    );
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_sampling_request_params() -> Result<()> {
     // Test sampling request parameters structure
@@ -319,3 +403,48 @@ async fn test_sampling_error_invalid_message_sequence() -> Result<()> {
     server_handle.await??;
     Ok(())
 }
+
+#[tokio::test]
+async fn test_dropped_sampling_request_sends_cancellation() -> Result<()> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    let (cancelled_tx, mut cancelled_rx) = tokio::sync::mpsc::unbounded_channel();
+
+    let client_handle = tokio::spawn(async move {
+        let client = PendingSamplingClient { cancelled_tx }
+            .serve(client_transport)
+            .await?;
+        client.waiting().await?;
+        anyhow::Ok(())
+    });
+
+    let server = TestServer::new().serve(server_transport).await?;
+
+    // start a server -> client request and drop it before the client answers
+    let request = server.peer().create_message(CreateMessageRequestParam {
+        messages: vec![SamplingMessage {
+            role: Role::User,
+            content: Content::text("Never answered"),
+        }],
+        include_context: None,
+        model_preferences: None,
+        system_prompt: None,
+        temperature: None,
+        max_tokens: 100,
+        stop_sequences: None,
+        metadata: None,
+    });
+    let result = tokio::time::timeout(tokio::time::Duration::from_millis(100), request).await;
+    assert!(result.is_err(), "client should never answer the request");
+
+    let cancelled = tokio::time::timeout(tokio::time::Duration::from_secs(5), cancelled_rx.recv())
+        .await?
+        .expect("client should receive cancellation");
+    assert_eq!(
+        cancelled.reason.as_deref(),
+        Some(rmcp::service::RequestHandle::<rmcp::RoleServer>::REQUEST_DROPPED_REASON)
+    );
+
+    server.cancel().await?;
+    client_handle.await??;
+    Ok(())
+}
@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+
+use rmcp::transport::auto::{AutoTransport, AutoTransportError};
+
+fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+    let vars: HashMap<String, String> = vars
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+    move |key| vars.get(key).cloned()
+}
+
+fn select(args: &[&str], vars: &[(&str, &str)]) -> Result<AutoTransport, AutoTransportError> {
+    AutoTransport::select(args.iter().copied(), env(vars))
+}
+
+#[test]
+fn test_stdio_by_default() {
+    assert_eq!(select(&[], &[]).unwrap(), AutoTransport::Stdio);
+    assert_eq!(
+        select(&[], &[("MCP_TRANSPORT", "")]).unwrap(),
+        AutoTransport::Stdio
+    );
+    // the bind address is only parsed for the http transports
+    assert_eq!(
+        select(&[], &[("MCP_TRANSPORT", "stdio"), ("MCP_BIND", "cinema")]).unwrap(),
+        AutoTransport::Stdio
+    );
+}
+
+#[test]
+fn test_select_from_the_env() {
+    assert_eq!(
+        select(&[], &[("MCP_TRANSPORT", "sse")]).unwrap(),
+        AutoTransport::Sse {
+            bind: "127.0.0.1:8000".parse().unwrap()
+        }
+    );
+    assert_eq!(
+        select(
+            &[],
+            &[
+                ("MCP_TRANSPORT", "Streamable-HTTP"),
+                ("MCP_BIND", "0.0.0.0:9000")
+            ]
+        )
+        .unwrap(),
+        AutoTransport::StreamableHttp {
+            bind: "0.0.0.0:9000".parse().unwrap()
+        }
+    );
+    assert_eq!(
+        select(&[], &[("MCP_TRANSPORT", "http")]).unwrap(),
+        AutoTransport::StreamableHttp {
+            bind: "127.0.0.1:8000".parse().unwrap()
+        }
+    );
+}
+
+#[test]
+fn test_flags_win_over_the_env() {
+    let vars = [("MCP_TRANSPORT", "sse"), ("MCP_BIND", "0.0.0.0:9000")];
+    assert_eq!(
+        select(&["--transport", "stdio"], &vars).unwrap(),
+        AutoTransport::Stdio
+    );
+    assert_eq!(
+        select(
+            &["--verbose", "--transport=http", "--bind", "127.0.0.1:9100"],
+            &vars
+        )
+        .unwrap(),
+        AutoTransport::StreamableHttp {
+            bind: "127.0.0.1:9100".parse().unwrap()
+        }
+    );
+    // the address still comes from the env
+    assert_eq!(
+        select(&["--transport", "http"], &vars).unwrap(),
+        AutoTransport::StreamableHttp {
+            bind: "0.0.0.0:9000".parse().unwrap()
+        }
+    );
+}
+
+#[test]
+fn test_invalid_selection() {
+    assert!(matches!(
+        select(&[], &[("MCP_TRANSPORT", "websocket")]),
+        Err(AutoTransportError::UnknownTransport(kind)) if kind == "websocket"
+    ));
+    assert!(matches!(
+        select(&["--transport=sse", "--bind=cinema"], &[]),
+        Err(AutoTransportError::InvalidBind(bind, _)) if bind == "cinema"
+    ));
+    assert!(matches!(
+        select(&["--transport"], &[]),
+        Err(AutoTransportError::MissingValue("--transport"))
+    ));
+}
+
+#[tokio::test]
+async fn test_serve_until_cancelled() -> anyhow::Result<()> {
+    #[derive(Clone)]
+    struct MovieServer;
+    impl rmcp::ServerHandler for MovieServer {}
+
+    let ct = tokio_util::sync::CancellationToken::new();
+    let server = tokio::spawn(
+        AutoTransport::StreamableHttp {
+            bind: "127.0.0.1:0".parse()?,
+        }
+        .serve(|| MovieServer, ct.clone()),
+    );
+    ct.cancel();
+    server.await??;
+    Ok(())
+}
@@ -0,0 +1,40 @@
+use rmcp::{
+    ServerHandler,
+    model::{ServerCapabilities, ServerInfo},
+};
+
+mod common;
+use common::movie::connect;
+
+#[derive(Clone)]
+struct MovieServer;
+
+impl ServerHandler for MovieServer {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo {
+            capabilities: ServerCapabilities::builder()
+                .enable_tools()
+                .enable_resources()
+                .build(),
+            instructions: Some("Ask about movies and cinemas".into()),
+            ..Default::default()
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_client_caches_server_info() -> anyhow::Result<()> {
+    let client = connect(MovieServer, ()).await?;
+
+    let advertised = MovieServer.get_info();
+    let server_info = client.server_info().expect("cached after initialize");
+    assert_eq!(server_info, &advertised);
+    assert_eq!(client.server_capabilities(), Some(&advertised.capabilities));
+    assert_eq!(
+        client.server_instructions(),
+        Some("Ask about movies and cinemas")
+    );
+
+    client.cancel().await?;
+    Ok(())
+}
@@ -0,0 +1,77 @@
+use std::time::Duration;
+
+use rmcp::{
+    ClientHandler, ErrorData, RoleClient, ServerHandler, ServiceError, ServiceExt,
+    model::{ListRootsResult, SessionClosedNotificationParam},
+    service::{NotificationContext, QuitReason, RequestContext},
+};
+use tokio::sync::mpsc;
+
+pub struct Server;
+
+impl ServerHandler for Server {}
+
+/// Never answers `roots/list`, so the request is still pending when the session is closed
+pub struct PendingRootsClient {
+    closed_tx: mpsc::UnboundedSender<String>,
+}
+
+impl ClientHandler for PendingRootsClient {
+    async fn list_roots(
+        &self,
+        _context: RequestContext<RoleClient>,
+    ) -> Result<ListRootsResult, ErrorData> {
+        std::future::pending().await
+    }
+
+    async fn on_session_closed(
+        &self,
+        params: SessionClosedNotificationParam,
+        _context: NotificationContext<RoleClient>,
+    ) {
+        let _ = self.closed_tx.send(params.reason);
+    }
+}
+
+#[tokio::test]
+async fn test_close_with_reason() -> anyhow::Result<()> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    let (closed_tx, mut closed_rx) = mpsc::unbounded_channel();
+    let client = tokio::spawn(async move {
+        let client = PendingRootsClient { closed_tx }
+            .serve(client_transport)
+            .await?;
+        anyhow::Ok(client.waiting().await?)
+    });
+    let server = Server.serve(server_transport).await?;
+
+    let pending = tokio::spawn({
+        let peer = server.peer().clone();
+        async move { peer.list_roots().await }
+    });
+    // let the request reach the client
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    server.close("server maintenance").await?;
+    let pending = pending.await?;
+    assert!(
+        matches!(&pending, Err(ServiceError::SessionClosed { reason }) if reason == "server maintenance"),
+        "unexpected result {pending:?}"
+    );
+    let server_quit = server.waiting().await?;
+    assert!(matches!(
+        server_quit,
+        QuitReason::SessionClosed { ref reason, by_peer: false } if reason == "server maintenance"
+    ));
+
+    let client_quit = tokio::time::timeout(Duration::from_secs(5), client).await???;
+    assert!(matches!(
+        client_quit,
+        QuitReason::SessionClosed { ref reason, by_peer: true } if reason == "server maintenance"
+    ));
+    assert_eq!(
+        closed_rx.recv().await.as_deref(),
+        Some("server maintenance")
+    );
+    Ok(())
+}
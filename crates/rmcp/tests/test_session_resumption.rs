@@ -0,0 +1,143 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use rmcp::{
+    ErrorData, RoleClient, RoleServer, ServerHandler, ServiceExt,
+    handler::server::{resumption::ResumptionSigner, tool::ToolRouter, wrapper::Parameters},
+    model::{CallToolRequestParam, ClientInfo, InitializeRequestParam, InitializeResult},
+    service::{RequestContext, RunningService},
+    tool, tool_handler, tool_router,
+};
+use serde_json::json;
+
+const SECRET: &str = "secret of the movie server, kept across restarts";
+
+/// The state persisted by the server, which survives a restart
+type Store = Arc<Mutex<HashMap<String, String>>>;
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct FavoriteCinema {
+    cinema: String,
+}
+
+#[derive(Clone)]
+struct MovieServer {
+    signer: ResumptionSigner,
+    store: Store,
+    session: Arc<Mutex<String>>,
+    tool_router: ToolRouter<Self>,
+}
+
+#[tool_router]
+impl MovieServer {
+    fn new(store: Store) -> Self {
+        Self {
+            signer: ResumptionSigner::new(SECRET),
+            store,
+            session: Default::default(),
+            tool_router: Self::tool_router(),
+        }
+    }
+
+    #[tool(description = "Remember the favorite cinema of the user")]
+    fn set_favorite_cinema(
+        &self,
+        Parameters(FavoriteCinema { cinema }): Parameters<FavoriteCinema>,
+    ) -> String {
+        let session = self.session.lock().unwrap().clone();
+        self.store.lock().unwrap().insert(session, cinema);
+        "saved".to_string()
+    }
+
+    #[tool(description = "Get the favorite cinema of the user")]
+    fn get_favorite_cinema(&self) -> String {
+        let session = self.session.lock().unwrap();
+        let store = self.store.lock().unwrap();
+        store.get(&*session).cloned().unwrap_or_default()
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for MovieServer {
+    async fn initialize(
+        &self,
+        request: InitializeRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<InitializeResult, ErrorData> {
+        let session = self
+            .signer
+            .resumed_session(&request)
+            .filter(|session| self.store.lock().unwrap().contains_key(session))
+            .unwrap_or_else(ResumptionSigner::new_session);
+        *self.session.lock().unwrap() = session.clone();
+        context.peer.set_peer_info(request);
+        let mut info = self.get_info();
+        info.capabilities
+            .set_resumption_token(self.signer.issue(&session));
+        Ok(info)
+    }
+}
+
+async fn connect(
+    store: &Store,
+    resumption_token: Option<String>,
+) -> anyhow::Result<(
+    RunningService<RoleServer, MovieServer>,
+    RunningService<RoleClient, ClientInfo>,
+)> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    let server = tokio::spawn(MovieServer::new(store.clone()).serve(server_transport));
+    let mut client_info = ClientInfo::default();
+    if let Some(token) = resumption_token {
+        client_info.capabilities.set_resumption_token(token);
+    }
+    let client = client_info.serve(client_transport).await?;
+    Ok((server.await??, client))
+}
+
+async fn favorite_cinema(
+    client: &RunningService<RoleClient, ClientInfo>,
+) -> anyhow::Result<String> {
+    let result = client
+        .call_tool(CallToolRequestParam {
+            name: "get_favorite_cinema".into(),
+            arguments: None,
+        })
+        .await?;
+    Ok(result.content[0].as_text().unwrap().text.clone())
+}
+
+#[tokio::test]
+async fn test_resume_session_after_restart() -> anyhow::Result<()> {
+    let store = Store::default();
+    let (server, client) = connect(&store, None).await?;
+    client
+        .call_tool(CallToolRequestParam {
+            name: "set_favorite_cinema".into(),
+            arguments: json!({ "cinema": "万达影城" }).as_object().cloned(),
+        })
+        .await?;
+    let token = client
+        .resumption_token()
+        .expect("the server issues a resumption token");
+
+    // the server restarts, only the store survives
+    server.cancel().await?;
+    let _ = client.cancel().await;
+
+    let (server, client) = connect(&store, Some(token.clone())).await?;
+    assert_eq!(favorite_cinema(&client).await?, "万达影城");
+    assert!(client.resumption_token().is_some());
+    server.cancel().await?;
+    let _ = client.cancel().await;
+
+    // a forged token starts a new session
+    let (payload, _) = token.split_once('.').unwrap();
+    let (server, client) = connect(&store, Some(format!("{payload}.forged"))).await?;
+    assert_eq!(favorite_cinema(&client).await?, "");
+    server.cancel().await?;
+    let _ = client.cancel().await;
+    Ok(())
+}
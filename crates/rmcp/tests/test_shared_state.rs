@@ -0,0 +1,161 @@
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use rmcp::{
+    RoleServer, ServerHandler,
+    handler::server::{tool::ToolRouter, wrapper::Parameters},
+    model::{CallToolRequestParam, ClientRequest, Request, ServerResult},
+    service::{PeerRequestOptions, RequestContext, SharedState},
+    tool, tool_handler, tool_router,
+};
+use serde_json::json;
+use tokio::sync::Notify;
+
+mod common;
+use common::movie::connect;
+
+#[derive(Clone)]
+struct MovieServer {
+    city_ids: SharedState<HashMap<String, i32>>,
+    /// Notified once a lookup is waiting for the upstream, which never answers
+    lookup_started: Arc<Notify>,
+    /// Notified once a lookup is dropped
+    lookup_dropped: Arc<Notify>,
+    tool_router: ToolRouter<Self>,
+}
+
+#[derive(serde::Deserialize, schemars::JsonSchema)]
+struct City {
+    name: String,
+}
+
+struct NotifyOnDrop(Arc<Notify>);
+
+impl Drop for NotifyOnDrop {
+    fn drop(&mut self) {
+        self.0.notify_one();
+    }
+}
+
+#[tool_router]
+impl MovieServer {
+    fn new() -> Self {
+        Self {
+            city_ids: SharedState::new(HashMap::from([("北京".to_string(), 1)])),
+            lookup_started: Arc::new(Notify::new()),
+            lookup_dropped: Arc::new(Notify::new()),
+            tool_router: Self::tool_router(),
+        }
+    }
+
+    #[tool(description = "Look up the ID of a city")]
+    async fn find_city(
+        &self,
+        Parameters(City { name }): Parameters<City>,
+        context: RequestContext<RoleServer>,
+    ) -> String {
+        if let Some(city_id) = self.city_ids.with(|city_ids| city_ids.get(&name).copied()) {
+            return city_id.to_string();
+        }
+        let _dropped = NotifyOnDrop(self.lookup_dropped.clone());
+        let lookup_started = self.lookup_started.clone();
+        let lookup = self.city_ids.with(|city_ids| {
+            let next_id = city_ids.len() as i32 + 1;
+            async move {
+                lookup_started.notify_one();
+                std::future::pending::<()>().await;
+                next_id
+            }
+        });
+        // the lookup is dropped once the request is cancelled
+        let city_id = tokio::select! {
+            city_id = lookup => city_id,
+            _ = context.ct.cancelled() => return "cancelled".to_string(),
+        };
+        self.city_ids
+            .with(|city_ids| city_ids.insert(name, city_id));
+        city_id.to_string()
+    }
+
+    #[tool(description = "List the cities looked up so far")]
+    fn list_cities(&self) -> String {
+        let mut cities: Vec<_> = self.city_ids.get().into_keys().collect();
+        cities.sort();
+        cities.join(",")
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for MovieServer {}
+
+fn call(name: &'static str, arguments: Option<serde_json::Value>) -> ClientRequest {
+    ClientRequest::CallToolRequest(Request::new(CallToolRequestParam {
+        name: name.into(),
+        arguments: arguments.and_then(|arguments| arguments.as_object().cloned()),
+    }))
+}
+
+fn text(response: ServerResult) -> String {
+    let ServerResult::CallToolResult(result) = response else {
+        panic!("expected call tool result, got {response:?}");
+    };
+    result.content[0]
+        .as_text()
+        .expect("text content")
+        .text
+        .clone()
+}
+
+#[tokio::test]
+async fn test_state_is_unlocked_while_awaiting_and_after_cancellation() -> anyhow::Result<()> {
+    let server = MovieServer::new();
+    let (lookup_started, lookup_dropped) =
+        (server.lookup_started.clone(), server.lookup_dropped.clone());
+    let client = connect(server, ()).await?;
+
+    let cached = client
+        .send_request(call("find_city", Some(json!({ "name": "北京" }))))
+        .await?;
+    assert_eq!(text(cached), "1");
+
+    let lookup = client
+        .send_cancellable_request(
+            call("find_city", Some(json!({ "name": "上海" }))),
+            PeerRequestOptions::no_options(),
+        )
+        .await?;
+    lookup_started.notified().await;
+    // the pending lookup doesn't keep the other requests waiting
+    let cities = tokio::time::timeout(
+        Duration::from_secs(5),
+        client.send_request(call("list_cities", None)),
+    )
+    .await??;
+    assert_eq!(text(cities), "北京");
+
+    lookup.cancel(Some("user gave up".into())).await?;
+    tokio::time::timeout(Duration::from_secs(5), lookup_dropped.notified()).await?;
+    let cities = tokio::time::timeout(
+        Duration::from_secs(5),
+        client.send_request(call("list_cities", None)),
+    )
+    .await??;
+    assert_eq!(text(cities), "北京");
+
+    client.cancel().await?;
+    Ok(())
+}
+
+#[test]
+fn test_state_survives_a_panic() {
+    let city_ids = SharedState::new(vec![1]);
+    let clone = city_ids.clone();
+    let panicked = std::panic::catch_unwind(move || {
+        clone.with(|city_ids| {
+            city_ids.push(2);
+            panic!("lookup failed");
+        })
+    });
+    assert!(panicked.is_err());
+    assert_eq!(city_ids.get(), vec![1, 2]);
+    assert_eq!(city_ids.replace(Vec::new()), vec![1, 2]);
+}
@@ -0,0 +1,66 @@
+use std::time::Duration;
+
+use rmcp::{ServiceExt, service::Shutdown};
+use tokio_util::sync::CancellationToken;
+
+mod common;
+use common::calculator::Calculator;
+
+#[tokio::test]
+async fn test_shutdown_stops_the_server_and_runs_cleanup() -> anyhow::Result<()> {
+    let shutdown = Shutdown::new();
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    let server = tokio::spawn({
+        let ct = shutdown.cancellation_token();
+        async move {
+            let server = Calculator::default()
+                .serve_with_ct(server_transport, ct)
+                .await?;
+            anyhow::Ok(server.waiting().await?)
+        }
+    });
+    let cleanup = tokio::spawn({
+        let stopped = shutdown.on_shutdown();
+        async move {
+            stopped.await;
+            "cleaned up"
+        }
+    });
+    let client = ().serve(client_transport).await?;
+    client.list_all_tools().await?;
+    assert!(!shutdown.is_triggered());
+
+    shutdown.trigger();
+    assert!(shutdown.is_triggered());
+    let reason = tokio::time::timeout(Duration::from_secs(5), server).await???;
+    assert!(matches!(reason, rmcp::service::QuitReason::Cancelled));
+    assert_eq!(cleanup.await?, "cleaned up");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_shutdown_interop_with_cancellation_token() -> anyhow::Result<()> {
+    let ct = CancellationToken::new();
+    let shutdown = Shutdown::from(ct.clone());
+    let child = shutdown.child_token();
+    child.cancel();
+    assert!(!shutdown.is_triggered());
+
+    ct.cancel();
+    assert!(shutdown.is_triggered());
+    tokio::time::timeout(Duration::from_secs(1), shutdown.on_shutdown()).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_shutdown_triggered_by_a_future() -> anyhow::Result<()> {
+    let (tx, rx) = tokio::sync::oneshot::channel::<()>();
+    let shutdown = Shutdown::new().trigger_on(rx);
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    assert!(!shutdown.is_triggered());
+
+    tx.send(()).unwrap();
+    tokio::time::timeout(Duration::from_secs(1), shutdown.on_shutdown()).await?;
+    assert!(shutdown.is_triggered());
+    Ok(())
+}
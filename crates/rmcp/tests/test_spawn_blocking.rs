@@ -0,0 +1,116 @@
+use std::{
+    sync::{Arc, Mutex, mpsc},
+    time::Duration,
+};
+
+use rmcp::{
+    ErrorData, RoleServer, ServerHandler,
+    handler::server::tool::ToolRouter,
+    model::{CallToolRequestParam, ErrorCode},
+    service::RequestContext,
+    tool, tool_handler, tool_router,
+};
+
+mod common;
+use common::movie::connect;
+
+#[derive(Clone)]
+struct MovieServer {
+    /// Holds the parsing until the test releases it
+    release: Arc<Mutex<mpsc::Receiver<()>>>,
+    tool_router: ToolRouter<Self>,
+}
+
+#[tool_router]
+impl MovieServer {
+    fn new(release: mpsc::Receiver<()>) -> Self {
+        Self {
+            release: Arc::new(Mutex::new(release)),
+            tool_router: Self::tool_router(),
+        }
+    }
+
+    #[tool(description = "Count the showtimes of all the cinemas")]
+    async fn count_showtimes(
+        &self,
+        context: RequestContext<RoleServer>,
+    ) -> Result<String, ErrorData> {
+        let release = self.release.clone();
+        context
+            .spawn_blocking(move || {
+                // blocks the thread, as a large parsing would
+                release.lock().unwrap().recv().unwrap();
+                let showtimes: Vec<serde_json::Value> = serde_json::from_str(&format!(
+                    "[{}]",
+                    vec!["{\"time\":\"19:30\"}"; 1000].join(",")
+                ))
+                .unwrap();
+                showtimes.len().to_string()
+            })
+            .await
+    }
+
+    #[tool(description = "Get the name of the cinema")]
+    async fn cinema_name(&self) -> String {
+        "万达影城".to_string()
+    }
+
+    #[tool(description = "Parse a broken schedule")]
+    async fn parse_schedule(
+        &self,
+        context: RequestContext<RoleServer>,
+    ) -> Result<String, ErrorData> {
+        context
+            .spawn_blocking(|| -> String { panic!("unexpected schedule") })
+            .await
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for MovieServer {}
+
+fn call(name: &'static str) -> CallToolRequestParam {
+    CallToolRequestParam {
+        name: name.into(),
+        arguments: None,
+    }
+}
+
+#[tokio::test]
+async fn test_blocking_tool_keeps_the_runtime_responsive() -> anyhow::Result<()> {
+    let (release, released) = mpsc::channel();
+    let client = connect(MovieServer::new(released), ()).await?;
+
+    let peer = client.peer().clone();
+    let blocking = tokio::spawn(async move { peer.call_tool(call("count_showtimes")).await });
+    // the blocking tool is still parsing, yet the single threaded runtime answers other calls
+    let name = tokio::time::timeout(
+        Duration::from_secs(5),
+        client.call_tool(call("cinema_name")),
+    )
+    .await??;
+    assert_eq!(name.content[0].as_text().unwrap().text, "万达影城");
+    assert!(!blocking.is_finished());
+
+    release.send(())?;
+    let count = blocking.await??;
+    assert_eq!(count.content[0].as_text().unwrap().text, "1000");
+
+    client.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_blocking_panic_is_an_internal_error() -> anyhow::Result<()> {
+    let (_release, released) = mpsc::channel();
+    let client = connect(MovieServer::new(released), ()).await?;
+
+    let error = client.call_tool(call("parse_schedule")).await.unwrap_err();
+    let rmcp::ServiceError::McpError(error) = error else {
+        panic!("expect an mcp error, got {error:?}");
+    };
+    assert_eq!(error.code, ErrorCode::INTERNAL_ERROR);
+
+    client.cancel().await?;
+    Ok(())
+}
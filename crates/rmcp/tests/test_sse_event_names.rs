@@ -0,0 +1,97 @@
+use std::time::Duration;
+
+use futures::StreamExt;
+use rmcp::{
+    ServiceExt,
+    model::{
+        ClientJsonRpcMessage, ClientNotification, ClientRequest, InitializeRequestParam,
+        InitializedNotification, LoggingLevel, LoggingMessageNotificationParam, NumberOrString,
+        Request, ServerRequest,
+    },
+    transport::{
+        SseServer,
+        sse_client::SseClient,
+        sse_server::{SseEventNames, SseServerConfig},
+    },
+};
+use tokio_util::sync::CancellationToken;
+mod common;
+use common::calculator::Calculator;
+
+#[tokio::test]
+async fn test_sse_event_names_per_message_type() -> anyhow::Result<()> {
+    const BIND_ADDRESS: &str = "127.0.0.1:8112";
+    let ct = CancellationToken::new();
+    let mut sse_server = SseServer::serve_with_config(SseServerConfig {
+        bind: BIND_ADDRESS.parse()?,
+        ct: ct.clone(),
+        event_names: SseEventNames {
+            response: "response".to_string(),
+            notification: "notification".to_string(),
+            request: "request".to_string(),
+        },
+        ..Default::default()
+    })
+    .await?;
+
+    let client = reqwest::Client::new();
+    let mut events = client
+        .get_stream(format!("http://{BIND_ADDRESS}/sse").parse()?, None, None)
+        .await?;
+    let endpoint = events.next().await.expect("endpoint event")?;
+    assert_eq!(endpoint.event.as_deref(), Some("endpoint"));
+    let endpoint = format!("http://{BIND_ADDRESS}{}", endpoint.data.unwrap_or_default());
+
+    tokio::spawn(async move {
+        let transport = sse_server.next_transport().await.expect("transport");
+        let server = Calculator::default().serve(transport).await?;
+        server
+            .notify_logging_message(LoggingMessageNotificationParam {
+                level: LoggingLevel::Info,
+                logger: None,
+                data: serde_json::json!("ready"),
+            })
+            .await?;
+        server
+            .send_request(ServerRequest::PingRequest(Default::default()))
+            .await?;
+        server.waiting().await?;
+        anyhow::Ok(())
+    });
+
+    client
+        .post_message(
+            endpoint.parse()?,
+            ClientJsonRpcMessage::request(
+                ClientRequest::InitializeRequest(Request::new(InitializeRequestParam::default())),
+                NumberOrString::Number(1),
+            ),
+            None,
+        )
+        .await?;
+    let mut next_event = async || {
+        tokio::time::timeout(Duration::from_secs(5), events.next())
+            .await
+            .expect("sse event in time")
+            .expect("sse event")
+    };
+    let response = next_event().await?;
+    assert_eq!(response.event.as_deref(), Some("response"));
+
+    client
+        .post_message(
+            endpoint.parse()?,
+            ClientJsonRpcMessage::notification(ClientNotification::InitializedNotification(
+                InitializedNotification::default(),
+            )),
+            None,
+        )
+        .await?;
+    let notification = next_event().await?;
+    assert_eq!(notification.event.as_deref(), Some("notification"));
+    let request = next_event().await?;
+    assert_eq!(request.event.as_deref(), Some("request"));
+
+    ct.cancel();
+    Ok(())
+}
@@ -0,0 +1,106 @@
+use axum::{http::HeaderValue, response::Response};
+use rmcp::{
+    ServiceExt,
+    transport::{
+        SseClientTransport, SseServer, common::server_side_http::HealthCheck,
+        sse_server::SseServerConfig,
+    },
+};
+use tokio_util::sync::CancellationToken;
+mod common;
+use common::calculator::Calculator;
+
+const SERVED_BY_HEADER: &str = "x-served-by";
+
+async fn served_by(mut response: Response) -> Response {
+    response
+        .headers_mut()
+        .insert(SERVED_BY_HEADER, HeaderValue::from_static("movie"));
+    response
+}
+
+#[tokio::test]
+async fn test_layers_apply_to_every_route() -> anyhow::Result<()> {
+    const BIND_ADDRESS: &str = "127.0.0.1:8173";
+    let ct = CancellationToken::new();
+    let sse_server = SseServer::serve_with_layers(
+        SseServerConfig {
+            bind: BIND_ADDRESS.parse()?,
+            ct: ct.clone(),
+            health_check: Some(HealthCheck::default()),
+            ..Default::default()
+        },
+        axum::middleware::map_response(served_by),
+    )
+    .await?;
+    sse_server.with_service(Calculator::default);
+
+    let http = reqwest::Client::new();
+    let health = http
+        .get(format!(
+            "http://{BIND_ADDRESS}{}",
+            HealthCheck::DEFAULT_PATH
+        ))
+        .send()
+        .await?;
+    assert!(health.status().is_success());
+    assert_eq!(health.headers()[SERVED_BY_HEADER], "movie");
+
+    let sse = http
+        .get(format!("http://{BIND_ADDRESS}/sse"))
+        .send()
+        .await?;
+    assert!(sse.status().is_success());
+    assert_eq!(sse.headers()[SERVED_BY_HEADER], "movie");
+    drop(sse);
+
+    // the MCP routes still serve clients through the layer
+    let transport = SseClientTransport::start(format!("http://{BIND_ADDRESS}/sse")).await?;
+    let client = ().serve(transport).await?;
+    assert!(client.peer_info().is_some());
+
+    client.cancel().await?;
+    ct.cancel();
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_router_with_layers_keeps_the_mcp_routes() -> anyhow::Result<()> {
+    const BIND_ADDRESS: &str = "127.0.0.1:8174";
+    let ct = CancellationToken::new();
+    let (sse_server, router) = SseServer::router_with_layers(
+        SseServerConfig {
+            bind: BIND_ADDRESS.parse()?,
+            ct: ct.clone(),
+            ..Default::default()
+        },
+        axum::middleware::map_response(served_by),
+    );
+    // a route of our own, added after the layers, isn't wrapped by them
+    let router = router.route("/version", axum::routing::get(|| async { "1.0" }));
+    let listener = tokio::net::TcpListener::bind(BIND_ADDRESS).await?;
+    tokio::spawn({
+        let ct = ct.clone();
+        async move {
+            axum::serve(listener, router)
+                .with_graceful_shutdown(ct.cancelled_owned())
+                .await
+        }
+    });
+    sse_server.with_service(Calculator::default);
+
+    let http = reqwest::Client::new();
+    let version = http
+        .get(format!("http://{BIND_ADDRESS}/version"))
+        .send()
+        .await?;
+    assert!(!version.headers().contains_key(SERVED_BY_HEADER));
+
+    let transport = SseClientTransport::start(format!("http://{BIND_ADDRESS}/sse")).await?;
+    let client = ().serve(transport).await?;
+    assert!(client.peer_info().is_some());
+
+    client.cancel().await?;
+    ct.cancel();
+    Ok(())
+}
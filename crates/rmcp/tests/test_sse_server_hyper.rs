@@ -0,0 +1,110 @@
+use std::time::Duration;
+
+use rmcp::{
+    ServiceExt,
+    transport::{
+        HyperSseServer, SseClientTransport, common::server_side_http::HealthCheck,
+        sse_server::SseServerConfig,
+    },
+};
+use tokio_util::sync::CancellationToken;
+mod common;
+use common::calculator::Calculator;
+
+const BIND_ADDRESS: &str = "127.0.0.1:8128";
+
+#[tokio::test]
+async fn test_hyper_sse_server() -> anyhow::Result<()> {
+    let ct = CancellationToken::new();
+    let mut sse_server = HyperSseServer::serve_with_config(SseServerConfig {
+        bind: BIND_ADDRESS.parse()?,
+        ct: ct.clone(),
+        health_check: Some(HealthCheck::default()),
+        ..Default::default()
+    })
+    .await?;
+
+    let client = tokio::spawn(async move {
+        ().serve(SseClientTransport::start(format!("http://{BIND_ADDRESS}/sse")).await?)
+            .await
+            .map_err(anyhow::Error::from)
+    });
+    let transport = sse_server.next_transport().await.expect("transport");
+    tokio::spawn({
+        let ct = ct.child_token();
+        async move {
+            let server = Calculator::default().serve_with_ct(transport, ct).await?;
+            server.waiting().await?;
+            anyhow::Ok(())
+        }
+    });
+    let client = client.await??;
+
+    let server_info = client.peer_info().expect("initialized");
+    assert_eq!(
+        server_info.instructions.as_deref(),
+        Some("A simple calculator")
+    );
+    let sessions = tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            let sessions = sse_server.sessions().await;
+            if sessions.len() == 1 && sessions[0].initialized {
+                break sessions;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await?;
+    assert_eq!(sessions.len(), 1);
+
+    let http = reqwest::Client::new();
+    let not_found = http
+        .get(format!("http://{BIND_ADDRESS}/unknown"))
+        .send()
+        .await?;
+    assert_eq!(not_found.status(), reqwest::StatusCode::NOT_FOUND);
+    let health = http
+        .get(format!("http://{BIND_ADDRESS}/healthz"))
+        .send()
+        .await?;
+    assert_eq!(health.status(), reqwest::StatusCode::OK);
+    let wrong_method = http
+        .get(format!("http://{BIND_ADDRESS}/message"))
+        .send()
+        .await?;
+    assert_eq!(
+        wrong_method.status(),
+        reqwest::StatusCode::METHOD_NOT_ALLOWED
+    );
+    let unknown_session = http
+        .post(format!("http://{BIND_ADDRESS}/message?sessionId=unknown"))
+        .json(&serde_json::json!({ "jsonrpc": "2.0", "id": 1, "method": "ping" }))
+        .send()
+        .await?;
+    assert_eq!(unknown_session.status(), reqwest::StatusCode::NOT_FOUND);
+    // a client may percent-encode the session id, as accepted by the axum server
+    let encoded: String = sessions[0]
+        .id
+        .bytes()
+        .map(|byte| format!("%{byte:02X}"))
+        .collect();
+    let encoded_session = http
+        .post(format!("http://{BIND_ADDRESS}/message?sessionId={encoded}"))
+        .json(
+            &serde_json::json!({ "jsonrpc": "2.0", "method": "notifications/roots/list_changed" }),
+        )
+        .send()
+        .await?;
+    assert_eq!(encoded_session.status(), reqwest::StatusCode::ACCEPTED);
+
+    sse_server.drain();
+    let draining = http
+        .get(format!("http://{BIND_ADDRESS}/healthz"))
+        .send()
+        .await?;
+    assert_eq!(draining.status(), reqwest::StatusCode::SERVICE_UNAVAILABLE);
+
+    client.cancel().await?;
+    ct.cancel();
+    Ok(())
+}
@@ -0,0 +1,151 @@
+use std::time::Duration;
+
+use rmcp::{
+    ServerHandler, ServiceExt,
+    handler::server::tool::ToolRouter,
+    model::{CallToolRequestParam, ServerCapabilities, ServerInfo},
+    tool, tool_handler, tool_router,
+    transport::{SseClientTransport, SseServer, sse_server::SseServerConfig},
+};
+use tokio_util::sync::CancellationToken;
+
+#[derive(Debug, Clone)]
+struct Clock {
+    tool_router: ToolRouter<Self>,
+}
+
+#[tool_router]
+impl Clock {
+    fn new() -> Self {
+        Self {
+            tool_router: Self::tool_router(),
+        }
+    }
+
+    #[tool(description = "Tell the time")]
+    fn now(&self) -> String {
+        "noon".to_string()
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for Clock {}
+
+#[derive(Debug, Clone)]
+struct Weather {
+    tool_router: ToolRouter<Self>,
+}
+
+#[tool_router]
+impl Weather {
+    fn new() -> Self {
+        Self {
+            tool_router: Self::tool_router(),
+        }
+    }
+
+    #[tool(description = "Tell the weather")]
+    fn forecast(&self) -> String {
+        "sunny".to_string()
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for Weather {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo {
+            capabilities: ServerCapabilities::builder().enable_tools().build(),
+            ..Default::default()
+        }
+    }
+}
+
+fn config(ct: &CancellationToken) -> SseServerConfig {
+    SseServerConfig {
+        bind: BIND_ADDRESS.parse().expect("valid address"),
+        ct: ct.clone(),
+        ..Default::default()
+    }
+}
+
+const BIND_ADDRESS: &str = "127.0.0.1:8117";
+
+#[tokio::test]
+async fn test_sse_servers_mounted_on_one_router() -> anyhow::Result<()> {
+    let ct = CancellationToken::new();
+    let (clock, clock_router) = SseServer::new(config(&ct));
+    let (weather, weather_router) = SseServer::new(config(&ct));
+    let app = axum::Router::new()
+        .nest("/clock", clock_router)
+        .nest("/weather", weather_router);
+    let listener = tokio::net::TcpListener::bind(BIND_ADDRESS).await?;
+    tokio::spawn({
+        let ct = ct.clone();
+        async move {
+            axum::serve(listener, app)
+                .with_graceful_shutdown(async move { ct.cancelled().await })
+                .await
+        }
+    });
+    clock.with_service(Clock::new);
+    weather.with_service(Weather::new);
+
+    let clock_client =
+        ().serve(SseClientTransport::start(format!("http://{BIND_ADDRESS}/clock/sse")).await?)
+            .await?;
+    let weather_client =
+        ().serve(SseClientTransport::start(format!("http://{BIND_ADDRESS}/weather/sse")).await?)
+            .await?;
+
+    let time = clock_client
+        .call_tool(CallToolRequestParam {
+            name: "now".into(),
+            arguments: None,
+        })
+        .await?;
+    assert_eq!(time.content[0].as_text().expect("text").text, "noon");
+    let forecast = weather_client
+        .call_tool(CallToolRequestParam {
+            name: "forecast".into(),
+            arguments: None,
+        })
+        .await?;
+    assert_eq!(forecast.content[0].as_text().expect("text").text, "sunny");
+    // each mount only routes to its own service
+    let tools = weather_client.list_all_tools().await?;
+    assert_eq!(tools.len(), 1);
+    assert_eq!(tools[0].name, "forecast");
+
+    // a session of one mount is unknown to the other one
+    let http = reqwest::Client::new();
+    let mut sse = http
+        .get(format!("http://{BIND_ADDRESS}/clock/sse"))
+        .send()
+        .await?;
+    let endpoint = tokio::time::timeout(Duration::from_secs(5), async {
+        let mut received = String::new();
+        while let Some(chunk) = sse.chunk().await? {
+            received.push_str(&String::from_utf8_lossy(&chunk));
+            if let Some(line) = received.lines().find(|line| line.starts_with("data:")) {
+                return anyhow::Ok(line.trim_start_matches("data:").trim().to_string());
+            }
+        }
+        anyhow::bail!("sse stream closed before the endpoint event")
+    })
+    .await??;
+    assert!(endpoint.starts_with("/clock/message?sessionId="));
+    let session_id = endpoint.split("sessionId=").nth(1).expect("session id");
+    let response = http
+        .post(format!(
+            "http://{BIND_ADDRESS}/weather/message?sessionId={session_id}"
+        ))
+        .json(&serde_json::json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }))
+        .send()
+        .await?;
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+
+    clock_client.cancel().await?;
+    weather_client.cancel().await?;
+    ct.cancel();
+    Ok(())
+}
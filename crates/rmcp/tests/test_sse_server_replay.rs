@@ -0,0 +1,114 @@
+use std::time::Duration;
+
+use futures::{SinkExt, StreamExt};
+use rmcp::{
+    model::{
+        LoggingLevel, LoggingMessageNotification, LoggingMessageNotificationParam, Notification,
+        ServerJsonRpcMessage, ServerNotification,
+    },
+    transport::{SseServer, sse_server::SseServerConfig},
+};
+use tokio_util::sync::CancellationToken;
+
+const BIND_ADDRESS: &str = "127.0.0.1:8133";
+
+fn log(data: &str) -> ServerJsonRpcMessage {
+    let notification: LoggingMessageNotification =
+        Notification::new(LoggingMessageNotificationParam {
+            level: LoggingLevel::Info,
+            logger: None,
+            data: serde_json::json!(data),
+        });
+    ServerJsonRpcMessage::notification(ServerNotification::LoggingMessageNotification(notification))
+}
+
+/// Read the raw event stream until it contains all the `expected` lines
+async fn read_until<B: AsRef<[u8]>>(
+    stream: &mut (impl futures::Stream<Item = reqwest::Result<B>> + Unpin),
+    expected: &[&str],
+) -> anyhow::Result<String> {
+    let mut text = String::new();
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while !expected.iter().all(|line| text.contains(line)) {
+            let chunk = stream.next().await.expect("sse stream open")?;
+            text.push_str(std::str::from_utf8(chunk.as_ref())?);
+        }
+        anyhow::Ok(())
+    })
+    .await??;
+    Ok(text)
+}
+
+/// Reconnect with `last_event_id`, waiting for the server to notice the previous stream is gone
+async fn resume(
+    client: &reqwest::Client,
+    last_event_id: &str,
+) -> anyhow::Result<reqwest::Response> {
+    tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            let response = client
+                .get(format!("http://{BIND_ADDRESS}/sse"))
+                .header("Accept", "text/event-stream")
+                .header("Last-Event-ID", last_event_id)
+                .send()
+                .await?;
+            if response.status() != reqwest::StatusCode::CONFLICT {
+                break anyhow::Ok(response);
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    })
+    .await?
+}
+
+#[tokio::test]
+async fn test_replay_beyond_buffer_is_rejected() -> anyhow::Result<()> {
+    let ct = CancellationToken::new();
+    let mut sse_server = SseServer::serve_with_config(SseServerConfig {
+        bind: BIND_ADDRESS.parse()?,
+        ct: ct.clone(),
+        replay_buffer_size: 2,
+        ..Default::default()
+    })
+    .await?;
+
+    let client = reqwest::Client::new();
+    let mut stream = client
+        .get(format!("http://{BIND_ADDRESS}/sse"))
+        .header("Accept", "text/event-stream")
+        .send()
+        .await?
+        .error_for_status()?
+        .bytes_stream();
+    let mut transport = sse_server.next_transport().await.expect("transport");
+    let session = sse_server.sessions().await[0].id.clone();
+
+    for data in ["one", "two", "three", "four"] {
+        transport.send(log(data)).await?;
+    }
+    let text = read_until(&mut stream, &[&format!("id: {session}/4")]).await?;
+    assert!(text.contains(&format!("id: {session}/0")), "{text}");
+    drop(stream);
+
+    // the last two events are still retained
+    let response = resume(&client, &format!("{session}/2")).await?;
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let mut stream = response.bytes_stream();
+    let text = read_until(&mut stream, &["\"four\""]).await?;
+    assert!(text.contains("\"three\""), "{text}");
+    assert!(!text.contains("\"two\""), "{text}");
+    assert!(!text.contains("endpoint"), "{text}");
+    drop(stream);
+
+    // the event after `/1` was evicted
+    let response = resume(&client, &format!("{session}/1")).await?;
+    assert_eq!(response.status(), reqwest::StatusCode::GONE);
+    assert_eq!(response.text().await?, "replay window exceeded");
+
+    let response = resume(&client, "unknown/1").await?;
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+
+    drop(transport);
+    ct.cancel();
+    Ok(())
+}
@@ -0,0 +1,75 @@
+use std::time::Duration;
+
+use rmcp::{
+    ServiceExt,
+    transport::{SseClientTransport, SseServer, sse_server::SseServerConfig},
+};
+use tokio_util::sync::CancellationToken;
+mod common;
+use common::calculator::Calculator;
+
+#[tokio::test]
+async fn test_sse_server_sessions_registry() -> anyhow::Result<()> {
+    const BIND_ADDRESS: &str = "127.0.0.1:8102";
+    let ct = CancellationToken::new();
+    let mut sse_server = SseServer::serve_with_config(SseServerConfig {
+        bind: BIND_ADDRESS.parse()?,
+        ct: ct.clone(),
+        ..Default::default()
+    })
+    .await?;
+    assert!(sse_server.sessions().await.is_empty());
+
+    let url = format!("http://{BIND_ADDRESS}/sse");
+    let client_a = tokio::spawn({
+        let url = url.clone();
+        async move {
+            ().serve(SseClientTransport::start(url).await?)
+                .await
+                .map_err(anyhow::Error::from)
+        }
+    });
+    let client_b = tokio::spawn({
+        let url = url.clone();
+        async move {
+            ().serve(SseClientTransport::start(url).await?)
+                .await
+                .map_err(anyhow::Error::from)
+        }
+    });
+
+    // accept both transports and serve them
+    for _ in 0..2 {
+        let transport = sse_server.next_transport().await.expect("transport");
+        let ct = ct.child_token();
+        tokio::spawn(async move {
+            let server = Calculator::default().serve_with_ct(transport, ct).await?;
+            server.waiting().await?;
+            anyhow::Ok(())
+        });
+    }
+    let client_a = client_a.await??;
+    let client_b = client_b.await??;
+
+    let sessions = tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            let sessions = sse_server.sessions().await;
+            if sessions.len() == 2 && sessions.iter().all(|s| s.initialized) {
+                break sessions;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await?;
+    assert_ne!(sessions[0].id, sessions[1].id);
+    assert!(
+        sessions
+            .iter()
+            .all(|s| s.connected_at <= std::time::SystemTime::now())
+    );
+
+    client_a.cancel().await?;
+    client_b.cancel().await?;
+    ct.cancel();
+    Ok(())
+}
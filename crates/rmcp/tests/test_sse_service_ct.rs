@@ -0,0 +1,38 @@
+use std::time::Duration;
+
+use rmcp::{
+    ServiceExt,
+    transport::{SseClientTransport, SseServer, sse_server::SseServerConfig},
+};
+mod common;
+use common::calculator::Calculator;
+
+#[tokio::test]
+async fn test_service_ct_fires_on_cancel() -> anyhow::Result<()> {
+    const BIND_ADDRESS: &str = "127.0.0.1:8170";
+    let sse_server = SseServer::serve_with_config(SseServerConfig {
+        bind: BIND_ADDRESS.parse()?,
+        ..Default::default()
+    })
+    .await?;
+    let (cancel, service_ct) = sse_server.with_service_and_ct(Calculator::default);
+
+    // a background task stopping along with the services
+    let background = tokio::spawn({
+        let service_ct = service_ct.clone();
+        async move { service_ct.cancelled().await }
+    });
+
+    let transport = SseClientTransport::start(format!("http://{BIND_ADDRESS}/sse")).await?;
+    let client = ().serve(transport).await?;
+    assert!(client.peer_info().is_some());
+    assert!(!service_ct.is_cancelled());
+    assert!(!background.is_finished());
+
+    cancel.cancel();
+    tokio::time::timeout(Duration::from_secs(5), background).await??;
+    assert!(service_ct.is_cancelled());
+
+    drop(client);
+    Ok(())
+}
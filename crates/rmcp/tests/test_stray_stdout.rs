@@ -0,0 +1,61 @@
+use rmcp::{
+    ServiceExt,
+    transport::async_rw::{AsyncRwTransport, StrayOutput},
+};
+use tokio::io::{AsyncReadExt, AsyncWriteExt, ReadHalf, SimplexStream, WriteHalf};
+mod common;
+use common::calculator::Calculator;
+
+/// Spawn a calculator whose stdout starts with a banner, like a server calling `println!` at
+/// startup, and return the pipes of a client
+fn noisy_server() -> (ReadHalf<SimplexStream>, WriteHalf<SimplexStream>) {
+    let (server_stdin, client_stdin) = tokio::io::simplex(4096);
+    let (mut server_output, server_stdout) = tokio::io::simplex(4096);
+    let (client_stdout, mut stdout) = tokio::io::simplex(4096);
+    tokio::spawn(async move {
+        let server = Calculator::new()
+            .serve((server_stdin, server_stdout))
+            .await?;
+        server.waiting().await?;
+        anyhow::Ok(())
+    });
+    tokio::spawn(async move {
+        // written along with the first message, so the codec gets both in one read
+        let mut output = b"Starting calculator server...\n42\n".to_vec();
+        let mut chunk = vec![0; 4096];
+        loop {
+            let n = server_output.read(&mut chunk).await?;
+            if n == 0 {
+                break;
+            }
+            output.extend_from_slice(&chunk[..n]);
+            stdout.write_all(&output).await?;
+            output.clear();
+        }
+        anyhow::Ok(())
+    });
+    (client_stdout, client_stdin)
+}
+
+#[tokio::test]
+async fn test_session_survives_stray_stdout() -> anyhow::Result<()> {
+    let (stdout, stdin) = noisy_server();
+    let client = ().serve(AsyncRwTransport::new_client(stdout, stdin)).await?;
+
+    let info = client.peer_info().expect("initialized");
+    assert_eq!(info.instructions.as_deref(), Some("A simple calculator"));
+    // and the session goes on
+    assert!(client.list_all_resources().await?.is_empty());
+
+    client.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_stray_stdout_can_fail_the_session() -> anyhow::Result<()> {
+    let (stdout, stdin) = noisy_server();
+    let transport =
+        AsyncRwTransport::new_client(stdout, stdin).with_stray_output(StrayOutput::Error);
+    assert!(().serve(transport).await.is_err());
+    Ok(())
+}
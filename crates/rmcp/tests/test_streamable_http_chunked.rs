@@ -0,0 +1,202 @@
+use std::{sync::Arc, time::Duration};
+
+use futures::StreamExt;
+use rmcp::{
+    ServerHandler,
+    handler::server::tool::ToolRouter,
+    model::Content,
+    tool, tool_handler, tool_router,
+    transport::{
+        StreamableHttpServerConfig,
+        streamable_http_server::{
+            session::local::LocalSessionManager, tower::StreamableHttpService,
+        },
+    },
+};
+use serde_json::json;
+use tokio::sync::Semaphore;
+use tokio_util::sync::CancellationToken;
+
+const SESSION_ID_HEADER: &str = "Mcp-Session-Id";
+
+#[derive(Clone)]
+struct MovieServer {
+    /// The rest of the cinemas are only produced once a permit is added
+    rest: Arc<Semaphore>,
+    tool_router: ToolRouter<Self>,
+}
+
+#[tool_router]
+impl MovieServer {
+    fn new(rest: Arc<Semaphore>) -> Self {
+        Self {
+            rest,
+            tool_router: Self::tool_router(),
+        }
+    }
+
+    #[tool(streaming, description = "List the cinemas nearby")]
+    fn get_cinema_list(&self) -> impl futures::Stream<Item = Content> + Send + 'static {
+        let rest = self.rest.clone();
+        futures::stream::once(async { Content::text("万达影城") }).chain(
+            futures::stream::once(async move {
+                rest.acquire().await.expect("open semaphore").forget();
+                futures::stream::iter(["博纳国际影城", "CGV影城"].map(Content::text))
+            })
+            .flatten(),
+        )
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for MovieServer {}
+
+async fn post(
+    client: &reqwest::Client,
+    bind_address: &str,
+    session_id: Option<&str>,
+    body: serde_json::Value,
+) -> reqwest::Result<reqwest::Response> {
+    let mut request = client
+        .post(format!("http://{bind_address}/mcp"))
+        .header("Accept", "application/json, text/event-stream")
+        .json(&body);
+    if let Some(session_id) = session_id {
+        request = request.header(SESSION_ID_HEADER, session_id);
+    }
+    request.send().await?.error_for_status()
+}
+
+/// Read the raw event stream until it contains all the `expected` strings
+async fn read_until<B: AsRef<[u8]>>(
+    stream: &mut (impl futures::Stream<Item = reqwest::Result<B>> + Unpin),
+    text: &mut String,
+    expected: &[&str],
+) -> anyhow::Result<()> {
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while !expected.iter().all(|line| text.contains(line)) {
+            let chunk = stream.next().await.expect("sse stream open")?;
+            text.push_str(std::str::from_utf8(chunk.as_ref())?);
+        }
+        anyhow::Ok(())
+    })
+    .await?
+}
+
+/// Call the streaming tool, and check its first cinema is received before the tool is done
+async fn test_first_chunk_before_the_handler_finishes(
+    bind_address: &str,
+    stateful_mode: bool,
+) -> anyhow::Result<()> {
+    let rest = Arc::new(Semaphore::new(0));
+    let service: StreamableHttpService<MovieServer, LocalSessionManager> =
+        StreamableHttpService::new(
+            {
+                let rest = rest.clone();
+                move || Ok(MovieServer::new(rest.clone()))
+            },
+            Default::default(),
+            StreamableHttpServerConfig {
+                stateful_mode,
+                sse_keep_alive: None,
+                ..Default::default()
+            },
+        );
+    let router = axum::Router::new().nest_service("/mcp", service);
+    let tcp_listener = tokio::net::TcpListener::bind(bind_address).await?;
+    let ct = CancellationToken::new();
+    let handle = tokio::spawn({
+        let ct = ct.clone();
+        async move {
+            let _ = axum::serve(tcp_listener, router)
+                .with_graceful_shutdown(async move { ct.cancelled_owned().await })
+                .await;
+        }
+    });
+
+    let client = reqwest::Client::new();
+    let mut session_id = None;
+    if stateful_mode {
+        let initialize = post(
+            &client,
+            bind_address,
+            None,
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "initialize",
+                "params": {
+                    "protocolVersion": "2025-03-26",
+                    "capabilities": {},
+                    "clientInfo": { "name": "test", "version": "0.0.1" }
+                }
+            }),
+        )
+        .await?;
+        session_id = Some(
+            initialize
+                .headers()
+                .get(SESSION_ID_HEADER)
+                .expect("session id")
+                .to_str()?
+                .to_owned(),
+        );
+        post(
+            &client,
+            bind_address,
+            session_id.as_deref(),
+            json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }),
+        )
+        .await?;
+    }
+
+    let response = post(
+        &client,
+        bind_address,
+        session_id.as_deref(),
+        json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "tools/call",
+            "params": {
+                "name": "get_cinema_list",
+                "_meta": { "progressToken": "cinemas", "rmcp/partialResults": true }
+            }
+        }),
+    )
+    .await?;
+    // streamed as it's produced, the length is unknown upfront
+    assert!(response.content_length().is_none());
+    let mut stream = response.bytes_stream();
+    let mut text = String::new();
+    read_until(
+        &mut stream,
+        &mut text,
+        &["notifications/rmcp/tool_partial_result", "万达影城"],
+    )
+    .await?;
+    assert!(!text.contains("博纳国际影城"), "{text}");
+    assert!(
+        !text.contains(r#""result""#),
+        "the tool is still running: {text}"
+    );
+
+    rest.add_permits(1);
+    read_until(&mut stream, &mut text, &["CGV影城", r#""result""#]).await?;
+    let closed = tokio::time::timeout(Duration::from_secs(5), stream.next()).await?;
+    assert!(closed.is_none(), "the stream ends with the response");
+
+    ct.cancel();
+    handle.await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_stateful_response_streams_partial_results() -> anyhow::Result<()> {
+    test_first_chunk_before_the_handler_finishes("127.0.0.1:8164", true).await
+}
+
+#[tokio::test]
+async fn test_stateless_response_streams_partial_results() -> anyhow::Result<()> {
+    test_first_chunk_before_the_handler_finishes("127.0.0.1:8165", false).await
+}
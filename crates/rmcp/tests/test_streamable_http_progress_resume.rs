@@ -0,0 +1,183 @@
+use std::{sync::Arc, time::Duration};
+
+use futures::StreamExt;
+use rmcp::{
+    handler::server::fn_handler::{FnServerHandler, ServerHandlerBuilder},
+    model::{CallToolResult, Content, ProgressNotificationParam},
+    transport::{
+        StreamableHttpServerConfig,
+        streamable_http_server::{
+            session::local::LocalSessionManager, tower::StreamableHttpService,
+        },
+    },
+};
+use serde_json::json;
+use tokio::sync::Semaphore;
+use tokio_util::sync::CancellationToken;
+
+const BIND_ADDRESS: &str = "127.0.0.1:8139";
+const SESSION_ID_HEADER: &str = "Mcp-Session-Id";
+
+/// A tool reporting three progress steps, waiting for a permit of `steps` before each of the
+/// last two
+fn stepping_server(steps: Arc<Semaphore>) -> FnServerHandler {
+    ServerHandlerBuilder::new()
+        .call_tool(move |_request, context| {
+            let steps = steps.clone();
+            async move {
+                let progress_token = context.meta.get_progress_token().expect("progress token");
+                for step in 1..=3 {
+                    if step > 1 {
+                        steps.acquire().await.expect("open semaphore").forget();
+                    }
+                    context
+                        .peer
+                        .notify_progress(ProgressNotificationParam {
+                            progress_token: progress_token.clone(),
+                            progress: step as f64,
+                            total: Some(3.0),
+                            message: None,
+                        })
+                        .await
+                        .expect("notify progress");
+                }
+                Ok(CallToolResult::success(vec![Content::text("done")]))
+            }
+        })
+        .build()
+}
+
+async fn post(
+    client: &reqwest::Client,
+    session_id: Option<&str>,
+    body: serde_json::Value,
+) -> reqwest::Result<reqwest::Response> {
+    let mut request = client
+        .post(format!("http://{BIND_ADDRESS}/mcp"))
+        .header("Accept", "application/json, text/event-stream")
+        .json(&body);
+    if let Some(session_id) = session_id {
+        request = request.header(SESSION_ID_HEADER, session_id);
+    }
+    request.send().await?.error_for_status()
+}
+
+/// Read the raw event stream until it contains all the `expected` strings
+async fn read_until<B: AsRef<[u8]>>(
+    stream: &mut (impl futures::Stream<Item = reqwest::Result<B>> + Unpin),
+    expected: &[&str],
+) -> anyhow::Result<String> {
+    let mut text = String::new();
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while !expected.iter().all(|line| text.contains(line)) {
+            let chunk = stream.next().await.expect("sse stream open")?;
+            text.push_str(std::str::from_utf8(chunk.as_ref())?);
+        }
+        anyhow::Ok(())
+    })
+    .await??;
+    Ok(text)
+}
+
+#[tokio::test]
+async fn test_progress_continues_on_resumed_stream() -> anyhow::Result<()> {
+    let steps = Arc::new(Semaphore::new(0));
+    let service: StreamableHttpService<FnServerHandler, LocalSessionManager> =
+        StreamableHttpService::new(
+            {
+                let steps = steps.clone();
+                move || Ok(stepping_server(steps.clone()))
+            },
+            Default::default(),
+            StreamableHttpServerConfig {
+                sse_keep_alive: None,
+                ..Default::default()
+            },
+        );
+    let router = axum::Router::new().nest_service("/mcp", service);
+    let tcp_listener = tokio::net::TcpListener::bind(BIND_ADDRESS).await?;
+    let ct = CancellationToken::new();
+    let handle = tokio::spawn({
+        let ct = ct.clone();
+        async move {
+            let _ = axum::serve(tcp_listener, router)
+                .with_graceful_shutdown(async move { ct.cancelled_owned().await })
+                .await;
+        }
+    });
+
+    let client = reqwest::Client::new();
+    let initialize = post(
+        &client,
+        None,
+        json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "initialize",
+            "params": {
+                "protocolVersion": "2025-03-26",
+                "capabilities": {},
+                "clientInfo": { "name": "test", "version": "0.0.1" }
+            }
+        }),
+    )
+    .await?;
+    let session_id = initialize
+        .headers()
+        .get(SESSION_ID_HEADER)
+        .expect("session id")
+        .to_str()?
+        .to_owned();
+    post(
+        &client,
+        Some(&session_id),
+        json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }),
+    )
+    .await?;
+
+    let mut stream = post(
+        &client,
+        Some(&session_id),
+        json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "tools/call",
+            "params": { "name": "step", "_meta": { "progressToken": "steps" } }
+        }),
+    )
+    .await?
+    .bytes_stream();
+    let text = read_until(&mut stream, &[r#""progress":1.0"#]).await?;
+    let last_event_id = text
+        .lines()
+        .find_map(|line| line.strip_prefix("id:"))
+        .expect("event id")
+        .trim()
+        .to_owned();
+    // the connection drops while the tool is still running
+    drop(stream);
+    steps.add_permits(1);
+
+    let mut stream = client
+        .get(format!("http://{BIND_ADDRESS}/mcp"))
+        .header("Accept", "text/event-stream")
+        .header(SESSION_ID_HEADER, &session_id)
+        .header("Last-Event-ID", &last_event_id)
+        .send()
+        .await?
+        .error_for_status()?
+        .bytes_stream();
+    let text = read_until(&mut stream, &[r#""progress":2.0"#]).await?;
+    assert!(!text.contains(r#""progress":1.0"#), "{text}");
+
+    // progress made after the resume goes to the new connection as well
+    steps.add_permits(1);
+    let text = read_until(&mut stream, &[r#""progress":3.0"#, r#""text":"done""#]).await?;
+    assert!(text.contains(r#""progressToken":"steps""#), "{text}");
+    let closed = tokio::time::timeout(Duration::from_secs(5), stream.next()).await?;
+    assert!(closed.is_none(), "the stream ends with the response");
+
+    ct.cancel();
+    handle.await?;
+    Ok(())
+}
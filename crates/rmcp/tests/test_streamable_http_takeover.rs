@@ -0,0 +1,138 @@
+use std::time::Duration;
+
+use futures::StreamExt;
+use rmcp::transport::{
+    StreamableHttpServerConfig,
+    streamable_http_server::{session::local::LocalSessionManager, tower::StreamableHttpService},
+};
+use serde_json::json;
+use tokio_util::sync::CancellationToken;
+mod common;
+use common::calculator::Calculator;
+
+const BIND_ADDRESS: &str = "127.0.0.1:8127";
+const SESSION_ID_HEADER: &str = "Mcp-Session-Id";
+
+async fn post(
+    client: &reqwest::Client,
+    session_id: Option<&str>,
+    body: serde_json::Value,
+) -> reqwest::Result<reqwest::Response> {
+    let mut request = client
+        .post(format!("http://{BIND_ADDRESS}/mcp"))
+        .header("Accept", "application/json, text/event-stream")
+        .json(&body);
+    if let Some(session_id) = session_id {
+        request = request.header(SESSION_ID_HEADER, session_id);
+    }
+    request.send().await?.error_for_status()
+}
+
+async fn open_standalone_stream(
+    client: &reqwest::Client,
+    session_id: &str,
+) -> reqwest::Result<reqwest::Response> {
+    client
+        .get(format!("http://{BIND_ADDRESS}/mcp"))
+        .header("Accept", "text/event-stream")
+        .header(SESSION_ID_HEADER, session_id)
+        .send()
+        .await?
+        .error_for_status()
+}
+
+#[tokio::test]
+async fn test_reconnect_takes_over_standalone_stream() -> anyhow::Result<()> {
+    let service: StreamableHttpService<Calculator, LocalSessionManager> =
+        StreamableHttpService::new(
+            || Ok(Calculator::new()),
+            Default::default(),
+            StreamableHttpServerConfig {
+                sse_keep_alive: None,
+                ..Default::default()
+            },
+        );
+    let router = axum::Router::new().nest_service("/mcp", service);
+    let tcp_listener = tokio::net::TcpListener::bind(BIND_ADDRESS).await?;
+    let ct = CancellationToken::new();
+    let handle = tokio::spawn({
+        let ct = ct.clone();
+        async move {
+            let _ = axum::serve(tcp_listener, router)
+                .with_graceful_shutdown(async move { ct.cancelled_owned().await })
+                .await;
+        }
+    });
+
+    let client = reqwest::Client::new();
+    let initialize = post(
+        &client,
+        None,
+        json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "initialize",
+            "params": {
+                "protocolVersion": "2025-03-26",
+                "capabilities": {},
+                "clientInfo": { "name": "test", "version": "0.0.1" }
+            }
+        }),
+    )
+    .await?;
+    let session_id = initialize
+        .headers()
+        .get(SESSION_ID_HEADER)
+        .expect("session id")
+        .to_str()?
+        .to_owned();
+    post(
+        &client,
+        Some(&session_id),
+        json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }),
+    )
+    .await?;
+
+    let mut old_stream = open_standalone_stream(&client, &session_id)
+        .await?
+        .bytes_stream();
+    // reconnect with the same session id while the old connection is still open
+    let mut new_stream = open_standalone_stream(&client, &session_id)
+        .await?
+        .bytes_stream();
+
+    let old_closed = tokio::time::timeout(Duration::from_secs(5), async {
+        while let Some(chunk) = old_stream.next().await {
+            chunk?;
+        }
+        anyhow::Ok(())
+    })
+    .await;
+    assert!(
+        matches!(old_closed, Ok(Ok(()))),
+        "the old stream should be closed"
+    );
+    assert!(
+        tokio::time::timeout(Duration::from_millis(200), new_stream.next())
+            .await
+            .is_err(),
+        "the new stream should stay open"
+    );
+
+    // the session itself survives the take over
+    let response = post(
+        &client,
+        Some(&session_id),
+        json!({ "jsonrpc": "2.0", "id": 2, "method": "ping" }),
+    )
+    .await?
+    .text()
+    .await?;
+    assert!(response.contains(r#""result":{}"#), "{response}");
+
+    // graceful shutdown waits for the open connections
+    drop(new_stream);
+    ct.cancel();
+    handle.await?;
+    Ok(())
+}
@@ -0,0 +1,136 @@
+use rmcp::{
+    ClientHandler, RoleClient, ServerHandler, ServiceExt,
+    handler::server::{tool::ToolRouter, wrapper::Parameters},
+    model::{
+        CallToolRequestParam, ClientRequest, Content, Meta, Request, ServerResult,
+        ToolPartialResultNotificationParam,
+    },
+    service::{NotificationContext, PeerRequestOptions},
+    tool, tool_handler, tool_router,
+};
+use tokio::sync::mpsc;
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct CountRequest {
+    pub n: u32,
+}
+
+#[derive(Clone)]
+pub struct StreamingServer {
+    tool_router: ToolRouter<Self>,
+}
+
+#[tool_router]
+impl StreamingServer {
+    pub fn new() -> Self {
+        Self {
+            tool_router: Self::tool_router(),
+        }
+    }
+
+    #[tool(streaming, description = "Count from 1 to n")]
+    fn count(
+        &self,
+        Parameters(CountRequest { n }): Parameters<CountRequest>,
+    ) -> impl futures::Stream<Item = Content> + Send + 'static {
+        futures::stream::iter((1..=n).map(|i| Content::text(i.to_string())))
+    }
+}
+
+impl Default for StreamingServer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for StreamingServer {}
+
+pub struct PartialResultClient {
+    partial_tx: mpsc::UnboundedSender<ToolPartialResultNotificationParam>,
+}
+
+impl ClientHandler for PartialResultClient {
+    async fn on_tool_partial_result(
+        &self,
+        params: ToolPartialResultNotificationParam,
+        _context: NotificationContext<RoleClient>,
+    ) {
+        let _ = self.partial_tx.send(params);
+    }
+}
+
+fn texts(contents: &[Content]) -> Vec<String> {
+    contents
+        .iter()
+        .map(|content| content.as_text().expect("text content").text.clone())
+        .collect()
+}
+
+async fn call_count(
+    partial_results: bool,
+) -> anyhow::Result<(
+    Vec<String>,
+    mpsc::UnboundedReceiver<ToolPartialResultNotificationParam>,
+)> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    tokio::spawn(async move {
+        let server = StreamingServer::new().serve(server_transport).await?;
+        server.waiting().await?;
+        anyhow::Ok(())
+    });
+    let (partial_tx, partial_rx) = mpsc::unbounded_channel();
+    let client = PartialResultClient { partial_tx }
+        .serve(client_transport)
+        .await?;
+
+    let mut meta = Meta::new();
+    if partial_results {
+        meta.set_partial_results(true);
+    }
+    let response = client
+        .send_request_with_option(
+            ClientRequest::CallToolRequest(Request::new(CallToolRequestParam {
+                name: "count".into(),
+                arguments: serde_json::json!({ "n": 3 }).as_object().cloned(),
+            })),
+            PeerRequestOptions {
+                timeout: None,
+                meta: Some(meta),
+            },
+        )
+        .await?
+        .await_response()
+        .await?;
+    let ServerResult::CallToolResult(result) = response else {
+        panic!("expected call tool result, got {response:?}");
+    };
+    client.cancel().await?;
+    Ok((texts(&result.content), partial_rx))
+}
+
+#[tokio::test]
+async fn test_streaming_tool_sends_partial_results() -> anyhow::Result<()> {
+    let (aggregated, mut partial_rx) = call_count(true).await?;
+    assert_eq!(aggregated, ["1", "2", "3"]);
+
+    let mut chunks = Vec::new();
+    loop {
+        let partial = partial_rx.recv().await.expect("partial result");
+        if partial.done {
+            assert!(partial.content.is_empty());
+            break;
+        }
+        chunks.extend(texts(&partial.content));
+    }
+    assert_eq!(chunks, ["1", "2", "3"]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_streaming_tool_aggregates_without_opt_in() -> anyhow::Result<()> {
+    let (aggregated, mut partial_rx) = call_count(false).await?;
+    assert_eq!(aggregated, ["1", "2", "3"]);
+    assert!(partial_rx.recv().await.is_none());
+    Ok(())
+}
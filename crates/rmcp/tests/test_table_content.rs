@@ -0,0 +1,110 @@
+use rmcp::{
+    ServerHandler, ServiceExt,
+    handler::server::tool::ToolRouter,
+    model::{CallToolRequestParam, CallToolResult, Table, TableColumn},
+    tool, tool_handler, tool_router,
+};
+use serde_json::json;
+
+fn cinemas() -> Table {
+    Table::new([
+        TableColumn::new("name").with_title("Cinema"),
+        "address".into(),
+        "distance".into(),
+    ])
+    .with_row([json!("万达影城"), json!("朝阳路 1 号"), json!(1.2)])
+    .with_row([json!("CGV | IMAX"), json!(null), json!(3)])
+}
+
+#[test]
+fn test_table_serialization() -> anyhow::Result<()> {
+    let table = cinemas();
+    let value = serde_json::to_value(&table)?;
+    assert_eq!(
+        value,
+        json!({
+            "columns": [
+                { "name": "name", "title": "Cinema" },
+                { "name": "address" },
+                { "name": "distance" }
+            ],
+            "rows": [
+                ["万达影城", "朝阳路 1 号", 1.2],
+                ["CGV | IMAX", null, 3]
+            ]
+        })
+    );
+    assert_eq!(serde_json::from_value::<Table>(value)?, table);
+    Ok(())
+}
+
+#[test]
+fn test_table_result() -> anyhow::Result<()> {
+    let result = cinemas().into_call_tool_result();
+    let value = serde_json::to_value(&result)?;
+    assert_eq!(value["_meta"], json!({ "rmcp/experimental/ui": "table" }));
+    assert_eq!(value["structuredContent"], serde_json::to_value(cinemas())?);
+    // the markdown fallback for the clients not rendering tables
+    assert_eq!(
+        value["content"][0]["text"],
+        "| Cinema | address | distance |\n\
+         | --- | --- | --- |\n\
+         | 万达影城 | 朝阳路 1 号 | 1.2 |\n\
+         | CGV \\| IMAX |  | 3 |"
+    );
+
+    let result: CallToolResult = serde_json::from_value(value)?;
+    assert_eq!(Table::from_result(&result), Some(cinemas()));
+    // structured content without the hint isn't a table
+    assert_eq!(
+        Table::from_result(&CallToolResult::structured(
+            json!({ "columns": [], "rows": [] })
+        )),
+        None
+    );
+    Ok(())
+}
+
+#[derive(Clone)]
+struct CinemaServer {
+    tool_router: ToolRouter<Self>,
+}
+
+#[tool_router]
+impl CinemaServer {
+    fn new() -> Self {
+        Self {
+            tool_router: Self::tool_router(),
+        }
+    }
+
+    #[tool(description = "List the cinemas nearby")]
+    async fn get_cinema_list(&self) -> Table {
+        cinemas()
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for CinemaServer {}
+
+#[tokio::test]
+async fn test_tool_returning_a_table() -> anyhow::Result<()> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    let (server, client) = tokio::join!(
+        CinemaServer::new().serve(server_transport),
+        ().serve(client_transport)
+    );
+    let (server, client) = (server?, client?);
+
+    let result = client
+        .call_tool(CallToolRequestParam {
+            name: "get_cinema_list".into(),
+            arguments: None,
+        })
+        .await?;
+    assert_eq!(Table::from_result(&result), Some(cinemas()));
+
+    client.cancel().await?;
+    server.waiting().await?;
+    Ok(())
+}
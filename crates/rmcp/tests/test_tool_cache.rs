@@ -0,0 +1,186 @@
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+
+use rmcp::{
+    ServerHandler, ServiceExt,
+    handler::server::{
+        common::Locale,
+        router::tool::{ToolResultCache, ToolRouter},
+        wrapper::Parameters,
+    },
+    model::{CallToolRequestParam, CallToolResult, ClientInfo, Content},
+    tool, tool_handler, tool_router,
+};
+
+mod common;
+use common::movie::connect;
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct MovieRequest {
+    pub movie_id: i32,
+}
+
+#[derive(Clone)]
+pub struct MovieServer {
+    upstream_calls: Arc<AtomicUsize>,
+    tool_router: ToolRouter<Self>,
+}
+
+#[tool_router]
+impl MovieServer {
+    pub fn new() -> Self {
+        Self {
+            upstream_calls: Arc::new(AtomicUsize::new(0)),
+            tool_router: Self::tool_router(),
+        }
+    }
+
+    #[tool(description = "Get the movie detail", cache_ttl_ms = 60000)]
+    async fn movie_detail(
+        &self,
+        Parameters(MovieRequest { movie_id }): Parameters<MovieRequest>,
+    ) -> String {
+        let calls = self.upstream_calls.fetch_add(1, Ordering::SeqCst) + 1;
+        format!("movie {movie_id}, upstream call {calls}")
+    }
+
+    #[tool(description = "Get the movie title", cache_ttl_ms = 60000)]
+    async fn movie_title(
+        &self,
+        Locale(locale): Locale,
+        Parameters(MovieRequest { movie_id }): Parameters<MovieRequest>,
+    ) -> String {
+        self.upstream_calls.fetch_add(1, Ordering::SeqCst);
+        match locale.as_str() {
+            "fr" => format!("film {movie_id}"),
+            _ => format!("movie {movie_id}"),
+        }
+    }
+}
+
+impl Default for MovieServer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for MovieServer {}
+
+#[tokio::test]
+async fn test_identical_call_served_from_cache() -> anyhow::Result<()> {
+    let server = MovieServer::new();
+    let handle = server.clone();
+    let client = connect(server, ()).await?;
+    let call = async |movie_id: i32| {
+        let result = client
+            .call_tool(CallToolRequestParam {
+                name: "movie_detail".into(),
+                arguments: serde_json::json!({ "movie_id": movie_id })
+                    .as_object()
+                    .cloned(),
+            })
+            .await?;
+        let text = result.content[0]
+            .as_text()
+            .expect("text content")
+            .text
+            .clone();
+        anyhow::Ok(text)
+    };
+
+    assert_eq!(call(1).await?, "movie 1, upstream call 1");
+    assert_eq!(call(1).await?, "movie 1, upstream call 1");
+    assert_eq!(handle.upstream_calls.load(Ordering::SeqCst), 1);
+
+    assert_eq!(call(2).await?, "movie 2, upstream call 2");
+
+    assert!(handle.tool_router.invalidate_cache("movie_detail", None));
+    assert_eq!(call(1).await?, "movie 1, upstream call 3");
+    assert!(!handle.tool_router.invalidate_cache("unknown", None));
+
+    client.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_cached_result_is_scoped_to_the_locale() -> anyhow::Result<()> {
+    let server = MovieServer::new();
+    let title = async |locale: &str| {
+        let (server_transport, client_transport) = tokio::io::duplex(4096);
+        tokio::spawn({
+            let server = server.clone();
+            async move {
+                let server = server.serve(server_transport).await?;
+                server.waiting().await?;
+                anyhow::Ok(())
+            }
+        });
+        let mut client_info = ClientInfo::default();
+        client_info.capabilities.set_locale(locale);
+        let client = client_info.serve(client_transport).await?;
+        let result = client
+            .call_tool(CallToolRequestParam {
+                name: "movie_title".into(),
+                arguments: serde_json::json!({ "movie_id": 7 }).as_object().cloned(),
+            })
+            .await?;
+        client.cancel().await?;
+        let text = result.content[0]
+            .as_text()
+            .expect("text content")
+            .text
+            .clone();
+        anyhow::Ok(text)
+    };
+
+    assert_eq!(title("en").await?, "movie 7");
+    assert_eq!(title("fr").await?, "film 7");
+    assert_eq!(title("fr").await?, "film 7");
+    assert_eq!(server.upstream_calls.load(Ordering::SeqCst), 2);
+
+    // invalidation drops the results of every locale
+    assert!(server.tool_router.invalidate_cache(
+        "movie_title",
+        serde_json::json!({ "movie_id": 7 }).as_object()
+    ));
+    assert_eq!(title("en").await?, "movie 7");
+    assert_eq!(server.upstream_calls.load(Ordering::SeqCst), 3);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_tool_result_cache_ttl_and_lru() {
+    let cache = ToolResultCache::new(Duration::from_millis(50), 2);
+    let result = |text: &str| CallToolResult::success(vec![Content::text(text)]);
+    cache.insert("a".to_string(), result("a"));
+    cache.insert("b".to_string(), result("b"));
+    // touch `a`, so `b` is the least recently used one
+    assert_eq!(cache.get("a"), Some(result("a")));
+    cache.insert("c".to_string(), result("c"));
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.get("b"), None);
+    assert_eq!(cache.get("c"), Some(result("c")));
+
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    assert_eq!(cache.get("a"), None);
+    assert_eq!(cache.get("c"), None);
+}
+
+#[test]
+fn test_tool_result_cache_key_ignores_key_order() {
+    let key = |arguments: serde_json::Value| ToolResultCache::key(arguments.as_object());
+    assert_eq!(
+        key(serde_json::json!({ "movie_id": 7, "cinema": { "id": 1, "city": "北京" } })),
+        key(serde_json::json!({ "cinema": { "city": "北京", "id": 1 }, "movie_id": 7 })),
+    );
+    assert_eq!(
+        key(serde_json::json!({ "movie_id": 7, "city": "北京" })),
+        r#"{"city":"北京","movie_id":7}"#
+    );
+}
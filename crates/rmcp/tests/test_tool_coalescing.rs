@@ -0,0 +1,216 @@
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+
+use rmcp::{
+    ErrorData, RoleServer, ServerHandler, ServiceExt,
+    handler::server::{
+        router::tool::ToolRouter,
+        tool::ToolCallContext,
+        wrapper::{Json, Parameters},
+    },
+    model::{CallToolRequestParam, CallToolResult, ClientInfo, ServerCapabilities, ServerInfo},
+    service::{RequestContext, RunningService},
+    tool, tool_router,
+};
+use serde_json::json;
+use tokio::sync::Semaphore;
+
+mod common;
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct GetMovieDetailInfoRequest {
+    movie_id: i32,
+}
+
+#[derive(Clone)]
+struct MovieServer {
+    /// The calls received, coalesced or not
+    received: Arc<AtomicUsize>,
+    /// The upstream requests, i.e. the executions of the tool
+    upstream: Arc<AtomicUsize>,
+    /// The upstream answers once a permit is added
+    answers: Arc<Semaphore>,
+    tool_router: ToolRouter<Self>,
+}
+
+#[tool_router]
+impl MovieServer {
+    fn new() -> Self {
+        Self::with_router(Self::tool_router())
+    }
+
+    fn with_router(tool_router: ToolRouter<Self>) -> Self {
+        Self {
+            received: Default::default(),
+            upstream: Default::default(),
+            answers: Arc::new(Semaphore::new(0)),
+            tool_router,
+        }
+    }
+
+    #[tool(description = "Get movie details based on the movie ID", coalesce)]
+    async fn get_movie_detail_info(
+        &self,
+        Parameters(req): Parameters<GetMovieDetailInfoRequest>,
+    ) -> Json<serde_json::Value> {
+        let upstream = self.upstream.fetch_add(1, Ordering::SeqCst) + 1;
+        self.answers
+            .acquire()
+            .await
+            .expect("open semaphore")
+            .forget();
+        Json(json!({ "movieId": req.movie_id, "upstream": upstream }))
+    }
+}
+
+impl ServerHandler for MovieServer {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo {
+            capabilities: ServerCapabilities::builder().enable_tools().build(),
+            ..Default::default()
+        }
+    }
+
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, ErrorData> {
+        self.received.fetch_add(1, Ordering::SeqCst);
+        self.tool_router
+            .call(ToolCallContext::new(self, request, context))
+            .await
+    }
+}
+
+fn get_movie_detail_info(movie_id: i32) -> CallToolRequestParam {
+    CallToolRequestParam {
+        name: "get_movie_detail_info".into(),
+        arguments: json!({ "movie_id": movie_id }).as_object().cloned(),
+    }
+}
+
+/// Open a session of `server` for a client declaring the `locale`
+async fn connect(
+    server: &MovieServer,
+    locale: &str,
+) -> anyhow::Result<RunningService<rmcp::RoleClient, ClientInfo>> {
+    let mut client_info = ClientInfo::default();
+    client_info.capabilities.set_locale(locale);
+    common::movie::connect(server.clone(), client_info).await
+}
+
+/// Wait until the counter reaches `count`
+async fn reach(counter: &AtomicUsize, count: usize) -> anyhow::Result<()> {
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while counter.load(Ordering::SeqCst) < count {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    })
+    .await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_concurrent_identical_calls_share_one_execution() -> anyhow::Result<()> {
+    let server = MovieServer::new();
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    tokio::spawn({
+        let server = server.clone();
+        async move {
+            let server = server.serve(server_transport).await?;
+            server.waiting().await?;
+            anyhow::Ok(())
+        }
+    });
+    let client = ().serve(client_transport).await?;
+
+    let call = |movie_id| {
+        let peer = client.peer().clone();
+        tokio::spawn(async move { peer.call_tool(get_movie_detail_info(movie_id)).await })
+    };
+    let first = call(1297);
+    reach(&server.upstream, 1).await?;
+    let second = call(1297);
+    let other = call(42);
+    reach(&server.received, 3).await?;
+    reach(&server.upstream, 2).await?;
+
+    server.answers.add_permits(2);
+    let (first, second, other) = (first.await??, second.await??, other.await??);
+    assert_eq!(first.structured_content, second.structured_content);
+    assert_eq!(
+        first.structured_content,
+        Some(json!({ "movieId": 1297, "upstream": 1 }))
+    );
+    assert_eq!(
+        other.structured_content,
+        Some(json!({ "movieId": 42, "upstream": 2 }))
+    );
+    assert_eq!(server.upstream.load(Ordering::SeqCst), 2);
+
+    // unlike a cache, nothing is kept once the call is done
+    server.answers.add_permits(1);
+    let again = client.call_tool(get_movie_detail_info(1297)).await?;
+    assert_eq!(
+        again.structured_content,
+        Some(json!({ "movieId": 1297, "upstream": 3 }))
+    );
+
+    client.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_calls_of_different_callers_are_not_coalesced() -> anyhow::Result<()> {
+    // the clients are told apart by their locale, and by the key of the router
+    let server =
+        MovieServer::with_router(MovieServer::tool_router().with_coalescing_key(|context| {
+            context
+                .peer
+                .peer_info()
+                .map(|info| info.client_info.name.clone())
+                .unwrap_or_default()
+        }));
+    let english = connect(&server, "en").await?;
+    let french = connect(&server, "fr").await?;
+    let other_english = connect(&server, "en").await?;
+
+    let call = |client: &RunningService<rmcp::RoleClient, ClientInfo>| {
+        let peer = client.peer().clone();
+        tokio::spawn(async move { peer.call_tool(get_movie_detail_info(1297)).await })
+    };
+    let first = call(&english);
+    reach(&server.upstream, 1).await?;
+    let second = call(&french);
+    let third = call(&other_english);
+    reach(&server.received, 3).await?;
+    reach(&server.upstream, 2).await?;
+
+    server.answers.add_permits(2);
+    let (first, second, third) = (first.await??, second.await??, third.await??);
+    assert_eq!(first.structured_content, third.structured_content);
+    assert_ne!(first.structured_content, second.structured_content);
+    assert_eq!(server.upstream.load(Ordering::SeqCst), 2);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_coalescing_with_visibility_needs_a_key() -> anyhow::Result<()> {
+    let server = MovieServer::with_router(
+        MovieServer::tool_router().with_visibility(|_tool, _context| true),
+    );
+    let client = connect(&server, "en").await?;
+    let error = client
+        .call_tool(get_movie_detail_info(1297))
+        .await
+        .expect_err("coalescing without a key");
+    assert!(error.to_string().contains("coalescing key"), "{error}");
+    assert_eq!(server.upstream.load(Ordering::SeqCst), 0);
+    Ok(())
+}
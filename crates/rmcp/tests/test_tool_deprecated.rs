@@ -0,0 +1,142 @@
+use std::{
+    io::Write,
+    sync::{Arc, Mutex},
+};
+
+use rmcp::{
+    ServerHandler, ServiceExt,
+    handler::server::tool::ToolRouter,
+    model::{CallToolRequestParam, ServerCapabilities, ServerInfo, Tool},
+    tool, tool_handler, tool_router,
+};
+use serde_json::json;
+use tracing_subscriber::util::SubscriberInitExt;
+
+#[derive(Clone)]
+struct MovieServer {
+    tool_router: ToolRouter<Self>,
+}
+
+#[tool_router]
+impl MovieServer {
+    fn new() -> Self {
+        Self {
+            tool_router: Self::tool_router(),
+        }
+    }
+
+    #[tool(
+        description = "Get the cinemas nearby",
+        deprecated = "use get_cinema_list instead"
+    )]
+    fn get_cinemas(&self) -> String {
+        "万达影城".to_string()
+    }
+
+    #[tool(description = "Get the cinemas of a city")]
+    fn get_cinema_list(&self) -> String {
+        "万达影城".to_string()
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for MovieServer {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo {
+            capabilities: ServerCapabilities::builder().enable_tools().build(),
+            ..Default::default()
+        }
+    }
+}
+
+/// The logs written by the subscriber
+#[derive(Clone, Default)]
+struct Logs(Arc<Mutex<Vec<u8>>>);
+
+impl Logs {
+    fn text(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).into_owned()
+    }
+}
+
+impl Write for Logs {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_deprecation_metadata() -> anyhow::Result<()> {
+    let tool = MovieServer::get_cinemas_tool_attr();
+    assert_eq!(tool.deprecation(), Some("use get_cinema_list instead"));
+    assert_eq!(
+        serde_json::to_value(&tool)?["_meta"],
+        json!({ "rmcp/deprecated": "use get_cinema_list instead" })
+    );
+
+    let tool = MovieServer::get_cinema_list_tool_attr();
+    assert_eq!(tool.deprecation(), None);
+    assert!(serde_json::to_value(&tool)?.get("_meta").is_none());
+
+    let tool = Tool::new("get_movies", "Get the movies", Arc::default())
+        .with_deprecation("use get_movie_list instead");
+    assert_eq!(tool.deprecation(), Some("use get_movie_list instead"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_deprecated_tool_call_warns() -> anyhow::Result<()> {
+    let logs = Logs::default();
+    let writer = logs.clone();
+    // the test runtime is single threaded, the default subscriber sees the server
+    let _guard = tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::WARN)
+        .with_ansi(false)
+        .with_writer(move || writer.clone())
+        .finish()
+        .set_default();
+
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    let (server, client) = tokio::join!(
+        MovieServer::new().serve(server_transport),
+        ().serve(client_transport)
+    );
+    let (server, client) = (server?, client?);
+
+    // the client sees the deprecation
+    let tools = client.list_all_tools().await?;
+    let deprecated: Vec<_> = tools
+        .iter()
+        .filter_map(|tool| Some((&*tool.name, tool.deprecation()?)))
+        .collect();
+    assert_eq!(
+        deprecated,
+        vec![("get_cinemas", "use get_cinema_list instead")]
+    );
+
+    for name in ["get_cinema_list", "get_cinemas"] {
+        client
+            .call_tool(CallToolRequestParam {
+                name: name.into(),
+                arguments: None,
+            })
+            .await?;
+    }
+    let logs = logs.text();
+    let warnings: Vec<_> = logs.lines().filter(|line| line.contains("WARN")).collect();
+    assert_eq!(warnings.len(), 1, "{logs}");
+    assert!(warnings[0].contains("deprecated tool called"), "{logs}");
+    assert!(warnings[0].contains("tool=get_cinemas"), "{logs}");
+    assert!(
+        warnings[0].contains("deprecation=use get_cinema_list instead"),
+        "{logs}"
+    );
+
+    client.cancel().await?;
+    server.cancel().await?;
+    Ok(())
+}
@@ -0,0 +1,109 @@
+use rmcp::{
+    handler::server::{router::tool::ToolRouter, wrapper::Parameters},
+    tool, tool_router,
+};
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct CinemaListRequest {
+    /// The name of the city
+    pub cityname: String,
+    pub lat: f64,
+    pub lng: f64,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct CinemaRequest {
+    pub cinema_id: i32,
+    pub city_id: i32,
+    /// The days of the schedule, today only if absent
+    pub days: Option<Vec<String>>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct MovieDetailRequest {
+    /// The ID of the movie
+    pub movie_id: i32,
+}
+
+pub struct MovieServer;
+
+#[tool_router(vis = "pub")]
+impl MovieServer {
+    #[tool(description = "Gets the current system time")]
+    fn get_current_time(&self) -> String {
+        String::new()
+    }
+
+    #[tool(description = "Get a list of nearby movie theaters")]
+    fn get_cinema_list(&self, Parameters(_): Parameters<CinemaListRequest>) -> String {
+        String::new()
+    }
+
+    #[tool(description = "Get detailed information about the cinema and its movie schedule")]
+    fn get_cinema_information(&self, Parameters(_): Parameters<CinemaRequest>) -> String {
+        String::new()
+    }
+
+    #[tool(description = "Get movie details based on the movie ID")]
+    fn get_movie_detail_info(&self, Parameters(_): Parameters<MovieDetailRequest>) -> String {
+        String::new()
+    }
+}
+
+#[test]
+fn test_describe_lists_every_tool() {
+    let router: ToolRouter<MovieServer> = MovieServer::tool_router();
+    let index = router.describe();
+    for tool in router.list_all() {
+        let line = format!("{} - {}", tool.name, tool.description.unwrap_or_default());
+        assert!(index.lines().any(|l| l == line), "{line:?} not in\n{index}");
+    }
+
+    let names: Vec<_> = router
+        .summaries()
+        .into_iter()
+        .map(|summary| summary.name)
+        .collect();
+    assert_eq!(
+        names,
+        [
+            "get_cinema_information",
+            "get_cinema_list",
+            "get_current_time",
+            "get_movie_detail_info"
+        ]
+    );
+}
+
+#[test]
+fn test_describe_parameters() {
+    let router: ToolRouter<MovieServer> = MovieServer::tool_router();
+    let index = router.describe();
+    assert!(index.contains("\n    cityname: string (required) - The name of the city\n"));
+    assert!(index.contains("\n    lat: number (required)\n"));
+    assert!(
+        index.contains("\n    days: string[] - The days of the schedule, today only if absent")
+    );
+    assert!(index.contains("\n    movie_id: integer (required) - The ID of the movie"));
+
+    let summary = router
+        .summaries()
+        .into_iter()
+        .find(|summary| summary.name == "get_current_time")
+        .expect("summary");
+    assert!(summary.parameters.is_empty());
+}
+
+#[tokio::test]
+async fn test_describe_skips_disabled_tools() {
+    let router: ToolRouter<MovieServer> = MovieServer::tool_router();
+    assert!(router.set_enabled("get_cinema_list", false).await);
+    assert!(
+        router
+            .summaries()
+            .iter()
+            .all(|summary| summary.name != "get_cinema_list")
+    );
+    assert!(!router.describe().contains("get_cinema_list"));
+    assert!(router.describe().contains("get_movie_detail_info"));
+}
@@ -0,0 +1,169 @@
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
+
+use rmcp::{
+    ClientHandler, RoleClient, RoleServer, ServerHandler, ServiceError, ServiceExt,
+    handler::server::tool::ToolRouter,
+    model::{CallToolRequestParam, ErrorCode},
+    service::NotificationContext,
+    tool, tool_handler, tool_router,
+};
+use tokio::sync::Notify;
+
+#[derive(Clone)]
+struct MovieServer {
+    tool_router: ToolRouter<Self>,
+}
+
+#[tool_router]
+impl MovieServer {
+    fn new() -> Self {
+        Self {
+            tool_router: Self::tool_router(),
+        }
+    }
+
+    #[tool(description = "Get a list of nearby movie theaters")]
+    fn get_cinema_list(&self) -> String {
+        "万达影城".to_string()
+    }
+
+    #[tool(description = "Get movie details based on the movie ID")]
+    fn get_movie_detail_info(&self) -> String {
+        "{}".to_string()
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for MovieServer {
+    async fn on_initialized(&self, context: NotificationContext<RoleServer>) {
+        self.tool_router.notify_list_changed_to(context.peer);
+    }
+}
+
+#[derive(Clone, Default)]
+struct ListChangedClient {
+    list_changed: Arc<Notify>,
+    received: Arc<AtomicUsize>,
+}
+
+impl ClientHandler for ListChangedClient {
+    async fn on_tool_list_changed(&self, _context: NotificationContext<RoleClient>) {
+        self.received.fetch_add(1, Ordering::SeqCst);
+        self.list_changed.notify_one();
+    }
+}
+
+async fn tool_names(client: &rmcp::Peer<RoleClient>) -> anyhow::Result<Vec<String>> {
+    let mut names: Vec<_> = client
+        .list_all_tools()
+        .await?
+        .into_iter()
+        .map(|tool| tool.name.to_string())
+        .collect();
+    names.sort();
+    Ok(names)
+}
+
+#[tokio::test]
+async fn test_disabled_tool_is_hidden_and_unavailable() -> anyhow::Result<()> {
+    let server = MovieServer::new();
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    tokio::spawn({
+        let server = server.clone();
+        async move {
+            let server = server.serve(server_transport).await?;
+            server.waiting().await?;
+            anyhow::Ok(())
+        }
+    });
+    let client_handler = ListChangedClient::default();
+    let client = client_handler.clone().serve(client_transport).await?;
+    let call_cinema_list = || {
+        client.call_tool(CallToolRequestParam {
+            name: "get_cinema_list".into(),
+            arguments: None,
+        })
+    };
+    assert_eq!(
+        tool_names(&client).await?,
+        ["get_cinema_list", "get_movie_detail_info"]
+    );
+
+    assert!(
+        server
+            .tool_router
+            .set_enabled("get_cinema_list", false)
+            .await
+    );
+    tokio::time::timeout(
+        std::time::Duration::from_secs(5),
+        client_handler.list_changed.notified(),
+    )
+    .await?;
+    assert_eq!(tool_names(&client).await?, ["get_movie_detail_info"]);
+    let Err(ServiceError::McpError(error)) = call_cinema_list().await else {
+        panic!("expect the disabled tool to fail");
+    };
+    assert_eq!(error.code, ErrorCode::UNAVAILABLE);
+    assert!(error.is_retryable());
+    assert_eq!(
+        error.message,
+        "tool get_cinema_list is temporarily unavailable"
+    );
+
+    assert!(
+        server
+            .tool_router
+            .set_enabled("get_cinema_list", true)
+            .await
+    );
+    tokio::time::timeout(
+        std::time::Duration::from_secs(5),
+        client_handler.list_changed.notified(),
+    )
+    .await?;
+    assert_eq!(
+        tool_names(&client).await?,
+        ["get_cinema_list", "get_movie_detail_info"]
+    );
+    let result = call_cinema_list().await?;
+    assert_eq!(result.content[0].as_text().unwrap().text, "万达影城");
+
+    assert!(!server.tool_router.set_enabled("book_ticket", false).await);
+    client.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_peer_registered_twice_is_notified_once() -> anyhow::Result<()> {
+    let server = MovieServer::new();
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    let client_handler = ListChangedClient::default();
+    let client = tokio::spawn(client_handler.clone().serve(client_transport));
+    let running = server.clone().serve(server_transport).await?;
+    let client = client.await??;
+    // on top of the registration from on_initialized
+    for _ in 0..3 {
+        server
+            .tool_router
+            .notify_list_changed_to(running.peer().clone());
+    }
+
+    server
+        .tool_router
+        .set_enabled("get_cinema_list", false)
+        .await;
+    tokio::time::timeout(
+        std::time::Duration::from_secs(5),
+        client_handler.list_changed.notified(),
+    )
+    .await?;
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    assert_eq!(client_handler.received.load(Ordering::SeqCst), 1);
+
+    client.cancel().await?;
+    Ok(())
+}
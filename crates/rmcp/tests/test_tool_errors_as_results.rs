@@ -0,0 +1,114 @@
+use rmcp::{
+    ErrorData, ServerHandler,
+    handler::server::{router::tool::ToolRouter, wrapper::Parameters},
+    model::{CallToolRequestParam, ErrorCode},
+    service::ServiceError,
+    tool, tool_handler, tool_router,
+};
+use serde_json::json;
+
+mod common;
+use common::movie::connect;
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct MovieDetailRequest {
+    pub movie_id: i32,
+}
+
+#[derive(Clone)]
+struct MovieServer {
+    tool_router: ToolRouter<Self>,
+}
+
+#[tool_router]
+impl MovieServer {
+    fn new(errors_as_results: bool) -> Self {
+        let tool_router = Self::tool_router();
+        Self {
+            tool_router: if errors_as_results {
+                tool_router.with_errors_as_results()
+            } else {
+                tool_router
+            },
+        }
+    }
+
+    #[tool(description = "Get movie details based on the movie ID")]
+    fn get_movie_detail_info(
+        &self,
+        Parameters(request): Parameters<MovieDetailRequest>,
+    ) -> Result<String, ErrorData> {
+        if request.movie_id == 1297 {
+            Ok("哪吒之魔童闹海".to_string())
+        } else {
+            Err(ErrorData::invalid_params(
+                format!("no movie with the ID {}", request.movie_id),
+                None,
+            ))
+        }
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for MovieServer {}
+
+fn call(name: &'static str, arguments: serde_json::Value) -> CallToolRequestParam {
+    CallToolRequestParam {
+        name: name.into(),
+        arguments: arguments.as_object().cloned(),
+    }
+}
+
+#[tokio::test]
+async fn test_tool_error_becomes_a_readable_result() -> anyhow::Result<()> {
+    let client = connect(MovieServer::new(true), ()).await?;
+
+    let result = client
+        .call_tool(call("get_movie_detail_info", json!({ "movie_id": 42 })))
+        .await?;
+    assert_eq!(result.is_error, Some(true));
+    assert_eq!(
+        result.content[0].as_text().expect("text").text,
+        "no movie with the ID 42"
+    );
+
+    // invalid arguments too
+    let result = client
+        .call_tool(call("get_movie_detail_info", json!({ "movie_id": "42" })))
+        .await?;
+    assert_eq!(result.is_error, Some(true));
+    assert!(!result.content[0].as_text().expect("text").text.is_empty());
+
+    let result = client
+        .call_tool(call("get_movie_detail_info", json!({ "movie_id": 1297 })))
+        .await?;
+    assert_eq!(result.is_error, Some(false));
+
+    // a missing tool is still a protocol error
+    let error = client
+        .call_tool(call("get_movie", json!({})))
+        .await
+        .expect_err("tool not found");
+    assert!(matches!(error, ServiceError::McpError(_)), "{error:?}");
+
+    client.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_tool_error_is_a_protocol_error_by_default() -> anyhow::Result<()> {
+    let client = connect(MovieServer::new(false), ()).await?;
+
+    let error = client
+        .call_tool(call("get_movie_detail_info", json!({ "movie_id": 42 })))
+        .await
+        .expect_err("tool error");
+    let ServiceError::McpError(error) = error else {
+        panic!("expected an mcp error, got {error:?}");
+    };
+    assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+    assert_eq!(error.message, "no movie with the ID 42");
+
+    client.cancel().await?;
+    Ok(())
+}
@@ -0,0 +1,94 @@
+use rmcp::{
+    ServerHandler, handler::server::tool::ToolRouter, model::ToolGroup, tool, tool_handler,
+    tool_router,
+};
+
+mod common;
+use common::movie::connect;
+
+#[derive(Clone)]
+struct MovieServer {
+    tool_router: ToolRouter<Self>,
+}
+
+#[tool_router]
+impl MovieServer {
+    fn new() -> Self {
+        Self {
+            tool_router: Self::tool_router()
+                .with_group("Movies", ["get_movie_detail_info", "get_cinema_list"])
+                .with_group("Location", ["get_cinema_list"])
+                .with_group("Movies", ["get_movie_list"])
+                .with_group("Admin", ["admin_clear_cache"]),
+        }
+    }
+
+    #[tool(description = "Get a list of nearby movie theaters")]
+    fn get_cinema_list(&self) -> String {
+        "万达影城".to_string()
+    }
+
+    #[tool(description = "Get the movies showing in a cinema")]
+    fn get_movie_list(&self) -> String {
+        "[]".to_string()
+    }
+
+    #[tool(description = "Get movie details based on the movie ID")]
+    fn get_movie_detail_info(&self) -> String {
+        "{}".to_string()
+    }
+
+    #[tool(description = "Book a movie ticket")]
+    fn book_ticket(&self) -> String {
+        "booked".to_string()
+    }
+
+    #[tool(description = "Drop the cached movie details")]
+    fn admin_clear_cache(&self) -> String {
+        "cleared".to_string()
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for MovieServer {}
+
+#[tokio::test]
+async fn test_tool_groups_in_list_meta() -> anyhow::Result<()> {
+    let server = MovieServer::new();
+    server
+        .tool_router
+        .set_enabled("admin_clear_cache", false)
+        .await;
+    let client = connect(server, ()).await?;
+
+    let result = client.list_tools(None).await?;
+    let groups = result
+        .meta
+        .as_ref()
+        .and_then(|meta| meta.tool_groups())
+        .expect("tools/list carries the tool groups");
+    assert_eq!(
+        groups,
+        [
+            ToolGroup::new("Movies", ["get_movie_detail_info", "get_movie_list"]),
+            ToolGroup::new("Location", ["get_cinema_list"]),
+        ]
+    );
+    // the tools themselves are unchanged, the disabled tool isn't grouped
+    let mut names: Vec<_> = result.tools.iter().map(|tool| tool.name.as_ref()).collect();
+    names.sort();
+    assert_eq!(
+        names,
+        [
+            "book_ticket",
+            "get_cinema_list",
+            "get_movie_detail_info",
+            "get_movie_list"
+        ]
+    );
+
+    let raw = serde_json::to_value(&result)?;
+    assert_eq!(raw["_meta"]["rmcp/toolGroups"][1]["label"], "Location");
+    client.cancel().await?;
+    Ok(())
+}
@@ -60,6 +60,22 @@ impl Server {
 
     #[tool]
     async fn empty_param(&self) {}
+
+    /// A synchronous tool, no future is needed for its body.
+    #[tool(description = "Get the current time")]
+    fn get_current_time(
+        &self,
+        Parameters(TimeRequest { zone }): Parameters<TimeRequest>,
+    ) -> String {
+        format!("12:00 {zone}")
+    }
+}
+
+/// Parameters for time tool.
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct TimeRequest {
+    /// Time zone of interest.
+    pub zone: String,
 }
 
 /// Generic service trait.
@@ -367,3 +383,44 @@ async fn test_optional_i64_field_with_null_input() -> anyhow::Result<()> {
     server_handle.await??;
     Ok(())
 }
+
+#[tokio::test]
+async fn test_sync_tool() -> anyhow::Result<()> {
+    // the method itself stays synchronous
+    let time: String =
+        Server::new().get_current_time(Parameters(TimeRequest { zone: "UTC".into() }));
+    assert_eq!(time, "12:00 UTC");
+
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    let server_handle = tokio::spawn(async move {
+        Server::new()
+            .serve(server_transport)
+            .await?
+            .waiting()
+            .await?;
+        anyhow::Ok(())
+    });
+    let client = DummyClientHandler::default()
+        .serve(client_transport)
+        .await?;
+
+    let tools = client.list_all_tools().await?;
+    assert!(tools.iter().any(|tool| tool.name == "get_current_time"));
+    let result = client
+        .call_tool(CallToolRequestParam {
+            name: "get_current_time".into(),
+            arguments: serde_json::json!({ "zone": "UTC+8" }).as_object().cloned(),
+        })
+        .await?;
+    let result_text = result
+        .content
+        .first()
+        .and_then(|content| content.raw.as_text())
+        .map(|text| text.text.as_str())
+        .expect("Expected text content");
+    assert_eq!(result_text, "12:00 UTC+8");
+
+    client.cancel().await?;
+    server_handle.await??;
+    Ok(())
+}
@@ -0,0 +1,95 @@
+use rmcp::{
+    ServerHandler, ServiceExt,
+    handler::server::{router::tool::ToolRouter, wrapper::Parameters},
+    model::{CallToolRequestParam, ErrorCode},
+    service::ServiceError,
+    tool, tool_handler, tool_router,
+};
+use serde_json::json;
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct ReviewRequest {
+    pub movie_id: i32,
+    pub review: String,
+}
+
+#[derive(Clone)]
+struct MovieServer {
+    tool_router: ToolRouter<Self>,
+}
+
+#[tool_router]
+impl MovieServer {
+    fn new() -> Self {
+        Self {
+            tool_router: Self::tool_router(),
+        }
+    }
+
+    #[tool(description = "Post a short review of a movie", max_arg_bytes = 256)]
+    fn post_review(&self, Parameters(request): Parameters<ReviewRequest>) -> String {
+        format!("{} characters", request.review.chars().count())
+    }
+
+    #[tool(description = "Post a full article about a movie")]
+    fn post_article(&self, Parameters(request): Parameters<ReviewRequest>) -> String {
+        format!("{} characters", request.review.chars().count())
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for MovieServer {}
+
+fn review(name: &'static str, review: String) -> CallToolRequestParam {
+    CallToolRequestParam {
+        name: name.into(),
+        arguments: json!({ "movie_id": 1297, "review": review })
+            .as_object()
+            .cloned(),
+    }
+}
+
+#[tokio::test]
+async fn test_reject_oversized_arguments() -> anyhow::Result<()> {
+    let (server_transport, client_transport) = tokio::io::duplex(1 << 16);
+    tokio::spawn(async move {
+        let server = MovieServer::new().serve(server_transport).await?;
+        server.waiting().await?;
+        anyhow::Ok(())
+    });
+    let client = ().serve(client_transport).await?;
+
+    let result = client
+        .call_tool(review("post_review", "好看".to_string()))
+        .await?;
+    assert_eq!(result.content[0].as_text().unwrap().text, "2 characters");
+
+    let error = client
+        .call_tool(review("post_review", "好看".repeat(1000)))
+        .await
+        .expect_err("oversized arguments");
+    let ServiceError::McpError(error) = error else {
+        panic!("expected an mcp error, got {error:?}");
+    };
+    assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+    assert!(error.message.contains("too large"), "{}", error.message);
+    let data = error.data.expect("error data");
+    assert_eq!(data["limit"], 256);
+    assert!(data["size"].as_u64().unwrap() > 256);
+
+    // no limit by default
+    let result = client
+        .call_tool(review("post_article", "好看".repeat(1000)))
+        .await?;
+    assert_eq!(result.content[0].as_text().unwrap().text, "2000 characters");
+
+    client.cancel().await?;
+    Ok(())
+}
+
+#[test]
+fn test_max_arg_bytes_on_the_route() {
+    let router = MovieServer::tool_router();
+    assert_eq!(router.map["post_review"].max_arg_bytes, Some(256));
+    assert_eq!(router.map["post_article"].max_arg_bytes, None);
+}
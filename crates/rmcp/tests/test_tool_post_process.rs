@@ -0,0 +1,96 @@
+use rmcp::{
+    ServerHandler, ServiceExt,
+    handler::server::{
+        router::tool::{PostProcess, ToolRouter},
+        wrapper::Parameters,
+    },
+    model::{CallToolRequestParam, CallToolResult, Content, Tool},
+    service::{RequestContext, RoleClient, RoleServer, RunningService},
+    tool, tool_handler, tool_router,
+};
+use serde_json::json;
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct CinemaRequest {
+    pub cinema_id: i32,
+}
+
+#[derive(Clone)]
+struct MovieServer {
+    tool_router: ToolRouter<Self>,
+}
+
+#[tool_router]
+impl MovieServer {
+    fn new(tool_router: ToolRouter<Self>) -> Self {
+        Self { tool_router }
+    }
+
+    #[tool(description = "Get the phone number of a cinema")]
+    fn get_cinema_phone(&self, Parameters(request): Parameters<CinemaRequest>) -> String {
+        format!("cinema {}: 010-12345678, open from 9:00", request.cinema_id)
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for MovieServer {}
+
+/// Apply `f` to every text content
+fn map_text(result: CallToolResult, f: impl Fn(&str) -> String) -> CallToolResult {
+    let content = result
+        .content
+        .iter()
+        .map(|content| Content::text(f(&content.as_text().expect("text").text)))
+        .collect();
+    CallToolResult { content, ..result }
+}
+
+fn redact(_: &Tool, _: &RequestContext<RoleServer>, result: CallToolResult) -> PostProcess {
+    PostProcess::Continue(map_text(result, |text| text.replace("010-12345678", "***")))
+}
+
+async fn call(router: ToolRouter<MovieServer>) -> anyhow::Result<String> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    tokio::spawn(async move {
+        let server = MovieServer::new(router).serve(server_transport).await?;
+        server.waiting().await?;
+        anyhow::Ok(())
+    });
+    let client: RunningService<RoleClient, ()> = ().serve(client_transport).await?;
+    let result = client
+        .call_tool(CallToolRequestParam {
+            name: "get_cinema_phone".into(),
+            arguments: json!({ "cinema_id": 42 }).as_object().cloned(),
+        })
+        .await?;
+    client.cancel().await?;
+    Ok(result.content[0].as_text().expect("text").text.clone())
+}
+
+#[tokio::test]
+async fn test_post_processors_run_in_order() -> anyhow::Result<()> {
+    let router = MovieServer::tool_router()
+        .with_post_processor(redact)
+        .with_post_processor(|tool, _, result| {
+            PostProcess::Continue(map_text(result, |text| format!("[{}] {text}", tool.name)))
+        })
+        .with_post_processor(|_, _, result| {
+            // after the redaction, before the truncation
+            assert!(!result.content[0].as_text().unwrap().text.contains("010"));
+            PostProcess::Continue(map_text(result, |text| text.chars().take(31).collect()))
+        });
+    assert_eq!(call(router).await?, "[get_cinema_phone] cinema 42: *");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_post_processor_stops_the_pipeline() -> anyhow::Result<()> {
+    let router = MovieServer::tool_router()
+        .with_post_processor(|_, _, result| PostProcess::Stop(result))
+        .with_post_processor(redact);
+    assert_eq!(
+        call(router).await?,
+        "cinema 42: 010-12345678, open from 9:00"
+    );
+    Ok(())
+}
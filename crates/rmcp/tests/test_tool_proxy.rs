@@ -0,0 +1,230 @@
+use std::sync::Arc;
+
+use futures::StreamExt;
+use rmcp::{
+    ClientHandler, ErrorData, Peer, RoleClient, RoleServer, ServerHandler, ServiceExt,
+    handler::{
+        client::progress::ProgressDispatcher,
+        server::{
+            proxy::{ToolProxy, UpstreamClient},
+            tool::ToolRouter,
+        },
+    },
+    model::{
+        CallToolRequestParam, ClientRequest, ErrorCode, Meta, NumberOrString,
+        ProgressNotificationParam, ProgressToken, Request, ServerResult,
+    },
+    service::{ClientManager, NotificationContext, PeerRequestOptions, RunningService},
+    tool, tool_handler, tool_router,
+};
+
+#[derive(Clone)]
+struct MovieServer {
+    tool_router: ToolRouter<Self>,
+}
+
+#[tool_router]
+impl MovieServer {
+    fn new() -> Self {
+        Self {
+            tool_router: Self::tool_router(),
+        }
+    }
+
+    #[tool(description = "Get the cinemas nearby")]
+    fn get_cinema_list(&self) -> String {
+        "万达影城".to_string()
+    }
+
+    #[tool(description = "Book a ticket")]
+    fn book_ticket(&self) -> Result<String, ErrorData> {
+        Err(ErrorData::invalid_params("the show is sold out", None))
+    }
+
+    #[tool(description = "Download the trailer of a movie")]
+    async fn download_trailer(meta: Meta, client: Peer<RoleServer>) -> Result<String, ErrorData> {
+        let progress_token = meta.get_progress_token().ok_or(ErrorData::invalid_params(
+            "a progress token is required",
+            None,
+        ))?;
+        for step in 1..=3 {
+            let _ = client
+                .notify_progress(ProgressNotificationParam {
+                    progress_token: progress_token.clone(),
+                    progress: step as f64,
+                    total: Some(3.0),
+                    message: None,
+                })
+                .await;
+        }
+        Ok("trailer.mp4".to_string())
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for MovieServer {}
+
+#[derive(Clone)]
+struct LocalServer {
+    tool_router: ToolRouter<Self>,
+}
+
+#[tool_router]
+impl LocalServer {
+    fn new() -> Self {
+        Self {
+            tool_router: Self::tool_router(),
+        }
+    }
+
+    #[tool(description = "Get the current time")]
+    fn get_time(&self) -> String {
+        "12:00".to_string()
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for LocalServer {}
+
+#[derive(Default)]
+struct ProgressClient {
+    progress: ProgressDispatcher,
+}
+
+impl ClientHandler for ProgressClient {
+    async fn on_progress(
+        &self,
+        params: ProgressNotificationParam,
+        _context: NotificationContext<RoleClient>,
+    ) {
+        self.progress.handle_notification(params).await;
+    }
+}
+
+/// Serve `server` to `client` in memory
+async fn serve_in_memory<S: ServerHandler, C: ClientHandler>(
+    server: S,
+    client: C,
+) -> anyhow::Result<RunningService<RoleClient, C>> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    tokio::spawn(async move {
+        let server = server.serve(server_transport).await?;
+        server.waiting().await?;
+        anyhow::Ok(())
+    });
+    Ok(client.serve(client_transport).await?)
+}
+
+async fn proxy_client() -> anyhow::Result<RunningService<RoleClient, ProgressClient>> {
+    let upstreams = Arc::new(ClientManager::new());
+    upstreams
+        .connect("movie", || {
+            let (server_transport, client_transport) = tokio::io::duplex(4096);
+            tokio::spawn(async move {
+                let server = MovieServer::new().serve(server_transport).await?;
+                server.waiting().await?;
+                anyhow::Ok(())
+            });
+            UpstreamClient::default().serve(client_transport)
+        })
+        .await?;
+    serve_in_memory(
+        ToolProxy::new(LocalServer::new(), upstreams),
+        ProgressClient::default(),
+    )
+    .await
+}
+
+#[tokio::test]
+async fn test_proxy_merges_and_routes_tools() -> anyhow::Result<()> {
+    let client = proxy_client().await?;
+
+    let mut names: Vec<_> = client
+        .list_all_tools()
+        .await?
+        .into_iter()
+        .map(|tool| tool.name.to_string())
+        .collect();
+    names.sort();
+    assert_eq!(
+        names,
+        [
+            "get_time",
+            "movie__book_ticket",
+            "movie__download_trailer",
+            "movie__get_cinema_list"
+        ]
+    );
+
+    let result = client
+        .call_tool(CallToolRequestParam {
+            name: "movie__get_cinema_list".into(),
+            arguments: None,
+        })
+        .await?;
+    assert_eq!(result.content[0].as_text().unwrap().text, "万达影城");
+    let result = client
+        .call_tool(CallToolRequestParam {
+            name: "get_time".into(),
+            arguments: None,
+        })
+        .await?;
+    assert_eq!(result.content[0].as_text().unwrap().text, "12:00");
+
+    let error = client
+        .call_tool(CallToolRequestParam {
+            name: "movie__book_ticket".into(),
+            arguments: None,
+        })
+        .await
+        .unwrap_err();
+    let rmcp::ServiceError::McpError(error) = error else {
+        panic!("expect the error of the upstream server, got {error}");
+    };
+    assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+    assert_eq!(error.message, "the show is sold out");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_proxy_relays_upstream_progress() -> anyhow::Result<()> {
+    let client = proxy_client().await?;
+    let progress_token = ProgressToken(NumberOrString::String("trailer".into()));
+    let mut subscriber = client
+        .service()
+        .progress
+        .subscribe(progress_token.clone())
+        .await;
+    let mut meta = Meta::new();
+    meta.set_progress_token(progress_token.clone());
+    let response = client
+        .send_request_with_option(
+            ClientRequest::CallToolRequest(Request::new(CallToolRequestParam {
+                name: "movie__download_trailer".into(),
+                arguments: None,
+            })),
+            PeerRequestOptions {
+                timeout: None,
+                meta: Some(meta),
+            },
+        )
+        .await?
+        .await_response()
+        .await?;
+    let ServerResult::CallToolResult(result) = response else {
+        panic!("unexpected response {response:?}");
+    };
+    assert_eq!(result.content[0].as_text().unwrap().text, "trailer.mp4");
+
+    let mut progress = Vec::new();
+    while progress.len() < 3 {
+        let notification =
+            tokio::time::timeout(std::time::Duration::from_secs(5), subscriber.next())
+                .await?
+                .expect("subscribed");
+        assert_eq!(notification.progress_token, progress_token);
+        progress.push(notification.progress);
+    }
+    assert_eq!(progress, [1.0, 2.0, 3.0]);
+    Ok(())
+}
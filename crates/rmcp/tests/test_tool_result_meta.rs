@@ -1,6 +1,14 @@
-use rmcp::model::{CallToolResult, Content, Meta};
+use rmcp::{
+    ErrorData, ServerHandler,
+    handler::server::{tool::ToolRouter, wrapper::Parameters},
+    model::{CallToolRequestParam, CallToolResult, Content, Meta, PartialResult},
+    tool, tool_handler, tool_router,
+};
 use serde_json::{Value, json};
 
+mod common;
+use common::movie::connect;
+
 #[test]
 fn serialize_tool_result_with_meta() {
     let content = vec![Content::text("ok")];
@@ -43,3 +51,116 @@ fn serialize_tool_result_without_meta_omits_field() {
     // Ensure _meta is omitted
     assert!(v.get("_meta").is_none());
 }
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct MovieRequest {
+    movie_id: i32,
+}
+
+#[derive(Clone)]
+struct MovieServer {
+    tool_router: ToolRouter<Self>,
+}
+
+#[tool_router]
+impl MovieServer {
+    fn new() -> Self {
+        Self {
+            tool_router: Self::tool_router(),
+        }
+    }
+
+    #[tool(description = "Get movie details based on the movie ID")]
+    async fn get_movie_detail_info(
+        &self,
+        Parameters(MovieRequest { movie_id }): Parameters<MovieRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let mut meta = Meta::new();
+        meta.insert("upstreamRequestId".into(), json!("req-42"));
+        meta.insert("elapsedMs".into(), json!(12));
+        Ok(
+            CallToolResult::success(vec![Content::text(format!("movie {movie_id}"))])
+                .with_meta(meta),
+        )
+    }
+
+    #[tool(description = "Get the cinemas near a location")]
+    async fn get_cinema_list(&self) -> Result<CallToolResult, ErrorData> {
+        Ok(CallToolResult::error_with_code(
+            "NO_CINEMAS_NEARBY",
+            vec![Content::text("There's no cinema within 10 km")],
+        ))
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for MovieServer {}
+
+#[tokio::test]
+async fn test_tool_result_meta_round_trip() -> anyhow::Result<()> {
+    let client = connect(MovieServer::new(), ()).await?;
+
+    let result = client
+        .call_tool(CallToolRequestParam {
+            name: "get_movie_detail_info".into(),
+            arguments: json!({ "movie_id": 7 }).as_object().cloned(),
+        })
+        .await?;
+    let meta = result.meta.expect("response _meta");
+    assert_eq!(meta.get("upstreamRequestId"), Some(&json!("req-42")));
+    assert_eq!(meta.get("elapsedMs"), Some(&json!(12)));
+    assert_eq!(result.content[0].as_text().unwrap().text, "movie 7");
+
+    client.cancel().await?;
+    Ok(())
+}
+
+#[test]
+fn test_with_meta_merges_existing_meta() {
+    let result: CallToolResult = PartialResult::new()
+        .text_part("cinema", Ok::<_, String>("Wanda".to_string()))
+        .into();
+    let mut meta = Meta::new();
+    meta.insert("cacheTtlMs".into(), json!(60_000));
+    let result = result.with_meta(meta);
+
+    let meta = result.meta.as_ref().unwrap();
+    assert_eq!(meta.get("cacheTtlMs"), Some(&json!(60_000)));
+    assert!(meta.get(PartialResult::META_FIELD).is_some());
+    assert_eq!(PartialResult::parts_of(&result).unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_tool_error_code_round_trip() -> anyhow::Result<()> {
+    let client = connect(MovieServer::new(), ()).await?;
+
+    let result = client
+        .call_tool(CallToolRequestParam {
+            name: "get_cinema_list".into(),
+            arguments: None,
+        })
+        .await?;
+    assert_eq!(result.is_error, Some(true));
+    assert_eq!(result.error_code(), Some("NO_CINEMAS_NEARBY"));
+    assert_eq!(
+        result.content[0].as_text().unwrap().text,
+        "There's no cinema within 10 km"
+    );
+
+    client.cancel().await?;
+    Ok(())
+}
+
+#[test]
+fn test_error_code_is_in_meta() {
+    let result = CallToolResult::error_with_code("NO_CINEMAS_NEARBY", vec![]);
+    let v = serde_json::to_value(&result).unwrap();
+    assert_eq!(v["isError"], json!(true));
+    assert_eq!(v["_meta"], json!({ "rmcp/errorCode": "NO_CINEMAS_NEARBY" }));
+
+    // only an error has an error code
+    let mut meta = Meta::new();
+    meta.set_error_code("NO_CINEMAS_NEARBY");
+    let result = CallToolResult::success(vec![]).with_meta(meta);
+    assert_eq!(result.error_code(), None);
+}
@@ -0,0 +1,124 @@
+use rmcp::{
+    ErrorData, RoleServer, ServerHandler,
+    handler::server::{tool::ToolRouter, wrapper::Parameters},
+    model::{CallToolRequestParam, CallToolResult, Content, ErrorCode},
+    service::RequestContext,
+    tool, tool_handler, tool_router,
+};
+use serde_json::json;
+
+mod common;
+use common::movie::connect;
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct CityRequest {
+    city_name: String,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct CinemaRequest {
+    city_name: String,
+    cinema_id: i32,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct ToolNameRequest {
+    name: String,
+}
+
+#[derive(Clone)]
+struct MovieServer {
+    tool_router: ToolRouter<Self>,
+}
+
+#[tool_router]
+impl MovieServer {
+    fn new() -> Self {
+        Self {
+            tool_router: Self::tool_router(),
+        }
+    }
+
+    #[tool(description = "Get the city id based on the city name")]
+    fn get_city_id(
+        &self,
+        Parameters(CityRequest { city_name }): Parameters<CityRequest>,
+    ) -> String {
+        match city_name.as_str() {
+            "Beijing" => "1".to_string(),
+            _ => "0".to_string(),
+        }
+    }
+
+    #[tool(description = "Get the cinema information")]
+    async fn get_cinema_information(
+        &self,
+        Parameters(CinemaRequest {
+            city_name,
+            cinema_id,
+        }): Parameters<CinemaRequest>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let city = self
+            .tool_router
+            .call_tool(
+                self,
+                "get_city_id",
+                json!({ "city_name": city_name }),
+                context,
+            )
+            .await?;
+        let city_id = &city.content[0].as_text().expect("text").text;
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "cinema {cinema_id} in city {city_id}"
+        ))]))
+    }
+
+    #[tool(description = "Call a tool by name with array arguments")]
+    async fn call_by_name(
+        &self,
+        Parameters(ToolNameRequest { name }): Parameters<ToolNameRequest>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, ErrorData> {
+        self.tool_router
+            .call_tool(self, name, json!([]), context)
+            .await
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for MovieServer {}
+
+#[tokio::test]
+async fn test_tool_calls_another_tool_through_router() -> anyhow::Result<()> {
+    let client = connect(MovieServer::new(), ()).await?;
+
+    let result = client
+        .call_tool(CallToolRequestParam {
+            name: "get_cinema_information".into(),
+            arguments: json!({ "city_name": "Beijing", "cinema_id": 7 })
+                .as_object()
+                .cloned(),
+        })
+        .await?;
+    assert_eq!(
+        result.content[0].as_text().unwrap().text,
+        "cinema 7 in city 1"
+    );
+
+    // arguments which aren't an object are rejected
+    let error = client
+        .call_tool(CallToolRequestParam {
+            name: "call_by_name".into(),
+            arguments: json!({ "name": "get_city_id" }).as_object().cloned(),
+        })
+        .await
+        .unwrap_err();
+    match error {
+        rmcp::ServiceError::McpError(error) => assert_eq!(error.code, ErrorCode::INVALID_PARAMS),
+        other => panic!("expected invalid params, got {other:?}"),
+    }
+
+    client.cancel().await?;
+    Ok(())
+}
@@ -0,0 +1,65 @@
+use rmcp::{
+    handler::server::{router::tool::ToolRouter, wrapper::Parameters},
+    tool, tool_router,
+};
+
+mod v1 {
+    use super::*;
+
+    #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+    pub struct MovieDetailRequest {
+        pub movie_id: i32,
+    }
+
+    pub struct MovieServer;
+
+    #[tool_router(vis = "pub")]
+    impl MovieServer {
+        #[tool(description = "Get movie details based on the movie ID")]
+        fn get_movie_detail_info(&self, Parameters(req): Parameters<MovieDetailRequest>) -> String {
+            req.movie_id.to_string()
+        }
+    }
+}
+
+mod v2 {
+    use super::*;
+
+    #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+    pub struct MovieDetailRequest {
+        pub movie_id: i32,
+        /// Added later, optional for compatibility
+        pub language: Option<String>,
+    }
+
+    pub struct MovieServer;
+
+    #[tool_router(vis = "pub")]
+    impl MovieServer {
+        #[tool(description = "Get movie details based on the movie ID")]
+        fn get_movie_detail_info(&self, Parameters(req): Parameters<MovieDetailRequest>) -> String {
+            format!("{} {}", req.movie_id, req.language.unwrap_or_default())
+        }
+    }
+}
+
+#[test]
+fn test_schema_fingerprint_changes_with_optional_field() {
+    let before: ToolRouter<v1::MovieServer> = v1::MovieServer::tool_router();
+    let after: ToolRouter<v2::MovieServer> = v2::MovieServer::tool_router();
+
+    // stable for the same schemas
+    assert_eq!(
+        before.schema_fingerprint(),
+        v1::MovieServer::tool_router().schema_fingerprint()
+    );
+    assert_eq!(before.schema_fingerprint().len(), 16);
+
+    assert_ne!(before.schema_fingerprint(), after.schema_fingerprint());
+
+    let snapshot = after.schema_snapshot();
+    let properties = &snapshot["get_movie_detail_info"]["inputSchema"]["properties"];
+    assert!(properties.get("movie_id").is_some());
+    assert!(properties.get("language").is_some());
+    assert!(snapshot["get_movie_detail_info"]["outputSchema"].is_null());
+}
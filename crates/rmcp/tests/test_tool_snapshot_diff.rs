@@ -0,0 +1,92 @@
+use rmcp::{
+    handler::server::router::tool::{ToolDiff, ToolRoute, ToolRouter},
+    tool, tool_router,
+};
+
+#[derive(Clone)]
+struct MovieServer;
+
+#[tool_router]
+impl MovieServer {
+    #[tool(description = "Get the movies showing in a city")]
+    fn get_movie_list(&self) -> String {
+        "流浪地球".to_string()
+    }
+
+    #[tool(description = "Get the details of a movie")]
+    fn get_movie_detail_info(&self) -> String {
+        "流浪地球 - 科幻".to_string()
+    }
+}
+
+#[tool_router(router = cinema_router)]
+impl MovieServer {
+    #[tool(description = "Get the cinemas of a city")]
+    fn get_cinema_list(&self) -> String {
+        "万达影城".to_string()
+    }
+}
+
+fn names(diff: &ToolDiff) -> (Vec<&str>, Vec<&str>, Vec<&str>) {
+    (
+        diff.added.iter().map(|tool| &*tool.name).collect(),
+        diff.removed.iter().map(String::as_str).collect(),
+        diff.modified.iter().map(|tool| &*tool.name).collect(),
+    )
+}
+
+#[test]
+fn test_diff_after_adding_and_removing_tools() {
+    let mut router = MovieServer::tool_router();
+    let old = router.snapshot();
+    assert_eq!(old.len(), 2);
+    assert!(old.diff(&router.snapshot()).is_empty());
+
+    router.remove_route("get_movie_list");
+    router.merge(MovieServer::cinema_router());
+    let mut detail = MovieServer::get_movie_detail_info_tool_attr();
+    detail.description = Some("Get the details of a movie, with its rating".into());
+    router.add_route(ToolRoute::new(detail, MovieServer::get_movie_detail_info));
+
+    let new = router.snapshot();
+    let diff = old.diff(&new);
+    assert_eq!(
+        names(&diff),
+        (
+            vec!["get_cinema_list"],
+            vec!["get_movie_list"],
+            vec!["get_movie_detail_info"]
+        )
+    );
+    assert_eq!(
+        diff.modified[0].description.as_deref(),
+        Some("Get the details of a movie, with its rating")
+    );
+    assert_eq!(
+        diff.to_string(),
+        "added: get_cinema_list; removed: get_movie_list; modified: get_movie_detail_info"
+    );
+
+    // and back
+    let diff = new.diff(&old);
+    assert_eq!(
+        names(&diff),
+        (
+            vec!["get_movie_list"],
+            vec!["get_cinema_list"],
+            vec!["get_movie_detail_info"]
+        )
+    );
+}
+
+#[tokio::test]
+async fn test_disabled_tools_are_removed() {
+    let router: ToolRouter<MovieServer> = MovieServer::tool_router();
+    let old = router.snapshot();
+    router.set_enabled("get_movie_list", false).await;
+    let diff = old.diff(&router.snapshot());
+    assert_eq!(diff.to_string(), "removed: get_movie_list");
+
+    router.set_enabled("get_movie_list", true).await;
+    assert_eq!(old.diff(&router.snapshot()).to_string(), "unchanged");
+}
@@ -0,0 +1,95 @@
+use rmcp::{
+    ServerHandler, ServiceError,
+    handler::server::tool::ToolRouter,
+    model::{CallToolRequestParam, ErrorCode},
+    tool, tool_handler, tool_router,
+};
+use serde_json::json;
+
+mod common;
+use common::movie::connect;
+
+#[derive(Clone)]
+struct MovieServer {
+    tool_router: ToolRouter<Self>,
+}
+
+#[tool_router]
+impl MovieServer {
+    fn new(suggest: bool) -> Self {
+        let tool_router = Self::tool_router();
+        Self {
+            tool_router: if suggest {
+                tool_router.with_suggestions()
+            } else {
+                tool_router
+            },
+        }
+    }
+
+    #[tool(description = "Get a list of nearby movie theaters")]
+    fn get_cinema_list(&self) -> String {
+        "[]".to_string()
+    }
+
+    #[tool(description = "Get movie details based on the movie ID")]
+    fn get_movie_detail_info(&self) -> String {
+        "{}".to_string()
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for MovieServer {}
+
+async fn call_unknown_tool(server: MovieServer, name: &str) -> anyhow::Result<rmcp::ErrorData> {
+    let client = connect(server, ()).await?;
+    let result = client
+        .call_tool(CallToolRequestParam {
+            name: name.to_string().into(),
+            arguments: None,
+        })
+        .await;
+    client.cancel().await?;
+    match result {
+        Err(ServiceError::McpError(error)) => Ok(error),
+        other => panic!("expected an error, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_tool_not_found_suggestion() -> anyhow::Result<()> {
+    let error = call_unknown_tool(MovieServer::new(true), "get_cinemalist").await?;
+    assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+    assert_eq!(
+        error.message,
+        "tool not found, did you mean `get_cinema_list`?"
+    );
+    assert_eq!(error.data, Some(json!({ "suggestion": "get_cinema_list" })));
+
+    // nothing close enough
+    let error = call_unknown_tool(MovieServer::new(true), "book_ticket").await?;
+    assert_eq!(error.message, "tool not found");
+    assert_eq!(error.data, None);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_tool_not_found_suggestion_is_opt_in() -> anyhow::Result<()> {
+    let error = call_unknown_tool(MovieServer::new(false), "get_cinemalist").await?;
+    assert_eq!(error.message, "tool not found");
+    assert_eq!(error.data, None);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_disabled_tool_is_not_suggested() -> anyhow::Result<()> {
+    let server = MovieServer::new(true);
+    server
+        .tool_router
+        .set_enabled("get_cinema_list", false)
+        .await;
+    let error = call_unknown_tool(server, "get_cinemalist").await?;
+    assert_eq!(error.message, "tool not found");
+    assert_eq!(error.data, None);
+    Ok(())
+}
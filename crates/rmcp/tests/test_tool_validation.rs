@@ -0,0 +1,193 @@
+use rmcp::{
+    ErrorData, ServerHandler,
+    handler::server::{tool::ToolRouter, wrapper::Parameters},
+    model::{CallToolRequestParam, CallToolResult, Content, ErrorCode},
+    tool, tool_handler, tool_router,
+};
+use serde_json::json;
+
+mod common;
+use common::movie::connect;
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct GetCinemaListRequest {
+    /// Current location latitude
+    #[validate(range(min = -90.0, max = 90.0))]
+    latitude: f64,
+    /// Current location longitude
+    #[validate(range(min = -180.0, max = 180.0))]
+    longitude: f64,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct Location {
+    #[validate(range(min = -90.0, max = 90.0))]
+    latitude: f64,
+    #[validate(range(min = -180.0, max = 180.0))]
+    longitude: f64,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+struct BookTicketRequest {
+    /// The six digits of the showtime
+    #[schemars(regex(pattern = r"^\d{6}$"))]
+    showtime_id: String,
+    /// Where to send the ticket, if not to the cinema
+    delivery: Option<Location>,
+}
+
+#[derive(Clone)]
+struct CinemaServer {
+    tool_router: ToolRouter<Self>,
+}
+
+#[tool_router]
+impl CinemaServer {
+    fn new() -> Self {
+        Self {
+            tool_router: Self::tool_router(),
+        }
+    }
+
+    #[tool(description = "Get the cinemas around a location")]
+    async fn get_cinema_list(
+        &self,
+        Parameters(req): Parameters<GetCinemaListRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "cinemas around {},{}",
+            req.latitude, req.longitude
+        ))]))
+    }
+
+    #[tool(description = "Book a ticket for a showtime")]
+    async fn book_ticket(
+        &self,
+        Parameters(req): Parameters<BookTicketRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let delivery = req
+            .delivery
+            .map(|location| format!(" to {},{}", location.latitude, location.longitude))
+            .unwrap_or_default();
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "booked {}{delivery}",
+            req.showtime_id
+        ))]))
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for CinemaServer {}
+
+#[tokio::test]
+async fn test_out_of_range_latitude_is_rejected() -> anyhow::Result<()> {
+    let client = connect(CinemaServer::new(), ()).await?;
+
+    // the constraints are part of the schema
+    let tools = client.list_all_tools().await?;
+    let tool = tools
+        .iter()
+        .find(|tool| tool.name == "get_cinema_list")
+        .unwrap();
+    let latitude = &tool.input_schema["properties"]["latitude"];
+    assert_eq!(latitude["minimum"], json!(-90.0));
+    assert_eq!(latitude["maximum"], json!(90.0));
+
+    let result = client
+        .call_tool(CallToolRequestParam {
+            name: "get_cinema_list".into(),
+            arguments: json!({ "latitude": 39.9, "longitude": 116.4 })
+                .as_object()
+                .cloned(),
+        })
+        .await?;
+    assert_eq!(
+        result.content[0].as_text().unwrap().text,
+        "cinemas around 39.9,116.4"
+    );
+
+    let error = client
+        .call_tool(CallToolRequestParam {
+            name: "get_cinema_list".into(),
+            arguments: json!({ "latitude": 91.0, "longitude": 116.4 })
+                .as_object()
+                .cloned(),
+        })
+        .await
+        .unwrap_err();
+    match error {
+        rmcp::ServiceError::McpError(error) => {
+            assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+            assert_eq!(
+                error.message,
+                "invalid argument `latitude`: must be at most 90"
+            );
+            assert_eq!(error.data, Some(json!({ "path": "latitude" })));
+        }
+        other => panic!("expected invalid params, got {other:?}"),
+    }
+
+    client.cancel().await?;
+    Ok(())
+}
+
+async fn book_ticket(
+    client: &rmcp::Peer<rmcp::RoleClient>,
+    arguments: serde_json::Value,
+) -> Result<CallToolResult, rmcp::ErrorData> {
+    client
+        .call_tool(CallToolRequestParam {
+            name: "book_ticket".into(),
+            arguments: arguments.as_object().cloned(),
+        })
+        .await
+        .map_err(|error| match error {
+            rmcp::ServiceError::McpError(error) => error,
+            other => panic!("expected an error from the server, got {other:?}"),
+        })
+}
+
+#[tokio::test]
+async fn test_pattern_and_any_of_are_enforced() -> anyhow::Result<()> {
+    let client = connect(CinemaServer::new(), ()).await?;
+
+    let result = book_ticket(
+        &client,
+        json!({ "showtime_id": "202410", "delivery": { "latitude": 39.9, "longitude": 116.4 } }),
+    )
+    .await?;
+    assert_eq!(
+        result.content[0].as_text().unwrap().text,
+        "booked 202410 to 39.9,116.4"
+    );
+    book_ticket(
+        &client,
+        json!({ "showtime_id": "202410", "delivery": null }),
+    )
+    .await?;
+
+    let error = book_ticket(&client, json!({ "showtime_id": "tonight" }))
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+    assert_eq!(
+        error.message,
+        r"invalid argument `showtime_id`: must match the pattern `^\d{6}$`"
+    );
+
+    // the optional location is an `anyOf` of its definition and null
+    let error = book_ticket(
+        &client,
+        json!({ "showtime_id": "202410", "delivery": { "latitude": 91.0, "longitude": 116.4 } }),
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(
+        error.message,
+        "invalid argument `delivery.latitude`: must be at most 90"
+    );
+    assert_eq!(error.data, Some(json!({ "path": "delivery.latitude" })));
+
+    client.cancel().await?;
+    Ok(())
+}
@@ -0,0 +1,117 @@
+use rmcp::{
+    RoleClient, RoleServer, ServerHandler, ServiceError,
+    handler::server::tool::ToolRouter,
+    model::{CallToolRequestParam, ClientInfo, Implementation, Tool},
+    service::{RequestContext, RunningService},
+    tool, tool_handler, tool_router,
+};
+
+mod common;
+
+const ADMIN_CLIENT: &str = "movie-admin";
+
+/// Admin tools are only visible to the admin client
+fn visible(tool: &Tool, context: &RequestContext<RoleServer>) -> bool {
+    let is_admin = context
+        .peer
+        .peer_info()
+        .is_some_and(|info| info.client_info.name == ADMIN_CLIENT);
+    !tool.name.starts_with("admin_") || is_admin
+}
+
+#[derive(Clone)]
+struct MovieServer {
+    tool_router: ToolRouter<Self>,
+}
+
+#[tool_router]
+impl MovieServer {
+    fn new() -> Self {
+        Self {
+            tool_router: Self::tool_router()
+                .with_suggestions()
+                .with_visibility(visible),
+        }
+    }
+
+    #[tool(description = "Get a list of nearby movie theaters")]
+    fn get_cinema_list(&self) -> String {
+        "万达影城".to_string()
+    }
+
+    #[tool(description = "Drop the cached movie details")]
+    fn admin_clear_cache(&self) -> String {
+        "cleared".to_string()
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for MovieServer {}
+
+async fn connect(client_name: &str) -> anyhow::Result<RunningService<RoleClient, ClientInfo>> {
+    let client_info = ClientInfo {
+        client_info: Implementation {
+            name: client_name.to_string(),
+            ..Implementation::from_build_env()
+        },
+        ..Default::default()
+    };
+    common::movie::connect(MovieServer::new(), client_info).await
+}
+
+async fn tool_names(
+    client: &RunningService<RoleClient, ClientInfo>,
+) -> anyhow::Result<Vec<String>> {
+    let mut names: Vec<_> = client
+        .list_all_tools()
+        .await?
+        .into_iter()
+        .map(|tool| tool.name.to_string())
+        .collect();
+    names.sort();
+    Ok(names)
+}
+
+fn clear_cache() -> CallToolRequestParam {
+    CallToolRequestParam {
+        name: "admin_clear_cache".into(),
+        arguments: None,
+    }
+}
+
+#[tokio::test]
+async fn test_admin_tools_are_visible_to_the_admin() -> anyhow::Result<()> {
+    let admin = connect(ADMIN_CLIENT).await?;
+    assert_eq!(
+        tool_names(&admin).await?,
+        ["admin_clear_cache", "get_cinema_list"]
+    );
+    let result = admin.call_tool(clear_cache()).await?;
+    assert_eq!(result.content[0].as_text().unwrap().text, "cleared");
+    admin.cancel().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_hidden_tools_are_not_listed_nor_callable() -> anyhow::Result<()> {
+    let client = connect("movie-app").await?;
+    assert_eq!(tool_names(&client).await?, ["get_cinema_list"]);
+
+    let Err(ServiceError::McpError(error)) = client.call_tool(clear_cache()).await else {
+        panic!("expect the hidden tool to fail");
+    };
+    assert_eq!(error.message, "tool not found");
+    // nor suggested
+    let Err(ServiceError::McpError(error)) = client
+        .call_tool(CallToolRequestParam {
+            name: "admin_clear_cach".into(),
+            arguments: None,
+        })
+        .await
+    else {
+        panic!("expect an unknown tool to fail");
+    };
+    assert_eq!(error.message, "tool not found");
+    client.cancel().await?;
+    Ok(())
+}
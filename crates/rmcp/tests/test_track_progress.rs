@@ -0,0 +1,104 @@
+use futures::StreamExt;
+use rmcp::{
+    RoleServer, ServerHandler,
+    handler::server::tool::ToolRouter,
+    model::{
+        CallToolRequestParam, ClientRequest, Meta, NumberOrString, ProgressNotificationParam,
+        ProgressToken, Request, ServerResult,
+    },
+    service::{PeerRequestOptions, Progress, RequestContext},
+    tool, tool_handler, tool_router,
+};
+
+mod common;
+use common::movie::connect;
+
+const STEPS: [&str; 3] = ["pick the seats", "pay", "print the ticket"];
+
+#[derive(Clone)]
+struct MovieServer {
+    tool_router: ToolRouter<Self>,
+}
+
+#[tool_router]
+impl MovieServer {
+    fn new() -> Self {
+        Self {
+            tool_router: Self::tool_router(),
+        }
+    }
+
+    #[tool(description = "Book a movie ticket")]
+    async fn book_ticket(&self, context: RequestContext<RoleServer>) -> String {
+        let progress_token = context.meta.get_progress_token().expect("a progress token");
+        for (step, message) in STEPS.into_iter().enumerate() {
+            let _ = context
+                .peer
+                .notify_progress(ProgressNotificationParam {
+                    progress_token: progress_token.clone(),
+                    progress: (step + 1) as f64,
+                    total: Some(STEPS.len() as f64),
+                    message: Some(message.to_string()),
+                })
+                .await;
+        }
+        "万达影城 7排8座".to_string()
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for MovieServer {}
+
+fn book_ticket() -> ClientRequest {
+    ClientRequest::CallToolRequest(Request::new(CallToolRequestParam {
+        name: "book_ticket".into(),
+        arguments: None,
+    }))
+}
+
+#[tokio::test]
+async fn test_track_progress_of_a_request() -> anyhow::Result<()> {
+    let client = connect(MovieServer::new(), ()).await?;
+
+    // track our own token before sending, so no update is missed
+    let progress_token = ProgressToken(NumberOrString::String("booking".into()));
+    let progress = client.track_progress(progress_token.clone());
+    let mut meta = Meta::new();
+    meta.set_progress_token(progress_token.clone());
+    let tracked = client
+        .send_request_with_option(
+            book_ticket(),
+            PeerRequestOptions {
+                timeout: None,
+                meta: Some(meta),
+            },
+        )
+        .await?;
+    assert_eq!(tracked.progress_token, progress_token);
+    // a concurrent request reports its progress with its own token
+    let untracked = client
+        .send_cancellable_request(book_ticket(), PeerRequestOptions::no_options())
+        .await?;
+    assert_ne!(untracked.progress_token, progress_token);
+
+    untracked.await_response().await?;
+    let ServerResult::CallToolResult(result) = tracked.await_response().await? else {
+        panic!("expect a tool result");
+    };
+    assert_eq!(result.content[0].as_text().unwrap().text, "万达影城 7排8座");
+
+    // the stream ends with the response
+    let updates: Vec<Progress> = progress.collect().await;
+    assert_eq!(
+        updates
+            .iter()
+            .map(|update| update.message.as_deref())
+            .collect::<Vec<_>>(),
+        STEPS.map(Some)
+    );
+    assert_eq!(updates[0].fraction(), Some(1.0 / 3.0));
+    assert_eq!(updates[2].fraction(), Some(1.0));
+
+    client.cancel().await?;
+    Ok(())
+}
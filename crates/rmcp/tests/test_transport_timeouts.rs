@@ -0,0 +1,189 @@
+use std::time::Duration;
+
+use rmcp::{
+    ServiceExt,
+    model::{LoggingLevel, LoggingMessageNotificationParam},
+    service::QuitReason,
+    transport::{
+        StreamableHttpServerConfig, TimeoutTransport, TransportTimeout, TransportTimeouts,
+        async_rw::AsyncRwTransport,
+        streamable_http_server::{
+            session::local::LocalSessionManager, tower::StreamableHttpService,
+        },
+    },
+};
+use serde_json::{Value, json};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, DuplexStream, ReadHalf, WriteHalf};
+use tokio_util::sync::CancellationToken;
+
+mod common;
+use common::movie::MovieServer;
+
+const BIND_ADDRESS: &str = "127.0.0.1:8179";
+const SESSION_ID_HEADER: &str = "Mcp-Session-Id";
+
+fn rw_transport(
+    transport: DuplexStream,
+) -> AsyncRwTransport<rmcp::RoleServer, ReadHalf<DuplexStream>, WriteHalf<DuplexStream>> {
+    let (read, write) = tokio::io::split(transport);
+    AsyncRwTransport::new_server(read, write)
+}
+
+fn initialize_request() -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": "2025-03-26",
+            "capabilities": {},
+            "clientInfo": { "name": "half-open movie client", "version": "0.1.0" }
+        }
+    })
+}
+
+/// Initialize the server over `transport` as a raw client, returning the half it reads from
+async fn initialize(transport: DuplexStream) -> anyhow::Result<BufReader<DuplexStream>> {
+    let mut transport = BufReader::new(transport);
+    let initialize = initialize_request();
+    let initialized = json!({ "jsonrpc": "2.0", "method": "notifications/initialized" });
+    transport
+        .get_mut()
+        .write_all(format!("{initialize}\n").as_bytes())
+        .await?;
+    let mut line = String::new();
+    transport.read_line(&mut line).await?;
+    transport
+        .get_mut()
+        .write_all(format!("{initialized}\n").as_bytes())
+        .await?;
+    Ok(transport)
+}
+
+#[tokio::test]
+async fn test_close_after_a_stalled_read() -> anyhow::Result<()> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    let timeouts = TransportTimeouts::default().with_read(Duration::from_millis(200));
+    let transport = TimeoutTransport::new(rw_transport(server_transport), timeouts);
+    let server = tokio::spawn(MovieServer.serve(transport));
+
+    // the client initializes, then stops sending anything
+    let mut client = initialize(client_transport).await?;
+    let server = server.await??;
+    let quit_reason = tokio::time::timeout(Duration::from_secs(5), server.waiting()).await??;
+    let QuitReason::TransportTimeout(timeout) = quit_reason else {
+        panic!("the session should time out, not {quit_reason:?}");
+    };
+    assert_eq!(timeout, TransportTimeout::Read(Duration::from_millis(200)));
+
+    // and is told why, if it's still there
+    let mut line = String::new();
+    client.read_line(&mut line).await?;
+    let notification: Value = serde_json::from_str(&line)?;
+    assert_eq!(notification["method"], "notifications/rmcp/session_closed");
+    assert_eq!(
+        notification["params"]["reason"],
+        "no message received for 200ms"
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_close_after_a_stalled_write() -> anyhow::Result<()> {
+    let (server_transport, client_transport) = tokio::io::duplex(1024);
+    let timeouts = TransportTimeouts::default().with_write(Duration::from_millis(200));
+    let transport = TimeoutTransport::new(rw_transport(server_transport), timeouts);
+    let server = tokio::spawn(MovieServer.serve(transport));
+
+    // the client initializes, then stops reading
+    let _client = initialize(client_transport).await?;
+    let server = server.await??;
+    let log = LoggingMessageNotificationParam {
+        level: LoggingLevel::Info,
+        logger: None,
+        data: json!("放映厅".repeat(1024)),
+    };
+    let _ = server.peer().notify_logging_message(log).await;
+
+    let quit_reason = tokio::time::timeout(Duration::from_secs(5), server.waiting()).await??;
+    let QuitReason::TransportTimeout(timeout) = quit_reason else {
+        panic!("the session should time out, not {quit_reason:?}");
+    };
+    assert_eq!(timeout, TransportTimeout::Write(Duration::from_millis(200)));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_no_timeout_by_default() -> anyhow::Result<()> {
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    let transport =
+        TimeoutTransport::new(rw_transport(server_transport), TransportTimeouts::default());
+    let server = tokio::spawn(MovieServer.serve(transport));
+    let _client = initialize(client_transport).await?;
+    let server = server.await??;
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert!(!server.peer().is_transport_closed());
+    assert!(matches!(server.cancel().await?, QuitReason::Cancelled));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_streamable_http_session_closed_after_a_stalled_read() -> anyhow::Result<()> {
+    let service: StreamableHttpService<MovieServer, LocalSessionManager> =
+        StreamableHttpService::new(
+            || Ok(MovieServer),
+            Default::default(),
+            StreamableHttpServerConfig {
+                sse_keep_alive: None,
+                timeouts: TransportTimeouts::default().with_read(Duration::from_millis(200)),
+                ..Default::default()
+            },
+        );
+    let router = axum::Router::new().nest_service("/mcp", service);
+    let tcp_listener = tokio::net::TcpListener::bind(BIND_ADDRESS).await?;
+    let ct = CancellationToken::new();
+    tokio::spawn({
+        let ct = ct.clone();
+        async move {
+            let _ = axum::serve(tcp_listener, router)
+                .with_graceful_shutdown(async move { ct.cancelled_owned().await })
+                .await;
+        }
+    });
+
+    let client = reqwest::Client::new();
+    let post = |session_id: Option<&str>, body: Value| {
+        let mut request = client
+            .post(format!("http://{BIND_ADDRESS}/mcp"))
+            .header("Accept", "application/json, text/event-stream")
+            .json(&body);
+        if let Some(session_id) = session_id {
+            request = request.header(SESSION_ID_HEADER, session_id);
+        }
+        request.send()
+    };
+    let initialize = post(None, initialize_request()).await?.error_for_status()?;
+    let session_id = initialize
+        .headers()
+        .get(SESSION_ID_HEADER)
+        .expect("session id")
+        .to_str()?
+        .to_owned();
+    post(
+        Some(&session_id),
+        json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }),
+    )
+    .await?
+    .error_for_status()?;
+
+    // the client stops sending anything, the session is gone once the read timeout expires
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    let ping = post(
+        Some(&session_id),
+        json!({ "jsonrpc": "2.0", "id": 2, "method": "ping" }),
+    )
+    .await?;
+    assert!(!ping.status().is_success(), "{}", ping.status());
+    ct.cancel();
+    Ok(())
+}
@@ -0,0 +1,73 @@
+use std::sync::{Arc, Mutex};
+
+use rmcp::{
+    RoleServer, ServerHandler, ServiceExt,
+    handler::server::tool::ToolRouter,
+    model::{CallToolRequestParam, ResourceUpdatedNotificationParam},
+    service::{RequestContext, WeakPeer},
+    tool, tool_handler, tool_router,
+};
+
+#[derive(Clone)]
+struct MovieServer {
+    /// Who to tell when the showtimes change
+    subscriber: Arc<Mutex<Option<WeakPeer<RoleServer>>>>,
+    tool_router: ToolRouter<Self>,
+}
+
+#[tool_router]
+impl MovieServer {
+    fn new() -> Self {
+        Self {
+            subscriber: Default::default(),
+            tool_router: Self::tool_router(),
+        }
+    }
+
+    #[tool(description = "Get notified when the showtimes change")]
+    async fn subscribe_showtimes(&self, context: RequestContext<RoleServer>) -> String {
+        *self.subscriber.lock().unwrap() = Some(context.peer.downgrade());
+        "subscribed".to_string()
+    }
+}
+
+#[tool_handler]
+impl ServerHandler for MovieServer {}
+
+#[tokio::test]
+async fn test_weak_peer_fails_to_upgrade_after_disconnect() -> anyhow::Result<()> {
+    let movie_server = MovieServer::new();
+    let (server_transport, client_transport) = tokio::io::duplex(4096);
+    let (server, client) = tokio::join!(
+        movie_server.clone().serve(server_transport),
+        ().serve(client_transport)
+    );
+    let (server, client) = (server?, client?);
+
+    client
+        .call_tool(CallToolRequestParam {
+            name: "subscribe_showtimes".into(),
+            arguments: None,
+        })
+        .await?;
+    let subscriber = movie_server
+        .subscriber
+        .lock()
+        .unwrap()
+        .clone()
+        .expect("a subscriber");
+
+    // the session lives, the weak handle can still notify the client
+    let peer = subscriber.upgrade().expect("the session lives");
+    peer.notify_resource_updated(ResourceUpdatedNotificationParam {
+        uri: "movie://showtimes".to_string(),
+    })
+    .await?;
+    drop(peer);
+
+    client.cancel().await?;
+    server.waiting().await?;
+    // the session is gone, and not kept alive by the handler
+    assert!(subscriber.upgrade().is_none());
+    Ok(())
+}
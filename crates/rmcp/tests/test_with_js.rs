@@ -99,8 +99,8 @@ async fn test_with_js_streamable_http_client() -> anyhow::Result<()> {
             || Ok(Calculator::new()),
             Default::default(),
             StreamableHttpServerConfig {
-                stateful_mode: true,
                 sse_keep_alive: None,
+                ..Default::default()
             },
         );
     let router = axum::Router::new().nest_service("/mcp", service);
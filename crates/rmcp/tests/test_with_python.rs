@@ -6,7 +6,6 @@ use rmcp::{
     transport::{ConfigureCommandExt, SseServer, TokioChildProcess, sse_server::SseServerConfig},
 };
 use tokio::{io::AsyncReadExt, time::timeout};
-use tokio_util::sync::CancellationToken;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 mod common;
 use common::calculator::Calculator;
@@ -61,10 +60,7 @@ async fn test_nested_with_python_client() -> anyhow::Result<()> {
     // Create an SSE router
     let sse_config = SseServerConfig {
         bind: BIND_ADDRESS.parse()?,
-        sse_path: "/sse".to_string(),
-        post_path: "/message".to_string(),
-        ct: CancellationToken::new(),
-        sse_keep_alive: None,
+        ..Default::default()
     };
 
     let listener = tokio::net::TcpListener::bind(&sse_config.bind).await?;
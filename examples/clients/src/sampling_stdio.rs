@@ -29,6 +29,11 @@ impl SamplingDemoClient {
 }
 
 impl ClientHandler for SamplingDemoClient {
+    // without it, servers don't know they may ask for sampling
+    fn get_info(&self) -> ClientInfo {
+        ClientInfo::default().with_capabilities(ClientCapabilities::builder().enable_sampling())
+    }
+
     async fn create_message(
         &self,
         params: CreateMessageRequestParam,
@@ -188,6 +188,7 @@ impl ServerHandler for Counter {
                 self._create_resource_text("memo://insights", "memo-name"),
             ],
             next_cursor: None,
+            meta: None,
         })
     }
 
@@ -225,6 +226,7 @@ impl ServerHandler for Counter {
     ) -> Result<ListResourceTemplatesResult, McpError> {
         Ok(ListResourceTemplatesResult {
             next_cursor: None,
+            meta: None,
             resource_templates: Vec::new(),
         })
     }
@@ -1,29 +1,36 @@
 #![allow(dead_code)]
 
-use std::sync::Arc;
-
+use encoding_rs::Encoding;
 use reqwest;
 use rmcp::{
-    ErrorData, RoleServer, ServerHandler,
-    handler::server::{router::tool::ToolRouter, wrapper::Parameters},
+    ErrorData, McpError, RoleServer, ServerHandler,
+    handler::server::{
+        pagination::paginate,
+        router::tool::ToolRouter,
+        tool::{Accept, Locale},
+        wrapper::Parameters,
+    },
     model::*,
     schemars::{self, JsonSchema},
-    service::RequestContext,
+    service::{LazyInit, RequestContext, RequestOutcome, SharedState},
     tool, tool_handler, tool_router,
 };
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JSON_Value;
 use serde_json::json;
+use std::collections::HashMap;
 use std::result::Result;
-use tokio::sync::Mutex;
+use std::sync::Arc;
 
 use undrift_gps::gcj_to_wgs;
 
 #[derive(Serialize, Deserialize, JsonSchema)]
 pub struct GetCinemaListRequest {
     /// Current location latitude
+    #[validate(range(min = -90.0, max = 90.0))]
     pub latitude: f64,
     /// Current location longitude
+    #[validate(range(min = -180.0, max = 180.0))]
     pub longitude: f64,
 }
 
@@ -41,28 +48,107 @@ pub struct GetMovieDetailInfoRequest {
     pub movie_id: i32,
 }
 
+/// The resource listing all the cities, read it with `read_resource_typed`
+pub const CITIES_URI: &str = "movie://cities";
+
+/// The vendor method dropping the cached results of the tools
+pub const REFRESH_CACHE_METHOD: &str = "x-movie/refresh-cache";
+
+/// The least time left to the request for the movie schedule to be fetched
+const MIN_SCHEDULE_FETCH_TIME: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// The max number of resources listed at once
+const RESOURCES_PAGE_SIZE: usize = 50;
+
+/// Parameters of the `cinema://{cinema_id}/shows` resource template
+#[derive(Serialize, Deserialize, JsonSchema)]
+pub struct CinemaShowsParameters {
+    /// Cinema ID, as returned by `get_cinema_list`
+    pub cinema_id: i32,
+}
+
+/// The failures of the upstream, returned with `?` by the tools
+#[derive(Debug, McpError)]
+enum MovieError {
+    #[mcp(code = ErrorCode::UNAVAILABLE, message = "Failed to get {_0}")]
+    Unavailable(&'static str),
+    #[mcp(code = ErrorCode::INVALID_REQUEST, message = "Failed to parse {_0}")]
+    Invalid(&'static str),
+    #[mcp(code = ErrorCode::INVALID_REQUEST, message = "Missing {_0}")]
+    Missing(&'static str),
+}
+
 #[derive(Clone)]
 pub struct Movie {
     client: reqwest::Client,
-    city_id: Arc<Mutex<JSON_Value>>,
+    /// All city IDs, fetched in the background once the session is initialized
+    city_id: LazyInit<JSON_Value>,
+    /// The IDs of the city names looked up so far
+    city_ids_by_name: SharedState<HashMap<String, i32>>,
+    /// Charset used when the upstream `Content-Type` header doesn't declare one
+    default_charset: &'static Encoding,
     tool_router: ToolRouter<Self>,
 }
 
+/// Decode a response body with the charset declared in `Content-Type`,
+/// falling back to `default_charset` when it's missing or unknown.
+pub fn decode_body(
+    content_type: Option<&str>,
+    body: &[u8],
+    default_charset: &'static Encoding,
+) -> String {
+    let encoding = content_type
+        .and_then(|content_type| {
+            content_type.split(';').skip(1).find_map(|param| {
+                let (name, value) = param.split_once('=')?;
+                name.trim()
+                    .eq_ignore_ascii_case("charset")
+                    .then(|| value.trim().trim_matches('"'))
+            })
+        })
+        .and_then(|label| Encoding::for_label(label.as_bytes()))
+        .unwrap_or(default_charset);
+    let (text, _, _) = encoding.decode(body);
+    text.into_owned()
+}
+
 #[tool_router]
 impl Movie {
     pub fn new() -> Self {
         Self {
             client: reqwest::Client::new(),
-            city_id: Arc::new(Mutex::new(json!({}))),
-            tool_router: Self::tool_router(),
+            city_id: LazyInit::new(),
+            city_ids_by_name: SharedState::default(),
+            default_charset: encoding_rs::UTF_8,
+            // the tools are public anyway, help clients recover from a typo, and inline the
+            // nested parameter types for the clients which can't resolve `$ref`s
+            tool_router: Self::tool_router()
+                .with_suggestions()
+                .with_inlined_schemas(),
         }
     }
 
+    /// The tools with their parameters, one per line
+    pub fn describe_tools(&self) -> String {
+        self.tool_router.describe()
+    }
+
+    /// Set the charset used for upstream responses without a declared charset, e.g. `encoding_rs::GBK`
+    pub fn with_default_charset(mut self, default_charset: &'static Encoding) -> Self {
+        self.default_charset = default_charset;
+        self
+    }
+
     #[tool(description = "Gets the current system time")]
-    async fn get_current_time(&self) -> Result<CallToolResult, ErrorData> {
+    async fn get_current_time(&self, Locale(locale): Locale) -> Result<CallToolResult, ErrorData> {
         let now = chrono::Local::now();
-        let time_str = now.format("%Y-%m-%d %H:%M:%S").to_string();
-        Ok(CallToolResult::success(vec![Content::text(time_str)]))
+        let format = if is_chinese(&locale) {
+            "%Y年%m月%d日 %H:%M:%S"
+        } else {
+            "%Y-%m-%d %H:%M:%S"
+        };
+        let time_str = now.format(format).to_string();
+        Ok(CallToolResult::builder().text(time_str).build())
     }
 
     //List of nearby theaters
@@ -72,6 +158,8 @@ impl Movie {
     async fn get_cinema_list(
         &self,
         Parameters(req): Parameters<GetCinemaListRequest>,
+        Locale(locale): Locale,
+        accept: Accept,
     ) -> Result<CallToolResult, ErrorData> {
         let cityname = match self
             .get_cityname_by_lat_lng(req.latitude, req.longitude)
@@ -84,7 +172,7 @@ impl Movie {
             }
         };
 
-        let city_id = match self.get_city_id_by_cityname(cityname).await {
+        let city_id = match self.get_city_id_by_cityname(cityname, &locale).await {
             Ok(i) => i,
             Err(e) => {
                 tracing::error!("[get_cinema_list] Failed to get city ID: {:?}", e);
@@ -116,14 +204,20 @@ impl Movie {
             Ok(s) => s,
             Err(e) => {
                 tracing::error!("[get_cinema_list] Failed to get cinema list: {:?}", e);
-                return Err(ErrorData::invalid_request(
-                    "Failed to get cinema list",
-                    None,
-                ));
+                return Err(ErrorData::unavailable("Failed to get cinema list", None));
             }
         };
 
-        Ok(CallToolResult::success(vec![Content::text(response)]))
+        //A table for humans, or the raw JSON for programs
+        let table = cinema_table(&response);
+        let mut result = accept.negotiate(
+            Representations::new()
+                .with("text/markdown", Content::text(table.to_markdown()))
+                .with("application/json", Content::text(response)),
+        );
+        //Clients may render the cinemas as a table, whatever the representation
+        table.add_to(&mut result);
+        Ok(result)
     }
 
     //Get theater details
@@ -133,22 +227,95 @@ impl Movie {
     async fn get_cinema_information(
         &self,
         Parameters(req): Parameters<GetCinemaInformationRequest>,
+        context: RequestContext<RoleServer>,
     ) -> Result<CallToolResult, ErrorData> {
-        let city_id = match self.get_city_id_by_cityname(req.cityname).await {
-            Ok(i) => i,
-            Err(e) => {
-                tracing::error!("[get_cinema_information] Failed to get city ID: {:?}", e);
-                return Err(ErrorData::invalid_request("Failed to get city ID", None));
+        // the cinema and its movies are fetched separately, return whichever succeeded
+        let cinema = self.get_cinema_detail(req.cinema_id, &context).await;
+        // the schedule is the slow part, don't start it if it can't finish in time
+        let movies = match context.time_remaining() {
+            Some(remaining) if remaining < MIN_SCHEDULE_FETCH_TIME => Err(
+                ErrorData::internal_error("Not enough time left to get the movie schedule", None),
+            ),
+            _ => {
+                self.get_cinema_movies(req.cityname, req.cinema_id, context.locale())
+                    .await
             }
         };
+        Ok(PartialResult::new()
+            .text_part("cinema", cinema.map_err(|e| e.message))
+            .text_part("movies", movies.map_err(|e| e.message))
+            .into_call_tool_result())
+    }
+
+    //Get movie information
+    #[tool(
+        description = "Get movie details based on the movie ID",
+        cache_ttl_ms = 60000,
+        coalesce
+    )]
+    async fn get_movie_detail_info(
+        &self,
+        Parameters(req): Parameters<GetMovieDetailInfoRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let url = format!(
+            "https://apis.netstart.cn/maoyan/movie/intro?movieId={}",
+            req.movie_id
+        );
 
-        let cinema_info = match self.get_cinema_info(req.cinema_id).await {
+        let movie_info = self.send_request(url).await.map_err(|e| {
+            tracing::error!("[get_movie_detail_info] Failed to get movie info: {:?}", e);
+            MovieError::Unavailable("movie info")
+        })?;
+
+        // the details as JSON, or the raw body if it isn't
+        let result = match serde_json::from_str::<JSON_Value>(&movie_info) {
+            Ok(movie_info) => CallToolResult::builder().json(movie_info),
+            Err(_) => CallToolResult::builder().text(movie_info),
+        };
+        Ok(result.build())
+    }
+}
+
+impl Movie {
+    //Get city name based on latitude and longitude
+    async fn get_cityname_by_lat_lng(
+        &self,
+        latitude: f64,
+        longitude: f64,
+    ) -> Result<String, ErrorData> {
+        let url = format!(
+            "https://apis.netstart.cn/maoyan/city/latlng?lat={}&lng={}",
+            latitude, longitude
+        );
+
+        let text = self.send_request(url).await.map_err(|e| {
+            tracing::error!("[get_cityname_by_lat_lng] Failed to get response: {:?}", e);
+            MovieError::Unavailable("city data")
+        })?;
+
+        let val = serde_json::from_str::<JSON_Value>(&text).map_err(|e| {
+            tracing::error!("[get_cityname_by_lat_lng] Failed to parse JSON: {:?}", e);
+            MovieError::Invalid("city data")
+        })?;
+
+        let city = val["data"]["city"].as_str().ok_or_else(|| {
+            tracing::error!("[get_cityname_by_lat_lng] Missing city in response");
+            MovieError::Missing("city data")
+        })?;
+
+        Ok(city.to_string())
+    }
+
+    //Get the information of a cinema, with WGS-84 coordinates
+    async fn get_cinema_detail(
+        &self,
+        cinema_id: i32,
+        context: &RequestContext<RoleServer>,
+    ) -> Result<String, ErrorData> {
+        let cinema_info = match self.get_cinema_info(cinema_id).await {
             Ok(s) => s,
             Err(e) => {
-                tracing::error!(
-                    "[get_cinema_information] Failed to get cinema info: {:?}",
-                    e
-                );
+                tracing::error!("[get_cinema_detail] Failed to get cinema info: {:?}", e);
                 return Err(ErrorData::invalid_request(
                     "Failed to get cinema info",
                     None,
@@ -156,13 +323,14 @@ impl Movie {
             }
         };
 
-        let mut cinema_json = match serde_json::from_str::<JSON_Value>(&cinema_info) {
+        //The cinema data can be large, parse it off the runtime
+        let parsed = context
+            .spawn_blocking(move || serde_json::from_str::<JSON_Value>(&cinema_info))
+            .await?;
+        let mut cinema_json = match parsed {
             Ok(v) => v,
             Err(e) => {
-                tracing::error!(
-                    "[get_cinema_information] Failed to parse cinema JSON: {:?}",
-                    e
-                );
+                tracing::error!("[get_cinema_detail] Failed to parse cinema JSON: {:?}", e);
                 return Err(ErrorData::invalid_request(
                     "Failed to parse cinema data",
                     None,
@@ -173,14 +341,14 @@ impl Movie {
         let lat = match cinema_json["data"]["lat"].as_f64() {
             Some(a) => a,
             None => {
-                tracing::error!("[get_cinema_information] Missing latitude in response");
+                tracing::error!("[get_cinema_detail] Missing latitude in response");
                 0.0
             }
         };
         let lng = match cinema_json["data"]["lng"].as_f64() {
             Some(a) => a,
             None => {
-                tracing::error!("[get_cinema_information] Missing longitude in response");
+                tracing::error!("[get_cinema_detail] Missing longitude in response");
                 0.0
             }
         };
@@ -197,83 +365,33 @@ impl Movie {
             }
         };
 
-        let movie_info = match self.get_cinema_movie_info(req.cinema_id, city_id).await {
-            Ok(s) => s,
-            Err(e) => {
-                tracing::error!("[get_cinema_information] Failed to get movie info: {:?}", e);
-                return Err(ErrorData::invalid_request("Failed to get movie info", None));
-            }
-        };
-
-        Ok(CallToolResult::success(vec![
-            Content::text(new_cinema_info),
-            Content::text(movie_info),
-        ]))
-    }
-
-    //Get movie information
-    #[tool(description = "Get movie details based on the movie ID")]
-    async fn get_movie_detail_info(
-        &self,
-        Parameters(req): Parameters<GetMovieDetailInfoRequest>,
-    ) -> Result<CallToolResult, ErrorData> {
-        let url = format!(
-            "https://apis.netstart.cn/maoyan/movie/intro?movieId={}",
-            req.movie_id
-        );
-
-        let movie_info = match self.send_request(url).await {
-            Ok(s) => s,
-            Err(e) => {
-                tracing::error!("[get_movie_detail_info] Failed to get movie info: {:?}", e);
-                return Err(ErrorData::invalid_request("Failed to get movie info", None));
-            }
-        };
-
-        Ok(CallToolResult::success(vec![Content::text(movie_info)]))
+        Ok(new_cinema_info)
     }
-}
 
-impl Movie {
-    //Get city name based on latitude and longitude
-    async fn get_cityname_by_lat_lng(
+    //Get the movie schedule of a cinema
+    async fn get_cinema_movies(
         &self,
-        latitude: f64,
-        longitude: f64,
+        cityname: String,
+        cinema_id: i32,
+        locale: &str,
     ) -> Result<String, ErrorData> {
-        let url = format!(
-            "https://apis.netstart.cn/maoyan/city/latlng?lat={}&lng={}",
-            latitude, longitude
-        );
-
-        let text = match self.send_request(url).await {
+        let city_id = match self.get_city_id_by_cityname(cityname, locale).await {
             Ok(i) => i,
             Err(e) => {
-                tracing::error!("[get_cityname_by_lat_lng] Failed to get response: {:?}", e);
-                return Err(ErrorData::invalid_request("Failed to get city data", None));
+                tracing::error!("[get_cinema_movies] Failed to get city ID: {:?}", e);
+                return Err(ErrorData::invalid_request("Failed to get city ID", None));
             }
         };
 
-        let val = match serde_json::from_str::<JSON_Value>(&text) {
-            Ok(v) => v,
+        let movie_info = match self.get_cinema_movie_info(cinema_id, city_id).await {
+            Ok(s) => s,
             Err(e) => {
-                tracing::error!("[get_cityname_by_lat_lng] Failed to parse JSON: {:?}", e);
-                return Err(ErrorData::invalid_request(
-                    "Failed to parse city data",
-                    None,
-                ));
-            }
-        };
-
-        let city = match val["data"]["city"].as_str() {
-            Some(a) => a.to_string(),
-            None => {
-                tracing::error!("[get_cityname_by_lat_lng] Missing city in response");
-                return Err(ErrorData::invalid_request("Missing city data", None));
+                tracing::error!("[get_cinema_movies] Failed to get movie info: {:?}", e);
+                return Err(ErrorData::invalid_request("Failed to get movie info", None));
             }
         };
 
-        Ok(city)
+        Ok(movie_info)
     }
 
     //Get information about the studio
@@ -283,16 +401,10 @@ impl Movie {
             cinema_id
         );
 
-        let response = match self.send_request(url).await {
-            Ok(s) => s,
-            Err(e) => {
-                tracing::error!("[get_cinema_info] Failed to get cinema info: {:?}", e);
-                return Err(ErrorData::invalid_request(
-                    "Failed to get cinema info",
-                    None,
-                ));
-            }
-        };
+        let response = self.send_request(url).await.map_err(|e| {
+            tracing::error!("[get_cinema_info] Failed to get cinema info: {:?}", e);
+            MovieError::Unavailable("cinema info")
+        })?;
 
         Ok(response)
     }
@@ -308,13 +420,10 @@ impl Movie {
             cinema_id, city_id
         );
 
-        let response = match self.send_request(url).await {
-            Ok(s) => s,
-            Err(e) => {
-                tracing::error!("[get_cinema_movie_info] Failed to get movie info: {:?}", e);
-                return Err(ErrorData::invalid_request("Failed to get movie info", None));
-            }
-        };
+        let response = self.send_request(url).await.map_err(|e| {
+            tracing::error!("[get_cinema_movie_info] Failed to get movie info: {:?}", e);
+            MovieError::Unavailable("movie info")
+        })?;
 
         Ok(response)
     }
@@ -333,40 +442,42 @@ impl Movie {
             Err(e)=>
             {
                 tracing::error!("[send_request] Failed to send request: {:?}", e);
-                return Err(ErrorData::invalid_request("Failed to send request", None));
+                return Err(ErrorData::from_error(ErrorCode::INVALID_REQUEST, &e));
             }
         };
-        let result_text = match response.text().await {
+        let result_text = match Self::read_text(response, self.default_charset).await {
             Ok(s) => s,
             Err(e) => {
                 tracing::error!("[send_request] Failed to get response text: {:?}", e);
-                return Err(ErrorData::invalid_request(
-                    "Failed to get response text",
-                    None,
-                ));
+                return Err(ErrorData::from_error(ErrorCode::INVALID_REQUEST, &e));
             }
         };
 
         Ok(result_text)
     }
 
-    async fn init_movie(&self) -> Result<bool, ErrorData> {
-        let mut tmp = self.city_id.lock().await;
-        *tmp = match self.get_all_city_id().await {
-            Ok(a) => a,
-            Err(e) => {
-                tracing::error!("get response error,{:?}", e);
-                return Err(ErrorData::invalid_request("response error", None));
-            }
-        };
+    //Fetch all city IDs in the background, so initialize won't wait for the upstream.
+    //The task only holds the HTTP client, a clone of `self` would keep the server alive.
+    fn init_movie(&self) {
+        Self::fetch_cities(&self.client, self.default_charset, &self.city_id);
+    }
 
-        Ok(true)
+    fn fetch_cities(
+        client: &reqwest::Client,
+        default_charset: &'static Encoding,
+        city_id: &LazyInit<JSON_Value>,
+    ) {
+        let client = client.clone();
+        city_id.try_start(async move { Self::get_all_city_id(&client, default_charset).await });
     }
 
     //Get all city IDs
-    async fn get_all_city_id(&self) -> Result<JSON_Value, ErrorData> {
+    async fn get_all_city_id(
+        client: &reqwest::Client,
+        default_charset: &'static Encoding,
+    ) -> Result<JSON_Value, ErrorData> {
         let url = "https://apis.netstart.cn/maoyan/cities.json";
-        let response =match self.client.
+        let response =match client.
         get(url).
         header("User-Agent", "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/123.0.0.0 Safari/537.36").
         header("Accept", "text/html,application/xhtml+xml,application/xml;q=0.9,image/webp,*/*;q=0.8").
@@ -382,7 +493,14 @@ impl Movie {
             }
         };
 
-        let result_json = match response.json::<JSON_Value>().await {
+        let result_text = match Self::read_text(response, default_charset).await {
+            Ok(s) => s,
+            Err(e) => {
+                tracing::error!("get response error,{:?}", e);
+                return Err(ErrorData::invalid_request("response error", None));
+            }
+        };
+        let result_json = match serde_json::from_str::<JSON_Value>(&result_text) {
             Ok(s) => s,
             Err(e) => {
                 tracing::error!("get response error,{:?}", e);
@@ -393,9 +511,68 @@ impl Movie {
         Ok(result_json)
     }
 
+    //Read the response body as text, honoring the charset of the upstream response
+    async fn read_text(
+        response: reqwest::Response,
+        default_charset: &'static Encoding,
+    ) -> Result<String, reqwest::Error> {
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_owned);
+        let body = response.bytes().await?;
+        Ok(decode_body(content_type.as_deref(), &body, default_charset))
+    }
+
+    //All the cities with their IDs, as `{"cts": [{"id": 1, "nm": "北京"}, ...]}`
+    async fn cities(&self) -> Result<&JSON_Value, ErrorData> {
+        Self::cities_of(&self.client, self.default_charset, &self.city_id).await
+    }
+
+    async fn cities_of<'a>(
+        client: &reqwest::Client,
+        default_charset: &'static Encoding,
+        city_id: &'a LazyInit<JSON_Value>,
+    ) -> Result<&'a JSON_Value, ErrorData> {
+        match city_id.get().await {
+            Ok(city_data) => Ok(city_data),
+            Err(e) => {
+                tracing::error!("city id initialization error,{:?}", e);
+                // fetch them again for the next call
+                Self::fetch_cities(client, default_charset, city_id);
+                Err(ErrorData::internal_error("city id is unavailable", None))
+            }
+        }
+    }
+
     //Obtain the city ID based on the city name
-    async fn get_city_id_by_cityname(&self, name: String) -> Result<i32, ErrorData> {
-        let city_data = self.city_id.lock().await;
+    async fn get_city_id_by_cityname(&self, name: String, locale: &str) -> Result<i32, ErrorData> {
+        Self::city_id_by_name(
+            &self.client,
+            self.default_charset,
+            &self.city_id,
+            &self.city_ids_by_name,
+            name,
+            locale,
+        )
+        .await
+    }
+
+    //Takes the parts it needs rather than `self`, so a background task doesn't hold the server
+    async fn city_id_by_name(
+        client: &reqwest::Client,
+        default_charset: &'static Encoding,
+        city_id: &LazyInit<JSON_Value>,
+        city_ids_by_name: &SharedState<HashMap<String, i32>>,
+        name: String,
+        locale: &str,
+    ) -> Result<i32, ErrorData> {
+        if let Some(city_id) = city_ids_by_name.with(|ids| ids.get(&name).copied()) {
+            return Ok(city_id);
+        }
+        // not locked while waiting for the cities
+        let city_data = Self::cities_of(client, default_charset, city_id).await?;
 
         let data: &Vec<JSON_Value> = city_data["cts"]
             .as_array()
@@ -407,29 +584,282 @@ impl Movie {
                 .as_str()
                 .ok_or_else(|| ErrorData::invalid_request("data error", None))?;
 
-            if name.contains(city_name) {
+            if city_name_matches(&name, city_name, locale) {
                 // 找到匹配的城市，获取ID
                 let city_id = city["id"]
                     .as_i64()
                     .ok_or_else(|| ErrorData::invalid_request("data error", None))?;
 
-                return Ok(city_id as i32);
+                let city_id = city_id as i32;
+                city_ids_by_name.with(|ids| ids.insert(name, city_id));
+                return Ok(city_id);
             }
         }
 
-        Err(ErrorData::invalid_params("name is error", None))
+        if is_chinese(locale) {
+            Err(ErrorData::invalid_params("未找到该城市", None))
+        } else {
+            Err(ErrorData::invalid_params("name is error", None))
+        }
+    }
+}
+
+/// The cinemas with their address and distance, empty if the response isn't understood
+fn cinema_table(response: &str) -> Table {
+    let cinemas = serde_json::from_str::<JSON_Value>(response).unwrap_or_default();
+    let columns = [
+        ("nm", "Cinema"),
+        ("addr", "Address"),
+        ("distance", "Distance"),
+    ];
+    let mut table =
+        Table::new(columns.map(|(name, title)| TableColumn::new(name).with_title(title)));
+    for cinema in cinemas["cinemas"].as_array().into_iter().flatten() {
+        table.push_row(columns.map(|(name, _)| cinema[name].clone()));
+    }
+    table
+}
+
+fn is_chinese(locale: &str) -> bool {
+    locale == "zh" || locale.starts_with("zh-")
+}
+
+//Whether the requested name refers to the city, the city names of the upstream are in Chinese
+fn city_name_matches(name: &str, city_name: &str, locale: &str) -> bool {
+    if is_chinese(locale) {
+        return name.contains(city_name);
+    }
+    // names typed with a latin keyboard may differ in case and spacing
+    let normalize = |name: &str| {
+        name.chars()
+            .filter(|c| !c.is_whitespace())
+            .flat_map(char::to_lowercase)
+            .collect::<String>()
+    };
+    normalize(name).contains(&normalize(city_name))
+}
+
+impl Movie {
+    fn cinema_shows_template() -> RawResourceTemplate {
+        RawResourceTemplate::new("cinema://{cinema_id}/shows", "cinema_shows")
+            .with_description("The information and movie schedule of a cinema")
+            .with_mime_type("application/json")
+            .with_parameters::<CinemaShowsParameters>()
     }
 }
 
 #[tool_handler(router = self.tool_router)]
 impl ServerHandler for Movie {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo {
+            capabilities: ServerCapabilities::builder()
+                .enable_tools()
+                .enable_resources()
+                .build(),
+            instructions: Some(rmcp::include_instructions!("movie_instructions.md")),
+            ..Default::default()
+        }
+    }
+
+    async fn list_resources(
+        &self,
+        request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourcesResult, ErrorData> {
+        let mut cities = RawResource::new(CITIES_URI, "cities");
+        cities.description = Some("All the cities with their IDs".to_string());
+        cities.mime_type = Some("application/json".to_string());
+        let cursor = request.and_then(|request| request.cursor);
+        let page = paginate(
+            [cities.no_annotation()],
+            cursor.as_deref(),
+            RESOURCES_PAGE_SIZE,
+        )?;
+        Ok(ListResourcesResult {
+            next_cursor: page.next_cursor,
+            meta: None,
+            resources: page.items,
+        })
+    }
+
+    async fn list_resource_templates(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourceTemplatesResult, ErrorData> {
+        Ok(ListResourceTemplatesResult {
+            next_cursor: None,
+            meta: None,
+            resource_templates: vec![Self::cinema_shows_template().no_annotation()],
+        })
+    }
+
+    async fn read_resource(
+        &self,
+        ReadResourceRequestParam { uri }: ReadResourceRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ReadResourceResult, ErrorData> {
+        if uri == CITIES_URI {
+            let cities = self.cities().await?;
+            return Ok(ReadResourceResult {
+                contents: vec![ResourceContents::text(cities.to_string(), uri)],
+            });
+        }
+        let cinema_id = Self::cinema_shows_template()
+            .match_uri(&uri)
+            .and_then(|variables| variables["cinema_id"].parse::<i32>().ok())
+            .ok_or_else(|| {
+                ErrorData::resource_not_found("resource_not_found", Some(json!({ "uri": uri })))
+            })?;
+        let cinema_info = self.get_cinema_info(cinema_id).await?;
+        Ok(ReadResourceResult {
+            contents: vec![ResourceContents::text(cinema_info, uri)],
+        })
+    }
+
     async fn initialize(
         &self,
         _request: InitializeRequestParam,
-        _context: RequestContext<RoleServer>,
+        context: RequestContext<RoleServer>,
     ) -> Result<InitializeResult, ErrorData> {
-        let _ = self.init_movie().await;
+        self.init_movie();
+        // e.g. `"_meta": {"preloadCities": ["北京", "上海"]}`, resolved before the first tool call
+        let preload: Vec<String> = context
+            .meta
+            .get("preloadCities")
+            .and_then(JSON_Value::as_array)
+            .map(|names| {
+                names
+                    .iter()
+                    .filter_map(|name| name.as_str().map(str::to_owned))
+                    .collect()
+            })
+            .unwrap_or_default();
+        if !preload.is_empty() {
+            // not `self.clone()`, the task would keep the whole server alive past the session
+            let client = self.client.clone();
+            let default_charset = self.default_charset;
+            let city_id = self.city_id.clone();
+            let city_ids_by_name = self.city_ids_by_name.clone();
+            tokio::spawn(async move {
+                for name in preload {
+                    let resolved = Self::city_id_by_name(
+                        &client,
+                        default_charset,
+                        &city_id,
+                        &city_ids_by_name,
+                        name.clone(),
+                        "zh",
+                    )
+                    .await;
+                    if let Err(e) = resolved {
+                        tracing::warn!(city = %name, error = ?e, "failed to preload the city");
+                    }
+                }
+            });
+        }
+        //Log the messages for debugging, without the location of the user
+        context
+            .peer
+            .set_message_logging(Some(Arc::new(|field: &str| {
+                matches!(field, "latitude" | "longitude")
+            })));
+        //Log the outcome of the requests, to see how often the tool calls are abandoned
+        context.peer.set_metrics_recorder(Some(Arc::new(
+            |method: &str, outcome: &RequestOutcome| {
+                tracing::info!(
+                    method,
+                    outcome = outcome.label(),
+                    ?outcome,
+                    "request handled"
+                );
+            },
+        )));
 
         Ok(ServerHandler::get_info(self))
     }
+
+    async fn on_custom_request(
+        &self,
+        request: CustomRequest,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<CustomResult, ErrorData> {
+        match request.method.as_str() {
+            // drop the cached movie details, e.g. once the upstream data is updated
+            REFRESH_CACHE_METHOD => {
+                let refreshed = self
+                    .tool_router
+                    .invalidate_cache("get_movie_detail_info", None);
+                Ok(CustomResult::new(json!({ "refreshed": refreshed })))
+            }
+            method => Err(ErrorData::new(
+                ErrorCode::METHOD_NOT_FOUND,
+                method.to_string(),
+                None,
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Fail when a tool schema changes, which could break the clients.
+    ///
+    /// Run with `UPDATE_SCHEMA=1` to accept the change.
+    #[test]
+    fn test_tool_schemas_are_stable() {
+        const SNAPSHOT: &str = "src/common/movie_service_tool_schemas.json";
+        let snapshot = Movie::tool_router().schema_snapshot();
+        if std::env::var("UPDATE_SCHEMA").is_ok() {
+            let snapshot = serde_json::to_string_pretty(&snapshot).expect("serialize schemas");
+            std::fs::write(SNAPSHOT, snapshot + "\n").expect("write schema snapshot");
+            return;
+        }
+        let expected: JSON_Value =
+            serde_json::from_str(&std::fs::read_to_string(SNAPSHOT).expect("read schema snapshot"))
+                .expect("parse schema snapshot");
+        assert_eq!(
+            snapshot, expected,
+            "tool schemas changed, run with UPDATE_SCHEMA=1 if it's intended"
+        );
+    }
+
+    #[test]
+    fn test_cinema_shows_template() {
+        let template = Movie::cinema_shows_template();
+        let parameters = template.parameters().expect("documented parameters");
+        assert!(parameters["properties"]["cinema_id"]["description"].is_string());
+        let variables = template.match_uri("cinema://42/shows").expect("match");
+        assert_eq!(variables["cinema_id"], "42");
+    }
+
+    #[test]
+    fn test_city_name_matches_by_locale() {
+        assert!(city_name_matches("北京市", "北京", "zh-CN"));
+        assert!(!city_name_matches("hong kong", "Hong Kong", "zh-CN"));
+        assert!(city_name_matches("hong kong", "Hong Kong", "en"));
+        assert!(!city_name_matches("上海", "北京", "en"));
+    }
+
+    #[test]
+    fn test_decode_non_utf8_body() {
+        let (body, _, _) = encoding_rs::GBK.encode("北京市");
+        assert!(std::str::from_utf8(&body).is_err());
+
+        // charset declared by the upstream
+        let text = decode_body(
+            Some("application/json; charset=GBK"),
+            &body,
+            encoding_rs::UTF_8,
+        );
+        assert_eq!(text, "北京市");
+
+        // no charset declared, fall back to the configured default
+        let text = decode_body(Some("application/json"), &body, encoding_rs::GBK);
+        assert_eq!(text, "北京市");
+        let text = decode_body(None, &body, encoding_rs::GBK);
+        assert_eq!(text, "北京市");
+    }
 }
@@ -23,7 +23,6 @@ use rmcp::transport::{
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tokio::sync::RwLock;
-use tokio_util::sync::CancellationToken;
 use tower_http::cors::{Any, CorsLayer};
 use tracing::{debug, error, info, warn};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
@@ -645,8 +644,8 @@ async fn main() -> Result<()> {
         bind: addr,
         sse_path: "/mcp/sse".to_string(),
         post_path: "/mcp/message".to_string(),
-        ct: CancellationToken::new(),
         sse_keep_alive: Some(Duration::from_secs(15)),
+        ..Default::default()
     };
 
     // Create SSE server
@@ -21,10 +21,7 @@ async fn main() -> anyhow::Result<()> {
 
     let config = SseServerConfig {
         bind: BIND_ADDRESS.parse()?,
-        sse_path: "/sse".to_string(),
-        post_path: "/message".to_string(),
-        ct: tokio_util::sync::CancellationToken::new(),
-        sse_keep_alive: None,
+        ..Default::default()
     };
 
     let (sse_server, router) = SseServer::new(config);
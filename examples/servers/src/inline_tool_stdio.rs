@@ -0,0 +1,38 @@
+use anyhow::Result;
+use rmcp::{
+    ServiceExt,
+    handler::server::fn_handler::ServerHandlerBuilder,
+    model::{CallToolRequestParam, CallToolResult, Content, ErrorData},
+    transport::stdio,
+};
+use tracing_subscriber::{self, EnvFilter};
+
+/// A one-tool server built from a single `call_tool` closure, without implementing `ServerHandler`.
+///
+/// npx @modelcontextprotocol/inspector cargo run -p mcp-server-examples --example servers_inline_tool_stdio
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::from_default_env().add_directive(tracing::Level::DEBUG.into()))
+        .with_writer(std::io::stderr)
+        .with_ansi(false)
+        .init();
+
+    let handler = ServerHandlerBuilder::new()
+        .call_tool(|request: CallToolRequestParam, _context| async move {
+            if request.name != "echo" {
+                return Err(ErrorData::invalid_params("only `echo` is available", None));
+            }
+            let arguments = serde_json::Value::Object(request.arguments.unwrap_or_default());
+            Ok(CallToolResult::success(vec![Content::text(
+                arguments.to_string(),
+            )]))
+        })
+        .build();
+
+    let service = handler.serve(stdio()).await.inspect_err(|e| {
+        tracing::error!("serving error: {:?}", e);
+    })?;
+    service.waiting().await?;
+    Ok(())
+}
@@ -0,0 +1,22 @@
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+
+mod common;
+use common::movie_service::Movie;
+
+/// The movie server over the transport picked at launch, e.g.
+/// `MCP_TRANSPORT=sse cargo run --example servers_movie_auto`
+/// or `cargo run --example servers_movie_auto -- --transport http --bind 127.0.0.1:9000`
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    // stdout carries the messages over stdio
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| "info".to_string().into()),
+        )
+        .with(tracing_subscriber::fmt::layer().with_writer(std::io::stderr))
+        .init();
+
+    rmcp::serve_auto(Movie::new).await?;
+    Ok(())
+}
@@ -1,54 +1,82 @@
-use rmcp::transport::sse_server::{SseServer, SseServerConfig};
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
-
-mod common;
-use common::movie_service::Movie;
-
-const BIND_ADDRESS: &str = "127.0.0.1:9000";
-
-#[tokio::main]
-async fn main() -> anyhow::Result<()> {
-    tracing_subscriber::registry()
-        .with(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| "info".to_string().into()),
-        )
-        .with(tracing_subscriber::fmt::layer())
-        .init();
-
-    let config = SseServerConfig {
-        bind: BIND_ADDRESS.parse()?,
-        sse_path: "/sse".to_string(),
-        post_path: "/message".to_string(),
-        ct: tokio_util::sync::CancellationToken::new(),
-        sse_keep_alive: Some(std::time::Duration::from_secs(15)),
-    };
-
-    let (sse_server, router) = SseServer::new(config);
-
-    let listener = tokio::net::TcpListener::bind(sse_server.config.bind).await?;
-    let ct = sse_server.config.ct.child_token();
-
-    let server = axum::serve(listener, router).with_graceful_shutdown(async move {
-        ct.cancelled().await;
-        tracing::info!("movie sse server cancelled");
-    });
-
-    tokio::spawn(async move {
-        if let Err(e) = server.await {
-            tracing::error!(error = %e, "movie sse server shutdown with error");
-        }
-    });
-
-    let ct = sse_server.with_service(Movie::new);
-
-    tracing::info!(
-        "movie server ready over SSE; endpoints: http://{}/sse",
-        BIND_ADDRESS
-    );
-    tracing::info!("press Ctrl+C to stop");
-
-    tokio::signal::ctrl_c().await?;
-    ct.cancel();
-    Ok(())
-}
+use rmcp::{
+    model::JsonLimits,
+    service::Shutdown,
+    transport::{
+        TransportTimeouts,
+        common::server_side_http::{Cors, HealthCheck},
+        sse_server::{SseServer, SseServerConfig},
+    },
+};
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+
+mod common;
+use common::movie_service::Movie;
+
+const BIND_ADDRESS: &str = "127.0.0.1:9000";
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    if std::env::args().any(|arg| arg == "--describe-tools") {
+        println!("{}", Movie::new().describe_tools());
+        return Ok(());
+    }
+
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| "info".to_string().into()),
+        )
+        .with(tracing_subscriber::fmt::layer())
+        .init();
+
+    let shutdown = Shutdown::new().with_ctrl_c().with_sigterm();
+    let config = SseServerConfig {
+        bind: BIND_ADDRESS.parse()?,
+        ct: shutdown.cancellation_token(),
+        sse_keep_alive: Some(std::time::Duration::from_secs(15)),
+        health_check: Some(HealthCheck::default()),
+        // the endpoint is public, reject abusive messages before parsing them
+        json_limits: JsonLimits {
+            max_depth: 32,
+            max_size: Some(1024 * 1024),
+        },
+        // browser clients connect from any web page
+        cors: Some(Cors::permissive()),
+        // close the half-open connections instead of leaking their sessions
+        timeouts: TransportTimeouts::default()
+            .with_read(std::time::Duration::from_secs(600))
+            .with_write(std::time::Duration::from_secs(30)),
+        ..Default::default()
+    };
+
+    let (sse_server, router) = SseServer::new(config);
+
+    let listener = tokio::net::TcpListener::bind(sse_server.config.bind).await?;
+    // the sessions and the http server stop together, on ctrl-c or SIGTERM
+    let (_, service_ct) = sse_server.with_service_and_ct(Movie::new);
+
+    let server = axum::serve(listener, router).with_graceful_shutdown({
+        let service_ct = service_ct.clone();
+        async move {
+            service_ct.cancelled().await;
+            tracing::info!("movie sse server cancelled");
+        }
+    });
+
+    tokio::spawn(async move {
+        if let Err(e) = server.await {
+            tracing::error!(error = %e, "movie sse server shutdown with error");
+        }
+    });
+
+    tracing::info!(
+        "movie server ready over SSE; endpoints: http://{}/sse, health check: http://{}{}",
+        BIND_ADDRESS,
+        BIND_ADDRESS,
+        HealthCheck::DEFAULT_PATH
+    );
+    tracing::info!("press Ctrl+C to stop");
+
+    service_ct.cancelled().await;
+    Ok(())
+}
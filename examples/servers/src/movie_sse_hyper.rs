@@ -0,0 +1,39 @@
+use rmcp::transport::{HyperSseServer, sse_server::SseServerConfig};
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+
+mod common;
+use common::movie_service::Movie;
+
+const BIND_ADDRESS: &str = "127.0.0.1:9000";
+
+/// The movie server over SSE, served directly on hyper instead of axum
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| "info".to_string().into()),
+        )
+        .with(tracing_subscriber::fmt::layer())
+        .init();
+
+    let config = SseServerConfig {
+        bind: BIND_ADDRESS.parse()?,
+        sse_keep_alive: Some(std::time::Duration::from_secs(15)),
+        ..Default::default()
+    };
+
+    let ct = HyperSseServer::serve_with_config(config)
+        .await?
+        .with_service(Movie::new);
+
+    tracing::info!(
+        "movie server ready over SSE on hyper; endpoints: http://{}/sse",
+        BIND_ADDRESS
+    );
+    tracing::info!("press Ctrl+C to stop");
+
+    tokio::signal::ctrl_c().await?;
+    ct.cancel();
+    Ok(())
+}
@@ -51,10 +51,7 @@ async fn run_sse() -> anyhow::Result<()> {
     println!("Running SSE server");
     let config = SseServerConfig {
         bind: SSE_BIND_ADDRESS.parse()?,
-        sse_path: "/sse".to_string(),
-        post_path: "/message".to_string(),
-        ct: tokio_util::sync::CancellationToken::new(),
-        sse_keep_alive: None,
+        ..Default::default()
     };
 
     let (sse_server, router) = SseServer::new(config);
@@ -117,10 +114,7 @@ async fn run_all_transports() -> anyhow::Result<()> {
     // Start SSE server
     let sse_config = SseServerConfig {
         bind: SSE_BIND_ADDRESS.parse()?,
-        sse_path: "/sse".to_string(),
-        post_path: "/message".to_string(),
-        ct: tokio_util::sync::CancellationToken::new(),
-        sse_keep_alive: None,
+        ..Default::default()
     };
 
     let (sse_server, sse_router) = SseServer::new(sse_config);
@@ -123,8 +123,10 @@ impl ServerHandler for SamplingDemoServer {
                 output_schema: None,
                 annotations: None,
                 icons: None,
+                meta: None,
             }],
             next_cursor: None,
+            meta: None,
         })
     }
 }
@@ -17,7 +17,6 @@ use axum::{
     routing::get,
 };
 use rmcp::transport::{SseServer, sse_server::SseServerConfig};
-use tokio_util::sync::CancellationToken;
 mod common;
 use common::counter::Counter;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
@@ -118,10 +117,8 @@ async fn main() -> Result<()> {
     // Create SSE server configuration
     let sse_config = SseServerConfig {
         bind: addr,
-        sse_path: "/sse".to_string(),
-        post_path: "/message".to_string(),
-        ct: CancellationToken::new(),
         sse_keep_alive: Some(Duration::from_secs(15)),
+        ..Default::default()
     };
 
     // Create SSE server